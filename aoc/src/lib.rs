@@ -0,0 +1,147 @@
+//! The days behind a small API for notebooks, such as an evcxr Jupyter
+//! kernel, where a panic would take the whole kernel down with it:
+//!
+//! ```text
+//! :dep aoc = { path = "aoc" }
+//! let input = std::fs::read_to_string("inputs/day01.txt")?;
+//! aoc::run(1, 2, &input)?
+//! aoc::bench(1, std::time::Duration::from_millis(500), Some(&input))?
+//! ```
+//!
+//! [`Benchmarks`] show up as an HTML table in the notebook.
+
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+pub use solutions;
+pub use util;
+use util::{
+    timer::BenchmarkResult,
+    writer::{Align, Table},
+};
+
+/// `f`, with a panic turned into an error
+fn catching<T>(f: impl FnOnce() -> T) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload: Box<dyn Any + Send>| {
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(ToString::to_string));
+        anyhow!(message.unwrap_or_else(|| "The solution panicked".to_string()))
+    })
+}
+
+/// The days that can be run
+#[must_use]
+pub fn days() -> Vec<u8> {
+    solutions::all()
+        .iter()
+        .map(|solution| solution.day())
+        .collect()
+}
+
+/// The answer of a part of a day for `input`
+pub fn run(day: u8, part: u8, input: &str) -> Result<String> {
+    let solution = solutions::find(day)?;
+    catching(|| solutions::with_input(input, || solution.solve(false, part)))?
+        .ok_or_else(|| anyhow!("Part must be 1 or 2"))
+}
+
+/// Benchmarks of parsing and both parts of a day, as a table in notebooks
+#[derive(Clone, Debug)]
+pub struct Benchmarks(pub Vec<BenchmarkResult>);
+
+impl Benchmarks {
+    /// The benchmarks as a table of their timings, in the unit that reads best
+    /// for each
+    #[must_use]
+    pub fn table(&self) -> Table {
+        let mut table = Table::new([
+            "Phase",
+            "Iterations",
+            "Median",
+            "MAD",
+            "Mean",
+            "Std. dev.",
+            "Fastest",
+            "Slowest",
+        ]);
+        for column in 1..=7 {
+            table = table.align(column, Align::Right);
+        }
+        for result in &self.0 {
+            let format = result.human_readable_format();
+            table.row(
+                [result.name.clone(), result.iterations.to_string()]
+                    .into_iter()
+                    .chain(
+                        [
+                            result.median,
+                            result.mad,
+                            result.mean,
+                            result.std_dev,
+                            result.fastest,
+                            result.slowest,
+                        ]
+                        .map(&format),
+                    ),
+            );
+        }
+        table
+    }
+
+    /// The benchmarks as an HTML table
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        self.table().to_html()
+    }
+
+    /// Shows the benchmarks as an HTML table when they are the value of an
+    /// evcxr cell, which looks for a method of this name
+    #[allow(clippy::print_stdout)]
+    pub fn evcxr_display(&self) {
+        println!(
+            "EVCXR_BEGIN_CONTENT text/html\n{}EVCXR_END_CONTENT",
+            self.to_html()
+        );
+    }
+}
+
+/// Benchmark parsing and both parts of a day for about `time_limit` each, on
+/// `input` or else on the input file of the day
+pub fn bench(day: u8, time_limit: Duration, input: Option<&str>) -> Result<Benchmarks> {
+    let solution = solutions::find(day)?;
+    let bench = || solution.bench(time_limit);
+    let results =
+        catching(|| input.map_or_else(bench, |input| solutions::with_input(input, bench)))?;
+    Ok(Benchmarks(results.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() -> Result<()> {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        assert!(days().contains(&1));
+        assert_eq!(run(1, 1, input)?, "3");
+        assert_eq!(run(1, 2, input)?, "6");
+        assert!(run(1, 3, input).is_err());
+        assert!(run(26, 1, input).is_err());
+        // A panic while parsing is an error like any other
+        assert!(run(1, 1, "X1\n").is_err());
+        let benchmarks = bench(1, Duration::from_millis(10), Some(input))?;
+        assert_eq!(benchmarks.0.len(), 3);
+        let html = benchmarks.to_html();
+        assert!(
+            html.starts_with("<table>")
+                && html.contains("<td style=\"text-align: left\">Part 2</td>")
+        );
+        Ok(())
+    }
+}
@@ -1,16 +1,75 @@
 use anyhow::Result;
+use serde::Deserialize;
 use util::{
-    Solution,
-    reader::{parse_lines, read_file},
+    config,
+    grid::{Bounds, Walker},
+    prelude::*,
 };
 
-type Operation = i32;
+/// Clicks to turn the dial, negative for left
+type Operation = i64;
+
+/// A dial with positions `0..size`, as a walker on a ring
+#[derive(Clone, Copy, Debug)]
+struct Dial {
+    walker: Walker,
+}
+
+impl Dial {
+    /// A dial of `size` positions pointing at `start`
+    fn new(size: usize, start: usize) -> Self {
+        let start = i64::try_from(start)
+            .unwrap_or_else(|_| unreachable!("Dial sizes are checked to fit in an i64"));
+        let walker = Walker::new(Point::new(0, start), Direction::Right)
+            .with_bounds(Bounds::Wrap((1, size)));
+        Self { walker }
+    }
+
+    /// Position the dial is pointing at
+    const fn position(&self) -> i64 {
+        self.walker.pos().col
+    }
+
+    /// Turn the dial by `clicks`, and return how many times it points at 0
+    /// along the way, including where it stops. The walker works this out
+    /// without stepping through full circles.
+    fn turn(&mut self, clicks: Operation) -> u64 {
+        self.walker.forward_counting(clicks, Point::new(0, 0))
+    }
+}
+
+/// Tunables of the day from `config/day01.toml`, describing the dial of the
+/// real input unless it has headers of its own
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// Number of positions on the dial
+    size: usize,
+    /// Position the dial starts at, half the size unless given
+    start: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            size: Puzzle::DEFAULT_SIZE,
+            start: None,
+        }
+    }
+}
 
 struct Puzzle {
     operations: Vec<Operation>,
+    /// Number of positions on the dial
+    size: usize,
+    /// Position the dial starts at
+    start: usize,
 }
 
 impl Puzzle {
+    /// Dial size unless configured or the input has a `size: N` header
+    const DEFAULT_SIZE: usize = 100;
+
     fn parse_operation(input: &str) -> Result<Operation> {
         if input.is_empty() {
             anyhow::bail!("Empty input");
@@ -24,8 +83,51 @@ impl Puzzle {
     }
 
     fn new(example: bool) -> Result<Self> {
-        let operations = parse_lines(read_file(Self::DAY, example)?, Self::parse_operation)?;
-        Ok(Self { operations })
+        // The example has its answers for the dial of the puzzle only
+        let config = if example {
+            Config::default()
+        } else {
+            config::load(Self::DAY)?
+        };
+        Self::from_input(&read_file(Self::DAY, example)?, &config)
+    }
+
+    /// Parse the operations, optionally preceded by `size: N` and `start: N`
+    /// header lines describing the dial, which win over `config`. The size
+    /// defaults to 100 and the start to half the size, as in the puzzle.
+    fn from_input(input: &str, config: &Config) -> Result<Self> {
+        let (mut size, mut start) = (config.size, config.start);
+        let mut body = input.trim_start();
+        loop {
+            let (line, rest) = body.split_once('\n').unwrap_or((body, ""));
+            let Some((key, value)) = line.split_once(':') else {
+                break;
+            };
+            match key.trim() {
+                "size" => size = value.trim().parse()?,
+                "start" => start = Some(value.trim().parse()?),
+                _ => anyhow::bail!("Unknown header: {line}"),
+            }
+            body = rest;
+        }
+        let start = start.unwrap_or(size / 2);
+        // Positions are i64 on the dial, and below the size
+        if i64::try_from(size).is_err() {
+            anyhow::bail!("Dial size {size} is too large");
+        }
+        if start >= size {
+            anyhow::bail!("Start {start} is not on a dial of size {size}");
+        }
+        let operations = parse_lines(body, Self::parse_operation)?;
+        Ok(Self {
+            operations,
+            size,
+            start,
+        })
+    }
+
+    fn dial(&self) -> Dial {
+        Dial::new(self.size, self.start)
     }
 }
 
@@ -36,32 +138,45 @@ impl Solution for Puzzle {
         Self::new(example).unwrap_or_else(|e| panic!("Failed to parse input: {e}"))
     }
 
-    /// Simulate the operations and count the number of times we pass position 0
+    /// Simulate the operations and count the number of times we stop at
+    /// position 0
     fn part1(&self) -> String {
-        let (_, cnt) = self.operations.iter().fold((50, 0), |(pos, cnt), op| {
-            let new_pos = (pos + op).rem_euclid(100);
-            (new_pos, cnt + u32::from(new_pos == 0))
-        });
+        let mut dial = self.dial();
+        let cnt = self
+            .operations
+            .iter()
+            .filter(|&&op| {
+                dial.turn(op);
+                dial.position() == 0
+            })
+            .count();
         format!("{cnt}")
     }
 
-    /// Simulate the operations, breaking down large moves into full circles and
-    /// remainders and handle passing position 0 correctly for remainders
+    /// Simulate the operations and count the number of times we pass position
+    /// 0, see [`Dial::turn`]
     fn part2(&self) -> String {
-        let (_, cnt) = self.operations.iter().fold((50, 0), |(pos, cnt), op| {
-            let full_circle = (op.abs() / 100).unsigned_abs();
-            let new_pos = pos + (op % 100);
-            let rem_zero = u32::from(pos > 0 && new_pos <= 0 || pos < 100 && new_pos >= 100);
-            (new_pos.rem_euclid(100), cnt + rem_zero + full_circle)
-        });
+        let mut dial = self.dial();
+        let cnt: u64 = self.operations.iter().map(|&op| dial.turn(op)).sum();
         format!("{cnt}")
     }
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
@@ -70,26 +185,87 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use util::{
+        generate::{int_list, stress_test},
+        rng::Rng,
+        testing::assert_answer,
+    };
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "3");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "6");
+    fn test_header() -> Result<()> {
+        let example = read_file(Puzzle::DAY, true)?;
+        let default = Config::default();
+        let puzzle = Puzzle::from_input(&format!("size: 100\nstart: 50\n{example}"), &default)?;
+        assert_eq!((puzzle.part1(), puzzle.part2()), ("3".into(), "6".into()));
+        // Start defaults to half the size
+        let puzzle = Puzzle::from_input("size: 10\nR5\nL15", &default)?;
+        assert_eq!(
+            (puzzle.start, puzzle.part1(), puzzle.part2()),
+            (5, "1".into(), "2".into())
+        );
+        // The same dial from the configuration, with headers winning over it
+        let config = config::parse(Puzzle::DAY, "size = 10\nstart = 3")?;
+        let puzzle = Puzzle::from_input("start: 5\nR5\nL15", &config)?;
+        assert_eq!((puzzle.size, puzzle.start), (10, 5));
+        assert!(Puzzle::from_input("start: 100\nR1", &default).is_err());
+        assert!(Puzzle::from_input("speed: 1\nR1", &default).is_err());
+        assert!(Puzzle::from_input("size: 0\nR1", &default).is_err());
+        assert!(Puzzle::from_input(&format!("size: {}\nR1", usize::MAX), &default).is_err());
+        assert!(Puzzle::from_input("R1", &config::parse(Puzzle::DAY, "start = 100")?).is_err());
         Ok(())
     }
 
+    #[test]
+    fn test_matches_clicks() {
+        // Any generated (size, start, operations) maps to a valid dial, so
+        // shrinking keeps the cases meaningful
+        let case = |(size, start, operations): &(usize, usize, Vec<Operation>)| Puzzle {
+            operations: operations.clone(),
+            size: size % 20 + 1,
+            start: start % (size % 20 + 1),
+        };
+        // Turn one click at a time, noting every time the dial points at 0
+        let clicks = |input: &_| {
+            let puzzle = case(input);
+            let mut dial = puzzle.dial();
+            let (mut stops, mut passes) = (0, 0);
+            for &op in &puzzle.operations {
+                for _ in 0..op.abs() {
+                    dial.turn(op.signum());
+                    passes += u64::from(dial.position() == 0);
+                }
+                stops += usize::from(dial.position() == 0);
+            }
+            (stops.to_string(), passes.to_string())
+        };
+        let fast = |input: &_| {
+            let puzzle = case(input);
+            (puzzle.part1(), puzzle.part2())
+        };
+        let generate = |rng: &mut Rng| {
+            (
+                rng.below(20),
+                rng.below(20),
+                int_list(rng, 0..=30, -60..=60),
+            )
+        };
+        assert_eq!(stress_test(clicks, fast, generate, 500), None);
+    }
+
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        Ok(Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)?)
     }
 }
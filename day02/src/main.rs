@@ -1,45 +1,106 @@
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, io::BufRead};
 
-use anyhow::Result;
-use rayon::prelude::*;
-use util::{
-    Solution,
-    reader::{parse_comma_separated, read_file},
-};
+use anyhow::{Result, anyhow};
+use util::{prelude::*, ranges::IntervalSet};
 
-type Range = (u64, u64);
+type Range = (u128, u128);
+
+/// Which IDs are invalid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pattern {
+    /// A block of digits repeated exactly twice, as in part 1
+    Twice,
+    /// A block of digits repeated any number of times, as in part 2
+    Repeated,
+}
 
 struct Puzzle {
-    ranges: Vec<Range>,
+    ranges: IntervalSet<u128>,
+    /// Numeric base the IDs are written in
+    radix: u32,
 }
 
 impl Puzzle {
-    fn parse_range(input: &str) -> Result<Range> {
+    /// IDs are decimal unless the input starts with a `radix: N` line
+    const DEFAULT_RADIX: u32 = 10;
+
+    fn parse_range(input: &str, radix: u32) -> Result<Range> {
         let Some((start, end)) = input.split_once('-') else {
             anyhow::bail!("Invalid range format: {input}");
         };
-        let start = start.parse()?;
-        let end = end.parse()?;
+        let start = u128::from_str_radix(start, radix)?;
+        let end = u128::from_str_radix(end, radix)?;
+        if start == 0 || start > end {
+            anyhow::bail!("Invalid range bounds: {input}");
+        }
         Ok((start, end))
     }
 
     fn new(example: bool) -> Result<Self> {
-        let mut ranges = parse_comma_separated(read_file(Self::DAY, example)?, Self::parse_range)?;
+        Self::from_input(&read_file(Self::DAY, example)?)
+    }
+
+    /// Parse the comma separated ranges, optionally preceded by a header line
+    /// such as `radix: 16` giving the base of the IDs, from 2 to 36.
+    fn from_input(input: &str) -> Result<Self> {
+        let (radix, ranges) = Self::stream_ranges(input.as_bytes())?;
         // Merge overlapping or contiguous ranges
-        ranges.sort_unstable();
-        let ranges = ranges
-            .into_iter()
-            .fold(vec![], |mut acc: Vec<Range>, curr: Range| {
-                if let Some(last) = acc.last_mut()
-                    && curr.0 <= last.1 + 1
-                {
-                    last.1 = last.1.max(curr.1);
-                    return acc;
-                }
-                acc.push(curr);
-                acc
-            });
-        Ok(Self { ranges })
+        let ranges = ranges.collect::<Result<_>>()?;
+        Ok(Self { ranges, radix })
+    }
+
+    /// Parse the optional radix header, then lazily parse the ranges one at
+    /// a time as they are read, so that enormous range lists never need to be
+    /// held in memory at once.
+    fn stream_ranges<R: BufRead>(
+        mut reader: R,
+    ) -> Result<(u32, impl Iterator<Item = Result<Range>>)> {
+        // Skip leading blank space, which may span several buffer fills
+        loop {
+            let buffer = reader.fill_buf()?;
+            let blank = buffer
+                .iter()
+                .take_while(|b| b.is_ascii_whitespace())
+                .count();
+            let done = blank < buffer.len() || buffer.is_empty();
+            reader.consume(blank);
+            if done {
+                break;
+            }
+        }
+        let radix = if reader.fill_buf()?.starts_with(b"radix:") {
+            let mut header = String::new();
+            reader.read_line(&mut header)?;
+            header["radix:".len()..].trim().parse()?
+        } else {
+            Self::DEFAULT_RADIX
+        };
+        if !(2..=36).contains(&radix) {
+            anyhow::bail!("Radix must be between 2 and 36, got {radix}");
+        }
+        let ranges = reader.split(b',').map(move |chunk| {
+            let chunk = String::from_utf8(chunk?)?;
+            Self::parse_range(chunk.trim(), radix)
+        });
+        Ok((radix, ranges))
+    }
+
+    /// Sum of the invalid IDs of each range, computed lazily as the ranges
+    /// come in.
+    ///
+    /// Unlike [`Self::part1`] and [`Self::part2`], the ranges are not merged
+    /// first, so an ID in several overlapping ranges counts once per range.
+    fn stream_sums(
+        ranges: impl Iterator<Item = Result<Range>>,
+        radix: u32,
+        pattern: Pattern,
+    ) -> impl Iterator<Item = Result<u128>> {
+        ranges.map(move |range| Self::sum_invalid_ids(range?, radix, pattern))
+    }
+
+    /// Number of digits of `x` when written in the given radix
+    const fn digits(x: u128, radix: u32) -> u32 {
+        x.ilog(radix as u128) + 1
     }
 
     /// Find prime factors of a number
@@ -68,18 +129,29 @@ impl Puzzle {
     }
 
     /// Calculate the sum of invalid IDs in the given range for IDs using n
-    /// digits with a certain repeat pattern.
+    /// digits in the given radix with a certain repeat pattern.
+    ///
+    /// For example, for n=6 and repeat=3 in base 10, the invalid IDs are of
+    /// the form: ababab where a,b are digits from 0-9 (with a != 0)
     ///
-    /// For example, for n=6 and repeat=3, the invalid IDs are of the form:
-    /// ababab where a,b are digits from 0-9 (with a != 0)
-    fn get_sum_invalid_ids(range: Range, n: u32, repeat: u32) -> u64 {
+    /// Powers of the radix that do not fit in `u128` are beyond any ID, so
+    /// they just bound nothing, and the only real overflow is a sum too large
+    /// for `u128`, which is returned as `None`.
+    fn get_sum_invalid_ids(range: Range, n: u32, repeat: u32, radix: u32) -> Option<u128> {
+        let radix = u128::from(radix);
         // The pattern repeats every k = n / repeat digits
         let k = n / repeat;
-        // Calculate the lower and upper bounds for n-digit numbers with the given
-        // pattern
-        let upper = 10u64.pow(n) - 1;
-        let base = upper / (10u64.pow(k) - 1);
-        let lower = 10u64.pow(k - 1) * base;
+        // Invalid IDs are multiples of 1 + r^k + r^2k + ..., and the smallest
+        // one with n digits starts with a 1 followed by zeros in each block
+        let Some(base) =
+            (0..repeat).try_fold(0u128, |base, i| base.checked_add(radix.checked_pow(i * k)?))
+        else {
+            return Some(0);
+        };
+        let Some(lower) = radix.pow(k - 1).checked_mul(base) else {
+            return Some(0);
+        };
+        let upper = radix.checked_pow(n).map_or(u128::MAX, |p| p - 1);
         // Get the overlap between the given range and (lower, upper)
         let (start, end) = range;
         let start = start.max(lower);
@@ -88,10 +160,77 @@ impl Puzzle {
         let start = start.div_ceil(base);
         let end = end / base;
         if start > end {
-            return 0;
+            return Some(0);
+        }
+        // Generate all invalid IDs in the range, halving whichever of the count
+        // and the sum of ends is even so the product overflows only if needed
+        let (count, ends) = (end - start + 1, start + end);
+        let total = if count % 2 == 0 {
+            (count / 2).checked_mul(ends)
+        } else {
+            count.checked_mul(ends / 2)
+        };
+        total?.checked_mul(base)
+    }
+
+    /// Sum of the IDs in `range` matching `pattern`, or an error if it does
+    /// not fit in `u128`
+    fn sum_invalid_ids((start, end): Range, radix: u32, pattern: Pattern) -> Result<u128> {
+        let overflow = || anyhow!("Sum of invalid IDs in {start}-{end} overflows u128");
+        let mut total = 0u128;
+        for n in Self::digits(start, radix)..=Self::digits(end, radix) {
+            let sum = match pattern {
+                Pattern::Twice if n % 2 == 0 => {
+                    Self::get_sum_invalid_ids((start, end), n, 2, radix)
+                }
+                Pattern::Repeated if n > 1 => Self::sum_repeated((start, end), n, radix),
+                // Too few digits to repeat
+                Pattern::Twice | Pattern::Repeated => Some(0),
+            }
+            .ok_or_else(overflow)?;
+            total = total.checked_add(sum).ok_or_else(overflow)?;
         }
-        // Generate all invalid IDs in the range
-        (end - start + 1) * (start + end) / 2 * base
+        Ok(total)
+    }
+
+    /// Sum of the n-digit IDs in `range` repeating any number of times, by
+    /// inclusion-exclusion over the prime factors of n, see [`Self::part2`]
+    fn sum_repeated(range: Range, n: u32, radix: u32) -> Option<u128> {
+        let primes = Self::prime_factors(n);
+        // Every non-empty subset of primes, with their product as the repeat
+        // count, signed by the subset size
+        let (added, subtracted) = (1..1usize << primes.len()).try_fold(
+            (0u128, 0u128),
+            |(added, subtracted), subset| {
+                let repeat = primes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| subset >> i & 1 == 1)
+                    .map(|(_, &p)| p)
+                    .product();
+                let sum = Self::get_sum_invalid_ids(range, n, repeat, radix)?;
+                Some(if subset.count_ones() % 2 == 1 {
+                    (added.checked_add(sum)?, subtracted)
+                } else {
+                    (added, subtracted.checked_add(sum)?)
+                })
+            },
+        )?;
+        Some(added - subtracted)
+    }
+
+    /// Total over all ranges, summing each range in parallel
+    fn total(&self, pattern: Pattern) -> Result<u128> {
+        let sums = self
+            .ranges
+            .as_slice()
+            .maybe_par_iter()
+            .map(|&range| Self::sum_invalid_ids(range, self.radix, pattern))
+            .collect::<Result<Vec<_>>>()?;
+        sums.into_iter().try_fold(0u128, |a, b| {
+            a.checked_add(b)
+                .ok_or_else(|| anyhow!("Sum of invalid IDs overflows u128"))
+        })
     }
 }
 
@@ -108,26 +247,16 @@ impl Solution for Puzzle {
     /// 100100, 200200, ..., 999999 (base 1001)
     /// and so on.
     ///
-    /// We can generalize this to say that for any odd length n,
+    /// We can generalize this to say that for any even length n,
     /// the invalid IDs are of the form:
-    /// k * (10^ceil(n/2) + 1) for k in [10^(n//2), 10^ceil(n/2) - 1]
+    /// k * (r^(n/2) + 1) for k in [r^(n/2 - 1), r^(n/2) - 1]
+    /// where r is the radix, 10 for the puzzle itself.
     ///
     /// We then find the overlap of these ranges with the given ranges
     /// and sum the invalid IDs.
     fn part1(&self) -> String {
-        self.ranges
-            .par_iter()
-            .map(|&(start, end)| {
-                // Determine the min and max number of digits in the range
-                let min_n = start.ilog10() + 1;
-                let max_n = end.ilog10() + 1;
-
-                (min_n..=max_n)
-                    .filter(|n| n % 2 == 0)
-                    .map(|n| Self::get_sum_invalid_ids((start, end), n, 2))
-                    .sum::<u64>()
-            })
-            .sum::<u64>()
+        self.total(Pattern::Twice)
+            .unwrap_or_else(|e| panic!("Failed to sum invalid IDs: {e}"))
             .to_string()
     }
 
@@ -139,78 +268,216 @@ impl Solution for Puzzle {
     /// all k that are factors of n.
     ///
     /// We can further find that only prime factors need to be considered, since
-    /// any composite factor can be formed by combining smaller prime factors,
-    /// and their contributions have already been counted. For example, for n=8,
-    /// the pattern that repeats 4 times (e.g., abcdabcd) can be formed by
-    /// combining the patterns that repeat 2 times (e.g., abababab).
+    /// a pattern repeating a composite number of times also repeats each of
+    /// its prime factors times. For example, for n=8, the pattern that repeats
+    /// 4 times (e.g., abababab) also repeats 2 times (abab abab).
     ///
-    /// However, we still need to consider the case where all digits are the
-    /// same (e.g., 1111, 2222, ..., 9999 for n=4), which is covered by all
-    /// prime factors. Therefore, we handle this case separately, by
-    /// subtracting it out after adding the contributions from prime
-    /// factors.
+    /// However, the same ID can repeat for several prime factors, and we must
+    /// count it only once. An ID repeating both p and q times repeats p*q
+    /// times, so by inclusion-exclusion we add the IDs for each single prime,
+    /// subtract those for each product of two, add back those for three, and
+    /// so on. For n=12 and primes 2 and 3, that is the IDs repeating twice
+    /// plus those repeating three times, minus those repeating six times
+    /// (ababababab ab), which includes the ones with all digits the same.
     fn part2(&self) -> String {
-        self.ranges
-            .par_iter()
-            .map(|&(start, end)| {
-                // Determine the min and max number of digits in the range
-                let min_n = start.ilog10() + 1;
-                let max_n = end.ilog10() + 1;
-
-                (min_n..=max_n)
-                    .filter(|&n| n > 1)
-                    .map(|n| {
-                        // Sum of all repeating digits (e.g., 1111, 2222, ..., 9999 for n=4)
-                        let all_same = Self::get_sum_invalid_ids((start, end), n, n);
-                        // Get all patterns with smaller, prime repeat factors
-                        Self::prime_factors(n).into_iter().filter(|&k| k < n).fold(
-                            all_same,
-                            |mut sum, k| {
-                                sum += Self::get_sum_invalid_ids((start, end), n, k);
-                                sum -= all_same;
-                                sum
-                            },
-                        )
-                    })
-                    .sum::<u64>()
-            })
-            .sum::<u64>()
+        self.total(Pattern::Repeated)
+            .unwrap_or_else(|e| panic!("Failed to sum invalid IDs: {e}"))
             .to_string()
     }
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let args = diagnostics::args();
+    match args.first().map(String::as_str) {
+        // Sum range by range while reading, without merging the ranges
+        Some("--stream") => {
+            for (part, pattern) in [(1, Pattern::Twice), (2, Pattern::Repeated)] {
+                let (radix, ranges) = Puzzle::stream_ranges(open_file(Puzzle::DAY, false)?)?;
+                let total =
+                    Puzzle::stream_sums(ranges, radix, pattern).try_fold(0u128, |total, sum| {
+                        total
+                            .checked_add(sum?)
+                            .ok_or_else(|| anyhow!("Sum of invalid IDs overflows u128"))
+                    })?;
+                println!("Day {} Part {part} (streamed): {total}", Puzzle::DAY);
+            }
+            return Ok(());
+        }
+        Some(other) => anyhow::bail!("Unknown argument {other}, expected --stream"),
+        None => {}
+    }
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{io::BufReader, time::Duration};
 
-    use util::{Benchmark, Serializable};
+    use util::{generate::stress_test, rng::Rng, testing::assert_answer};
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "1227775554");
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    /// Write `x` in the given radix, most significant digit first
+    fn to_digits(mut x: u128, radix: u128) -> Vec<u128> {
+        let mut digits = Vec::new();
+        while x > 0 {
+            digits.push(x % radix);
+            x /= radix;
+        }
+        digits.reverse();
+        digits
+    }
+
+    /// Sum the IDs in the range whose digits are some block repeated a number
+    /// of times accepted by `repeats`, checking every ID one by one
+    fn brute_force(radix: u32, (start, end): Range, repeats: impl Fn(usize) -> bool) -> u128 {
+        (start..=end)
+            .filter(|&x| {
+                let digits = to_digits(x, radix.into());
+                (2..=digits.len()).any(|repeat| {
+                    repeats(repeat)
+                        && digits.len().is_multiple_of(repeat)
+                        && digits
+                            .chunks(digits.len() / repeat)
+                            .all(|chunk| chunk == &digits[..digits.len() / repeat])
+                })
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_radix_header() -> Result<()> {
+        let puzzle = Puzzle::from_input("radix: 16\nb-16,5f-73")?;
+        assert_eq!(puzzle.radix, 16);
+        assert_eq!(puzzle.ranges.as_slice(), [(11, 22), (95, 115)]);
+        assert_eq!(puzzle.part1(), (0x11 + 0x66).to_string());
+        assert!(Puzzle::from_input("radix: 2\n1-10,11-110").is_ok());
+        // Not a binary digit
+        assert!(Puzzle::from_input("radix: 2\n1-2").is_err());
+        assert!(Puzzle::from_input("radix: 37\n1-2").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        // Any generated (radix, start, length) maps to a valid small range, so
+        // shrinking keeps the cases meaningful
+        let case = |&(radix, start, len): &(usize, usize, usize)| {
+            let (radix, start, len) = (radix % 15 + 2, start as u128, len as u128);
+            let puzzle = Puzzle {
+                ranges: std::iter::once((start + 1, start + 1 + len)).collect(),
+                radix: u32::try_from(radix).unwrap_or_default(),
+            };
+            (puzzle, (start + 1, start + 1 + len))
+        };
+        let generate = |rng: &mut Rng| {
+            // Starts spread over magnitudes, to cover many digit counts
+            let magnitude = rng.below(17);
+            let start = rng.below(1 << magnitude);
+            (rng.below(15), start, rng.below(3000))
+        };
+        let part1 = stress_test(
+            |input| {
+                let (puzzle, range) = case(input);
+                brute_force(puzzle.radix, range, |repeat| repeat == 2).to_string()
+            },
+            |input| case(input).0.part1(),
+            generate,
+            300,
+        );
+        assert_eq!(part1, None);
+        let part2 = stress_test(
+            |input| {
+                let (puzzle, range) = case(input);
+                brute_force(puzzle.radix, range, |_| true).to_string()
+            },
+            |input| case(input).0.part2(),
+            generate,
+            300,
+        );
+        assert_eq!(part2, None);
+    }
+
+    /// Ranges far beyond `u64`, where the sums only just fit in `u128`, and
+    /// one where they do not
+    #[test]
+    fn test_u128_ranges() -> Result<()> {
+        // Checked one by one in windows around a few huge IDs
+        for start in [
+            10u128.pow(21) - 500,
+            12_345_678_912_345_678_912_345,
+            10u128.pow(37),
+        ] {
+            let range = (start, start + 2000);
+            let puzzle = Puzzle {
+                ranges: std::iter::once(range).collect(),
+                radix: 10,
+            };
+            assert_eq!(
+                puzzle.part1(),
+                brute_force(10, range, |repeat| repeat == 2).to_string()
+            );
+            assert_eq!(puzzle.part2(), brute_force(10, range, |_| true).to_string());
+        }
+        let puzzle = Puzzle::from_input("1-340282366920938463463374607431768211455")?;
+        assert_eq!(
+            puzzle.total(Pattern::Twice).map_err(|e| e.to_string()),
+            Err(
+                "Sum of invalid IDs in 1-340282366920938463463374607431768211455 overflows u128"
+                    .to_string()
+            )
+        );
         Ok(())
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "4174379265");
+    fn test_stream() -> Result<()> {
+        let input = read_file(Puzzle::DAY, true)?;
+        let puzzle = Puzzle::from_input(&input)?;
+        // A tiny buffer makes ranges span several reads
+        let padded = format!("\n\n{input}");
+        let reader = BufReader::with_capacity(3, padded.as_bytes());
+        let (radix, ranges) = Puzzle::stream_ranges(reader)?;
+        let sums =
+            Puzzle::stream_sums(ranges, radix, Pattern::Repeated).collect::<Result<Vec<_>>>()?;
+        assert_eq!(sums.len(), input.split(',').count());
+        assert_eq!(sums.iter().sum::<u128>().to_string(), puzzle.part2());
+        // Errors surface at the range that caused them, after the valid ones
+        let (_, ranges) = Puzzle::stream_ranges(&b"radix: 16\nb-16,5-1,5f-73"[..])?;
+        let sums = Puzzle::stream_sums(ranges, 16, Pattern::Twice).collect::<Vec<_>>();
+        assert_eq!(sums.len(), 3);
+        assert_eq!(sums[0].as_ref().ok(), Some(&0x11));
+        assert!(sums[1].is_err());
         Ok(())
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        Ok(Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)?)
     }
 }
@@ -1,22 +1,99 @@
-use anyhow::Result;
-use ndarray::{parallel::prelude::*, prelude::*};
-use rayon::prelude::*;
-use util::{
-    Solution,
-    reader::{parse_char_grid, read_file},
-};
+use std::env::{self, VarError};
+
+use anyhow::{Result, bail};
+use ndarray::prelude::*;
+use serde::Deserialize;
+use util::{config, dp::knapsack, math::U256, prelude::*};
+
+/// The largest number formed by `length` of the decimal `digits`, kept in
+/// their original order, or `None` if there are fewer than `length` digits.
+///
+/// This is a 0/1 knapsack where every digit weighs 1 and taking it appends
+/// it to the number. Numbers of up to [`Puzzle::MAX_LENGTH`] digits fit in
+/// the `u128` result.
+#[must_use]
+pub fn largest_subsequence(digits: &[u32], length: usize) -> Option<u128> {
+    let append = |&number: &u128, &digit: &u32| number * 10 + u128::from(digit);
+    knapsack(digits, length, 0, |_| 1, append, u128::max)
+        .pop()
+        .flatten()
+}
+
+/// Tunables of the day from `config/day03.toml`
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// Number of digits to select from each bank in part 2
+    length: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            length: Puzzle::DEFAULT_LENGTH,
+        }
+    }
+}
 
 struct Puzzle {
     banks: Array2<u32>,
+    /// Number of digits to select from each bank in part 2
+    length: usize,
 }
 
 impl Puzzle {
+    /// Environment variable overriding the selection length of part 2 on the
+    /// real input
+    const ENV: &str = "AOC_DAY03_LENGTH";
+
+    /// Selection length of part 2 in the puzzle itself, unless `length` is
+    /// in the configuration of the day
+    const DEFAULT_LENGTH: usize = 12;
+
+    /// Longest selection whose number still fits in a `u128`
+    const MAX_LENGTH: usize = 38;
+
     fn new(example: bool) -> Result<Self> {
         let banks = parse_char_grid(read_file(Self::DAY, example)?, |c| {
             c.to_digit(10)
                 .ok_or_else(|| anyhow::anyhow!("Failed to parse {c} as digit"))
         })?;
-        Ok(Self { banks })
+        // The example has its answer for the length of the puzzle only
+        let length = if example {
+            Self::DEFAULT_LENGTH
+        } else {
+            Self::configured_length()?
+        };
+        Self::from_banks(banks, length)
+    }
+
+    fn from_banks(banks: Array2<u32>, length: usize) -> Result<Self> {
+        if !(1..=Self::MAX_LENGTH).contains(&length) {
+            bail!(
+                "Selection length must be between 1 and {}",
+                Self::MAX_LENGTH
+            );
+        }
+        if banks.ncols() < length.max(2) {
+            bail!(
+                "Banks have {} digits, fewer than the {} to select",
+                banks.ncols(),
+                length.max(2)
+            );
+        }
+        Ok(Self { banks, length })
+    }
+
+    /// Selection length of part 2 from [`Self::ENV`], which wins over the
+    /// configuration of the day
+    fn configured_length() -> Result<usize> {
+        match env::var(Self::ENV) {
+            Err(VarError::NotPresent) => Ok(config::load::<Config>(Self::DAY)?.length),
+            Ok(length) => length
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid {}={length}: {e}", Self::ENV)),
+            Err(e) => bail!("Invalid {}: {e}", Self::ENV),
+        }
     }
 }
 
@@ -33,7 +110,7 @@ impl Solution for Puzzle {
     fn part1(&self) -> String {
         self.banks
             .outer_iter()
-            .par_bridge()
+            .maybe_par_bridge()
             .map(|bank| {
                 // Find the largest digit in the bank[:-1]
                 let (idx, &first_digit) = bank
@@ -59,30 +136,40 @@ impl Solution for Puzzle {
     }
 
     /// For each bank, use dynamic programming to find the largest 12-digit
-    /// number that can be formed by the digits in the bank while maintaining
-    /// their order.
+    /// (or [`Self::ENV`]) number that can be formed by the digits in the bank
+    /// while maintaining their order, see [`largest_subsequence`].
+    ///
+    /// Numbers beyond 19 digits overflow `u64`, so each one is a `u128`, and
+    /// the total is a `U256` so that summing many of them can't overflow.
     fn part2(&self) -> String {
         self.banks
             .outer_iter()
-            .par_bridge()
+            .maybe_par_bridge()
             .map(|bank| {
-                bank.iter().fold(vec![0u64; 13], |mut dp, &digit| {
-                    for len in (1..=12).rev() {
-                        // At each position, either take the digit or not
-                        dp[len] = dp[len].max(dp[len - 1] * 10 + u64::from(digit));
-                    }
-                    dp
-                })[12]
+                let number = largest_subsequence(&bank.to_vec(), self.length)
+                    .unwrap_or_else(|| unreachable!("Banks are checked to be long enough"));
+                U256::from(number)
             })
-            .sum::<u64>()
+            .sum::<U256>()
             .to_string()
     }
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
@@ -91,26 +178,73 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use util::{rng::Rng, testing::assert_answer};
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "357");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    #[test]
+    fn test_largest_subsequence() {
+        let mut rng = Rng::new(3);
+        for _ in 0..200 {
+            let digits = (0..rng.below(10))
+                .map(|_| u32::try_from(rng.below(10)).unwrap_or_default())
+                .collect::<Vec<_>>();
+            for length in 0..=digits.len() + 1 {
+                // Every choice of `length` positions, as a bitmask
+                let brute = (0u32..1 << digits.len())
+                    .filter(|mask| mask.count_ones() as usize == length)
+                    .map(|mask| {
+                        digits
+                            .iter()
+                            .enumerate()
+                            .filter(|&(i, _)| mask >> i & 1 == 1)
+                            .fold(0, |n, (_, &d)| n * 10 + u128::from(d))
+                    })
+                    .max();
+                assert_eq!(largest_subsequence(&digits, length), brute, "{digits:?}");
+            }
+        }
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "3121910778619");
+    fn test_lengths() -> Result<()> {
+        let banks = Puzzle::new(true)?.banks;
+        // Two digits is part 1 again
+        let puzzle = Puzzle::from_banks(banks.clone(), 2)?;
+        assert_eq!(puzzle.part2(), puzzle.part1());
+        // Selecting every digit gives the banks back
+        assert_eq!(
+            Puzzle::from_banks(banks.clone(), 15)?.part2(),
+            (987_654_321_111_111u64
+                + 811_111_111_111_119
+                + 234_234_234_234_278
+                + 818_181_911_112_111)
+                .to_string()
+        );
+        assert!(Puzzle::from_banks(banks, 16).is_err());
+        // 38 digits from a 40-digit bank go past u64, and four of them past u128
+        let long = Array2::from_shape_fn((4, 40), |(_, c)| if c < 2 { 0 } else { 9 });
+        let puzzle = Puzzle::from_banks(long.clone(), 38)?;
+        assert_eq!(
+            puzzle.part2(),
+            (U256::from(10u128.pow(38) - 1) * U256::from(4u64)).to_string()
+        );
+        assert!(Puzzle::from_banks(long, 39).is_err());
         Ok(())
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        Ok(Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)?)
     }
 }
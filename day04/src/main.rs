@@ -1,47 +1,74 @@
-use anyhow::Result;
-use ndarray::{Zip, parallel::prelude::*, prelude::*};
+use std::{fmt::Write as _, time::Duration};
+
+use anyhow::{Result, bail};
+use ndarray::prelude::*;
 use util::{
-    Solution,
-    reader::{parse_char_grid, read_file},
+    grid::{Automaton, kernel8, population},
+    prelude::*,
+    term::{Animation, Color, FrameSink, paint},
 };
 
+/// Empty cells are 0 and items 1
+static CELLS: CharMap<u8> = char_map!('.' => 0, '@' => 1);
+
 struct Puzzle {
     grid: Array2<u8>,
 }
 
 impl Puzzle {
     fn new(example: bool) -> Result<Self> {
-        let grid = parse_char_grid(read_file(Self::DAY, example)?, |c| match c {
-            '.' => Ok(0),
-            '@' => Ok(1),
-            _ => anyhow::bail!("Invalid character in grid: {c}"),
-        })?;
+        Self::from_input(&read_file(Self::DAY, example)?)
+    }
+
+    fn from_input(input: &str) -> Result<Self> {
+        let grid = parse_byte_grid(input, &CELLS)?;
         Ok(Self { grid })
     }
 
-    /// Find removable items in the grid. An item is removable if it is
-    /// non-empty and has less than 4 non-empty neighbors in the 8 directions.
-    /// Returns a boolean grid indicating which items are removable. This is a
-    /// helper function used in both parts.
-    fn find_removable(grid: &Array2<u8>) -> Array2<bool> {
-        let mut extended = Array2::from_elem([grid.nrows() + 2, grid.ncols() + 2], 0);
-        extended
-            .slice_mut(s![1..=grid.nrows(), 1..=grid.ncols()])
-            .assign(grid);
-        let count = extended
-            .windows([grid.nrows(), grid.ncols()])
-            .into_iter()
-            .enumerate()
-            // Only consider neighborhoods in 8 directions
-            .filter_map(|(idx, window)| (idx != 4).then_some(window))
-            .fold(Array2::zeros([grid.nrows(), grid.ncols()]), |mut acc, x| {
-                acc += &x;
-                acc
-            });
-
-        Zip::from(&count)
-            .and(grid)
-            .par_map_collect(|&cnt, &v| cnt < 4 && v > 0)
+    /// The removal rule: an item stays only if it has at least 4 non-empty
+    /// neighbors in the 8 directions, and nothing ever appears.
+    fn rule() -> Automaton {
+        Automaton::new(kernel8()).survive(4..=8)
+    }
+
+    /// Replay the removal round by round, as part 2 removes the items, with
+    /// one frame per round until nothing more goes.
+    ///
+    /// Items still there are drawn in cyan and the ones removed in the round
+    /// in red, so that the red items of the first frame are part 1.
+    fn animate(&self, animation: &mut impl FrameSink) -> Result<()> {
+        let rule = Self::rule();
+        let mut grid = self.grid.clone();
+        let (mut round, mut removed) = (0, 0);
+        loop {
+            let next = rule.step(&grid);
+            let gone = population(&grid) - population(&next);
+            if gone == 0 {
+                return Ok(());
+            }
+            round += 1;
+            removed += gone;
+            let mut frame = String::new();
+            for ((i, j), &before) in grid.indexed_iter() {
+                let cell = match (before > 0, next[[i, j]] > 0) {
+                    (true, true) => paint('@', Color::Cyan),
+                    (true, false) => paint('@', Color::Red),
+                    _ => paint('.', Color::Gray),
+                };
+                frame.push_str(&cell);
+                if j + 1 == grid.ncols() {
+                    frame.push('\n');
+                }
+            }
+            writeln!(
+                frame,
+                "Round {round}: {} removed, {} in total (part 2)",
+                paint(gone, Color::Red),
+                paint(removed, Color::Cyan),
+            )?;
+            animation.frame(&frame)?;
+            grid = next;
+        }
     }
 }
 
@@ -53,69 +80,173 @@ impl Solution for Puzzle {
     }
 
     /// Count the number of removable items in the initial grid. Nothing fancy,
-    /// just simulate the removal once.
+    /// just simulate the removal once and see how many items went.
     fn part1(&self) -> String {
-        Self::find_removable(&self.grid)
-            .par_iter()
-            .filter(|&&removable| removable)
-            .count()
-            .to_string()
+        let remaining = population(&Self::rule().step(&self.grid));
+        (population(&self.grid) - remaining).to_string()
     }
 
     /// Repeatedly remove removable items until no more can be removed. Count
-    /// the total number of removed items. Also straightforward simulation.
+    /// the total number of removed items.
+    ///
+    /// Rather than recomputing the neighbor counts of the whole grid every
+    /// round, [`Automaton::erode`] counts them once and erodes incrementally,
+    /// as removing an item only lowers the counts of its 8 neighbors.
     fn part2(&self) -> String {
-        let mut grid = self.grid.clone();
-        let mut count = 0;
-        loop {
-            let removable = Self::find_removable(&grid);
-            let num_removable = removable.par_iter().filter(|&&r| r).count();
-            if num_removable == 0 {
-                break;
-            }
-            count += num_removable;
-            Zip::from(&mut grid).and(&removable).par_for_each(|g, &r| {
-                if r {
-                    *g = 0;
-                }
-            });
-        }
-        count.to_string()
+        let remaining = population(&Self::rule().erode(&self.grid));
+        (population(&self.grid) - remaining).to_string()
     }
 }
 
+/// Run with `--animate [DELAY_MS]` to replay the removal in the terminal
+/// first, waiting 200ms between rounds unless given another delay, or with the
+/// `gif` feature and `--gif [DELAY_MS]` to save the replay as an animated GIF.
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let args = diagnostics::args();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        None => {}
+        Some("--animate") => {
+            let delay = args.next().map(|ms| ms.parse()).transpose()?.unwrap_or(200);
+            puzzle.animate(&mut Animation::stdout(Duration::from_millis(delay)))?;
+        }
+        #[cfg(feature = "gif")]
+        Some("--gif") => {
+            let delay = args.next().map(|ms| ms.parse()).transpose()?.unwrap_or(200);
+            let mut gif = util::term::Gif::new(delay, 4);
+            puzzle.animate(&mut gif)?;
+            println!("Animation written to {}", gif.save(Puzzle::DAY)?.display());
+        }
+        Some(other) => {
+            bail!("Unknown argument {other}, expected --animate [DELAY_MS] or --gif [DELAY_MS]")
+        }
+    }
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
-
-    use util::{Benchmark, Serializable};
+    use proptest::prelude::*;
+    use util::{
+        generate,
+        rng::Rng,
+        strategy::{corrupted, grid},
+        testing::assert_answer,
+        timer::measure_many,
+    };
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "13");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    #[test]
+    fn test_animate() -> Result<()> {
         let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "43");
+        let mut animation = Animation::new(Vec::new(), Duration::ZERO);
+        puzzle.animate(&mut animation)?;
+        let out = String::from_utf8(animation.into_inner())?;
+        // The first round removes part 1, and the last one ends at part 2
+        let rounds = out
+            .lines()
+            .filter(|line| line.starts_with("Round"))
+            .collect::<Vec<_>>();
+        assert!(rounds[0].contains(&paint(puzzle.part1(), Color::Red)));
+        let last = rounds.last().copied().unwrap_or_default();
+        assert!(last.contains(&paint(puzzle.part2(), Color::Cyan)), "{last}");
         Ok(())
     }
 
+    /// Part 2 as it was before the incremental erosion, recomputing every
+    /// neighbor count each round
+    fn erode_by_rounds(grid: &Array2<u8>) -> usize {
+        let (settled, _) = Puzzle::rule().settle(grid);
+        population(grid) - population(&settled)
+    }
+
+    #[test]
+    fn test_matches_rounds() {
+        for seed in 0..100 {
+            let grid = generate::grid(&mut Rng::new(seed), 1..=12, 1..=12, &[0, 1, 1, 1]);
+            let puzzle = Puzzle { grid };
+            assert_eq!(
+                puzzle.part2(),
+                erode_by_rounds(&puzzle.grid).to_string(),
+                "{:?}",
+                puzzle.grid
+            );
+        }
+        // At two thirds full, like the real input, erosion keeps going for
+        // dozens of rounds
+        let grid = generate::grid(&mut Rng::new(4), 300..=300, 300..=300, &[0, 1, 1]);
+        let puzzle = Puzzle { grid };
+        assert_eq!(puzzle.part2(), erode_by_rounds(&puzzle.grid).to_string());
+    }
+
+    proptest! {
+        /// Grids in the input format, about as full as the real one, parse to
+        /// their shape and erode the same way
+        #[test]
+        fn test_generated_matches_rounds(input in grid(1..=12, 1..=12, &['.', '@', '@'])) {
+            let puzzle = Puzzle::from_input(&input)
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let cols = input.find('\n').unwrap_or_default();
+            prop_assert_eq!(puzzle.grid.dim(), (input.lines().count(), cols));
+            prop_assert_eq!(puzzle.part2(), erode_by_rounds(&puzzle.grid).to_string());
+        }
+
+        #[test]
+        fn test_parse_never_panics(
+            input in corrupted(grid(1..=4, 1..=4, &['.', '@']), 3, &['.', '@', '\n', 'x']),
+        ) {
+            let _ = Puzzle::from_input(&input);
+        }
+    }
+
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        let limit = Duration::from_secs(1);
+        let mut results = Puzzle::bench_all(limit).to_vec();
+        // Part 2 as it was before the incremental erosion, on the same input
+        let puzzle = Puzzle::new(false)?;
+        results.push(measure_many("Part 2 (by rounds)", limit, || {
+            erode_by_rounds(&puzzle.grid)
+        }));
+        // Parsing with the lookup table against a closure matching on each
+        // character, which is what the table replaced
+        let input = read_file(Puzzle::DAY, false)?;
+        results.push(measure_many("Parse (lookup table)", limit, || {
+            parse_byte_grid(&input, &CELLS)
+        }));
+        results.push(measure_many("Parse (match closure)", limit, || {
+            parse_char_grid(&input, |c| match c {
+                '.' => Ok(0),
+                '@' => Ok(1),
+                _ => Err("Invalid character"),
+            })
+        }));
+        Ok(results.to_csv(Puzzle::DAY)?)
     }
 }
@@ -1,25 +1,48 @@
 use anyhow::Result;
-use rayon::prelude::*;
-use util::{
-    Solution,
-    reader::{parse_lines, read_file},
-};
+use util::{prelude::*, ranges::IntervalSet};
 
 type ID = u64;
 type Range = (ID, ID);
 
+/// How part 1 matches the sorted IDs against the merged ranges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Strategy {
+    /// Binary search the IDs for the ends of each range, `O(M log N)`
+    RangesOverIds,
+    /// Binary search the ranges for each ID, `O(N log M)`
+    IdsOverRanges,
+}
+
+impl Strategy {
+    /// Pick the strategy with the smaller estimated cost for `ranges` ranges
+    /// (M) and `ids` IDs (N). Each range takes two searches, one per end,
+    /// which puts the crossover between M = N / 10 and M = N as in the
+    /// benchmark.
+    fn choose(ranges: usize, ids: usize) -> Self {
+        let searches = |n: usize, len: usize| n * (len + 1).ilog2().max(1) as usize;
+        if 2 * searches(ranges, ids) <= searches(ids, ranges) {
+            Self::RangesOverIds
+        } else {
+            Self::IdsOverRanges
+        }
+    }
+}
+
 struct Puzzle {
-    ranges: Vec<Range>,
+    ranges: IntervalSet<ID>,
     ids: Vec<ID>,
 }
 
 impl Puzzle {
     fn new(example: bool) -> Result<Self> {
-        let content = read_file(Self::DAY, example)?;
+        Self::from_input(&read_file(Self::DAY, example)?)
+    }
+
+    fn from_input(content: &str) -> Result<Self> {
         let (ranges, ids) = content
             .split_once("\n\n")
             .ok_or_else(|| anyhow::anyhow!("Expected header and body separated by a blank line"))?;
-        let mut ranges = parse_lines(ranges.trim(), |line| {
+        let ranges = parse_lines(ranges.trim(), |line| {
             let (start, end) = line
                 .split_once('-')
                 .ok_or_else(|| anyhow::anyhow!("Invalid range format in header: {line}"))?;
@@ -27,68 +50,48 @@ impl Puzzle {
             let end: ID = end.parse()?;
             anyhow::Ok((start, end))
         })?;
-        let mut ids = parse_lines(ids.trim(), |line| {
+        let ids = parse_lines(ids.trim(), |line| {
             let id: ID = line.trim().parse()?;
             anyhow::Ok(id)
         })?;
-        // Sort ranges and ids for easier processing later
-        ranges.sort_unstable();
+        Ok(Self::from_parts(ranges, ids))
+    }
+
+    fn from_parts(ranges: impl IntoIterator<Item = Range>, mut ids: Vec<ID>) -> Self {
+        // Sort ids for easier processing later
         ids.sort_unstable();
         // Merge overlapping or contiguous ranges
-        ranges = ranges
-            .into_iter()
-            .fold(vec![], |mut acc: Vec<Range>, curr: Range| {
-                if let Some(last) = acc.last_mut()
-                    && curr.0 <= last.1 + 1
-                {
-                    last.1 = last.1.max(curr.1);
-                    return acc;
-                }
-                acc.push(curr);
-                acc
-            });
-        Ok(Self { ranges, ids })
+        let ranges = ranges.into_iter().collect();
+        Self { ranges, ids }
     }
 
-    /// Binary search for a range in the sorted list of IDs.
-    fn binary_search_ids(&self, range: Range) -> Option<(usize, usize)> {
+    /// Binary search for a range in the sorted list of IDs, returning the
+    /// indices of the first ID in it and of the first ID after it.
+    fn binary_search_ids(&self, range: Range) -> (usize, usize) {
         let (start, end) = range;
+        let left_idx = self.ids.partition_point(|&id| id < start);
+        let right_idx = left_idx + self.ids[left_idx..].partition_point(|&id| id <= end);
+        (left_idx, right_idx)
+    }
 
-        let mut left_idx = None;
-        let mut low = 0;
-        let mut high = self.ids.len() - 1;
-        while low <= high {
-            let mid = usize::midpoint(low, high);
-            if self.ids[mid] >= start {
-                left_idx = Some(mid);
-                high = mid - 1;
-            } else {
-                low = mid + 1;
-            }
-        }
-        let Some(left_idx) = left_idx else {
-            // No IDs >= start, so no IDs in range
-            return None;
-        };
-
-        let mut right_idx = None;
-        let mut low = left_idx;
-        let mut high = self.ids.len() - 1;
-        while low <= high {
-            let mid = usize::midpoint(low, high);
-            if self.ids[mid] <= end {
-                right_idx = Some(mid);
-                low = mid + 1;
-            } else {
-                high = mid - 1;
-            }
+    /// Count the IDs covered by any range with the given strategy
+    fn count_fresh(&self, strategy: Strategy) -> usize {
+        match strategy {
+            Strategy::RangesOverIds => self
+                .ranges
+                .as_slice()
+                .maybe_par_iter()
+                .map(|&range| {
+                    let (left_idx, right_idx) = self.binary_search_ids(range);
+                    right_idx - left_idx
+                })
+                .sum(),
+            Strategy::IdsOverRanges => self
+                .ids
+                .maybe_par_iter()
+                .filter(|&&id| self.ranges.contains(id))
+                .count(),
         }
-        let Some(right_idx) = right_idx else {
-            // No IDs <= end, so no IDs in range
-            return None;
-        };
-
-        Some((left_idx, right_idx))
     }
 }
 
@@ -103,21 +106,13 @@ impl Solution for Puzzle {
     /// 1. Iterate through all IDs and check if they are in any range
     /// 2. Iterate through ranges and count how many IDs fall into them
     ///
-    /// Given M ranges and N IDs, the first approach is O(M log N) while the
-    /// second is O(N log M). Since M is expected to be much smaller than N,
-    /// like a magnitude smaller, we choose the second approach.
+    /// Given M ranges and N IDs, the first approach is O(N log M) while the
+    /// second is O(M log N). M is expected to be much smaller than N, like a
+    /// magnitude smaller, which favors the second approach, but we measure
+    /// both and let [`Strategy::choose`] pick at runtime.
     fn part1(&self) -> String {
-        self.ranges
-            .par_iter()
-            .map(|&range| {
-                let (start, end) = range;
-                match self.binary_search_ids((start, end)) {
-                    Some((left_idx, right_idx)) => right_idx - left_idx + 1,
-                    None => 0,
-                }
-            })
-            .sum::<usize>()
-            .to_string()
+        let strategy = Strategy::choose(self.ranges.len(), self.ids.len());
+        self.count_fresh(strategy).to_string()
     }
 
     /// For part 2, we simply sum up the sizes of all ranges.
@@ -125,18 +120,25 @@ impl Solution for Puzzle {
     /// I don't know why it is actually easier than part 1...
     /// But well, let's just go with it.
     fn part2(&self) -> String {
-        self.ranges
-            .par_iter()
-            .map(|&(start, end)| end - start + 1)
-            .sum::<ID>()
-            .to_string()
+        self.ranges.total_len().to_string()
     }
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
@@ -145,26 +147,79 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use util::{
+        rng::Rng,
+        testing::assert_answer,
+        timer::{BenchmarkResult, measure_many},
+    };
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "3");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    /// `ranges` disjoint ranges with gaps in between, and `ids` IDs spread
+    /// over the same span
+    fn random_puzzle(ranges: usize, ids: usize, seed: u64) -> Puzzle {
+        let mut rng = Rng::new(seed);
+        let mut start = 0;
+        let ranges = (0..ranges)
+            .map(|_| {
+                let range = (start, start + rng.next_u64() % 1000);
+                start = range.1 + 2 + rng.next_u64() % 1000;
+                range
+            })
+            .collect::<Vec<_>>();
+        let ids = (0..ids).map(|_| rng.next_u64() % (start + 1)).collect();
+        Puzzle::from_parts(ranges, ids)
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
+    fn test_strategies_agree() -> Result<()> {
         let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "14");
+        assert_eq!(puzzle.count_fresh(Strategy::RangesOverIds), 3);
+        assert_eq!(puzzle.count_fresh(Strategy::IdsOverRanges), 3);
+        for (seed, (ranges, ids)) in [(0, 0), (0, 5), (5, 0), (1, 1), (10, 100), (100, 10)]
+            .into_iter()
+            .enumerate()
+        {
+            let puzzle = random_puzzle(ranges, ids, seed as u64);
+            let expected = puzzle
+                .ids
+                .iter()
+                .filter(|&&id| puzzle.ranges.iter().any(|(s, e)| (s..=e).contains(&id)))
+                .count();
+            assert_eq!(puzzle.count_fresh(Strategy::RangesOverIds), expected);
+            assert_eq!(puzzle.count_fresh(Strategy::IdsOverRanges), expected);
+        }
+        assert_eq!(Strategy::choose(10, 1000), Strategy::RangesOverIds);
+        assert_eq!(Strategy::choose(1000, 10), Strategy::IdsOverRanges);
+        assert_eq!(Strategy::choose(10_000, 10_000), Strategy::IdsOverRanges);
         Ok(())
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        let mut results = Puzzle::bench_all(Duration::from_secs(1)).to_vec();
+        // Both strategies with 10000 IDs and more and more ranges, so that the
+        // crossover shows up in the CSV
+        let limit = Duration::from_millis(200);
+        for ranges in [10, 100, 1_000, 10_000, 100_000] {
+            let puzzle = random_puzzle(ranges, 10_000, 5);
+            for strategy in [Strategy::RangesOverIds, Strategy::IdsOverRanges] {
+                let name = format!("Part 1 ({strategy:?}, M={ranges}, N=10000)");
+                let result: BenchmarkResult =
+                    measure_many(name, limit, || puzzle.count_fresh(strategy));
+                results.push(result);
+            }
+        }
+        Ok(results.to_csv(Puzzle::DAY)?)
     }
 }
@@ -1,9 +1,6 @@
 use anyhow::Result;
-use ndarray::{Zip, parallel::prelude::*, prelude::*};
-use util::{
-    Solution,
-    reader::{parse_fixed_width_grid, parse_whitespace_separated, read_file},
-};
+use ndarray::prelude::*;
+use util::prelude::*;
 
 #[derive(Clone, Copy)]
 enum Operator {
@@ -11,25 +8,43 @@ enum Operator {
     Multiply,
 }
 
-#[derive(Clone, Copy)]
-enum AlignedValue {
-    Left(u64),
-    Right(u64),
+/// A number as written in its fixed-width column of the worksheet
+#[derive(Clone)]
+struct Cell {
+    /// The number read left to right, for part 1
+    value: u64,
+    /// Digit at each character position of the column, `None` for padding,
+    /// for part 2
+    digits: Box<[Option<u8>]>,
 }
 
 struct Puzzle {
-    numbers: Array2<AlignedValue>,
+    numbers: Array2<Cell>,
     operators: Array1<Operator>,
 }
 
 impl Puzzle {
-    /// Parse the input into a grid of aligned numbers and a list of operators.
+    fn parse_cell(s: &str) -> Result<Cell> {
+        let value = s.trim().parse()?;
+        // Digits are ASCII, so every byte is a character position
+        let digits = s
+            .bytes()
+            .map(|b| b.is_ascii_digit().then(|| b - b'0'))
+            .collect();
+        Ok(Cell { value, digits })
+    }
+
+    fn new(example: bool) -> Result<Self> {
+        Self::from_input(&read_file(Self::DAY, example)?)
+    }
+
+    /// Parse the input into a grid of numbers and a list of operators.
     ///
     /// This does the heavy lifting of parsing fixed-width columns where each
-    /// column may have numbers aligned either to the left or right. This
-    /// alignment affects how we interpret the digits in part 2.
-    fn new(example: bool) -> Result<Self> {
-        let content = read_file(Self::DAY, example)?;
+    /// column may have numbers aligned either to the left or right. Each cell
+    /// keeps the digits at every position of its column, as the alignment
+    /// affects how we interpret them in part 2.
+    fn from_input(content: &str) -> Result<Self> {
         let num_lines = content.lines().count();
         // Only the last line contains operators, the rest are numbers
         let operator_line = content
@@ -48,93 +63,45 @@ impl Puzzle {
             _ => anyhow::bail!("Unknown operator: {s}"),
         })?
         .into();
-        let numbers = parse_fixed_width_grid(
+        let numbers = parse_padded_fixed_width_grid(
             content
                 .lines()
                 .take(num_lines - 1)
                 .collect::<Vec<&str>>()
                 .join("\n"),
             column_widths,
-            |s| match s {
-                v if v.starts_with(' ') => {
-                    let num: u64 = v.trim().parse()?;
-                    anyhow::Ok(AlignedValue::Right(num))
-                }
-                v => {
-                    let num: u64 = v.trim().parse()?;
-                    anyhow::Ok(AlignedValue::Left(num))
-                }
-            },
+            Self::parse_cell,
         )?;
         Ok(Self { numbers, operators })
     }
 
-    fn row_compute(numbers: &ArrayView1<AlignedValue>, op: Operator) -> u64 {
+    fn apply(values: impl Iterator<Item = u64>, op: Operator) -> u64 {
         match op {
-            Operator::Add => numbers
-                .iter()
-                .map(|&v| match v {
-                    AlignedValue::Left(n) | AlignedValue::Right(n) => n,
-                })
-                .sum::<u64>(),
-            Operator::Multiply => numbers
-                .iter()
-                .map(|&v| match v {
-                    AlignedValue::Left(n) | AlignedValue::Right(n) => n,
-                })
-                .product(),
+            Operator::Add => values.sum(),
+            Operator::Multiply => values.product(),
         }
     }
 
-    fn column_compute(numbers: &ArrayView1<AlignedValue>, op: Operator) -> u64 {
-        // First, get the maximum number of digits in any number as full column width
-        let max_digit = numbers
+    fn row_compute(numbers: &ArrayView1<Cell>, op: Operator) -> u64 {
+        Self::apply(numbers.iter().map(|cell| cell.value), op)
+    }
+
+    /// Read the numbers down each character position of the column instead,
+    /// straight from the digits captured at parse time. Positions with no
+    /// digit in any row, like the separator, give no number.
+    fn column_compute(numbers: &ArrayView1<Cell>, op: Operator) -> u64 {
+        let width = numbers
             .iter()
-            .map(|&v| match v {
-                AlignedValue::Left(n) | AlignedValue::Right(n) => n,
-            })
+            .map(|cell| cell.digits.len())
             .max()
-            .unwrap_or(1)
-            .ilog10()
-            + 1;
-        let values = numbers
-            .iter()
-            // For each aligned number, extract its digits, then pad with None to align to
-            // `max_digit`. For example, for `max_digit`=4:
-            // - `AlignedValue::Left(23)` -> `[None, None, Some(2), Some(3)]`
-            // - `AlignedValue::Right(45)` -> `[Some(4), Some(5), None, None]`
-            // We cannot simply use 0 as padding because that would affect the value.
-            .map(|&v| match v {
-                AlignedValue::Left(n) => {
-                    let digits = n.ilog10() + 1;
-                    std::iter::repeat_n(None, (max_digit - digits) as usize)
-                        .chain((0..digits).map(|d| Some(n / 10u64.pow(d) % 10)))
-                        .rev()
-                        .collect::<Vec<_>>()
-                }
-                AlignedValue::Right(n) => {
-                    let digits = n.ilog10() + 1;
-                    std::iter::repeat_n(None, (max_digit - digits) as usize)
-                        .chain((0..digits).map(|d| Some(n / 10u64.pow(d) % 10)).rev())
-                        .collect::<Vec<_>>()
-                }
-            })
-            // Then we can interpret the columns as numbers by folding the digits.
-            // `None` are ignored.
-            .fold(
-                std::iter::repeat_n(0, max_digit as usize).collect::<Vec<_>>(),
-                |acc, digits| {
-                    acc.into_iter()
-                        .zip(digits)
-                        .map(|(n, digit)| digit.map_or(n, |d| n * 10 + d))
-                        .collect()
-                },
-            );
-
-        match op {
-            Operator::Add => values.into_iter().sum::<u64>(),
-            Operator::Multiply => values.into_iter().product(),
-        }
+            .unwrap_or(0);
+        let values = (0..width).filter_map(|pos| {
+            numbers
+                .iter()
+                .filter_map(|cell| cell.digits.get(pos).copied().flatten())
+                .fold(None, |n, d| Some(n.unwrap_or(0) * 10 + u64::from(d)))
+        });
+        Self::apply(values, op)
     }
 }
 
@@ -149,10 +116,9 @@ impl Solution for Puzzle {
     ///
     /// Pretty straightforward.
     fn part1(&self) -> String {
-        Zip::from(self.numbers.lanes(Axis(0)))
-            .and(&self.operators)
-            .into_par_iter()
-            .map(|(lane, &op)| Self::row_compute(&lane, op))
+        (0..self.operators.len())
+            .maybe_into_par_iter()
+            .map(|i| Self::row_compute(&self.numbers.column(i), self.operators[i]))
             .sum::<u64>()
             .to_string()
     }
@@ -162,47 +128,149 @@ impl Solution for Puzzle {
     ///
     /// This is so f**king tedious but straightforward.
     fn part2(&self) -> String {
-        Zip::from(self.numbers.lanes(Axis(0)))
-            .and(&self.operators)
-            .into_par_iter()
-            .map(|(lane, &op)| Self::column_compute(&lane, op))
+        (0..self.operators.len())
+            .maybe_into_par_iter()
+            .map(|i| Self::column_compute(&self.numbers.column(i), self.operators[i]))
             .sum::<u64>()
             .to_string()
     }
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{fmt::Write, time::Duration};
 
-    use util::{Benchmark, Serializable};
+    use util::{
+        rng::Rng,
+        testing::{assert_answer, expected},
+        timer::measure_many,
+    };
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "4277556");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "3263827");
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    /// A worksheet of `problems` problems with `rows` numbers each, of up to
+    /// 4 digits, aligned to the left or right of their column at random
+    fn worksheet(problems: usize, rows: usize, seed: u64) -> Result<String> {
+        let mut rng = Rng::new(seed);
+        let mut lines = vec![String::new(); rows + 1];
+        for _ in 0..problems {
+            let width = 1 + rng.below(4);
+            let (left, full) = (rng.chance(0.5), rng.below(rows));
+            for (row, line) in lines[..rows].iter_mut().enumerate() {
+                // One number fills the column, so that it is exactly `width`
+                let len = if row == full {
+                    width
+                } else {
+                    1 + rng.below(width)
+                };
+                let number = (0..len)
+                    .map(|i| {
+                        // No leading zeros
+                        let lowest = usize::from(i == 0);
+                        char::from(b"0123456789"[lowest + rng.below(10 - lowest)])
+                    })
+                    .collect::<String>();
+                if left {
+                    write!(line, "{number:<width$} ")?;
+                } else {
+                    write!(line, "{number:>width$} ")?;
+                }
+            }
+            let op = if rng.chance(0.5) { '+' } else { '*' };
+            write!(lines[rows], "{op:<width$} ")?;
+        }
+        Ok(lines
+            .iter()
+            .map(|line| line.trim_end_matches(' ').to_string() + "\n")
+            .collect())
+    }
+
+    /// Part 2 read straight off the text: every character column is a
+    /// number, and blank ones separate the problems
+    fn transposed(input: &str) -> u64 {
+        let lines = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+        let (numbers, operators) = lines.split_at(lines.len() - 1);
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let mut total = 0;
+        let mut problem = (Operator::Add, Vec::new());
+        for col in 0..=width {
+            let digits = numbers
+                .iter()
+                .filter_map(|line| line.get(col).filter(|b| b.is_ascii_digit()))
+                .fold(None, |n: Option<u64>, &b| {
+                    Some(n.unwrap_or(0) * 10 + u64::from(b - b'0'))
+                });
+            match operators[0].get(col) {
+                Some(b'+') => problem.0 = Operator::Add,
+                Some(b'*') => problem.0 = Operator::Multiply,
+                _ => {}
+            }
+            if let Some(n) = digits {
+                problem.1.push(n);
+            } else {
+                let (op, values) = std::mem::replace(&mut problem, (Operator::Add, Vec::new()));
+                total += Puzzle::apply(values.into_iter(), op);
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_matches_transposed() -> Result<()> {
+        let example = read_file(Puzzle::DAY, true)?;
+        assert_eq!(
+            transposed(&example).to_string(),
+            expected(Puzzle::DAY, "example", 2)
+        );
+        for seed in 0..50 {
+            let input = worksheet(20, 4, seed)?;
+            let puzzle = Puzzle::from_input(&input)?;
+            assert_eq!(puzzle.part2(), transposed(&input).to_string(), "{input}");
+        }
+        let input = worksheet(10_000, 4, 50)?;
+        let puzzle = Puzzle::from_input(&input)?;
+        assert_eq!(puzzle.part2(), transposed(&input).to_string());
         Ok(())
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        let limit = Duration::from_secs(1);
+        let mut results = Puzzle::bench_all(limit).to_vec();
+        // Part 2 read straight off the text, on the same input
+        let input = read_file(Puzzle::DAY, false)?;
+        results.push(measure_many("Part 2 (text columns)", limit, || {
+            transposed(&input)
+        }));
+        Ok(results.to_csv(Puzzle::DAY)?)
     }
 }
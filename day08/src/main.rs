@@ -1,20 +1,19 @@
-use std::{
-    cmp::Reverse,
-    collections::{BTreeMap, BinaryHeap},
-};
+use std::{cmp::Reverse, collections::BinaryHeap};
 
-use anyhow::Result;
-use ndarray::{parallel::prelude::*, prelude::*};
+use anyhow::{Result, bail};
 use util::{
-    Solution,
-    reader::{parse_grid, read_file},
+    prelude::*,
+    spatial::KdTree,
+    writer::{Dot, write_output},
 };
 
 struct DisjointSet {
-    /// Root of each element
+    /// Parent of each element, which is itself for roots
     parent: Vec<usize>,
-    /// Map from root to component size (only for part 1)
-    sizes: BTreeMap<usize, u64>,
+    /// Size of the component of each root, stale for other elements
+    size: Vec<u64>,
+    /// Number of disjoint components
+    components: usize,
 }
 
 impl DisjointSet {
@@ -22,7 +21,8 @@ impl DisjointSet {
     fn new(size: usize) -> Self {
         Self {
             parent: (0..size).collect(),
-            sizes: (0..size).map(|i| (i, 1)).collect::<BTreeMap<_, _>>(),
+            size: vec![1; size],
+            components: size,
         }
     }
 
@@ -42,47 +42,216 @@ impl DisjointSet {
         root
     }
 
-    /// Union the sets containing x and y
-    fn union(&mut self, x: usize, y: usize) {
-        let root_x = self.find(x);
-        let root_y = self.find(y);
-        if root_x != root_y {
-            // Set the parent of root_y to root_x
-            self.parent[root_y] = root_x;
-            // Then update sizes map
-            let size_y = self.sizes.remove(&root_y).unwrap_or(1);
-            self.sizes
-                .entry(root_x)
-                .and_modify(|s| *s += size_y)
-                .or_insert(size_y);
+    /// Union the sets containing x and y, attaching the smaller one below the
+    /// larger one. Returns whether they were separate sets before.
+    fn union(&mut self, x: usize, y: usize) -> bool {
+        let mut root_x = self.find(x);
+        let mut root_y = self.find(y);
+        if root_x == root_y {
+            return false;
+        }
+        if self.size[root_x] < self.size[root_y] {
+            std::mem::swap(&mut root_x, &mut root_y);
         }
+        self.parent[root_y] = root_x;
+        self.size[root_x] += self.size[root_y];
+        self.components -= 1;
+        true
+    }
+
+    /// Number of disjoint components, in `O(1)`
+    const fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Sizes of all components, in no particular order
+    fn sizes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.parent
+            .iter()
+            .enumerate()
+            .filter(|&(i, &parent)| parent == i)
+            .map(|(i, _)| self.size[i])
     }
 }
 
 struct Puzzle {
     /// Maximum number of steps to connect nodes (only for part 1)
     max_steps: usize,
-    /// Coordinates of nodes: [N, 3]
-    nodes: Array2<i64>,
+    /// Coordinates of nodes
+    nodes: Vec<Vec3>,
+    /// Spatial index over the nodes for nearest neighbor queries
+    tree: KdTree,
 }
 
 impl Puzzle {
+    /// Components per chunk of the reduction in part 1, which fixes how the
+    /// sizes are grouped whatever the number of threads
+    const CHUNK: usize = 256;
+
     fn new(example: bool) -> Result<Self> {
         let content = read_file(Self::DAY, example)?.replace(',', " ");
-        let nodes = parse_grid(content, str::parse)?;
+        let nodes = parse_grid(content, str::parse)?
+            .rows()
+            .into_iter()
+            .map(|row| Vec3::new(row[0], row[1], row[2]))
+            .collect::<Vec<_>>();
+        // A single node has no edge to connect it with in part 2
+        if nodes.len() < 2 {
+            bail!("Expected at least two junction boxes, got {}", nodes.len());
+        }
         let max_steps = if example { 10 } else { 1000 };
-        Ok(Self { max_steps, nodes })
+        Ok(Self::from_nodes(nodes, max_steps))
     }
 
-    /// Helper function to compute squared Euclidean distance between nodes i
-    /// and j
-    fn dist(&self, i: usize, j: usize) -> i64 {
-        if i >= self.nodes.nrows() || j >= self.nodes.nrows() {
-            return i64::MAX;
+    /// Index `nodes` for the neighbor queries of both parts
+    fn from_nodes(nodes: Vec<Vec3>, max_steps: usize) -> Self {
+        let tree = KdTree::new(&nodes.iter().map(|&node| node.into()).collect::<Vec<_>>());
+        Self {
+            max_steps,
+            nodes,
+            tree,
+        }
+    }
+
+    /// The components after connecting the `max_steps` shortest edges.
+    ///
+    /// Those edges all lie within some radius, so we collect the pairs within
+    /// a radius from the k-d tree, doubling it until there are enough of them,
+    /// and only sort those. Starting from a typical nearest neighbor distance,
+    /// this only ever looks at a few times `max_steps` pairs instead of all
+    /// `O(n^2)` of them.
+    fn clusters(&self) -> DisjointSet {
+        let n = self.nodes.len();
+        let wanted = self.max_steps.min(n * n.saturating_sub(1) / 2);
+        let pairs_within = |radius2| {
+            (0..n)
+                .maybe_into_par_iter()
+                .flat_map_iter(|i| {
+                    // Keep each pair once, as (i, j) with j > i, skipping the node itself
+                    self.tree
+                        .within_radius(&self.nodes[i].into(), radius2)
+                        .into_iter()
+                        .filter_map(move |(d, j)| (j > i).then_some((d, i, j)))
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut nearest = (0..n)
+            .maybe_into_par_iter()
+            .filter_map(|i| self.tree.nearest_where(&self.nodes[i].into(), |j| j != i))
+            .map(|(d, _)| d)
+            .collect::<Vec<_>>();
+        let mut radius2 = if nearest.is_empty() {
+            0
+        } else {
+            *nearest.select_nth_unstable(n / 2).1
+        };
+        let mut edges = pairs_within(radius2);
+        while edges.len() < wanted {
+            // Squared distances, so this doubles the radius
+            radius2 = radius2.saturating_mul(4).max(1);
+            edges = pairs_within(radius2);
+        }
+        edges.sort_unstable();
+        let mut dsu = DisjointSet::new(n);
+        for &(_, i, j) in &edges[..wanted] {
+            dsu.union(i, j);
         }
-        (&self.nodes.row(i) - &self.nodes.row(j))
-            .mapv(|x| x * x)
-            .sum()
+        dsu
+    }
+
+    /// The edges of a minimum spanning tree, in the order Kruskal's algorithm
+    /// picks them, so that the last one connects everything.
+    ///
+    /// Kruskal's algorithm needs the edges in ascending order only up to the
+    /// one connecting everything. Each node lists its nearest neighbors in
+    /// ascending order, so merging these lists with a min-heap yields every
+    /// edge in ascending order, once from each end. Lists are fetched from the
+    /// k-d tree a few neighbors at a time, doubling whenever one runs out, so
+    /// we never look at the many long edges past the last one needed. Edges
+    /// within an already connected component are simply skipped.
+    fn spanning_edges(&self) -> Vec<(usize, usize)> {
+        let n = self.nodes.len();
+        // The nearest neighbors of node i, without i itself
+        let fetch = |i: usize, k: usize| {
+            self.tree
+                .k_nearest(&self.nodes[i].into(), k + 1)
+                .into_iter()
+                .filter(|&(_, j)| j != i)
+                .collect::<Vec<_>>()
+        };
+        let mut neighbors = (0..n)
+            .maybe_into_par_iter()
+            .map(|i| fetch(i, 16))
+            .collect::<Vec<_>>();
+        // Position of the next edge of each node in its list
+        let mut next = vec![0; n];
+        let mut edges = neighbors
+            .iter()
+            .enumerate()
+            .filter_map(|(i, list)| list.first().map(|&(d, j)| Reverse((d, i, j))))
+            .collect::<BinaryHeap<_>>();
+        let mut dsu = DisjointSet::new(n);
+        let mut selected = Vec::new();
+        // We greedily process the closest edge
+        while let Some(Reverse((d, i, j))) = edges.pop() {
+            if dsu.union(i, j) {
+                selected.push((i, j));
+            }
+            // Stop as soon as all nodes are connected
+            if dsu.component_count() == 1 {
+                return selected;
+            }
+            // Otherwise, queue the next edge of node i, fetching more if needed.
+            // A longer list resumes past the edge just taken rather than at
+            // its position, so that no neighbor at a tied distance is skipped.
+            next[i] += 1;
+            if next[i] == neighbors[i].len() && next[i] < n - 1 {
+                neighbors[i] = fetch(i, 2 * next[i]);
+                next[i] = neighbors[i].partition_point(|&edge| edge <= (d, j));
+            }
+            if let Some(&(d, k)) = neighbors[i].get(next[i]) {
+                edges.push(Reverse((d, i, k)));
+            }
+        }
+        selected
+    }
+
+    /// The clusters of part 1 and the spanning tree of part 2 as a DOT graph.
+    ///
+    /// Nodes of the same cluster share a fill color, from the largest cluster
+    /// down, while nodes left on their own are white. Spanning tree edges
+    /// within a cluster take its color, and the ones joining clusters, which
+    /// part 1 never got to, are dashed.
+    fn to_dot(&self) -> Dot {
+        const PALETTE: [&str; 8] = [
+            "#e41a1c", "#377eb8", "#4daf4a", "#984ea3", "#ff7f00", "#a65628", "#f781bf", "#999999",
+        ];
+        let n = self.nodes.len();
+        let mut clusters = self.clusters();
+        let roots = (0..n).map(|i| clusters.find(i)).collect::<Vec<_>>();
+        let mut ranked = (0..n)
+            .filter(|&i| roots[i] == i && clusters.size[i] > 1)
+            .collect::<Vec<_>>();
+        ranked.sort_by_key(|&root| (Reverse(clusters.size[root]), root));
+        let mut color = vec!["white"; n];
+        for (rank, &root) in ranked.iter().enumerate() {
+            color[root] = PALETTE[rank % PALETTE.len()];
+        }
+        let color = |i: usize| color[roots[i]];
+        let mut dot = Dot::graph();
+        dot.defaults("node", &[("shape", "circle"), ("style", "filled")]);
+        for (i, node) in self.nodes.iter().enumerate() {
+            let position = format!("{},{},{}", node.x, node.y, node.z);
+            dot.node(i, &[("fillcolor", color(i)), ("tooltip", &position)]);
+        }
+        for (i, j) in self.spanning_edges() {
+            if roots[i] == roots[j] {
+                dot.edge(i, j, &[("color", color(i))]);
+            } else {
+                dot.edge(i, j, &[("style", "dashed")]);
+            }
+        }
+        dot
     }
 }
 
@@ -93,110 +262,74 @@ impl Solution for Puzzle {
         Self::new(example).unwrap_or_else(|e| panic!("Failed to parse input: {e}"))
     }
 
-    /// Since we only need to find top `max_steps` smallest edges, we can use a
-    /// max-heap to keep track while iterating through all pairs of nodes.
+    /// Connect the `max_steps` shortest edges, see [`Self::clusters`], and
+    /// multiply the sizes of the three largest components.
     fn part1(&self) -> String {
-        let mut dsu = DisjointSet::new(self.nodes.nrows());
-        (0..self.nodes.nrows())
-            .into_par_iter()
-            .flat_map_iter(|i| {
-                // Generate all pairs (i, j) with j > i for upper triangular matrix
-                ((i + 1)..self.nodes.nrows())
-                    .map(|j| (i, j))
-                    .collect::<Vec<_>>()
-            })
-            .fold(BinaryHeap::new, |mut heap, (i, j)| {
-                // Push the distance and the pair into the heap, pop the largest if exceeding
-                // max_steps to keep only smallest distances
-                let d = self.dist(i, j);
-                heap.push((d, i, j));
-                if heap.len() > self.max_steps {
-                    heap.pop();
-                }
-                heap
-            })
-            .reduce(BinaryHeap::new, |mut acc, mut heap| {
-                // Further reduce between threads to get global smallest distances
-                acc.extend(heap.drain());
-                while acc.len() > self.max_steps {
-                    acc.pop();
-                }
-                acc
-            })
-            .into_iter()
-            // Finally, perform the unions
-            .for_each(|(_, i, j)| dsu.union(i, j));
-        // Get the first three largest components
-        dsu.sizes
-            .values()
-            .fold(BinaryHeap::new(), |mut heap, &size| {
+        // Get the first three largest components, keeping the three largest
+        // of each chunk and merging those
+        let top3 = |mut heap: BinaryHeap<_>| {
+            while heap.len() > 3 {
+                heap.pop();
+            }
+            heap
+        };
+        let sizes = self.clusters().sizes().collect::<Vec<_>>();
+        ordered_fold(
+            &sizes,
+            Self::CHUNK,
+            BinaryHeap::new,
+            |mut heap, &size| {
                 heap.push(Reverse(size));
-                if heap.len() > 3 {
-                    heap.pop();
-                }
-                heap
-            })
-            .iter()
-            .map(|&Reverse(x)| x)
-            .product::<u64>()
-            .to_string()
+                top3(heap)
+            },
+            |mut heap, other| {
+                heap.extend(other);
+                top3(heap)
+            },
+        )
+        .iter()
+        .map(|&Reverse(x)| x)
+        .product::<u64>()
+        .to_string()
     }
 
-    /// We can ignore connections that has no effect, i.e., connections between
-    /// already connected components. Since we already have the disjoint set,
-    /// this is easily achievable. On top of that, we can always keep track of
-    /// the closest neighbor for each node, and only update when a connection is
-    /// made, so that we don't have to consider all pairs every time.
+    /// Run Kruskal's algorithm, see [`Self::spanning_edges`], and multiply the
+    /// X coordinates of the last edge, which connects all nodes.
     fn part2(&self) -> String {
-        // Initialize closest neighbor for each node, stored in a min-heap
-        let mut closest_neighbor = (0..self.nodes.nrows())
-            .into_par_iter()
-            .map(|i| {
-                (0..self.nodes.nrows())
-                    .filter_map(|j| (j != i).then_some((self.dist(i, j), i, j)))
-                    .min_by_key(|&(dist, _, _)| dist)
-                    .map_or_else(
-                        || unreachable!("There should be at least one other node"),
-                        Reverse,
-                    )
-            })
-            .collect::<BinaryHeap<_>>();
-        let mut dsu = DisjointSet::new(self.nodes.nrows());
-        loop {
-            // We greedily process the closest edge
-            let Some(Reverse((_, i, j))) = closest_neighbor.pop() else {
-                panic!("No more edges to process");
-            };
-            let root_i = dsu.find(i);
-            let root_j = dsu.find(j);
-            // If they belong to different components, connect them
-            if root_i != root_j {
-                dsu.union(i, j);
-            }
-            // If we find that all nodes are connected after this union,
-            // we can return the product of the X coordinates of this last edge
-            if dsu.sizes.len() == 1 {
-                return (self.nodes[[i, 0]] * self.nodes[[j, 0]]).to_string();
-            }
-            // Otherwise, we need to continue updating the closest neighbor for node i
-            closest_neighbor.push(
-                (0..self.nodes.nrows())
-                    // Filter out nodes in the same component as i
-                    .filter_map(|k| (root_i != dsu.find(k)).then_some((self.dist(i, k), i, k)))
-                    .min_by_key(|&(dist, _, _)| dist)
-                    .map_or_else(
-                        || unreachable!("At least one different component should exist"),
-                        Reverse,
-                    ),
-            );
-        }
+        let &(i, j) = self
+            .spanning_edges()
+            .last()
+            .unwrap_or_else(|| unreachable!("Inputs are checked to have at least two nodes"));
+        (self.nodes[i].x * self.nodes[j].x).to_string()
     }
 }
 
+/// Run with `--dot` to also write the clusters and the spanning tree to
+/// `outputs/clusters-day08.dot`, see [`Puzzle::to_dot`].
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let args = diagnostics::args();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("--dot") => {
+            let path = write_output(Puzzle::DAY, "clusters", "dot", &puzzle.to_dot().to_string())?;
+            println!("Wrote {}", path.display());
+        }
+        Some(other) => bail!("Unknown argument {other}, expected --dot"),
+    }
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
@@ -205,26 +338,144 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use util::{rng::Rng, testing::assert_answer, timer::measure_many};
 
     use super::*;
 
+    /// `n` random junction boxes spread over a cube like the real input's
+    fn random_nodes(n: usize, seed: u64) -> Vec<Vec3> {
+        let mut rng = Rng::new(seed);
+        (0..n)
+            .map(|_| {
+                let mut coord = || rng.between(0, 100_000);
+                Vec3::new(coord(), coord(), coord())
+            })
+            .collect()
+    }
+
+    /// Both parts by sorting all `O(n^2)` pairs, as before the k-d tree
+    fn brute_force(nodes: &[Vec3], max_steps: usize) -> (String, String) {
+        let mut edges = (0..nodes.len())
+            .flat_map(|i| (i + 1..nodes.len()).map(move |j| ((nodes[i] - nodes[j]).norm2(), i, j)))
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        let mut dsu = DisjointSet::new(nodes.len());
+        for &(_, i, j) in &edges[..max_steps] {
+            dsu.union(i, j);
+        }
+        let mut sizes = dsu.sizes().collect::<Vec<_>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        let part1 = sizes.iter().take(3).product::<u64>().to_string();
+        let mut dsu = DisjointSet::new(nodes.len());
+        let part2 = edges
+            .iter()
+            .find_map(|&(_, i, j)| {
+                dsu.union(i, j);
+                (dsu.component_count() == 1).then(|| (nodes[i].x * nodes[j].x).to_string())
+            })
+            .unwrap_or_default();
+        (part1, part2)
+    }
+
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "40");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut dsu = DisjointSet::new(5);
+        assert_eq!(dsu.component_count(), 5);
+        dsu.union(0, 1);
+        dsu.union(3, 4);
+        dsu.union(1, 0);
+        assert_eq!(dsu.component_count(), 3);
+        dsu.union(4, 1);
+        assert_eq!(dsu.component_count(), 2);
+        assert_eq!(dsu.find(0), dsu.find(3));
+        let mut sizes = dsu.sizes().collect::<Vec<_>>();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 4]);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for seed in 0..4 {
+            let nodes = random_nodes(300, seed);
+            let (part1, part2) = brute_force(&nodes, 100);
+            let puzzle = Puzzle::from_nodes(nodes, 100);
+            assert_eq!(puzzle.part1(), part1);
+            assert_eq!(puzzle.part2(), part2);
+        }
+    }
+
+    #[test]
+    fn test_equidistant_nodes() {
+        // A lattice with some nodes repeated, so that most distances tie,
+        // including across the batches of neighbors fetched from the tree
+        let mut nodes = (0..7)
+            .flat_map(|x| (0..7).flat_map(move |y| (0..7).map(move |z| (x, y, z))))
+            .map(|(x, y, z)| Vec3::new(1000 * x + 1, 1000 * y, 1000 * z))
+            .collect::<Vec<_>>();
+        nodes.extend(nodes.clone().into_iter().step_by(5));
+        let (part1, part2) = brute_force(&nodes, 100);
+        let puzzle = Puzzle::from_nodes(nodes, 100);
+        assert_eq!(puzzle.part1(), part1);
+        assert_eq!(puzzle.part2(), part2);
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
+    fn test_scales_to_many_nodes() {
+        // As many steps as the real input, over more nodes than it has
+        let nodes = random_nodes(2000, 8);
+        let (part1, part2) = brute_force(&nodes, 1000);
+        let puzzle = Puzzle::from_nodes(nodes, 1000);
+        assert_eq!(puzzle.part1(), part1);
+        assert_eq!(puzzle.part2(), part2);
+    }
+
+    #[test]
+    fn test_dot() -> Result<()> {
         let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "25272");
+        let dot = puzzle.to_dot().to_string();
+        let n = puzzle.nodes.len();
+        assert_eq!(dot.matches("fillcolor").count(), n);
+        assert_eq!(dot.matches(" -- ").count(), n - 1);
+        // Part 1 leaves the largest clusters complete, so the spanning tree
+        // only needs one dashed edge to join each of them to the rest
+        let clusters = puzzle.clusters().component_count();
+        assert_eq!(dot.matches("dashed").count(), clusters - 1);
+        // Sizes 5, 4, 2, 2 and singletons in the example
+        assert_eq!(dot.matches("#e41a1c").count(), 5 + 4);
+        assert_eq!(dot.matches("white").count(), n - 5 - 4 - 2 - 2);
         Ok(())
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        let mut results = Puzzle::bench_all(Duration::from_secs(1)).to_vec();
+        // The k-d tree against sorting all pairs as before, with more and
+        // more nodes
+        let limit = Duration::from_millis(200);
+        for n in [1_000, 2_000] {
+            let nodes = random_nodes(n, 8);
+            let puzzle = Puzzle::from_nodes(nodes.clone(), 1000);
+            results.push(measure_many(
+                format!("Both parts (k-d tree, N={n})"),
+                limit,
+                || (puzzle.part1(), puzzle.part2()),
+            ));
+            results.push(measure_many(
+                format!("Both parts (all pairs, N={n})"),
+                limit,
+                || brute_force(&nodes, 1000),
+            ));
+        }
+        Ok(results.to_csv(Puzzle::DAY)?)
     }
 }
@@ -1,44 +1,36 @@
 use anyhow::Result;
-use ndarray::{parallel::prelude::*, prelude::*};
-use util::{
-    Solution,
-    reader::{parse_grid, read_file},
-};
+use util::{geom::SegmentIndex, prelude::*};
 
 struct Puzzle {
-    nodes: Array2<i64>,
+    nodes: Vec<Point>,
+    /// Edges of the polygon, indexed for rectangle queries
+    edges: SegmentIndex,
 }
 
-type Edge = (usize, usize);
-
 impl Puzzle {
     fn new(example: bool) -> Result<Self> {
         let content = read_file(Self::DAY, example)?.replace(',', " ");
-        let nodes = parse_grid(content, str::parse)?;
-        Ok(Self { nodes })
+        let nodes = parse_grid(content, str::parse)?
+            .rows()
+            .into_iter()
+            .map(|row| Point::new(row[0], row[1]))
+            .collect();
+        Self::from_nodes(nodes)
     }
 
-    fn measure(&self, i: usize, j: usize) -> u64 {
-        (&self.nodes.row(i) - &self.nodes.row(j))
-            .mapv(|v| v.unsigned_abs() + 1)
-            .product()
+    fn from_nodes(nodes: Vec<Point>) -> Result<Self> {
+        let edges = SegmentIndex::from_polygon(&nodes)?;
+        Ok(Self { nodes, edges })
     }
 
-    /// Get all vertical and horizontal edges of the polygon
-    ///
-    /// Returns a tuple of two vectors:
-    /// - First vector contains vertical edges as pairs of node indices
-    /// - Second vector contains horizontal edges as pairs of node indices
-    fn get_edges(&self) -> (Vec<Edge>, Vec<Edge>) {
-        let n = self.nodes.nrows();
-        (0..n)
-            .into_par_iter()
-            .map(|i| (i, (i + 1) % n))
-            .partition(|&(i, j)| self.nodes[[i, 0]] == self.nodes[[j, 0]])
+    /// All unique pairs of nodes, each defining a rectangle by its corners
+    fn rectangles(&self) -> impl MaybeParallelIterator<Item = Rect> + '_ {
+        maybe_par_pairs(self.nodes.len())
+            .map(|(i, j)| Rect::from_corners(self.nodes[i], self.nodes[j]))
     }
 
-    /// Check if the space between two sides of a rectangle contains any part of
-    /// edges (except the corners)
+    /// Check if the rectangle strictly contains any part of the edges of the
+    /// polygon (except the corners)
     ///
     /// For example, given a pair of vertical sides and a horizontal edge from
     /// the polygon, if the condition is met, we get something like this:
@@ -63,28 +55,11 @@ impl Puzzle {
     /// rectangle. As edges are the interfaces of valid and invalid areas, this
     /// means that the rectangle cannot be fully contained within a valid area,
     /// i.e., at least some part of the rectangle is invalid.
-    fn intersect_edge(
-        &self,
-        xs: (i64, i64),
-        ys: (i64, i64),
-        edges: &[Edge],
-        transpose: bool,
-    ) -> bool {
-        let (x1, x2) = xs;
-        let (x1, x2) = (x1.min(x2), x1.max(x2));
-        let (y1, y2) = ys;
-        let (y1, y2) = (y1.min(y2), y1.max(y2));
-        edges.into_par_iter().any(|&(i, j)| {
-            let i = self.nodes.row(i);
-            let j = self.nodes.row(j);
-            let (ex1, ex2, ey) = if transpose {
-                (i[1].min(j[1]), i[1].max(j[1]), j[0])
-            } else {
-                (i[0].min(j[0]), i[0].max(j[0]), i[1])
-            };
-
-            ey > y1 && ey < y2 && ex1 < x2 && ex2 > x1
-        })
+    ///
+    /// Rather than testing every edge, the edges are indexed once in a
+    /// [`SegmentIndex`], which answers this in `O(log^2 n)`.
+    fn intersect_edge(&self, rect: &Rect) -> bool {
+        self.edges.crosses(rect)
     }
 }
 
@@ -98,14 +73,8 @@ impl Solution for Puzzle {
     /// Find the largest area defined by any two nodes, without any constraints,
     /// so we can brute-force the search and just measure all unique pairs.
     fn part1(&self) -> String {
-        (0..self.nodes.nrows())
-            .into_par_iter()
-            .flat_map_iter(|i| {
-                (i + 1..self.nodes.nrows())
-                    .map(|j| (i, j))
-                    .collect::<Vec<_>>()
-            })
-            .map(|(i, j)| self.measure(i, j))
+        self.rectangles()
+            .map(|rect| rect.area())
             .max()
             .unwrap_or_else(|| unreachable!("Must have at least one pair of nodes"))
             .to_string()
@@ -119,26 +88,9 @@ impl Solution for Puzzle {
     /// any parts of edges of the polygon. Otherwise, the rectangle would cross
     /// into invalid areas, so we discard it.
     fn part2(&self) -> String {
-        let (vertical_edges, horizontal_edges) = self.get_edges();
-
-        (0..self.nodes.nrows())
-            .into_par_iter()
-            .flat_map_iter(|i| {
-                (i + 1..self.nodes.nrows())
-                    .map(|j| (i, j))
-                    .collect::<Vec<_>>()
-            })
-            .filter_map(|(i, j)| {
-                let (px1, py1) = (self.nodes[[i, 0]], self.nodes[[j, 1]]);
-                let (px2, py2) = (self.nodes[[j, 0]], self.nodes[[i, 1]]);
-                if !self.intersect_edge((px1, px2), (py1, py2), &horizontal_edges, false)
-                    && !self.intersect_edge((py1, py2), (px1, px2), &vertical_edges, true)
-                {
-                    Some(self.measure(i, j))
-                } else {
-                    None
-                }
-            })
+        self.rectangles()
+            .filter(|rect| !self.intersect_edge(rect))
+            .map(|rect| rect.area())
             .max()
             .unwrap_or_else(|| unreachable!("Must have at least one pair of nodes"))
             .to_string()
@@ -146,9 +98,20 @@ impl Solution for Puzzle {
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
@@ -157,26 +120,53 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use util::{geom::edges, testing::assert_answer};
 
     use super::*;
 
+    /// A comb of `teeth` teeth pointing down, with teeth and gaps of varying
+    /// widths and depths
+    fn comb(teeth: i64) -> Vec<Point> {
+        let mut nodes = vec![Point::new(0, 0)];
+        let mut col = 0;
+        for i in 0..teeth {
+            let depth = 10 + (i * 7) % 13;
+            let width = 1 + (i * 5) % 4;
+            nodes.push(Point::new(depth, col));
+            nodes.push(Point::new(depth, col + width));
+            nodes.push(Point::new(1, col + width));
+            col += width + 1 + i % 3;
+            nodes.push(Point::new(1, col));
+        }
+        nodes.push(Point::new(0, col));
+        nodes
+    }
+
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "50");
+    fn test_matches_all_edges() -> Result<()> {
+        let puzzle = Puzzle::from_nodes(comb(60))?;
+        let expected = puzzle
+            .rectangles()
+            .filter(|rect| !edges(&puzzle.nodes).any(|(a, b)| rect.crossed_by(a, b)))
+            .map(|rect| rect.area())
+            .max()
+            .unwrap_or_default();
+        assert_eq!(puzzle.part2(), expected.to_string());
         Ok(())
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "24");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        Ok(Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)?)
     }
 }
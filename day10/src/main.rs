@@ -1,14 +1,21 @@
-use std::collections::{BTreeMap, HashMap};
+use std::env::{self, VarError};
 
-use anyhow::Result;
-use rayon::prelude::*;
+use anyhow::{Result, bail};
+use serde::Deserialize;
+#[cfg(feature = "ilp")]
+use util::ilp::{Cmp, Ilp};
 use util::{
-    Solution,
-    reader::{parse_lines, read_file},
+    bits::BitSet,
+    config,
+    math::gf2::BitMatrix,
+    memo::{LruCache, Memo},
+    prelude::*,
 };
 
 type LightState = u16;
 type Count = u8;
+/// Bounded memo table of subproblem solutions, keyed by compressed goals
+type SubproblemCache = Memo<[Count], u128, Option<u16>, LruCache<u128, Option<u16>>>;
 
 struct Machine {
     /// Target light configuration, compressed
@@ -19,8 +26,60 @@ struct Machine {
     count: Vec<Count>,
 }
 
+/// Tunables of the day from `config/day10.toml`
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// Name of the [`Solver`] of part 2, `dnc` unless given
+    solver: Option<String>,
+}
+
+/// Backend for part 2, chosen at runtime through [`Solver::ENV`] or `solver`
+/// in the configuration of the day
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Solver {
+    /// [`Puzzle::divide_and_conquer`], the default
+    DivideAndConquer,
+    /// [`Puzzle::integer_program`], only with the `ilp` feature
+    #[cfg(feature = "ilp")]
+    Ilp,
+}
+
+impl Solver {
+    /// Environment variable selecting the backend, either `dnc` or `ilp`
+    const ENV: &str = "AOC_DAY10_SOLVER";
+
+    /// The backend named by [`Self::ENV`], which wins over the configuration
+    /// of the day
+    fn configured() -> Result<Self> {
+        let name = match env::var(Self::ENV) {
+            Ok(name) => Some(name),
+            Err(VarError::NotPresent) => config::load::<Config>(Puzzle::DAY)?.solver,
+            Err(e) => bail!("Invalid {}: {e}", Self::ENV),
+        };
+        match name.as_deref() {
+            None | Some("dnc") => Ok(Self::DivideAndConquer),
+            #[cfg(feature = "ilp")]
+            Some("ilp") => Ok(Self::Ilp),
+            #[cfg(not(feature = "ilp"))]
+            Some("ilp") => bail!("The ilp solver needs the `ilp` feature of day10"),
+            Some(other) => bail!("Unknown solver {other}, expected dnc or ilp"),
+        }
+    }
+
+    /// Fewest presses reaching the joltage counts of `machine`
+    fn solve(self, machine: &Machine) -> Option<u16> {
+        match self {
+            Self::DivideAndConquer => Puzzle::divide_and_conquer(&machine.count, &machine.buttons),
+            #[cfg(feature = "ilp")]
+            Self::Ilp => Puzzle::integer_program(&machine.count, &machine.buttons),
+        }
+    }
+}
+
 struct Puzzle {
     machines: Vec<Machine>,
+    solver: Solver,
 }
 
 impl Puzzle {
@@ -43,8 +102,12 @@ impl Puzzle {
         for part in input.split_whitespace() {
             match part.chars().next() {
                 Some('[') => {
-                    goal = Some(
-                        part.trim_matches(|c| c == '[' || c == ']')
+                    let lights = part.trim_matches(|c| c == '[' || c == ']');
+                    if lights.len() > LightState::BITS as usize {
+                        anyhow::bail!("Too many lights in goal: {lights}");
+                    }
+                    goal = Some((
+                        lights
                             .chars()
                             .map(|c| match c {
                                 '.' => Ok(0),
@@ -55,15 +118,21 @@ impl Puzzle {
                             .into_iter()
                             .rev()
                             .fold(0, |acc, b| (acc << 1) | b),
-                    );
+                        lights.len(),
+                    ));
                 }
                 Some('(') => buttons.push(
                     part.trim_matches(|c| c == '(' || c == ')')
                         .split(',')
-                        .map(|s| s.parse::<u8>().map_err(Into::into))
+                        .map(|s| {
+                            let light = s.parse::<u32>()?;
+                            LightState::from(1u8).checked_shl(light).ok_or_else(|| {
+                                anyhow::anyhow!("Button toggles light {light}, which cannot exist")
+                            })
+                        })
                         .collect::<Result<Vec<_>>>()?
                         .into_iter()
-                        .fold(0, |acc, b| acc | (1 << b)),
+                        .fold(0, |acc, b| acc | b),
                 ),
                 Some('{') => {
                     count = Some(
@@ -81,7 +150,17 @@ impl Puzzle {
             (None, _, _) => anyhow::bail!("Missing goal definition"),
             (_, true, _) => anyhow::bail!("Missing button definitions"),
             (_, _, None) => anyhow::bail!("Missing joltage definition"),
-            (Some(goal), false, Some(count)) => Ok(Machine {
+            (Some((_, lights)), false, Some(count)) if count.len() != lights => {
+                anyhow::bail!("{} joltages for {lights} lights", count.len())
+            }
+            (Some((_, lights)), false, Some(_))
+                if buttons
+                    .iter()
+                    .any(|&b| (LightState::BITS - b.leading_zeros()) as usize > lights) =>
+            {
+                anyhow::bail!("Button toggles a light past the last of {lights}")
+            }
+            (Some((goal, _)), false, Some(count)) => Ok(Machine {
                 goal,
                 buttons,
                 count,
@@ -91,40 +170,31 @@ impl Puzzle {
 
     fn new(example: bool) -> Result<Self> {
         let machines = parse_lines(read_file(Self::DAY, example)?, Self::parse_machine)?;
-        Ok(Self { machines })
+        let solver = Solver::configured()?;
+        Ok(Self { machines, solver })
     }
 
     /// For any given goal state and button transitions, find the minimum number
-    /// of button presses as a binary backpack problem, solved with dynamic
-    /// programming.
+    /// of button presses by solving a linear system over GF(2).
     ///
-    /// This is feasible since pressing a button twice is equivalent to not
-    /// pressing it at all (XOR operation), and thus each button can only be
-    /// pressed 0 or 1 time in the final solution.
+    /// Pressing a button twice is equivalent to not pressing it at all (XOR
+    /// operation), so each button is pressed 0 or 1 time in the final solution
+    /// and the goal is reached exactly when `A x = goal`, where column `j` of
+    /// `A` holds the lights toggled by button `j`.
     ///
-    /// The state space is limited to 2^n where n is the number of lights (at
-    /// most 10), making this approach efficient.
-    fn binary_backpack(goal: LightState, transition: &[LightState]) -> Option<u16> {
-        let mut dp = BTreeMap::from_iter([(0, 0)]);
-        for &t in transition {
-            // Not pressing the button is implicitly handled by carrying over existing
-            // states
-            dp = dp.iter().fold(dp.clone(), |mut acc, (&state, &cost)| {
-                // Try pressing the button, resulting in a new state and increased cost
-                let state = state ^ t;
-                let cost = cost + 1;
-                acc.entry(state)
-                    .and_modify(|c| {
-                        if *c > cost {
-                            *c = cost;
-                        }
-                    })
-                    .or_insert(cost);
-                acc
-            });
-        }
-        // Return the cost to reach the goal state, if achievable
-        dp.get(&goal).copied()
+    /// Gaussian elimination gives one solution plus a basis of the null space,
+    /// which is typically tiny, so we enumerate the whole solution space and
+    /// keep the one with the fewest presses.
+    fn solve_xor_system(lights: usize, goal: LightState, transition: &[LightState]) -> Option<u16> {
+        let toggles = |mask: LightState, i: usize| mask >> i & 1 == 1;
+        let matrix = BitMatrix::from_fn(lights, transition.len(), |r, c| toggles(transition[c], r));
+        let goal = BitSet::from_indices(lights, (0..lights).filter(|&i| toggles(goal, i)));
+        matrix
+            .solve(&goal)?
+            .iter()
+            .map(|presses| presses.count_ones())
+            .min()
+            .and_then(|presses| u16::try_from(presses).ok())
     }
 
     /// The original solution for this is to use a integer linear programming
@@ -182,10 +252,42 @@ impl Puzzle {
                 bits
             })
             .collect::<Vec<_>>();
-        let mut cache = HashMap::new();
-        Self::try_divide_cached(&mut cache, goal, &transition)
+        let mut cache = Memo::with_cache(Self::compress, LruCache::new(Self::CACHE_CAPACITY));
+        let presses = Self::try_divide_cached(&mut cache, goal, &transition);
+        cache.cache().report("subproblems");
+        presses
+    }
+
+    /// The original formulation as an integer linear program: one integer
+    /// variable per button counting its presses, minimizing their sum subject
+    /// to every light receiving exactly its count.
+    #[cfg(feature = "ilp")]
+    fn integer_program(goal: &[Count], transition: &[LightState]) -> Option<u16> {
+        let max = goal.iter().copied().max().map_or(0, i32::from);
+        let mut ilp = Ilp::minimize();
+        let presses = transition
+            .iter()
+            .map(|_| ilp.integer_var(1.0, 0..=max))
+            .collect::<Vec<_>>();
+        for (i, &count) in goal.iter().enumerate() {
+            let terms = transition
+                .iter()
+                .zip(&presses)
+                .filter(|&(&t, _)| t >> i & 1 == 1)
+                .map(|(_, &var)| (var, 1.0));
+            ilp.constraint(terms, Cmp::Eq, f64::from(count));
+        }
+        let solution = ilp.solve().ok()?;
+        presses
+            .iter()
+            .map(|&var| u16::try_from(solution.int_value(var)).ok())
+            .sum()
     }
 
+    /// Bound on cached subproblems per machine, so that deep recursions on
+    /// large goals cannot exhaust memory
+    const CACHE_CAPACITY: usize = 1 << 16;
+
     /// Compress the goal state into a single integer for caching
     fn compress(goal: &[Count]) -> u128 {
         goal.iter().fold(0, |acc, &g| (acc << 8) | u128::from(g))
@@ -193,56 +295,53 @@ impl Puzzle {
 
     /// Try to solve the subproblem with caching
     fn try_divide_cached(
-        cache: &mut HashMap<u128, Option<u16>>,
+        cache: &mut SubproblemCache,
         goal: &[Count],
         transition: &[Vec<u8>],
     ) -> Option<u16> {
-        // Check cache first
-        if let Some(&res) = cache.get(&Self::compress(goal)) {
-            return res;
-        }
-        // Base case: if all counts are 0, no button press is needed
-        if goal.iter().all(|&g| g == 0) {
-            return Some(0);
-        }
-        // Try splitting the problem into two parts
-        let mut optimal = None;
-        for (cnt, residual) in transition
-            .iter()
-            .fold(vec![(0, vec![0; goal.len()])], |mut acc, t| {
-                let new = acc
+        cache.get_or_compute(goal, |cache| {
+            // Base case: if all counts are 0, no button press is needed
+            if goal.iter().all(|&g| g == 0) {
+                return Some(0);
+            }
+            // Try splitting the problem into two parts
+            let mut optimal = None;
+            for (cnt, residual) in transition
+                .iter()
+                .fold(vec![(0, vec![0; goal.len()])], |mut acc, t| {
+                    let new = acc
+                        .iter()
+                        .map(|(cnt, a)| {
+                            (
+                                cnt + 1,
+                                a.iter()
+                                    .zip(t.iter())
+                                    .map(|(&a, &b)| a + b)
+                                    .collect::<Vec<_>>(),
+                            )
+                        })
+                        .filter(|(_, s)| s.iter().zip(goal.iter()).all(|(x, g)| x <= g))
+                        .collect::<Vec<_>>();
+                    acc.extend(new);
+                    acc
+                })
+                .into_iter()
+                .filter(|(_, s)| s.iter().zip(goal).all(|(a, b)| a % 2 == b % 2))
+            {
+                let remaining = goal
                     .iter()
-                    .map(|(cnt, a)| {
-                        (
-                            cnt + 1,
-                            a.iter()
-                                .zip(t.iter())
-                                .map(|(&a, &b)| a + b)
-                                .collect::<Vec<_>>(),
-                        )
-                    })
-                    .filter(|(_, s)| s.iter().zip(goal.iter()).all(|(x, g)| x <= g))
+                    .zip(residual.iter())
+                    .map(|(&g, &r)| (g - r) / 2)
                     .collect::<Vec<_>>();
-                acc.extend(new);
-                acc
-            })
-            .into_iter()
-            .filter(|(_, s)| s.iter().zip(goal).all(|(a, b)| a % 2 == b % 2))
-        {
-            let remaining = goal
-                .iter()
-                .zip(residual.iter())
-                .map(|(&g, &r)| (g - r) / 2)
-                .collect::<Vec<_>>();
-            if let Some(subsolution) = Self::try_divide_cached(cache, &remaining, transition) {
-                let solution = cnt + 2 * subsolution;
-                optimal = optimal.map_or(Some(solution), |s: u16| Some(s.min(solution)));
+                if let Some(subsolution) = Self::try_divide_cached(cache, &remaining, transition) {
+                    let solution = cnt + 2 * subsolution;
+                    optimal = optimal.map_or(Some(solution), |s: u16| Some(s.min(solution)));
+                }
             }
-        }
-        // `None` means the current state is not achievable, `Some(x)` means we found a
-        // solution which guarantees to be optimal
-        cache.insert(Self::compress(goal), optimal);
-        optimal
+            // `None` means the current state is not achievable, `Some(x)` means we found
+            // a solution which guarantees to be optimal
+            optimal
+        })
     }
 }
 
@@ -255,9 +354,9 @@ impl Solution for Puzzle {
 
     fn part1(&self) -> String {
         self.machines
-            .par_iter()
+            .maybe_par_iter()
             .map(|machine| {
-                Self::binary_backpack(machine.goal, &machine.buttons)
+                Self::solve_xor_system(machine.count.len(), machine.goal, &machine.buttons)
                     // The problem guarantees that a solution exists for every machine
                     .unwrap_or_else(|| unreachable!("No solution found for machine"))
             })
@@ -267,9 +366,10 @@ impl Solution for Puzzle {
 
     fn part2(&self) -> String {
         self.machines
-            .par_iter()
+            .maybe_par_iter()
             .map(|machine| {
-                Self::divide_and_conquer(&machine.count, &machine.buttons)
+                self.solver
+                    .solve(machine)
                     // The problem guarantees that a solution exists for every machine
                     .unwrap_or_else(|| unreachable!("No solution found for machine"))
             })
@@ -279,9 +379,20 @@ impl Solution for Puzzle {
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
@@ -290,26 +401,337 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use proptest::{prelude::*, sample::subsequence};
+    use util::{
+        generate::stress_test,
+        rng::Rng,
+        strategy::{corrupted, lines},
+        testing::assert_answer,
+        timer::measure_many,
+    };
 
     use super::*;
 
+    /// The subset DP part 1 used before [`Puzzle::solve_xor_system`], which
+    /// tracks the fewest presses reaching each of the `2^lights` states.
+    ///
+    /// A button pairs up the states it toggles between, and each state of a
+    /// pair only depends on the other, so both are updated in place.
+    fn binary_backpack(goal: LightState, transition: &[LightState]) -> Option<u16> {
+        let lights = transition.iter().fold(goal, |acc, &t| acc | t);
+        let states = 1 << (LightState::BITS - lights.leading_zeros());
+        let mut dp = vec![None; states];
+        dp[0] = Some(0);
+        for t in transition.iter().map(|&t| usize::from(t)) {
+            for a in (0..states).filter(|&a| a < a ^ t) {
+                let (x, y) = (dp[a], dp[a ^ t]);
+                let pressed = |presses: Option<u16>| presses.map(|p| p + 1);
+                dp[a] = x.into_iter().chain(pressed(y)).min();
+                dp[a ^ t] = y.into_iter().chain(pressed(x)).min();
+            }
+        }
+        dp[usize::from(goal)]
+    }
+
+    /// Fewest presses in total over every way of pressing each button up to
+    /// `bounds[b]` times, such that the presses received by each light are
+    /// `accepted`. Exponential in the number of buttons, so only usable on
+    /// small machines.
+    fn exhaustive(
+        buttons: &[LightState],
+        bounds: &[u16],
+        received: &mut [u16],
+        accepted: &impl Fn(&[u16]) -> bool,
+    ) -> Option<u16> {
+        let Some((&button, rest)) = buttons.split_first() else {
+            return accepted(received).then_some(0);
+        };
+        let lights = (0..received.len()).filter(|&i| button >> i & 1 == 1);
+        let mut best = None;
+        for presses in 0..=bounds[0] {
+            if let Some(more) = exhaustive(rest, &bounds[1..], received, accepted) {
+                best = Some(best.map_or(presses + more, |b: u16| b.min(presses + more)));
+            }
+            lights.clone().for_each(|i| received[i] += 1);
+        }
+        lights.for_each(|i| received[i] -= bounds[0] + 1);
+        best
+    }
+
+    /// A small machine from arbitrary generated values: at most 4 lights with
+    /// counts below 16 and at most 5 buttons, each toggling some of the
+    /// lights. Any input is valid, so shrinking keeps the cases meaningful.
+    fn small_machine((buttons, count): &(Vec<LightState>, Vec<Count>)) -> Machine {
+        let count = count.iter().take(4).map(|c| c % 16).collect::<Vec<_>>();
+        let mask = (1 << count.len()) - 1;
+        Machine {
+            goal: (0..count.len())
+                .filter(|&i| count[i] % 2 == 1)
+                .map(|i| 1 << i)
+                .sum(),
+            buttons: buttons.iter().take(5).map(|b| b & mask).collect(),
+            count,
+        }
+    }
+
+    /// Small machines whose joltages are mostly reachable, from random
+    /// presses of each button
+    fn generate_machine(rng: &mut Rng) -> (Vec<LightState>, Vec<Count>) {
+        let lights = 1 + rng.below(4);
+        let buttons = (0..=rng.below(5))
+            .map(|_| {
+                (0..lights)
+                    .filter(|_| rng.chance(0.5))
+                    .map(|i| 1 << i)
+                    .sum()
+            })
+            .collect::<Vec<LightState>>();
+        let mut count = vec![0; lights];
+        for &button in &buttons {
+            let presses = [0, 1, 2][rng.below(3)];
+            for (i, c) in count.iter_mut().enumerate() {
+                if button >> i & 1 == 1 {
+                    *c += presses;
+                }
+            }
+        }
+        // Sometimes off by one, to also cover unreachable joltages
+        if rng.chance(0.2) {
+            count[0] += 1;
+        }
+        (buttons, count)
+    }
+
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "7");
+    fn test_matches_exhaustive() {
+        // Part 1 presses each button up to twice, to show once is enough
+        let lights = |input: &_| {
+            let machine = small_machine(input);
+            let bounds = vec![2; machine.buttons.len()];
+            let parity = |received: &[u16]| {
+                (0..received.len()).all(|i| received[i] % 2 == machine.goal >> i & 1)
+            };
+            let received = &mut vec![0; machine.count.len()];
+            let best = exhaustive(&machine.buttons, &bounds, received, &parity);
+            (best, best)
+        };
+        let fast_lights = |input: &_| {
+            let machine = small_machine(input);
+            (
+                Puzzle::solve_xor_system(machine.count.len(), machine.goal, &machine.buttons),
+                binary_backpack(machine.goal, &machine.buttons),
+            )
+        };
+        assert_eq!(
+            stress_test(lights, fast_lights, generate_machine, 500),
+            None
+        );
+        // Part 2 presses each button until one of its lights would overshoot
+        let joltage = |input: &_| {
+            let machine = small_machine(input);
+            let bounds = machine
+                .buttons
+                .iter()
+                .map(|&b| {
+                    let counts = (0..machine.count.len()).filter(|&i| b >> i & 1 == 1);
+                    counts
+                        .map(|i| u16::from(machine.count[i]))
+                        .min()
+                        .unwrap_or(0)
+                })
+                .collect::<Vec<_>>();
+            let exact = |received: &[u16]| {
+                received
+                    .iter()
+                    .zip(&machine.count)
+                    .all(|(&r, &c)| r == u16::from(c))
+            };
+            exhaustive(
+                &machine.buttons,
+                &bounds,
+                &mut vec![0; machine.count.len()],
+                &exact,
+            )
+        };
+        let fast_joltage = |input: &_| {
+            let machine = small_machine(input);
+            Puzzle::divide_and_conquer(&machine.count, &machine.buttons)
+        };
+        assert_eq!(
+            stress_test(joltage, fast_joltage, generate_machine, 500),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_machine() -> Result<()> {
+        let machine = Puzzle::parse_machine("[.##.] (3) (1,3) (2) {3,5,4,7}")?;
+        assert_eq!((machine.goal, machine.buttons), (0b0110, vec![8, 10, 4]));
+        for invalid in [
+            "[.##.] (16) {3,5,4,7}",
+            "[.##.] (4) {3,5,4,7}",
+            "[.##.] (3) {3,5,4}",
+            "[.................] (1) {1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1}",
+        ] {
+            assert!(Puzzle::parse_machine(invalid).is_err(), "{invalid}");
+        }
         Ok(())
     }
 
+    /// Characters with a meaning in the input, to corrupt it with
+    const SYNTAX: &[char] = &['[', ']', '(', ')', '{', '}', ',', ' ', '\n', '#', '9'];
+
+    /// Lines of the input, with up to 10 lights and 13 buttons like the real
+    /// machines, and arbitrary goals and joltages
+    fn machine() -> impl Strategy<Value = String> {
+        (1..=10usize).prop_flat_map(|lights| {
+            let button = subsequence((0..lights).collect::<Vec<_>>(), 1..=lights);
+            (
+                proptest::collection::vec(any::<bool>(), lights),
+                proptest::collection::vec(button, 1..=13),
+                proptest::collection::vec(any::<Count>(), lights),
+            )
+                .prop_map(|(goal, buttons, count)| {
+                    let goal = goal.iter().map(|&on| if on { '#' } else { '.' });
+                    let buttons = buttons.iter().map(|button| {
+                        let lights = button.iter().map(ToString::to_string);
+                        format!("({})", lights.collect::<Vec<_>>().join(","))
+                    });
+                    let count = count.iter().map(ToString::to_string);
+                    format!(
+                        "[{}] {} {{{}}}",
+                        goal.collect::<String>(),
+                        buttons.collect::<Vec<_>>().join(" "),
+                        count.collect::<Vec<_>>().join(",")
+                    )
+                })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated(input in lines(machine(), 1..=5)) {
+            let machines = parse_lines(&input, Puzzle::parse_machine)
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(machines.len(), input.lines().count());
+        }
+
+        #[test]
+        fn test_parse_never_panics(
+            input in corrupted(lines(machine(), 1..=3), 4, SYNTAX),
+        ) {
+            let _ = parse_lines(&input, Puzzle::parse_machine);
+        }
+
+        /// Solving the XOR system finds as few presses as the subset DP
+        #[test]
+        fn test_xor_system_matches_backpack(line in machine()) {
+            let machine = Puzzle::parse_machine(&line)
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(
+                Puzzle::solve_xor_system(machine.count.len(), machine.goal, &machine.buttons),
+                binary_backpack(machine.goal, &machine.buttons)
+            );
+        }
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
+    }
+
     #[test]
-    fn test_part2() -> Result<()> {
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    /// Random machines as large as the real ones, with 10 lights and 13
+    /// buttons, whose goals are reachable by construction
+    fn random_machines(n: usize) -> Vec<(LightState, Vec<LightState>)> {
+        let mut rng = Rng::new(10);
+        (0..n)
+            .map(|_| {
+                let buttons = (0..13)
+                    .map(|_| (0..10).filter(|_| rng.chance(0.3)).map(|i| 1 << i).sum())
+                    .collect::<Vec<LightState>>();
+                let goal = buttons
+                    .iter()
+                    .filter(|_| rng.chance(0.5))
+                    .fold(0, |goal, b| goal ^ b);
+                (goal, buttons)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_xor_system_matches_subset_dp() -> Result<()> {
         let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "33");
+        let mut machines = random_machines(200);
+        machines.extend(puzzle.machines.iter().map(|m| (m.goal, m.buttons.clone())));
+        let xor = || {
+            machines
+                .iter()
+                .map(|(goal, buttons)| Puzzle::solve_xor_system(10, *goal, buttons))
+                .collect::<Vec<_>>()
+        };
+        let dp = || {
+            machines
+                .iter()
+                .map(|(goal, buttons)| binary_backpack(*goal, buttons))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(xor(), dp());
+        Ok(())
+    }
+
+    #[cfg(feature = "ilp")]
+    #[test]
+    fn test_solvers_agree() -> Result<()> {
+        let mut machines = Puzzle::new(true)?.machines;
+        // Joltages reachable by construction, from random presses of each button
+        let mut rng = Rng::new(10);
+        for (_, buttons) in random_machines(50) {
+            let mut count = vec![0; 10];
+            for &button in &buttons {
+                let presses = rng.below(8);
+                for (i, c) in count.iter_mut().enumerate() {
+                    if button >> i & 1 == 1 {
+                        *c += u8::try_from(presses)?;
+                    }
+                }
+            }
+            machines.push(Machine {
+                goal: 0,
+                buttons,
+                count,
+            });
+        }
+        for machine in &machines {
+            let expected = Solver::DivideAndConquer.solve(machine);
+            assert!(expected.is_some());
+            assert_eq!(Solver::Ilp.solve(machine), expected);
+        }
         Ok(())
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        let limit = Duration::from_secs(1);
+        let mut results = Puzzle::bench_all(limit).to_vec();
+        // Part 1 as it was before the GF(2) solver, on the same input
+        let puzzle = Puzzle::new(false)?;
+        results.push(measure_many("Part 1 (subset DP)", limit, || {
+            (puzzle.machines.iter())
+                .map(|machine| binary_backpack(machine.goal, &machine.buttons))
+                .sum::<Option<u16>>()
+        }));
+        // Compare the backends side by side, whatever the environment selects
+        #[cfg(feature = "ilp")]
+        {
+            let mut puzzle = puzzle;
+            puzzle.solver = Solver::Ilp;
+            results.push(measure_many("Part 2 (ILP)", limit, || puzzle.part2()));
+        }
+        Ok(results.to_csv(Puzzle::DAY)?)
     }
 }
@@ -1,44 +1,121 @@
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    ops::Add,
-    str::FromStr,
-};
+use std::{collections::BTreeMap, str::FromStr};
 
-use anyhow::Result;
-use rayon::prelude::*;
+use anyhow::{Result, bail};
+use serde::Deserialize;
 use util::{
-    Solution,
-    reader::{parse_lines, parse_whitespace_separated, read_file},
+    config,
+    graph::{condense, dag_dp_all},
+    math::U256,
+    prelude::*,
+    writer::{Dot, GraphWriter, Mermaid, write_output},
 };
 
+/// Nodes reachable from any of `from` following `edges`, including `from`
+fn reach(from: &[usize], edges: &[Vec<usize>]) -> Vec<bool> {
+    let mut seen = vec![false; edges.len()];
+    let mut stack = from.to_vec();
+    while let Some(node) = stack.pop() {
+        if !std::mem::replace(&mut seen[node], true) {
+            stack.extend(&edges[node]);
+        }
+    }
+    seen
+}
+
+/// Tunables of the day from `config/day11.toml`
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// Machines every path in part 2 must visit, [`Puzzle::CHECKPOINTS`]
+    /// unless given
+    checkpoints: Option<Vec<String>>,
+}
+
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 struct Puzzle {
-    /// Incoming nodes for each node (parents)
-    in_nodes: Vec<BTreeSet<usize>>,
-    /// Outgoing nodes for each node (children)
+    /// Outgoing nodes for each node (children) of the condensed graph
     out_nodes: Vec<Vec<usize>>,
     /// Mapping from machine names to node indices (just for convenience)
     names: BTreeMap<String, usize>,
+    /// Machines every path in part 2 must visit, which the cache leaves out
+    /// as they come from the configuration rather than the input
+    #[cfg_attr(feature = "cache", serde(skip))]
+    checkpoints: Vec<String>,
 }
 
 impl Puzzle {
+    /// Machines the paths are counted from
+    const SOURCES: [&str; 2] = ["you", "svr"];
+
+    /// Machines every path in part 2 must visit, unless configured otherwise
+    const CHECKPOINTS: [&str; 2] = ["dac", "fft"];
+
+    /// Most checkpoints, as the paths are counted by subset of them
+    const MAX_CHECKPOINTS: usize = 16;
+
     fn new(example: bool) -> Result<Self> {
-        let content = read_file(Self::DAY, example)?.replace(':', "");
+        // The example has its answer for the checkpoints of the puzzle only
+        let config = if example {
+            Config::default()
+        } else {
+            config::load(Self::DAY)?
+        };
+        Self::from_input(&read_file(Self::DAY, example)?)?.configure(config)
+    }
+
+    /// Take the checkpoints of part 2 from the configuration of the day.
+    ///
+    /// This is apart from parsing, and from the cache, as the checkpoints are
+    /// not part of the input.
+    fn configure(mut self, config: Config) -> Result<Self> {
+        let Some(checkpoints) = config.checkpoints else {
+            self.checkpoints = Self::CHECKPOINTS.map(String::from).to_vec();
+            return Ok(self);
+        };
+        if checkpoints.len() > Self::MAX_CHECKPOINTS {
+            bail!(
+                "At most {} checkpoints, not {}",
+                Self::MAX_CHECKPOINTS,
+                checkpoints.len()
+            );
+        }
+        if let Some(name) = checkpoints
+            .iter()
+            .find(|&name| !self.names.contains_key(name))
+        {
+            bail!("Checkpoint {name} is not a machine");
+        }
+        self.checkpoints = checkpoints;
+        Ok(self)
+    }
+
+    /// Build the graph of machines, with cycles condensed into single nodes.
+    ///
+    /// The path counting would silently skip contributions through cycles,
+    /// so the strongly connected components are merged first, which always
+    /// leaves a DAG. This is only sound for cycles that no counted path goes
+    /// through, as the number of paths through a cycle is infinite, so a cycle
+    /// between a source and `out` is an error naming its machines.
+    fn from_input(input: &str) -> Result<Self> {
+        let content = input.replace(':', "");
         let mut machines =
             parse_lines(content, |s| parse_whitespace_separated(s, String::from_str))?;
         // Create an extra out node
         machines.push(vec!["out".to_string()]);
-        let names = machines
-            .iter()
-            .enumerate()
-            .map(|(i, m)| {
-                Ok((
-                    m.first()
-                        .ok_or_else(|| anyhow::anyhow!("Empty line"))?
-                        .to_owned(),
-                    i,
-                ))
-            })
-            .collect::<Result<BTreeMap<_, _>>>()?;
+        let mut names = BTreeMap::new();
+        for (i, m) in machines.iter().enumerate() {
+            let name = m.first().ok_or_else(|| anyhow::anyhow!("Empty line"))?;
+            // The last definition wins, as it always did
+            if let Some(first) = names.insert(name.to_owned(), i) {
+                diagnostics::warn(
+                    Some(i + 1),
+                    format!(
+                        "Machine {name} is defined again, its outputs on line {} are ignored",
+                        first + 1
+                    ),
+                )?;
+            }
+        }
         let out_nodes = machines
             .iter()
             .map(|m| {
@@ -53,91 +130,254 @@ impl Puzzle {
                     .collect::<Result<_>>()
             })
             .collect::<Result<Vec<Vec<_>>>>()?;
-        let in_nodes = out_nodes.iter().enumerate().fold(
-            vec![BTreeSet::new(); out_nodes.len()],
-            |mut acc, (i, outs)| {
-                for &j in outs {
-                    acc[j].insert(i);
-                }
-                acc
-            },
+        let graph = condense(&out_nodes);
+        let sources = Self::SOURCES
+            .iter()
+            .filter_map(|&name| names.get(name).map(|&i| graph.component[i]))
+            .collect::<Vec<_>>();
+        let mut in_nodes = vec![Vec::new(); graph.out_nodes.len()];
+        for (from, children) in graph.out_nodes.iter().enumerate() {
+            for &to in children {
+                in_nodes[to].push(from);
+            }
+        }
+        let (from_sources, to_out) = (
+            reach(&sources, &graph.out_nodes),
+            reach(&[graph.component[names["out"]]], &in_nodes),
         );
+        if let Some(c) =
+            (0..graph.members.len()).find(|&c| graph.cyclic[c] && from_sources[c] && to_out[c])
+            && let Some(cycle) = graph.cycle(&out_nodes, c)
+        {
+            let path = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|&i| machines[i][0].as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            bail!("Infinitely many paths to out through the cycle {path}");
+        }
+        let names = names
+            .into_iter()
+            .map(|(name, i)| (name, graph.component[i]))
+            .collect();
         Ok(Self {
-            in_nodes,
-            out_nodes,
+            out_nodes: graph.out_nodes,
             names,
+            checkpoints: Self::CHECKPOINTS.map(String::from).to_vec(),
         })
     }
+}
+
+impl Puzzle {
+    /// The checkpoints of part 2, by name
+    fn checkpoints(&self) -> Vec<&str> {
+        self.checkpoints.iter().map(String::as_str).collect()
+    }
 
-    /// Generalized topology dynamic programming framework for DAGs.
+    /// Count the paths from `from` to `out` visiting every one of the named
+    /// `checkpoints`, in any order.
     ///
-    /// It does a topological traversal of the DAG from `start` to `goal`,
-    fn topology_dynamic_programming<T, FT, FU>(
-        &self,
-        start: usize,
-        goal: usize,
-        default_state: T,
-        start_state: T,
-        transit: FT,
-        update: FU,
-    ) -> T
-    where
-        T: Clone + Copy + Send + Sync,
-        FT: Fn(T, T) -> T + Send + Sync,
-        FU: Fn(T, usize) -> T + Send + Sync,
-    {
-        let mut in_nodes = self.in_nodes.clone();
-        let mut count = vec![default_state; in_nodes.len()];
-        count[start] = start_state;
-        let mut frontier = in_nodes
+    /// The state of a node holds a count for each subset of checkpoints, as a
+    /// bitmask, of the paths reaching it having visited exactly that subset.
+    /// The counts are `U256` as the number of paths can be really large, even
+    /// more so in denser graphs.
+    ///
+    /// Reaching checkpoint `k` "shifts" the counts, so that paths that visited
+    /// the subset `m` have now visited `m | 1 << k`. With the two checkpoints
+    /// of part 2, `(A, B, C, D)` for paths that visited neither, only the
+    /// first, only the second and both becomes `(0, A + B, 0, C + D)` at the
+    /// first one. Merging paths from different parents just adds the counts
+    /// subset by subset.
+    fn count_paths(&self, from: &str, checkpoints: &[&str]) -> U256 {
+        let states = self.path_states(from, checkpoints);
+        // Only the paths that have visited every checkpoint count
+        states[self.names["out"]][(1 << checkpoints.len()) - 1]
+    }
+
+    /// The counts of [`Self::count_paths`] at every node, by subset of
+    /// `checkpoints` visited, final for every node with a path to `out`
+    fn path_states(&self, from: &str, checkpoints: &[&str]) -> Vec<Vec<U256>> {
+        let ckpts = checkpoints
             .iter()
-            .enumerate()
-            .filter_map(|(i, ins)| if ins.is_empty() { Some(i) } else { None })
+            .map(|&name| self.names[name])
             .collect::<Vec<_>>();
-        let mut visited = BTreeSet::<usize>::new();
-        // This loop is fail-safe because even the graph is not a DAG, we will just be
-        // stuck when there is a cycle and no new nodes can be added to the frontier. So
-        // the algorithm will terminate, and the contribution from the cycle will just
-        // not be counted.
-        while !frontier.is_empty() {
-            // Update and finalize the states for all nodes in the frontier
-            for &node in &frontier {
-                count[node] = update(count[node], node);
+        let subsets = 1 << ckpts.len();
+        let mut start = vec![U256::ZERO; subsets];
+        start[0] = U256::ONE;
+        dag_dp_all(
+            &self.out_nodes,
+            self.names[from],
+            self.names["out"],
+            vec![U256::ZERO; subsets],
+            start,
+            // Carry over counts when merging from different paths
+            |a: &mut Vec<U256>, b: &Vec<U256>| {
+                for (a, &b) in a.iter_mut().zip(b) {
+                    *a += b;
+                }
+            },
+            // Mark paths that visit a checkpoint by shifting counts
+            |state: &mut Vec<U256>, node: usize| {
+                for (k, _) in ckpts.iter().enumerate().filter(|&(_, &c)| c == node) {
+                    let bit = 1 << k;
+                    for m in 0..subsets {
+                        if m & bit != 0 {
+                            let carried = state[m ^ bit];
+                            state[m] += carried;
+                        }
+                    }
+                    for m in (0..subsets).filter(|m| m & bit == 0) {
+                        state[m] = U256::ZERO;
+                    }
+                }
+            },
+        )
+    }
+
+    /// Up to `limit` of the paths [`Self::count_paths`] counts, as the names
+    /// of the machines along them.
+    ///
+    /// The counts at every node double as predecessor tracking: walking back
+    /// from `out` with every checkpoint visited, a parent can come before a
+    /// node exactly when it has paths with a subset of checkpoints that the
+    /// node turns into the current one. Following only those never hits a
+    /// dead end, so each path found costs no more than its length times the
+    /// number of parents along it.
+    fn paths(&self, from: &str, checkpoints: &[&str], limit: usize) -> Vec<Vec<String>> {
+        /// Everything the backward walk needs besides where it is
+        struct Walk<'a> {
+            start: usize,
+            states: &'a [Vec<U256>],
+            in_nodes: &'a [Vec<usize>],
+            /// Checkpoints at each node, as a bitmask
+            marks: &'a [usize],
+            limit: usize,
+        }
+
+        impl Walk<'_> {
+            /// Extend `suffix`, the reversed end of a path, back from `node`
+            /// where the paths have visited exactly the checkpoints in `mask`
+            fn extend(
+                &self,
+                node: usize,
+                mask: usize,
+                suffix: &mut Vec<usize>,
+                paths: &mut Vec<Vec<usize>>,
+            ) {
+                let marks = self.marks[node];
+                if mask & marks != marks {
+                    return;
+                }
+                suffix.push(node);
+                // Subsets before the node's own checkpoints were added
+                let mut extra = marks;
+                loop {
+                    let before = (mask & !marks) | extra;
+                    if node == self.start && before == 0 {
+                        paths.push(suffix.iter().rev().copied().collect());
+                    }
+                    for &parent in &self.in_nodes[node] {
+                        if paths.len() >= self.limit {
+                            break;
+                        }
+                        if self.states[parent][before] > U256::ZERO {
+                            self.extend(parent, before, suffix, paths);
+                        }
+                    }
+                    if extra == 0 || paths.len() >= self.limit {
+                        break;
+                    }
+                    extra = (extra - 1) & marks;
+                }
+                suffix.pop();
             }
-            visited.extend(&frontier);
-            // Early exit if we have reached the goal
-            if visited.contains(&goal) {
-                break;
+        }
+
+        let states = self.path_states(from, checkpoints);
+        let mut in_nodes = vec![Vec::new(); self.out_nodes.len()];
+        for (parent, children) in self.out_nodes.iter().enumerate() {
+            for &child in children {
+                in_nodes[child].push(parent);
             }
-            // Propagate states to outgoing nodes in parallel
-            // (to speed up for large graphs, hopefully)
-            let edits = frontier
-                .into_par_iter()
-                .flat_map_iter(|from| {
-                    let cnt = count[from];
-                    self.out_nodes[from].iter().map(move |&to| (from, to, cnt))
-                })
-                .collect::<Vec<_>>();
-            // Apply the edits
-            for (from, to, cnt) in edits {
-                count[to] = transit(count[to], cnt);
-                in_nodes[to].remove(&from);
+        }
+        let mut marks = vec![0; self.out_nodes.len()];
+        for (k, &name) in checkpoints.iter().enumerate() {
+            marks[self.names[name]] |= 1 << k;
+        }
+        let walk = Walk {
+            start: self.names[from],
+            states: &states,
+            in_nodes: &in_nodes,
+            marks: &marks,
+            limit,
+        };
+        let mut paths = Vec::new();
+        let out = self.names["out"];
+        if limit > 0 && states[out][(1 << checkpoints.len()) - 1] > U256::ZERO {
+            walk.extend(
+                out,
+                (1 << checkpoints.len()) - 1,
+                &mut Vec::new(),
+                &mut paths,
+            );
+        }
+        let members = self.members();
+        paths
+            .into_iter()
+            .map(|path| path.into_iter().map(|c| members[c].join(", ")).collect())
+            .collect()
+    }
+
+    /// Names of the machines in each node of the condensed graph
+    fn members(&self) -> Vec<Vec<&str>> {
+        let mut members = vec![Vec::new(); self.out_nodes.len()];
+        for (name, &c) in &self.names {
+            members[c].push(name.as_str());
+        }
+        members
+    }
+}
+
+impl Puzzle {
+    /// Fill of the sources and checkpoints when highlighting
+    const MARKED: &str = "#ffd700";
+    /// Fill of the machines reachable from `svr` when highlighting
+    const REACHED: &str = "#add8e6";
+
+    /// Write the graph of machines to `out`, one node per machine or per
+    /// condensed cycle, labelled with the names of its machines.
+    ///
+    /// With `highlight`, the sources and checkpoints are marked, and so is
+    /// everything reachable from `svr`, which is where part 2 paths can go.
+    fn dump_graph(&self, out: &mut impl GraphWriter, highlight: bool) {
+        let members = self.members();
+        let reached = self.names.get("svr").map_or_else(
+            || vec![false; self.out_nodes.len()],
+            |&c| reach(&[c], &self.out_nodes),
+        );
+        let marked = Self::SOURCES
+            .into_iter()
+            .chain(self.checkpoints())
+            .filter_map(|name| self.names.get(name).copied())
+            .collect::<Vec<_>>();
+        for (c, names) in members.iter().enumerate() {
+            let fill = if !highlight {
+                None
+            } else if marked.contains(&c) {
+                Some(Self::MARKED)
+            } else if reached[c] {
+                Some(Self::REACHED)
+            } else {
+                None
+            };
+            out.add_node(names[0], &names.join(", "), fill);
+        }
+        for (from, children) in self.out_nodes.iter().enumerate() {
+            for &to in children {
+                out.add_edge(members[from][0], members[to][0]);
             }
-            // Find new frontier nodes with no remaining incoming edges
-            frontier = in_nodes
-                .iter()
-                .enumerate()
-                .filter_map(|(i, ins)| {
-                    if ins.is_empty() && !visited.contains(&i) {
-                        Some(i)
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>();
         }
-        // Return the final state at the goal node
-        count[goal]
     }
 }
 
@@ -148,91 +388,122 @@ impl Solution for Puzzle {
         Self::new(example).unwrap_or_else(|e| panic!("Failed to parse input: {e}"))
     }
 
-    /// Part 1 we just count the number of paths, no special update or transit
-    /// logic needed.
+    /// Part 1 we just count the number of paths, no checkpoints needed.
     fn part1(&self) -> String {
-        self.topology_dynamic_programming(
-            self.names["you"],
-            self.names["out"],
-            0,
-            // Give 1 path at the start
-            1,
-            Add::add,
-            // No special update needed
-            |state, _| state,
-        )
-        .to_string()
+        self.count_paths("you", &[]).to_string()
     }
 
-    /// Part 2 we need to track different "kinds" of paths based on whether they
-    /// visit two special nodes (dac and fft), or not. This gives 4 combinations
-    /// of paths. We use a tuple of 4 u64 integers to track the counts of each
-    /// kind of path as it turns out that the number of paths can be really
-    /// large and any other compact representation (e.g., bitmask) won't work
-    /// because we don't have such a large integer type to use.
-    ///
-    /// The update function will check if the current node is one of the special
-    /// nodes, and if so, it will "shift" the counts accordingly to mark that
-    /// the paths have visited that node. For example, if (A, B, C, D)
-    /// represents the counts of paths that have visited neither node, only dac,
-    /// only fft, and both nodes respectively, then visiting dac will transform
-    /// the state to (0, A + B, 0, C + D), effectively moving the counts to
-    /// reflect that those paths have now visited dac.
-    ///
-    /// The transit function simply adds the counts from different paths
-    /// together as before, we are just adding tuples element-wise instead of
-    /// single integers.
+    /// Part 2 we need to track different "kinds" of paths based on which of
+    /// the checkpoints they have visited so far, [`Self::CHECKPOINTS`] unless
+    /// configured otherwise, and only count the
+    /// ones that visited all of them. See [`Self::count_paths`].
     ///
     /// Compared to yesterday's problem, this one is much, much, MUCH more
     /// straightforward and enjoyable. What a nice and relaxing ride!
     fn part2(&self) -> String {
-        // State: (--, -+, +-, ++) for 4 combinations of visiting two nodes or not
-        type State = (u64, u64, u64, u64);
-        // Nodes (checkpoints) to track
-        let ckpts = (self.names["dac"], self.names["fft"]);
-
-        self.topology_dynamic_programming(
-            self.names["svr"],
-            self.names["out"],
-            (0, 0, 0, 0),
-            // Start with only 1 path (both unvisited)
-            (1, 0, 0, 0),
-            // Carry over states when merging from different paths
-            |a: State, b: State| (a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3),
-            // Mark paths that visit dac and fft by shifting counts
-            move |state: State, node: usize| match ckpts {
-                (x, _) if node == x => (0, state.0 + state.1, 0, state.2 + state.3),
-                (_, y) if node == y => (0, 0, state.0 + state.2, state.1 + state.3),
-                _ => state,
-            },
-        )
-        // Return the count of paths that have visited both checkpoints
-        .3
-        .to_string()
+        self.count_paths("svr", &self.checkpoints()).to_string()
     }
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let args = diagnostics::args();
+    #[cfg(feature = "cache")]
+    let args = util::cache::args(args);
+    // Condensing the graph is most of the parsing, which the cache skips
+    #[cfg(feature = "cache")]
+    let parse = || {
+        util::cache::cached(Puzzle::DAY, false, include_str!("main.rs"), || {
+            Puzzle::from_input(&read_file(Puzzle::DAY, false)?)
+        })?
+        .configure(config::load(Puzzle::DAY)?)
+    };
+    #[cfg(not(feature = "cache"))]
+    let parse = || Puzzle::new(false);
+    let puzzle = trace::parse(Puzzle::DAY, parse)?;
+    diagnostics::report();
+    let mut args = args.into_iter().peekable();
+    if args.next_if_eq("--dump-graph").is_some() {
+        let format = args
+            .next_if(|arg| !arg.starts_with("--"))
+            .unwrap_or_else(|| "dot".to_string());
+        let highlight = args.next_if_eq("--highlight").is_some();
+        if let Some(other) = args.next() {
+            bail!("Unknown argument {other}, expected --highlight");
+        }
+        let (extension, contents) = match format.as_str() {
+            "dot" => {
+                let mut graph = Dot::digraph();
+                puzzle.dump_graph(&mut graph, highlight);
+                ("dot", graph.to_string())
+            }
+            "mermaid" => {
+                let mut chart = Mermaid::new();
+                puzzle.dump_graph(&mut chart, highlight);
+                ("mmd", chart.to_string())
+            }
+            other => bail!("Unknown graph format {other}, expected dot or mermaid"),
+        };
+        let path = write_output(Puzzle::DAY, "graph", extension, &contents)?;
+        println!("Graph written to {}", path.display());
+        return Ok(());
+    }
+    if args.next_if_eq("--paths").is_some() {
+        let limit = match args.next() {
+            Some(limit) => limit.parse()?,
+            None => bail!("Expected the number of paths after --paths"),
+        };
+        let checkpoints = puzzle.checkpoints();
+        let paths = puzzle.paths("svr", &checkpoints, limit);
+        for path in &paths {
+            println!("{}", path.join(" -> "));
+        }
+        println!(
+            "Showing {} of {} paths from svr through {}",
+            paths.len(),
+            puzzle.count_paths("svr", &checkpoints),
+            checkpoints.join(" and ")
+        );
+        return Ok(());
+    }
+    if let Some(other) = args.next() {
+        bail!(
+            "Unknown argument {other}, expected --dump-graph [dot|mermaid] [--highlight] or \
+             --paths N"
+        );
+    }
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{collections::BTreeSet, fmt::Write as _, time::Duration};
 
-    use util::{Benchmark, Serializable};
+    use proptest::{prelude::*, sample::subsequence};
+    use util::{
+        rng::Rng,
+        strategy::{corrupted, dag},
+        testing::assert_answer,
+        timer::measure_many,
+    };
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "5");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
     }
 
     /// I didn't expect the example to change for part 2, but it did.
@@ -251,14 +522,266 @@ mod tests {
     /// to you so that it can also be used for part 1. But you will need to
     /// change the expected answer for part 1.
     #[test]
-    fn test_part2() -> Result<()> {
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
+    #[test]
+    fn test_checkpoints() -> Result<()> {
         let puzzle = Puzzle::new(true)?;
+        assert_eq!(puzzle.count_paths("you", &["ccc"]), U256::from(3u64));
+        assert_eq!(puzzle.count_paths("you", &["dac", "ccc"]), U256::ZERO);
+        // Every path through fft goes on through ggg
+        assert_eq!(puzzle.count_paths("svr", &["ggg", "dac", "fft"]), U256::ONE);
+        // Part 2 through the checkpoints of the configuration instead
+        let puzzle = puzzle.configure(config::parse(Puzzle::DAY, "checkpoints = [\"ccc\"]")?)?;
+        assert_eq!(puzzle.part2(), "6");
+        let unknown = config::parse(Puzzle::DAY, "checkpoints = [\"zzz\"]")?;
+        assert!(puzzle.configure(unknown).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycles() -> Result<()> {
+        let example = read_file(Puzzle::DAY, true)?;
+        // Cycles feeding into the graph from elsewhere, or reached but never
+        // leading to out, do not change any count
+        let harmless = example.replace("ggg: out", "ggg: out lll\nlll: mmm\nmmm: lll")
+            + "zzz: yyy\nyyy: zzz ccc\n";
+        let puzzle = Puzzle::from_input(&harmless)?;
+        assert_eq!(puzzle.part1(), "5");
         assert_eq!(puzzle.part2(), "1");
+        // A cycle on the way to out gives infinitely many paths
+        let looping = example.replace("eee: out", "eee: out ccc");
+        let Err(error) = Puzzle::from_input(&looping) else {
+            panic!("Paths through a cycle should be rejected");
+        };
+        assert_eq!(
+            error.to_string(),
+            "Infinitely many paths to out through the cycle ccc -> eee -> ccc"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_names() -> Result<()> {
+        let example = read_file(Puzzle::DAY, true)? + "ggg: out\n";
+        let lines = example.lines().count();
+        let puzzle = Puzzle::from_input(&example)?;
+        assert_eq!(puzzle.part1(), "5");
+        let warnings = diagnostics::take();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, Some(lines));
+        // With --strict, the warning fails the parse
+        diagnostics::set_strict(true);
+        let result = Puzzle::from_input(&example);
+        diagnostics::set_strict(false);
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_paths() -> Result<()> {
+        let puzzle = Puzzle::new(true)?;
+        let paths = puzzle.paths("svr", &Puzzle::CHECKPOINTS, 10);
+        assert_eq!(paths, vec![["svr", "you", "dac", "fft", "ggg", "out"]]);
+        let mut paths = puzzle.paths("you", &[], 10);
+        assert_eq!(paths.len(), 5);
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 5);
+        assert!(
+            paths
+                .iter()
+                .all(|path| path[0] == "you" && path[path.len() - 1] == "out")
+        );
+        assert_eq!(puzzle.paths("you", &[], 2).len(), 2);
+        assert_eq!(
+            puzzle.paths("you", &["ccc"], 10).len().to_string(),
+            puzzle.count_paths("you", &["ccc"]).to_string()
+        );
+        assert!(puzzle.paths("you", &["dac", "ccc"], 10).is_empty());
+        Ok(())
+    }
+
+    /// Every listed path is a real path visiting the checkpoints, and there
+    /// are as many as counted, on random DAGs with checkpoints anywhere
+    #[test]
+    fn test_paths_match_counts() -> Result<()> {
+        let names = |i: usize| format!("m{i}");
+        for seed in 0..50 {
+            let mut rng = Rng::new(seed);
+            let nodes = 3 + rng.below(10);
+            let mut input = String::new();
+            for i in 0..nodes {
+                let to_out = i == nodes - 1 || rng.chance(0.3);
+                let children = (i + 1..nodes)
+                    .filter(|_| rng.chance(0.4))
+                    .map(names)
+                    .chain(to_out.then(|| "out".to_string()))
+                    .collect::<Vec<_>>();
+                writeln!(input, "{}: {}", names(i), children.join(" "))?;
+            }
+            let puzzle = Puzzle::from_input(&input)?;
+            let (a, b) = (names(rng.below(nodes)), names(rng.below(nodes)));
+            let checkpoints = [a.as_str(), b.as_str()];
+            let paths = puzzle.paths("m0", &checkpoints, usize::MAX);
+            assert_eq!(
+                paths.len().to_string(),
+                puzzle.count_paths("m0", &checkpoints).to_string(),
+                "{input}"
+            );
+            for path in &paths {
+                assert!(checkpoints.iter().all(|c| path.iter().any(|m| m == c)));
+                for step in path.windows(2) {
+                    let (from, to) = (puzzle.names[&step[0]], puzzle.names[&step[1]]);
+                    assert!(puzzle.out_nodes[from].contains(&to), "{path:?}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Random DAGs of machines, with the sources and checkpoints anywhere, as
+    /// each machine and the machines it is connected to
+    fn machines() -> impl Strategy<Value = Vec<(String, Vec<String>)>> {
+        dag(4..=10, 0.4).prop_flat_map(|children| {
+            let n = children.len();
+            let special = subsequence((0..n).collect::<Vec<_>>(), 4).prop_shuffle();
+            let to_out = proptest::collection::vec(proptest::bool::weighted(0.3), n);
+            (Just(children), special, to_out).prop_map(|(children, special, to_out)| {
+                let mut names = (0..children.len())
+                    .map(|i| format!("m{i}"))
+                    .collect::<Vec<_>>();
+                let specials = Puzzle::SOURCES.iter().chain(&Puzzle::CHECKPOINTS);
+                for (&i, &name) in special.iter().zip(specials) {
+                    names[i] = name.to_string();
+                }
+                // The last machine always leads to out, so that some paths do
+                let last = names.len() - 1;
+                (children.iter().zip(to_out).enumerate())
+                    .map(|(i, (children, to_out))| {
+                        let children = children.iter().map(|&c| names[c].clone());
+                        let out = (to_out || i == last).then(|| "out".to_string());
+                        (names[i].clone(), children.chain(out).collect())
+                    })
+                    .collect()
+            })
+        })
+    }
+
+    /// Characters with a meaning in the input, to corrupt it with
+    const SYNTAX: &[char] = &[':', ' ', '\n', 'o', 'u', 't'];
+
+    /// The input listing `machines`
+    fn render(machines: &[(String, Vec<String>)]) -> String {
+        let lines = machines
+            .iter()
+            .map(|(name, children)| format!("{name}: {}\n", children.join(" ")));
+        lines.collect::<Vec<_>>().concat()
+    }
+
+    /// Paths from the machine `from` to `out` visiting every one of
+    /// `checkpoints`, by walking all of them
+    fn walk_paths(machines: &[(String, Vec<String>)], from: &str, checkpoints: &[&str]) -> u64 {
+        if from == "out" {
+            return u64::from(checkpoints.is_empty());
+        }
+        let left = (checkpoints.iter().copied())
+            .filter(|&c| c != from)
+            .collect::<Vec<_>>();
+        (machines.iter())
+            .filter(|(name, _)| name == from)
+            .flat_map(|(_, children)| children)
+            .map(|child| walk_paths(machines, child, &left))
+            .sum()
+    }
+
+    proptest! {
+        /// Both parts count as many paths as walking them one by one
+        #[test]
+        fn test_matches_walking(machines in machines()) {
+            let puzzle = Puzzle::from_input(&render(&machines))
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(puzzle.part1(), walk_paths(&machines, "you", &[]).to_string());
+            prop_assert_eq!(
+                puzzle.part2(),
+                walk_paths(&machines, "svr", &Puzzle::CHECKPOINTS).to_string()
+            );
+        }
+
+        #[test]
+        fn test_parse_never_panics(
+            input in corrupted(machines().prop_map(|m| render(&m)), 4, SYNTAX),
+        ) {
+            let _ = Puzzle::from_input(&input);
+        }
+    }
+
+    #[test]
+    fn test_dump_graph() -> Result<()> {
+        let example = read_file(Puzzle::DAY, true)?;
+        let puzzle = Puzzle::from_input(&(example + "zzz: yyy\nyyy: zzz ccc\n"))?;
+        let mut chart = Mermaid::new();
+        puzzle.dump_graph(&mut chart, true);
+        let chart = chart.to_string();
+        // The cycle is a single node, and svr is marked
+        assert!(chart.contains("[\"yyy, zzz\"]"));
+        assert!(chart.contains("fill:#ffd700"));
+        let mut plain = Dot::digraph();
+        puzzle.dump_graph(&mut plain, false);
+        let plain = plain.to_string();
+        assert!(!plain.contains("fillcolor"));
+        let edges = puzzle.out_nodes.iter().map(Vec::len).sum::<usize>();
+        assert_eq!(plain.matches(" -> ").count(), edges);
+        assert_eq!(chart.matches(" --> ").count(), edges);
         Ok(())
     }
 
+    /// Paths from `from` to `out` counted the way [`dag_dp_all`] did before
+    /// counting edges, keeping the parents left of each node in a set and
+    /// rescanning every node for the next frontier
+    fn frontier_paths(puzzle: &Puzzle, from: &str) -> U256 {
+        let out_nodes = &puzzle.out_nodes;
+        let mut in_nodes = vec![BTreeSet::new(); out_nodes.len()];
+        for (from, outs) in out_nodes.iter().enumerate() {
+            for &to in outs {
+                in_nodes[to].insert(from);
+            }
+        }
+        let mut states = vec![U256::ZERO; out_nodes.len()];
+        states[puzzle.names[from]] = U256::ONE;
+        let goal = puzzle.names["out"];
+        let mut frontier = (0..out_nodes.len())
+            .filter(|&i| in_nodes[i].is_empty())
+            .collect::<Vec<_>>();
+        let mut visited = BTreeSet::<usize>::new();
+        while !frontier.is_empty() && !frontier.contains(&goal) {
+            visited.extend(&frontier);
+            for &from in &frontier {
+                for &to in &out_nodes[from] {
+                    let carried = states[from];
+                    states[to] += carried;
+                    in_nodes[to].remove(&from);
+                }
+            }
+            frontier = (0..out_nodes.len())
+                .filter(|&i| in_nodes[i].is_empty() && !visited.contains(&i))
+                .collect();
+        }
+        states[goal]
+    }
+
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        let limit = Duration::from_secs(1);
+        let mut results = Puzzle::bench_all(limit).to_vec();
+        // Part 1 as the traversal was before counting edges, on the same input
+        let puzzle = Puzzle::new(false)?;
+        assert_eq!(frontier_paths(&puzzle, "you").to_string(), puzzle.part1());
+        results.push(measure_many("Part 1 (frontier maps)", limit, || {
+            frontier_paths(&puzzle, "you")
+        }));
+        Ok(results.to_csv(Puzzle::DAY)?)
     }
 }
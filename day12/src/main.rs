@@ -1,28 +1,413 @@
-use anyhow::Result;
-use ndarray::{parallel::prelude::*, prelude::*};
+use std::fmt::Write as _;
+
+use anyhow::{Result, bail};
 use util::{
-    Solution,
-    reader::{parse_char_grid, parse_lines, parse_whitespace_separated, read_file},
+    dp::subset_sums,
+    poly::Polyomino,
+    prelude::*,
+    term::{Color, paint},
 };
 
+/// A piece shape with everything the checks need precomputed
+struct Piece {
+    /// All distinct rotations and reflections
+    orientations: Vec<Polyomino>,
+    /// Number of cells covered
+    size: usize,
+    /// Side of the smallest square holding the piece in any orientation
+    side: usize,
+    /// Difference between the cells on the two colors of a checkerboard,
+    /// which is the same wherever and however the piece is placed
+    imbalance: usize,
+}
+
+impl Piece {
+    fn new(shape: &Polyomino) -> Self {
+        let (rows, cols) = shape.dim();
+        let black = shape
+            .cells()
+            .iter()
+            .filter(|p| (p.row + p.col) % 2 == 0)
+            .count();
+        Self {
+            orientations: shape.orientations(),
+            size: shape.len(),
+            side: rows.max(cols),
+            imbalance: black.abs_diff(shape.len() - black),
+        }
+    }
+}
+
+/// A region to fill, with the number of copies of each piece
+struct Region {
+    width: usize,
+    height: usize,
+    counts: Vec<u8>,
+}
+
+impl Region {
+    const fn area(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Pieces to place, with each copy listed separately
+    fn copies<'a>(&'a self, pieces: &'a [Piece]) -> impl Iterator<Item = &'a Piece> {
+        self.counts
+            .iter()
+            .zip(pieces)
+            .flat_map(|(&count, piece)| std::iter::repeat_n(piece, usize::from(count)))
+    }
+
+    /// Indices of the pieces to place, with each copy listed separately
+    fn copy_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &count)| std::iter::repeat_n(i, usize::from(count)))
+    }
+}
+
+/// Pieces placed in a region, each as the piece and the cells it covers,
+/// with cells flattened in reading order
+struct Packing {
+    width: usize,
+    height: usize,
+    placements: Vec<(usize, Vec<usize>)>,
+}
+
+impl Packing {
+    /// Letters for the placed pieces, reused once they run out
+    const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    /// Colors for the piece shapes, reused once they run out
+    const COLORS: [Color; 6] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+    ];
+
+    /// Each copy in its own square box of the region, in reading order,
+    /// which only works when [`BoxFit`] says so
+    fn boxes(region: &Region, pieces: &[Piece]) -> Self {
+        let side = region.copies(pieces).map(|p| p.side).max().unwrap_or(1);
+        let per_row = (region.width / side).max(1);
+        let placements = region
+            .copy_indices()
+            .enumerate()
+            .map(|(k, i)| {
+                let origin = UPoint::new(k / per_row * side, k % per_row * side);
+                let cells = pieces[i].orientations[0]
+                    .cells_at(origin)
+                    .map(|p| p.row * region.width + p.col)
+                    .collect();
+                (i, cells)
+            })
+            .collect();
+        Self {
+            width: region.width,
+            height: region.height,
+            placements,
+        }
+    }
+
+    /// The placement covering each cell, if any, checking that placements
+    /// stay inside the region and never overlap
+    fn cover(&self) -> Result<Vec<Option<usize>>> {
+        let mut cover = vec![None; self.width * self.height];
+        for (k, (_, cells)) in self.placements.iter().enumerate() {
+            for &c in cells {
+                match cover.get_mut(c) {
+                    None => bail!("Piece {k} is placed outside the region"),
+                    Some(Some(other)) => bail!("Pieces {other} and {k} overlap"),
+                    Some(cell @ None) => *cell = Some(k),
+                }
+            }
+        }
+        Ok(cover)
+    }
+
+    /// The region as text, with a letter for every placed piece and `.` for
+    /// empty cells, colored by piece shape if `color` is set
+    fn render(&self, color: bool) -> Result<String> {
+        let cover = self.cover()?;
+        let mut text = String::new();
+        for row in cover.chunks(self.width.max(1)) {
+            for &cell in row {
+                let Some(k) = cell else {
+                    text.push_str(&if color {
+                        paint('.', Color::Gray)
+                    } else {
+                        ".".to_string()
+                    });
+                    continue;
+                };
+                let letter = char::from(Self::LETTERS[k % Self::LETTERS.len()]);
+                if color {
+                    let piece = self.placements[k].0;
+                    write!(
+                        text,
+                        "{}",
+                        paint(letter, Self::COLORS[piece % Self::COLORS.len()])
+                    )?;
+                } else {
+                    text.push(letter);
+                }
+            }
+            text.push('\n');
+        }
+        Ok(text)
+    }
+}
+
+/// One stage of the feasibility pipeline
+trait Check: Sync {
+    /// Name reported when this check decides a region
+    fn name(&self) -> &'static str;
+
+    /// Whether the pieces fit into the region, or `None` if this check cannot
+    /// tell
+    fn decide(&self, region: &Region, pieces: &[Piece]) -> Option<bool>;
+}
+
+/// The pieces cannot cover more cells than the region has
+struct AreaBound;
+
+impl Check for AreaBound {
+    fn name(&self) -> &'static str {
+        "area"
+    }
+
+    fn decide(&self, region: &Region, pieces: &[Piece]) -> Option<bool> {
+        let needed = region.copies(pieces).map(|p| p.size).sum::<usize>();
+        (needed > region.area()).then_some(false)
+    }
+}
+
+/// The pieces trivially fit if each gets its own square box in the region
+struct BoxFit;
+
+impl Check for BoxFit {
+    fn name(&self) -> &'static str {
+        "boxes"
+    }
+
+    fn decide(&self, region: &Region, pieces: &[Piece]) -> Option<bool> {
+        let side = region.copies(pieces).map(|p| p.side).max().unwrap_or(1);
+        let boxes = (region.width / side) * (region.height / side);
+        (region.copies(pieces).count() <= boxes).then_some(true)
+    }
+}
+
+/// Every piece covers a fixed surplus of one checkerboard color over the
+/// other, so the pieces can only fit if the surpluses can be signed to
+/// leave enough cells of both colors
+struct Parity;
+
+impl Check for Parity {
+    fn name(&self) -> &'static str {
+        "parity"
+    }
+
+    fn decide(&self, region: &Region, pieces: &[Piece]) -> Option<bool> {
+        let (black, white) = (region.area().div_ceil(2), region.area() / 2);
+        let needed = region.copies(pieces).map(|p| p.size).sum::<usize>();
+        let imbalances = region
+            .copies(pieces)
+            .map(|p| p.imbalance)
+            .collect::<Vec<_>>();
+        let total = imbalances.iter().sum::<usize>();
+        // Flipping the pieces whose imbalances sum to `flipped` covers
+        // `(needed + total) / 2 - flipped` black cells, and the rest white
+        let fits = subset_sums(&imbalances, total).iter_ones().any(|flipped| {
+            let covered_black = needed.midpoint(total) - flipped;
+            covered_black <= black && needed - covered_black <= white
+        });
+        (!fits).then_some(false)
+    }
+}
+
+/// Backtracking search over placements, always branching on the free cell
+/// that the fewest placements can still cover, which is either covered by
+/// one of them or left empty
+struct ExactSearch;
+
+/// State of a single exact search, with cells flattened in reading order
+struct Search {
+    /// Every way to place every piece, as the piece and the cells it covers
+    placements: Vec<(usize, Vec<usize>)>,
+    /// Whether each cell is covered or given up as empty
+    occupied: Vec<bool>,
+    /// Copies of each piece still to place
+    counts: Vec<u8>,
+    /// Placements taken so far, which make up the packing once it succeeds
+    placed: Vec<usize>,
+}
+
+impl Search {
+    fn new(region: &Region, pieces: &[Piece]) -> Self {
+        let (rows, cols) = (region.height, region.width);
+        let mut placements = Vec::new();
+        for (i, piece) in pieces.iter().enumerate() {
+            for shape in &piece.orientations {
+                let (height, width) = shape.dim();
+                for row in 0..=rows.saturating_sub(height) {
+                    for col in 0..=cols.saturating_sub(width) {
+                        let cells = shape
+                            .cells_at(UPoint::new(row, col))
+                            .filter(|p| p.row < rows && p.col < cols)
+                            .map(|p| p.row * cols + p.col)
+                            .collect::<Vec<_>>();
+                        if cells.len() == shape.len() {
+                            placements.push((i, cells));
+                        }
+                    }
+                }
+            }
+        }
+        Self {
+            placements,
+            occupied: vec![false; rows * cols],
+            counts: region.counts.clone(),
+            placed: Vec::new(),
+        }
+    }
+
+    /// Whether `remaining` more pieces fit, leaving at most `slack` more cells
+    /// empty. Only placements among `candidates` can still be available,
+    /// as the search only ever takes cells and pieces away.
+    fn search(&mut self, candidates: &[usize], remaining: usize, slack: usize) -> bool {
+        if remaining == 0 {
+            return true;
+        }
+        // Count the placements still available over each free cell
+        let available = candidates
+            .iter()
+            .copied()
+            .filter(|&p| {
+                let (piece, cells) = &self.placements[p];
+                self.counts[*piece] > 0 && cells.iter().all(|&c| !self.occupied[c])
+            })
+            .collect::<Vec<_>>();
+        let mut cover = vec![0usize; self.occupied.len()];
+        for &p in &available {
+            for &c in &self.placements[p].1 {
+                cover[c] += 1;
+            }
+        }
+        // Free cells that nothing covers any more are bound to stay empty
+        let forced = (0..self.occupied.len())
+            .filter(|&c| !self.occupied[c] && cover[c] == 0)
+            .collect::<Vec<_>>();
+        let Some(slack) = slack.checked_sub(forced.len()) else {
+            return false;
+        };
+        let Some(cell) = (0..self.occupied.len())
+            .filter(|&c| !self.occupied[c] && cover[c] > 0)
+            .min_by_key(|&c| cover[c])
+        else {
+            return false;
+        };
+        self.set(&forced, true);
+        let fits = self.branch(cell, &available, remaining, slack);
+        self.set(&forced, false);
+        fits
+    }
+
+    /// Cover `cell` with each of the `available` placements in turn, or
+    /// leave it empty
+    fn branch(&mut self, cell: usize, available: &[usize], remaining: usize, slack: usize) -> bool {
+        for &p in available {
+            let (piece, cells) = &self.placements[p];
+            if !cells.contains(&cell) {
+                continue;
+            }
+            let piece = *piece;
+            let cells = cells.clone();
+            self.set(&cells, true);
+            self.counts[piece] -= 1;
+            self.placed.push(p);
+            let fits = self.search(available, remaining - 1, slack);
+            self.counts[piece] += 1;
+            self.set(&cells, false);
+            if fits {
+                return true;
+            }
+            self.placed.pop();
+        }
+        if slack == 0 {
+            return false;
+        }
+        self.occupied[cell] = true;
+        let fits = self.search(available, remaining, slack - 1);
+        self.occupied[cell] = false;
+        fits
+    }
+
+    fn set(&mut self, cells: &[usize], value: bool) {
+        for &c in cells {
+            self.occupied[c] = value;
+        }
+    }
+}
+
+impl ExactSearch {
+    /// A packing of the pieces into the region, or `None` if there is none
+    fn pack(region: &Region, pieces: &[Piece]) -> Option<Packing> {
+        let needed = region.copies(pieces).map(|p| p.size).sum::<usize>();
+        let slack = region.area().checked_sub(needed)?;
+        let remaining = region.copies(pieces).count();
+        let mut search = Search::new(region, pieces);
+        let candidates = (0..search.placements.len()).collect::<Vec<_>>();
+        if !search.search(&candidates, remaining, slack) {
+            return None;
+        }
+        let placements = search
+            .placed
+            .iter()
+            .map(|&p| search.placements[p].clone())
+            .collect();
+        Some(Packing {
+            width: region.width,
+            height: region.height,
+            placements,
+        })
+    }
+}
+
+impl Check for ExactSearch {
+    fn name(&self) -> &'static str {
+        "search"
+    }
+
+    fn decide(&self, region: &Region, pieces: &[Piece]) -> Option<bool> {
+        Some(Self::pack(region, pieces).is_some())
+    }
+}
+
+/// Checks from cheapest to most expensive, ending with one that always decides
+const PIPELINE: [&dyn Check; 4] = [&AreaBound, &BoxFit, &Parity, &ExactSearch];
+
 struct Puzzle {
-    pieces: Vec<Array2<u8>>,
-    regions: Vec<(u8, u8, Vec<u8>)>,
+    pieces: Vec<Piece>,
+    regions: Vec<Region>,
 }
 
 impl Puzzle {
-    fn parse_piece(input: &str) -> Result<Array2<u8>> {
+    fn parse_piece(input: &str) -> Result<Piece> {
         let Some((_, shape)) = input.split_once('\n') else {
             anyhow::bail!("Invalid piece input")
         };
-        parse_char_grid(shape, |c| match c {
+        let grid = parse_char_grid(shape, |c| match c {
             '.' => Ok(0),
             '#' => Ok(1),
             _ => anyhow::bail!("Invalid character in piece"),
-        })
+        })?;
+        Ok(Piece::new(&Polyomino::from_grid(&grid)))
     }
 
-    fn parse_regions(input: &str) -> Result<(u8, u8, Vec<u8>)> {
+    fn parse_regions(input: &str) -> Result<Region> {
         let Some((shape, counts)) = input.split_once(": ") else {
             anyhow::bail!("Invalid region input: {input}")
         };
@@ -31,7 +416,11 @@ impl Puzzle {
         };
         let (width, height) = (width.parse()?, height.parse()?);
         let counts = parse_whitespace_separated(counts, str::parse)?;
-        Ok((width, height, counts))
+        Ok(Region {
+            width,
+            height,
+            counts,
+        })
     }
 
     fn new(example: bool) -> Result<Self> {
@@ -50,6 +439,40 @@ impl Puzzle {
         let regions = parse_lines(regions, Self::parse_regions)?;
         Ok(Self { pieces, regions })
     }
+
+    /// Run `region` through the pipeline, returning whether the pieces fit
+    /// and the name of the check that decided it
+    fn decide(&self, region: &Region) -> (bool, &'static str) {
+        PIPELINE
+            .iter()
+            .find_map(|check| Some((check.decide(region, &self.pieces)?, check.name())))
+            .unwrap_or_else(|| unreachable!("The exact search always decides"))
+    }
+
+    /// An actual packing of `region`, or `None` if the pieces cannot fit.
+    ///
+    /// Regions the box check accepts get their boxes, the ones the other
+    /// cheap checks reject get nothing, and the rest go to the exact search.
+    fn packing(&self, region: &Region) -> Option<Packing> {
+        if BoxFit.decide(region, &self.pieces) == Some(true) {
+            return Some(Packing::boxes(region, &self.pieces));
+        }
+        let rejected = [&AreaBound as &dyn Check, &Parity]
+            .iter()
+            .any(|check| check.decide(region, &self.pieces) == Some(false));
+        if rejected {
+            return None;
+        }
+        ExactSearch::pack(region, &self.pieces)
+    }
+
+    /// Decision for every region, in input order
+    fn decisions(&self) -> Vec<(bool, &'static str)> {
+        self.regions
+            .maybe_par_iter()
+            .map(|region| self.decide(region))
+            .collect()
+    }
 }
 
 impl Solution for Puzzle {
@@ -59,40 +482,23 @@ impl Solution for Puzzle {
         Self::new(example).unwrap_or_else(|e| panic!("Failed to parse input: {e}"))
     }
 
-    /// TBH, I had the feeling that this is too hard for a general case, so some
-    /// simple heuristic like testing for capacity might be useful to reduce the
-    /// number of searches. I just couldn't convince myself that this naive and
-    /// stupid approach may be the final solution. And even after some Googling,
-    /// I'm still not sure how to solve this in practice. Bin-packing is
-    /// NP-hard, and I don't know how to solve it efficiently.
+    /// Bin-packing is NP-hard, so instead of a single exact solver every region
+    /// goes through a pipeline of increasingly expensive checks, and the first
+    /// one that can tell decides:
     ///
-    /// - Brute force might work for small inputs, but it's not a viable
-    ///   solution for larger inputs
-    /// - Search algorithms like A* might be a good choice, but the heuristics
-    ///   are not trivial to come up with
-    /// - Genetic algorithms might be another option, but given the state space
-    ///   (which is quite large, ~300 coordinates * at most 8
-    ///   rotations/flipping), the population size and the number of generations
-    ///   would be massive, and the performance would be questionable.
-    /// - Constraint programming might be a good choice and the constraints
-    ///   seems approachable, but given the size of the state space, I don't
-    ///   think it's feasible for ANY solver to handle this in a reasonable
-    ///   amount of time.
+    /// 1. If the pieces need more cells than the region has, they cannot fit.
+    /// 2. If every piece gets its own square box, they trivially fit.
+    /// 3. On a checkerboard, each piece covers a fixed surplus of one color,
+    ///    and if no choice of signs leaves enough cells of both colors, they
+    ///    cannot fit.
+    /// 4. Otherwise, an exact backtracking search settles it.
     ///
-    ///  I'm not sure if there's a better way to ACTUALLY solve this problem.
-    /// Hate to say it, but I think this problem is just not solvable in a
-    /// reasonable amount of time.
+    /// The real inputs are decided by the first two checks alone, while the
+    /// search keeps the answer correct on the tricky example regions.
     fn part1(&self) -> String {
-        self.regions
-            .par_iter()
-            .filter(|(width, height, counts)| {
-                counts
-                    .iter()
-                    .zip(self.pieces.iter())
-                    .map(|(&c, s)| u64::from(c) * u64::from(s.sum()))
-                    .sum::<u64>()
-                    <= u64::from(*width) * u64::from(*height)
-            })
+        self.decisions()
+            .into_iter()
+            .filter(|&(fits, _)| fits)
             .count()
             .to_string()
     }
@@ -106,9 +512,37 @@ impl Solution for Puzzle {
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let args = diagnostics::args();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    match args.first().map(String::as_str) {
+        Some("--render") => {
+            for (i, region) in puzzle.regions.iter().enumerate() {
+                let (width, height) = (region.width, region.height);
+                match puzzle.packing(region) {
+                    Some(packing) => {
+                        println!("Region {i} ({width}x{height}):\n{}", packing.render(true)?);
+                    }
+                    None => println!("Region {i} ({width}x{height}): pieces do not fit\n"),
+                }
+            }
+            return Ok(());
+        }
+        Some(other) => bail!("Unknown argument {other}, expected --render"),
+        None => {}
+    }
+    println!(
+        "Day {} Part 1: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 1, || puzzle.part1())
+    );
+    println!(
+        "Day {} Part 2: {}",
+        Puzzle::DAY,
+        trace::part(Puzzle::DAY, 2, || puzzle.part2())
+    );
 
     Ok(())
 }
@@ -117,29 +551,96 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use util::testing::assert_answer;
 
     use super::*;
 
     #[test]
     fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        // Well... I guess this is not a good test case...
-        // The example input would require a different solution, but I haven't ACTUALLY
-        // implemented it. I just cheated on this one.
-        assert_eq!(puzzle.part1(), "3");
+        assert_answer!(Puzzle, part1, example);
+        // None of the example regions is easy enough for the cheap checks
+        assert_eq!(
+            Puzzle::new(true)?.decisions(),
+            vec![(true, "search"), (true, "search"), (false, "search")]
+        );
         Ok(())
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
+    fn test_cheap_checks() {
+        let region = |width, height, counts| Region {
+            width,
+            height,
+            counts,
+        };
+        let t_piece = Polyomino::new([(0, 0), (0, 1), (0, 2), (1, 1)].map(UPoint::from));
+        let puzzle = Puzzle {
+            pieces: vec![Piece::new(&t_piece)],
+            regions: vec![
+                region(3, 3, vec![3]),
+                region(6, 6, vec![4]),
+                // Five T-tetrominoes never tile a 4x5 rectangle
+                region(4, 5, vec![5]),
+                region(4, 4, vec![4]),
+            ],
+        };
+        assert_eq!(
+            puzzle.decisions(),
+            vec![
+                (false, "area"),
+                (true, "boxes"),
+                (false, "parity"),
+                (true, "search")
+            ]
+        );
+    }
+
+    /// Every packing covers each piece's cells exactly once, and holds as
+    /// many copies of each piece as the region asks for
+    #[test]
+    fn test_packing() -> Result<()> {
         let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "Final star on top of the tree");
+        let boxed = Region {
+            width: 10,
+            height: 4,
+            counts: vec![1, 0, 2, 0, 0, 0],
+        };
+        for region in puzzle.regions.iter().chain([&boxed]) {
+            let Some(packing) = puzzle.packing(region) else {
+                assert!(!puzzle.decide(region).0);
+                continue;
+            };
+            assert!(puzzle.decide(region).0);
+            let text = packing.render(false)?;
+            assert_eq!(text.lines().count(), region.height);
+            assert!(text.lines().all(|line| line.len() == region.width));
+            let mut counts = vec![0u8; region.counts.len()];
+            for (k, &(piece, _)) in packing.placements.iter().enumerate() {
+                counts[piece] += 1;
+                let letter = char::from(Packing::LETTERS[k]);
+                assert_eq!(text.matches(letter).count(), puzzle.pieces[piece].size);
+            }
+            assert_eq!(counts, region.counts);
+        }
+        let overlapping = Packing {
+            width: 2,
+            height: 2,
+            placements: vec![(0, vec![0, 1]), (0, vec![1, 3])],
+        };
+        assert_eq!(
+            overlapping.render(false).map_err(|e| e.to_string()),
+            Err("Pieces 0 and 1 overlap".to_string())
+        );
         Ok(())
     }
 
+    #[test]
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
+    }
+
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        Ok(Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)?)
     }
 }
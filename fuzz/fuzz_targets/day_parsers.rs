@@ -0,0 +1,17 @@
+//! The parsers of the days reject malformed inputs with errors, not panics.
+//!
+//! The first byte picks the day, the rest is its input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+/// Days with parsers worth fuzzing: day 10's machines with their bracketed
+/// lists, and day 12's pieces and regions
+const DAYS: [u8; 2] = [10, 12];
+
+fuzz_target!(|data: (u8, &str)| {
+    let (pick, input) = data;
+    let day = DAYS[usize::from(pick) % DAYS.len()];
+    let _ = solutions::parse(day, input);
+});
@@ -0,0 +1,12 @@
+//! Any text either parses as a rectangular grid of characters or is rejected
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use util::reader::parse_char_grid;
+
+fuzz_target!(|input: &str| {
+    if let Ok(grid) = parse_char_grid(input, anyhow::Ok) {
+        assert_eq!(grid.len(), input.lines().map(|line| line.chars().count()).sum());
+    }
+});
@@ -0,0 +1,13 @@
+//! Column widths never make the fixed-width parser slice out of bounds or
+//! through a character
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use util::reader::parse_fixed_width_grid;
+
+fuzz_target!(|data: (Vec<u8>, &str)| {
+    let (widths, input) = data;
+    let widths = widths.into_iter().map(usize::from).collect::<Vec<_>>();
+    let _ = parse_fixed_width_grid(input, &widths, |s| anyhow::Ok(s.len()));
+});
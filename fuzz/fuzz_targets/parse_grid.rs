@@ -0,0 +1,12 @@
+//! Any text either parses as a rectangular grid of numbers or is rejected
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use util::reader::parse_grid;
+
+fuzz_target!(|input: &str| {
+    if let Ok(grid) = parse_grid(input, str::parse::<i64>) {
+        assert_eq!(grid.nrows(), input.lines().count());
+    }
+});
@@ -0,0 +1,195 @@
+//! Perturbed copies of real inputs, to share an input that triggers a bug
+//! without redistributing the input itself, which the site asks not to do.
+//!
+//! Each supported day changes what makes an input someone's own while keeping
+//! what the solution relies on, so the copy is still a valid input of the same
+//! size and shape. The answers change, but a bug usually survives.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    iter,
+};
+
+use anyhow::{Result, anyhow, bail};
+use util::{aoc_api::read_input, rng::Rng, writer::write_output};
+
+use crate::Flags;
+
+/// Days with an anonymizer
+const DAYS: [u8; 4] = [3, 8, 9, 11];
+
+/// Machines of day 11 that the puzzle refers to by name
+const KEPT_NAMES: [&str; 5] = ["you", "svr", "dac", "fft", "out"];
+
+/// Every digit of every bank replaced by a random one, as no bank means
+/// anything beyond its digits
+fn banks(input: &str, rng: &mut Rng) -> Result<String> {
+    input
+        .lines()
+        .map(|line| {
+            if let Some(c) = line.chars().find(|c| !matches!(c, '1'..='9')) {
+                bail!("Unexpected character {c:?} in bank {line}");
+            }
+            Ok(line
+                .chars()
+                .map(|_| char::from(b"123456789"[rng.below(9)]))
+                .chain(['\n'])
+                .collect::<String>())
+        })
+        .collect()
+}
+
+/// Values of one axis moved by a few percent of their largest, keeping them
+/// non-negative, distinct where they were and in the same order, so that
+/// shapes drawn by the points keep their turns and crossings
+fn jitter(values: impl IntoIterator<Item = i64>, rng: &mut Rng) -> BTreeMap<i64, i64> {
+    let values = values.into_iter().collect::<BTreeSet<_>>();
+    let spread = values.last().map_or(1, |&max| (max / 50).max(1));
+    let mut moved = BTreeMap::new();
+    let mut previous = -1;
+    for value in values {
+        let next = (value + rng.between(-spread, spread)).max(previous + 1);
+        moved.insert(value, next);
+        previous = next;
+    }
+    moved
+}
+
+/// Comma separated points, every axis jittered on its own
+fn points(input: &str, rng: &mut Rng) -> Result<String> {
+    let points = input
+        .lines()
+        .map(|line| {
+            line.split(',')
+                .map(|x| {
+                    x.trim()
+                        .parse::<i64>()
+                        .map_err(|e| anyhow!("Invalid coordinate {x:?} in {line}: {e}"))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let dimensions = points.first().map_or(0, Vec::len);
+    if points.iter().any(|point| point.len() != dimensions) {
+        bail!("Points do not all have {dimensions} coordinates");
+    }
+    let axes = (0..dimensions)
+        .map(|axis| jitter(points.iter().map(|point| point[axis]), rng))
+        .collect::<Vec<_>>();
+    Ok(points
+        .iter()
+        .map(|point| {
+            let moved = point.iter().zip(&axes).map(|(x, axis)| axis[x].to_string());
+            moved.collect::<Vec<_>>().join(",") + "\n"
+        })
+        .collect())
+}
+
+/// A random lowercase name of three letters
+fn name(rng: &mut Rng) -> String {
+    (0..3)
+        .map(|_| char::from(b"abcdefghijklmnopqrstuvwxyz"[rng.below(26)]))
+        .collect()
+}
+
+/// Machines renamed at random, except the ones the puzzle names, with the
+/// lines and the machines on each of them shuffled
+fn machines(input: &str, rng: &mut Rng) -> Result<String> {
+    let mut names = KEPT_NAMES
+        .iter()
+        .map(|&name| (name.to_string(), name.to_string()))
+        .collect::<BTreeMap<_, _>>();
+    let mut taken = KEPT_NAMES
+        .map(String::from)
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let mut lines = Vec::new();
+    for line in input.lines() {
+        let Some((machine, outputs)) = line.split_once(':') else {
+            bail!("Missing ':' in {line}");
+        };
+        let mut line = Vec::new();
+        for machine in iter::once(machine).chain(outputs.split_whitespace()) {
+            let renamed = names.entry(machine.trim().to_string()).or_insert_with(|| {
+                let mut renamed = name(rng);
+                while !taken.insert(renamed.clone()) {
+                    renamed = name(rng);
+                }
+                renamed
+            });
+            line.push(renamed.clone());
+        }
+        rng.shuffle(&mut line[1..]);
+        lines.push(format!("{}: {}\n", line[0], line[1..].join(" ")));
+    }
+    rng.shuffle(&mut lines);
+    Ok(lines.concat())
+}
+
+/// A perturbed copy of `input`, the input of `day`, the same for the same
+/// `seed`
+fn anonymize(day: u8, input: &str, seed: u64) -> Result<String> {
+    let rng = &mut Rng::new(seed);
+    match day {
+        3 => banks(input, rng),
+        8 | 9 => points(input, rng),
+        11 => machines(input, rng),
+        _ => bail!(
+            "Day {day} has no anonymizer, only days {} do",
+            DAYS.map(|day| day.to_string()).join(", ")
+        ),
+    }
+}
+
+/// `anonymize --day N [--seed S]`: write a perturbed copy of the real input of
+/// a day to `outputs/anonymized-dayNN.txt`
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["day", "seed"])?;
+    let day = flags.day()?;
+    let seed = flags.get("seed")?.unwrap_or_default();
+    let contents = anonymize(day, &read_input(day)?, seed)?;
+    let path = write_output(day, "anonymized", "txt", &contents)?;
+    println!("Anonymized input written to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anonymize() -> Result<()> {
+        let banks = anonymize(3, "987654321111111\n811111111111119\n", 1)?;
+        assert_ne!(banks, "987654321111111\n811111111111119\n");
+        assert_eq!(banks.lines().map(str::len).collect::<Vec<_>>(), [15, 15]);
+        assert!(banks.lines().all(|bank| !bank.contains('0')));
+        assert_eq!(
+            banks,
+            anonymize(3, "987654321111111\n811111111111119\n", 1)?
+        );
+        // The tiles keep their order on both axes, and which share an axis
+        let tiles = anonymize(9, "7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3\n", 2)?;
+        let tiles = tiles
+            .lines()
+            .filter_map(|line| line.split_once(','))
+            .map(|(x, y)| Ok((x.parse::<i64>()?, y.parse::<i64>()?)))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(tiles.len(), 8);
+        assert!(tiles[0].1 == tiles[1].1 && tiles[1].0 == tiles[2].0);
+        assert!(tiles[0].0 < tiles[1].0 && tiles[5].0 < tiles[0].0);
+        assert!(tiles.iter().all(|&(x, y)| x >= 0 && y >= 0));
+        // The named machines stay, and so does the shape of the graph
+        let graph = anonymize(11, "you: bbb ccc\nbbb: out\nccc: bbb out\n", 3)?;
+        assert!(!graph.contains("bbb") && !graph.contains("ccc"));
+        let mut degrees = graph
+            .lines()
+            .map(|line| line.split_whitespace().count())
+            .collect::<Vec<_>>();
+        degrees.sort_unstable();
+        assert_eq!(degrees, [2, 3, 3]);
+        assert!(graph.contains("you: "));
+        assert!(anonymize(4, "..@@\n", 0).is_err());
+        assert!(anonymize(8, "1,2,3\n4,5\n", 0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+//! Benchmarks of two revisions side by side, as saved in `outputs` by the
+//! `benchmark` test of every day, with how much faster or slower each phase
+//! got.
+//!
+//! Benchmarks can also be saved to a directory of their own, to compare builds
+//! of the same revision, such as with another global allocator.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+use anyhow::{Result, bail};
+use util::{
+    allocator, get_workspace_root,
+    timer::BenchmarkResult,
+    writer::{Align, CsvEntry, Table},
+};
+
+use crate::Flags;
+
+/// Where saved benchmarks are read from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// A directory of benchmark CSVs, such as `outputs` in the working tree
+    Dir(PathBuf),
+    /// The `outputs` directory of a git revision
+    Rev(String),
+}
+
+impl Source {
+    /// A directory if there is one at `spec`, a git revision otherwise
+    fn parse(spec: &str, root: &Path) -> Result<Self> {
+        if Path::new(spec).is_dir() {
+            return Ok(Self::Dir(spec.into()));
+        }
+        let status = Command::new("git")
+            .args([
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{spec}^{{commit}}"),
+            ])
+            .current_dir(root)
+            .output()?
+            .status;
+        if !status.success() {
+            bail!("{spec} is neither a directory nor a git revision");
+        }
+        Ok(Self::Rev(spec.to_string()))
+    }
+
+    /// The saved benchmarks of a day, if there are any
+    pub fn benchmarks(&self, root: &Path, day: u8) -> Result<Option<Vec<BenchmarkResult>>> {
+        let file = format!("benchmark-day{day:02}.csv");
+        let content = match self {
+            Self::Dir(dir) => {
+                let path = dir.join(file);
+                if !path.exists() {
+                    return Ok(None);
+                }
+                fs::read_to_string(path)?
+            }
+            Self::Rev(rev) => {
+                let output = Command::new("git")
+                    .args(["show", &format!("{rev}:outputs/{file}")])
+                    .current_dir(root)
+                    .output()?;
+                if !output.status.success() {
+                    return Ok(None);
+                }
+                String::from_utf8(output.stdout)?
+            }
+        };
+        Ok(Some(BenchmarkResult::from_csv(&content)?))
+    }
+}
+
+/// `median ± mad` in the unit the benchmark was saved in
+fn timing(result: &BenchmarkResult) -> String {
+    let format = result.human_readable_format();
+    format!("{} ± {}", format(result.median), format(result.mad))
+}
+
+/// How many times faster `head` is than `base`, or slower, by their medians,
+/// with the uncertainty carried over from their deviations as hyperfine does
+fn change(base: &BenchmarkResult, head: &BenchmarkResult) -> String {
+    let (b, h) = (base.median.as_secs_f64(), head.median.as_secs_f64());
+    if b == 0.0 || h == 0.0 {
+        return String::new();
+    }
+    let ratio = b / h;
+    let spread = ratio * (base.mad.as_secs_f64() / b).hypot(head.mad.as_secs_f64() / h);
+    if ratio >= 1.0 {
+        format!("{ratio:.2} ± {spread:.2} times faster")
+    } else {
+        // The uncertainty of the inverse ratio, to first order
+        let spread = spread / ratio / ratio;
+        format!("{:.2} ± {spread:.2} times slower", 1.0 / ratio)
+    }
+}
+
+/// A row for every phase of every day benchmarked on either side, with the
+/// timings of `base` and `head` in columns named after them
+fn compare(
+    days: impl IntoIterator<Item = (u8, Vec<BenchmarkResult>, Vec<BenchmarkResult>)>,
+    names: [&str; 2],
+) -> Table {
+    let mut table = Table::new(["Day", "Phase", names[0], names[1], "Change"])
+        .align(0, Align::Right)
+        .align(2, Align::Right)
+        .align(3, Align::Right)
+        .align(4, Align::Right);
+    for (day, base, head) in days {
+        let mut phases = base.iter().map(|r| &r.name).collect::<Vec<_>>();
+        for result in &head {
+            if !phases.contains(&&result.name) {
+                phases.push(&result.name);
+            }
+        }
+        for phase in phases {
+            let base = base.iter().find(|r| &r.name == phase);
+            let head = head.iter().find(|r| &r.name == phase);
+            table.row([
+                day.to_string(),
+                phase.clone(),
+                base.map(timing).unwrap_or_default(),
+                head.map(timing).unwrap_or_default(),
+                base.zip(head)
+                    .map(|(base, head)| change(base, head))
+                    .unwrap_or_default(),
+            ]);
+        }
+    }
+    table
+}
+
+/// `bench-compare --base REV|DIR [--head REV|DIR] [--format text|markdown]`:
+/// compare the saved benchmarks of two revisions, or of directories of
+/// benchmark CSVs, against the working tree by default
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["base", "head", "format"])?;
+    let format = flags.format()?;
+    let root = get_workspace_root()?;
+    let base_spec = flags.require::<String>("base")?;
+    let base = Source::parse(&base_spec, &root)?;
+    let (head_spec, head) = match flags.get::<String>("head")? {
+        Some(spec) => (spec.clone(), Source::parse(&spec, &root)?),
+        None => (
+            "working tree".to_string(),
+            Source::Dir(root.join("outputs")),
+        ),
+    };
+    let mut days = Vec::new();
+    for day in 1..=25 {
+        let (base, head) = (base.benchmarks(&root, day)?, head.benchmarks(&root, day)?);
+        if base.is_some() || head.is_some() {
+            days.push((day, base.unwrap_or_default(), head.unwrap_or_default()));
+        }
+    }
+    if days.is_empty() {
+        bail!("No saved benchmarks in {base_spec} or {head_spec}");
+    }
+    print!(
+        "{}",
+        format.render(&compare(days, [&base_spec, &head_spec]))
+    );
+    Ok(())
+}
+
+/// The benchmarks as the `benchmark` test of a day saves them
+fn to_csv(results: &[BenchmarkResult]) -> String {
+    let lines = results.iter().map(|result| result.values().join(","));
+    std::iter::once(BenchmarkResult::columns().join(","))
+        .chain(lines)
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// `bench-save --dir DIR [--seconds S]`: benchmark every day for about `S`
+/// seconds per phase, and save the results to `DIR` to compare them with
+/// `bench-compare`
+pub fn save(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["dir", "seconds"])?;
+    let dir = PathBuf::from(flags.require::<String>("dir")?);
+    let time_limit = Duration::try_from_secs_f64(flags.get("seconds")?.unwrap_or(1.0))?;
+    fs::create_dir_all(&dir)?;
+    for solution in solutions::all() {
+        let day = solution.day();
+        let results = solution.bench(time_limit);
+        fs::write(
+            dir.join(format!("benchmark-day{day:02}.csv")),
+            to_csv(&results),
+        )?;
+        println!(
+            "Day {day:02} benchmarked with the {} allocator",
+            allocator::NAME
+        );
+    }
+    println!("Benchmarks saved to {}", dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn result(name: &str, median: u64, mad: u64) -> BenchmarkResult {
+        BenchmarkResult {
+            name: name.to_string(),
+            time_limit: Duration::from_secs(1),
+            iterations: 1000,
+            fastest: Duration::from_micros(median - mad),
+            slowest: Duration::from_micros(median + mad),
+            mean: Duration::from_micros(median),
+            std_dev: Duration::from_micros(mad),
+            median: Duration::from_micros(median),
+            mad: Duration::from_micros(mad),
+            samples: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let days = [
+            (
+                1,
+                vec![result("Parse", 30, 3), result("Part 1", 10, 1)],
+                vec![result("Parse", 10, 1), result("Part 1", 20, 2)],
+            ),
+            (2, Vec::new(), vec![result("Part 2", 50, 5)]),
+        ];
+        assert_eq!(
+            compare(days, ["v1", "HEAD"]).to_text(),
+            "\
+Day  Phase                   v1                HEAD                    Change
+---  ------  ------------------  ------------------  ------------------------
+  1  Parse   30.000µs ± 3.000µs  10.000µs ± 1.000µs  3.00 ± 0.42 times faster
+  1  Part 1  10.000µs ± 1.000µs  20.000µs ± 2.000µs  2.00 ± 0.28 times slower
+  2  Part 2                      50.000µs ± 5.000µs
+"
+        );
+    }
+
+    #[test]
+    fn test_sources() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("bench-compare-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join("benchmark-day03.csv"),
+            "name,iterations,time_limit,fastest,slowest,mean,std_dev,median,mad\n\
+             Parse,10,1s,1µs,3µs,2µs,1µs,2µs,1µs\n",
+        )?;
+        let root = get_workspace_root()?;
+        let source = Source::parse(&dir.to_string_lossy(), &root)?;
+        assert_eq!(source, Source::Dir(dir.clone()));
+        assert_eq!(source.benchmarks(&root, 3)?.map(|r| r.len()), Some(1));
+        assert!(source.benchmarks(&root, 4)?.is_none());
+        assert!(Source::parse("no such revision", &root).is_err());
+        // Saved benchmarks read back as they were
+        let results = [result("Parse", 30, 3), result("Part 1", 10, 1)];
+        fs::write(dir.join("benchmark-day05.csv"), to_csv(&results))?;
+        let saved = source.benchmarks(&root, 5)?.unwrap_or_default();
+        assert_eq!(
+            saved
+                .iter()
+                .map(|r| (&r.name, r.median))
+                .collect::<Vec<_>>(),
+            results
+                .iter()
+                .map(|r| (&r.name, r.median))
+                .collect::<Vec<_>>()
+        );
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! The speedrun workflow: have the day ready before its puzzle unlocks, then
+//! fetch the input and run the solution the moment it does.
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::Path,
+    process::Command,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Result, bail};
+use util::{
+    aoc_api::{Client, until_unlock},
+    reader::sniff,
+};
+
+use crate::{Flags, sniff::skeleton};
+
+/// Attempts at fetching the input, as it can lag a moment behind the unlock
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+
+/// Manifest of a new day, the same as `cargo add anyhow util` leaves it, with
+/// the answers of the examples checked by its tests
+const MANIFEST: &str = r#"[package]
+name = "day000000"
+version = "0.1.0"
+edition = "2024"
+
+[dependencies]
+anyhow.workspace = true
+util.workspace = true
+
+[dev-dependencies]
+util = { workspace = true, features = ["testing"] }
+
+[lints]
+workspace = true
+"#;
+
+/// Time left as `hh:mm:ss`, with the days in front when there are any
+fn remaining(left: Duration) -> String {
+    let seconds = left.as_secs();
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match seconds / 86_400 {
+        0 => clock,
+        days => format!("{days}d {clock}"),
+    }
+}
+
+/// Create the crate of a day from `template/main.rs` under `root`, with
+/// empty input files, as `just new` does; returns whether it was missing
+///
+/// If an input of the day is already there, `new` starts with the reader
+/// call that seems to parse it.
+fn scaffold(root: &Path, day: u8) -> Result<bool> {
+    let name = format!("day{day:02}");
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Ok(false);
+    }
+    let inputs = root.join("inputs");
+    let input = [format!("{name}-example.txt"), format!("{name}.txt")]
+        .iter()
+        .filter_map(|file| fs::read_to_string(inputs.join(file)).ok())
+        .find(|input| !input.trim().is_empty());
+    let mut template = fs::read_to_string(root.join("template/main.rs"))?;
+    if let Some(input) = input {
+        let indent = " ".repeat(8);
+        let lines = skeleton(&input)
+            .into_iter()
+            .map(|line| format!("{indent}{line}\n"));
+        let body = format!("{}{indent}Ok(Self {{}})", lines.collect::<String>());
+        template = template.replacen(&format!("{indent}Ok(Self {{}})"), &body, 1);
+    }
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Cargo.toml"), MANIFEST.replace("day000000", &name))?;
+    fs::write(
+        dir.join("src/main.rs"),
+        template.replace("000000", &day.to_string()),
+    )?;
+    fs::create_dir_all(&inputs)?;
+    for file in [format!("{name}.txt"), format!("{name}-example.txt")] {
+        let path = inputs.join(file);
+        if !path.exists() {
+            fs::write(path, "")?;
+        }
+    }
+    Ok(true)
+}
+
+/// Sleep until the puzzle of a day unlocks, counting down on one line
+fn count_down(year: u16, day: u8) -> Result<()> {
+    let mut stdout = io::stdout();
+    while let Some(left) = until_unlock(year, day) {
+        write!(stdout, "\rDay {day} unlocks in {}  ", remaining(left))?;
+        stdout.flush()?;
+        // Wake up on the second, so the last sleep ends right at the unlock
+        let nanos = left.subsec_nanos();
+        thread::sleep(if nanos == 0 {
+            Duration::from_secs(1)
+        } else {
+            Duration::from_nanos(nanos.into())
+        });
+    }
+    writeln!(stdout, "\rDay {day} is unlocked{}", " ".repeat(16))?;
+    Ok(())
+}
+
+/// `wait-and-run --day N [--editor CMD]`: scaffold the day if needed and open
+/// it in the editor, wait for the unlock, then download the input and run it
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["day", "editor"])?;
+    let day = flags.day()?;
+    let client = Client::new()?;
+    let root = util::get_workspace_root()?;
+    if scaffold(&root, day)? {
+        println!("Created day{day:02} from the template");
+    }
+    if let Some(editor) = flags.get::<String>("editor")? {
+        Command::new(editor)
+            .arg(root.join(format!("day{day:02}/src/main.rs")))
+            .spawn()?;
+    }
+    count_down(client.year(), day)?;
+    let mut attempt = 1;
+    let path = loop {
+        match client.download_input(day) {
+            Ok(path) => break path,
+            Err(e) if attempt < DOWNLOAD_ATTEMPTS => {
+                eprintln!("{e}, trying again");
+                thread::sleep(Duration::from_secs(2));
+                attempt += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    };
+    println!("Input saved to {}", path.display());
+    let shape = sniff(&fs::read_to_string(&path)?);
+    println!("It looks like {}", shape.kind);
+    for anomaly in &shape.anomalies {
+        println!("Warning: {anomaly}");
+    }
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["run", "--release", "-p", &format!("day{day:02}")])
+        .status()?;
+    if !status.success() {
+        bail!("Running day {day} failed");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining() {
+        assert_eq!(remaining(Duration::from_secs(59)), "00:00:59");
+        assert_eq!(remaining(Duration::from_secs(3 * 3600 + 61)), "03:01:01");
+        assert_eq!(remaining(Duration::from_hours(49)), "2d 01:00:00");
+    }
+
+    #[test]
+    fn test_scaffold() -> Result<()> {
+        let root = env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("template"))?;
+        fs::write(root.join("template/main.rs"), "const DAY: u8 = 000000;\n")?;
+        assert!(scaffold(&root, 7)?);
+        assert_eq!(
+            fs::read_to_string(root.join("day07/src/main.rs"))?,
+            "const DAY: u8 = 7;\n"
+        );
+        assert!(fs::read_to_string(root.join("day07/Cargo.toml"))?.contains("name = \"day07\""));
+        assert!(root.join("inputs/day07-example.txt").exists());
+        // An existing day is left alone
+        fs::write(root.join("day07/src/main.rs"), "solved")?;
+        assert!(!scaffold(&root, 7)?);
+        assert_eq!(
+            fs::read_to_string(root.join("day07/src/main.rs"))?,
+            "solved"
+        );
+        // The reader call for an input already there
+        fs::write(
+            root.join("template/main.rs"),
+            "fn new() {\n        Ok(Self {})\n}\n",
+        )?;
+        fs::write(root.join("inputs/day08-example.txt"), "1,2\n3,4\n")?;
+        assert!(scaffold(&root, 8)?);
+        assert!(
+            fs::read_to_string(root.join("day08/src/main.rs"))?.contains(
+                "        let parsed = parse_lines(&input, |line| parse_comma_separated(line, \
+                 str::parse::<i64>))?;\n        Ok(Self {})"
+            )
+        );
+        fs::remove_dir_all(root)?;
+        Ok(())
+    }
+}
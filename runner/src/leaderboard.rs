@@ -0,0 +1,259 @@
+//! Private leaderboards, shown as the stars of every member or as how long
+//! each of them took on a day.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use util::{
+    aoc_api::{Client, unlock_time},
+    term::{Color, paint},
+    writer::{Align, Table},
+};
+
+use crate::{Flags, Format};
+
+/// Environment variable with the id of the leaderboard to show by default
+const LEADERBOARD_ENV: &str = "AOC_LEADERBOARD";
+
+/// When a member got a star, in seconds since the Unix epoch
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct Star {
+    get_star_ts: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Member {
+    id: u64,
+    name: Option<String>,
+    stars: u32,
+    local_score: u64,
+    last_star_ts: u64,
+    /// Stars by day and then by part
+    completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+impl Member {
+    /// The name shown for the member, as the site shows anonymous ones
+    fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    /// When the member got the star of a part of a day
+    fn star(&self, day: u8, part: u8) -> Option<u64> {
+        self.completion_day_level
+            .get(&day)?
+            .get(&part)
+            .map(|star| star.get_star_ts)
+    }
+
+    /// How many parts of a day the member solved
+    fn parts(&self, day: u8) -> usize {
+        self.completion_day_level.get(&day).map_or(0, BTreeMap::len)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Leaderboard {
+    members: BTreeMap<String, Member>,
+}
+
+impl Format {
+    /// The mark of a day with this many parts solved
+    fn stars(self, parts: usize) -> String {
+        match (self, parts) {
+            (Self::Text, 2) => paint("*", Color::Yellow),
+            (Self::Text, 1) => paint("*", Color::Gray),
+            (Self::Text, _) => paint(".", Color::Gray),
+            (Self::Markdown, 2) => "★".to_string(),
+            (Self::Markdown, 1) => "☆".to_string(),
+            (Self::Markdown, _) => String::new(),
+        }
+    }
+}
+
+/// `hh:mm:ss` for times within a day, as the site shows personal times
+fn clock(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds >= 24 * 3600 {
+        return ">24h".to_string();
+    }
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Members with the most points first, ties broken by the earliest last star
+fn ranking(leaderboard: &Leaderboard) -> Vec<&Member> {
+    let mut members = leaderboard.members.values().collect::<Vec<_>>();
+    members.sort_by_key(|m| (u64::MAX - m.local_score, m.last_star_ts, m.id));
+    members
+}
+
+/// Score and stars of every member, with a column of stars for every day
+/// someone has solved
+fn overview(leaderboard: &Leaderboard, format: Format) -> Table {
+    let days = leaderboard
+        .members
+        .values()
+        .filter_map(|m| m.completion_day_level.keys().max().copied())
+        .max()
+        .unwrap_or_default();
+    let headers = ["#", "Name", "Score", "Stars"]
+        .map(String::from)
+        .into_iter()
+        .chain((1..=days).map(|day| day.to_string()));
+    let mut table = Table::new(headers)
+        .align(0, Align::Right)
+        .align(2, Align::Right)
+        .align(3, Align::Right);
+    for day in 1..=days {
+        table = table.align(3 + usize::from(day), Align::Center);
+    }
+    for (rank, member) in ranking(leaderboard).into_iter().enumerate() {
+        let cells = [
+            format!("{})", rank + 1),
+            member.display_name(),
+            member.local_score.to_string(),
+            member.stars.to_string(),
+        ];
+        table.row(
+            cells
+                .into_iter()
+                .chain((1..=days).map(|day| format.stars(member.parts(day)))),
+        );
+    }
+    table
+}
+
+/// How long each member took on both parts of a day after it unlocked,
+/// fastest to solve part 2 first
+fn times(leaderboard: &Leaderboard, year: u16, day: u8) -> Table {
+    let unlock = unlock_time(year, day)
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let mut members = leaderboard
+        .members
+        .values()
+        .filter(|m| m.parts(day) > 0)
+        .collect::<Vec<_>>();
+    members.sort_by_key(|m| {
+        (
+            m.star(day, 2).unwrap_or(u64::MAX),
+            m.star(day, 1).unwrap_or(u64::MAX),
+            m.id,
+        )
+    });
+    let mut table = Table::new(["#", "Name", "Part 1", "Part 2"])
+        .align(0, Align::Right)
+        .align(2, Align::Right)
+        .align(3, Align::Right);
+    let elapsed = |time: Option<u64>| {
+        time.map(|time| clock(Duration::from_secs(time.saturating_sub(unlock))))
+            .unwrap_or_default()
+    };
+    for (rank, member) in members.into_iter().enumerate() {
+        table.row([
+            format!("{})", rank + 1),
+            member.display_name(),
+            elapsed(member.star(day, 1)),
+            elapsed(member.star(day, 2)),
+        ]);
+    }
+    table
+}
+
+/// `leaderboard [--id ID] [--day N] [--format text|markdown]`: show a private
+/// leaderboard, the one in `AOC_LEADERBOARD` unless given
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["id", "day", "format"])?;
+    let id = match flags.get("id")? {
+        Some(id) => id,
+        None => env::var(LEADERBOARD_ENV)
+            .map_err(|_| anyhow!("Missing --id, and {LEADERBOARD_ENV} is not set"))?
+            .trim()
+            .parse()?,
+    };
+    let format = flags.format()?;
+    let client = Client::new()?;
+    let leaderboard = serde_json::from_str::<Leaderboard>(&client.leaderboard(id)?)?;
+    let table = if flags.get::<u8>("day")?.is_some() {
+        times(&leaderboard, client.year(), flags.day()?)
+    } else {
+        overview(&leaderboard, format)
+    };
+    print!("{}", format.render(&table));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Day 1 of 2025 unlocks at 1764565200
+    const JSON: &str = r#"{
+        "event": "2025",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "Alice", "stars": 3, "local_score": 5,
+                "last_star_ts": 1764652000, "global_score": 0,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1764565300, "star_index": 0},
+                        "2": {"get_star_ts": 1764566000, "star_index": 1}
+                    },
+                    "2": {"1": {"get_star_ts": 1764652000, "star_index": 2}}
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 1, "local_score": 2,
+                "last_star_ts": 1764565261, "global_score": 0,
+                "completion_day_level": {
+                    "1": {"1": {"get_star_ts": 1764565261, "star_index": 3}}
+                }
+            },
+            "3": {
+                "id": 3, "name": "Carol", "stars": 0, "local_score": 0,
+                "last_star_ts": 0, "global_score": 0,
+                "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_leaderboard() -> Result<()> {
+        let leaderboard = serde_json::from_str::<Leaderboard>(JSON)?;
+        assert_eq!(
+            overview(&leaderboard, Format::Markdown).to_markdown(),
+            "\
+|   # | Name                | Score | Stars |  1  |  2  |
+| --: | ------------------- | ----: | ----: | :-: | :-: |
+|  1) | Alice               |     5 |     3 |  ★  |  ☆  |
+|  2) | (anonymous user #2) |     2 |     1 |  ☆  |     |
+|  3) | Carol               |     0 |     0 |     |     |
+"
+        );
+        assert_eq!(
+            times(&leaderboard, 2025, 1).to_text(),
+            " #  Name                   Part 1    Part 2
+--  -------------------  --------  --------
+1)  Alice                00:01:40  00:13:20
+2)  (anonymous user #2)  00:01:01
+"
+        );
+        assert_eq!(times(&leaderboard, 2025, 3).len(), 0);
+        assert_eq!(clock(Duration::from_hours(25)), ">24h");
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+//! Chores around the puzzles that are not about solving them, such as talking
+//! to the Advent of Code website, run as commands of the `runner` binary.
+//!
+//! The flags, formats and answers of the commands are shared with the tasks
+//! of `cargo xtask`.
+
+mod anonymize;
+mod compare;
+mod countdown;
+mod leaderboard;
+mod manifest;
+mod matrix;
+mod push;
+mod readme;
+mod results;
+mod sniff;
+mod statement;
+mod stress;
+mod submit;
+
+use std::{collections::BTreeMap, env, process::Command, str::FromStr};
+
+use anyhow::{Result, anyhow, bail};
+use util::writer::Table;
+
+pub use self::matrix::{Expected, REAL, Verdict, expected};
+
+/// `--name value` flags of a command, checked against the names it accepts,
+/// where a flag without a value, like `--dry-run`, is a switch turned on
+pub struct Flags {
+    values: BTreeMap<String, Option<String>>,
+}
+
+impl Flags {
+    pub fn parse(args: &[String], accepted: &[&str]) -> Result<Self> {
+        let mut values = BTreeMap::new();
+        let mut args = args.iter().peekable();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg
+                .strip_prefix("--")
+                .filter(|name| accepted.contains(name))
+            else {
+                bail!(
+                    "Unknown argument {arg}, expected one of --{}",
+                    accepted.join(", --")
+                );
+            };
+            let value = args.next_if(|next| !next.starts_with("--"));
+            values.insert(name.to_string(), value.cloned());
+        }
+        Ok(Self { values })
+    }
+
+    /// The value of an optional flag
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<Option<T>>
+    where
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        self.values
+            .get(name)
+            .map(|value| {
+                let value = value
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("Expected a value after --{name}"))?;
+                value
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid value {value} for --{name}: {e}"))
+            })
+            .transpose()
+    }
+
+    /// Whether a switch is on, as `--name` alone or `--name true`
+    pub fn switch(&self, name: &str) -> Result<bool> {
+        match self.values.get(name) {
+            Some(None) => Ok(true),
+            _ => Ok(self.get(name)?.unwrap_or(false)),
+        }
+    }
+
+    /// The value of a required flag
+    pub fn require<T: FromStr>(&self, name: &str) -> Result<T>
+    where
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        self.get(name)?
+            .ok_or_else(|| anyhow!("Missing required flag --{name}"))
+    }
+
+    /// The `--day` flag, between 1 and 25
+    pub fn day(&self) -> Result<u8> {
+        let day = self.require("day")?;
+        if !(1..=25).contains(&day) {
+            bail!("Day must be between 1 and 25");
+        }
+        Ok(day)
+    }
+
+    /// The `--format` flag, text unless given
+    pub fn format(&self) -> Result<Format> {
+        match self.get::<String>("format")?.as_deref().unwrap_or("text") {
+            "text" => Ok(Format::Text),
+            "markdown" => Ok(Format::Markdown),
+            other => bail!("Unknown format {other}, expected text or markdown"),
+        }
+    }
+}
+
+/// How a table is printed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Markdown,
+}
+
+impl Format {
+    #[must_use]
+    pub fn render(self, table: &Table) -> String {
+        match self {
+            Self::Text => table.to_text(),
+            Self::Markdown => table.to_markdown(),
+        }
+    }
+}
+
+/// The answers a day prints for its real input, by running its binary in
+/// release mode
+#[tracing::instrument]
+pub fn answers(day: u8) -> Result<[String; 2]> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .args(["run", "--release", "--quiet", "-p", &format!("day{day:02}")])
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "Running day {day} failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    let answer = |part: u8| {
+        let prefix = format!("Day {day} Part {part}: ");
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(&prefix))
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Day {day} printed no answer for part {part}"))
+    };
+    Ok([answer(1)?, answer(2)?])
+}
+
+/// Run the command of `args`, the arguments of the binary
+pub fn run(mut args: Vec<String>) -> Result<()> {
+    // Benchmarking a day on its stress corpus is a flag rather than a command
+    if let Some(i) = args.iter().position(|arg| arg == "--stress") {
+        args.remove(i);
+        let _span = tracing::info_span!("command", name = "--stress").entered();
+        return stress::run(&args);
+    }
+    let Some((command, rest)) = args.split_first() else {
+        bail!(
+            "Usage: runner <submit|statement|leaderboard|wait-and-run|anonymize|bench-compare|\
+             bench-save|bench-readme|bench-export|bench-merge|push-results|stress-gen|matrix|\
+             manifest|sniff> [...], or runner --stress --day N [...]"
+        );
+    };
+    let _span = tracing::info_span!("command", name = %command).entered();
+    match command.as_str() {
+        "submit" => submit::run(rest),
+        "statement" => statement::run(rest),
+        "leaderboard" => leaderboard::run(rest),
+        "wait-and-run" => countdown::run(rest),
+        "anonymize" => anonymize::run(rest),
+        "bench-compare" => compare::run(rest),
+        "bench-save" => compare::save(rest),
+        "bench-readme" => readme::run(rest),
+        "bench-export" => results::export(rest),
+        "bench-merge" => results::run(rest),
+        "push-results" => push::run(rest),
+        "stress-gen" => stress::generate_run(rest),
+        "matrix" => matrix::run(rest),
+        "manifest" => manifest::run(rest),
+        "sniff" => sniff::run(rest),
+        other => bail!(
+            "Unknown command {other}, expected submit, statement, leaderboard, wait-and-run, \
+             anonymize, bench-compare, bench-save, bench-readme, bench-export, bench-merge, \
+             push-results, stress-gen, matrix, manifest or sniff, or --stress"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags() -> Result<()> {
+        let args = ["--day", "3", "--part", "2"].map(String::from);
+        let flags = Flags::parse(&args, &["day", "part", "answer"])?;
+        assert_eq!(flags.day()?, 3);
+        assert_eq!(flags.require::<u8>("part")?, 2);
+        assert_eq!(flags.get::<String>("answer")?, None);
+        assert!(flags.require::<String>("answer").is_err());
+        assert!(Flags::parse(&args, &["day"]).is_err());
+        assert!(Flags::parse(&args[..1], &["day"])?.day().is_err());
+        let flags = Flags::parse(&["--day", "26"].map(String::from), &["day"])?;
+        assert!(flags.day().is_err());
+        assert_eq!(flags.format()?, Format::Text);
+        let flags = Flags::parse(&["--format", "html"].map(String::from), &["format"])?;
+        assert!(flags.format().is_err());
+        // Switches stand alone, or take a value like other flags
+        let args = ["--dry-run", "--day", "3", "--example", "false"].map(String::from);
+        let flags = Flags::parse(&args, &["day", "dry-run", "example", "check"])?;
+        assert!(flags.switch("dry-run")?);
+        assert!(!flags.switch("example")?);
+        assert!(!flags.switch("check")?);
+        assert_eq!(flags.day()?, 3);
+        assert!(flags.get::<String>("dry-run").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+//! Chores around the puzzles that are not about solving them, such as talking
+//! to the Advent of Code website.
+//!
+//! ```text
+//! cargo run -p runner -- submit --day N --part P [--answer A]
+//! cargo run -p runner -- statement --day N
+//! cargo run -p runner -- wait-and-run --day N [--editor CMD]
+//! cargo run -p runner -- leaderboard [--id ID] [--day N] [--format text|markdown]
+//! cargo run -p runner -- anonymize --day N [--seed S]
+//! cargo run -p runner -- bench-compare --base REV|DIR [--head REV|DIR] [--format text|markdown]
+//! cargo run -r -p runner -- bench-save --dir DIR [--seconds S]
+//! cargo run -p runner -- bench-readme
+//! cargo run -p runner -- bench-export [--label NAME]
+//! cargo run -p runner -- bench-merge [--dir DIR] [--format text|markdown]
+//! cargo run -p runner -- push-results [--url URL] [--file PATH] [--retries N] [--dry-run]
+//! cargo run -r -p runner -- stress-gen --day N --size S [--seed X]
+//! cargo run -r -p runner -- --stress --day N [--seconds S] [--format text|markdown]
+//! cargo run -r -p runner -- matrix [--format text|markdown]
+//! cargo run -r -p runner -- manifest [--label NAME]
+//! cargo run -p runner -- sniff --day N [--example]
+//! ```
+
+use std::env;
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    util::trace::init();
+    runner::run(env::args().skip(1).collect())
+}
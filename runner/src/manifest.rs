@@ -0,0 +1,191 @@
+//! One machine-readable record of a run of every day, written to
+//! `outputs/run-manifest.json`, so that badges, dashboards and the README
+//! read a single file instead of scraping what the days print.
+//!
+//! Every day is run once on its real input. The hash of the input tells
+//! whether the answers still belong to the input at hand, and the version of
+//! the solutions whether they still belong to the code.
+
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use util::{Error, Run, get_workspace_root, hash::md5_hex, reader::read_file, writer::output_file};
+
+use crate::{
+    Flags,
+    compare::Source,
+    results::{Environment, Timing},
+};
+
+/// The saved benchmarks of a day, where they come from and their timings
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Benchmark {
+    /// Path of the CSV file, relative to the workspace root
+    pub file: String,
+    pub timings: Vec<Timing>,
+}
+
+/// A run of a day on its real input
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayRun {
+    /// MD5 of the input, as hex
+    pub input_md5: String,
+    /// Answers of both parts, unless the day failed
+    pub answers: Option<[String; 2]>,
+    /// Nanoseconds spent parsing, on part 1 and on part 2, unless the day
+    /// failed
+    pub durations: Option<[u64; 3]>,
+    /// Why the day failed
+    pub error: Option<String>,
+    pub benchmark: Option<Benchmark>,
+}
+
+impl DayRun {
+    /// The record of a run of a day on `input`
+    fn new(input: &str, run: Result<Run>, benchmark: Option<Benchmark>) -> Self {
+        let nanos = |d: Duration| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX);
+        let (answers, durations, error) = match run {
+            Ok(run) => (Some(run.answers), Some(run.timings.map(nanos)), None),
+            Err(e) => (None, None, Some(e.to_string())),
+        };
+        Self {
+            input_md5: md5_hex(input.as_bytes()),
+            answers,
+            durations,
+            error,
+            benchmark,
+        }
+    }
+}
+
+/// Everything known about the last run of every day
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// `git describe` of the solutions that ran
+    pub version: String,
+    /// When the days ran, in seconds since the Unix epoch
+    pub generated: u64,
+    pub environment: Environment,
+    pub days: BTreeMap<u8, DayRun>,
+}
+
+/// Answers and timings of a day on `input`, with a panic turned into an error
+pub fn run_day(day: u8, input: &str) -> Result<Run> {
+    let solution = solutions::find(day)?;
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        solutions::with_input(input, || solution.run(false))
+    }))
+    .map_err(|payload: Box<dyn Any + Send>| {
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(ToString::to_string));
+        anyhow!(message.unwrap_or_else(|| "panicked".to_string()))
+    })
+}
+
+/// The version of the solutions, as `git describe` puts it
+fn version() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty", "--tags"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// `manifest [--label NAME]`: run every day on its real input and record the
+/// run to `outputs/run-manifest.json`, along with the machine it ran on
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["label"])?;
+    let root = get_workspace_root()?;
+    let outputs = Source::Dir(root.join("outputs"));
+    let mut days = BTreeMap::new();
+    // Failures are recorded in the manifest, and panic messages would bury
+    // the summary
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for solution in solutions::all() {
+        let day = solution.day();
+        // Days without an input yet are left out, any other failure to read
+        // one is worth stopping for
+        let input = match read_file(day, false) {
+            Ok(input) => input,
+            Err(Error::MissingInput { .. }) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let benchmark = outputs.benchmarks(&root, day)?.map(|results| Benchmark {
+            file: format!("outputs/benchmark-day{day:02}.csv"),
+            timings: results.iter().map(Timing::from).collect(),
+        });
+        days.insert(day, DayRun::new(&input, run_day(day, &input), benchmark));
+    }
+    panic::set_hook(hook);
+    let manifest = Manifest {
+        version: version(),
+        generated: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        environment: Environment::current(flags.get("label")?),
+        days,
+    };
+    let path = output_file("run-manifest.json")?;
+    fs::write(&path, serde_json::to_string_pretty(&manifest)?)?;
+    let failed = manifest
+        .days
+        .iter()
+        .filter(|(_, run)| run.error.is_some())
+        .map(|(day, _)| format!("{day:02}"))
+        .collect::<Vec<_>>();
+    println!(
+        "Runs of {} days at {} recorded to {}",
+        manifest.days.len(),
+        manifest.version,
+        path.display()
+    );
+    if !failed.is_empty() {
+        println!("Days {} failed", failed.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_run() -> Result<()> {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        let run = DayRun::new(input, run_day(1, input), None);
+        assert_eq!(run.answers, Some(["3".to_string(), "6".to_string()]));
+        assert_eq!(run.input_md5, md5_hex(input.as_bytes()));
+        assert!(run.durations.is_some() && run.error.is_none());
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let failed = DayRun::new("X1\n", run_day(1, "X1\n"), None);
+        panic::set_hook(hook);
+        assert!(failed.answers.is_none() && failed.durations.is_none());
+        assert!(failed.error.is_some());
+        // The manifest reads back as it was written
+        let manifest = Manifest {
+            version: "v1.0-3-gabcdef0".to_string(),
+            generated: 0,
+            environment: Environment::current(Some("test".to_string())),
+            days: BTreeMap::from([(1, run), (2, failed)]),
+        };
+        let json = serde_json::to_string(&manifest)?;
+        assert_eq!(serde_json::from_str::<Manifest>(&json)?, manifest);
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Answers of every day on every input at hand, checked against the answers
+//! known to be right, to catch a solution that only works on one input.
+//!
+//! The inputs are the example and the real input of each day in `inputs`,
+//! and the inputs of friends under `inputs/profiles/NAME/dayNN.txt`, each
+//! profile being a column of its own. Expected answers come from
+//! `answers.json` at the root of the workspace, which the example tests of
+//! the days also check against, then from `inputs/expected.json`, both of the
+//! form `{"example": {"1": ["3", "6"]}, "alice": {"1": ["982", null]}}`, and
+//! for the real input also from the answers the site accepted.
+
+use std::{collections::BTreeMap, fs, iter, panic, path::Path};
+
+use anyhow::{Result, bail};
+use util::{
+    get_workspace_root,
+    term::{Color, paint},
+    writer::{Align, Table},
+};
+
+use crate::{Flags, Format, manifest::run_day, submit};
+
+/// Column of the example inputs
+const EXAMPLE: &str = "example";
+/// Column of the real inputs
+pub const REAL: &str = "real";
+
+/// Expected answers of both parts, by input and by day
+pub type Expected = BTreeMap<String, BTreeMap<u8, [Option<String>; 2]>>;
+
+/// How an answer compares with the expected one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    Unknown,
+}
+
+impl Verdict {
+    /// How `answer` compares with `expected`, if any is known
+    #[must_use]
+    pub fn of(answer: &str, expected: Option<&String>) -> Self {
+        match expected {
+            Some(expected) if expected == answer => Self::Right,
+            Some(_) => Self::Wrong,
+            None => Self::Unknown,
+        }
+    }
+}
+
+impl Format {
+    /// An answer marked with how it compares with the expected one
+    #[must_use]
+    pub fn verdict(self, answer: &str, verdict: Verdict) -> String {
+        match (self, verdict) {
+            (Self::Text, Verdict::Right) => paint(answer, Color::Green),
+            (Self::Text, Verdict::Wrong) => paint(answer, Color::Red),
+            (Self::Markdown, Verdict::Right) => format!("{answer} ✓"),
+            (Self::Markdown, Verdict::Wrong) => format!("**{answer} ✗**"),
+            (_, Verdict::Unknown) => answer.to_string(),
+        }
+    }
+}
+
+/// Every input of every day, by the column it goes in
+fn inputs(dir: &Path, days: &[u8]) -> Result<BTreeMap<String, BTreeMap<u8, String>>> {
+    let mut inputs = BTreeMap::<String, BTreeMap<u8, String>>::new();
+    for &day in days {
+        for (column, name) in [
+            (EXAMPLE, format!("day{day:02}-example.txt")),
+            (REAL, format!("day{day:02}.txt")),
+        ] {
+            let path = dir.join(name);
+            if path.exists() {
+                let input = fs::read_to_string(path)?;
+                inputs
+                    .entry(column.to_string())
+                    .or_default()
+                    .insert(day, input);
+            }
+        }
+    }
+    let profiles = dir.join("profiles");
+    if profiles.is_dir() {
+        for entry in fs::read_dir(profiles)? {
+            let path = entry?.path();
+            let Some(name) = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            if [EXAMPLE, REAL].contains(&name.as_str()) {
+                bail!("Profile {name} is named like the column of the inputs in `inputs`");
+            }
+            for &day in days {
+                let path = path.join(format!("day{day:02}.txt"));
+                if path.exists() {
+                    let input = fs::read_to_string(path)?;
+                    inputs.entry(name.clone()).or_default().insert(day, input);
+                }
+            }
+        }
+    }
+    Ok(inputs)
+}
+
+/// A row for every day with a cell for every input, holding the answers of
+/// both parts marked against the expected ones, and how many are wrong
+fn matrix(
+    days: &[u8],
+    columns: &[String],
+    answers: &BTreeMap<(String, u8), Result<[String; 2]>>,
+    expected: &Expected,
+    format: Format,
+) -> (Table, usize) {
+    let headers = iter::once("Day".to_string()).chain(columns.iter().cloned());
+    let mut table = Table::new(headers).align(0, Align::Right);
+    let mut wrong = 0;
+    for &day in days {
+        let cells = columns.iter().map(|column| {
+            let Some(result) = answers.get(&(column.clone(), day)) else {
+                return String::new();
+            };
+            let Ok(parts) = result else {
+                wrong += 1;
+                return format.verdict("error", Verdict::Wrong);
+            };
+            let expected = expected.get(column).and_then(|days| days.get(&day));
+            let marked = parts.iter().enumerate().map(|(part, answer)| {
+                let verdict = Verdict::of(answer, expected.and_then(|parts| parts[part].as_ref()));
+                if verdict == Verdict::Wrong {
+                    wrong += 1;
+                }
+                format.verdict(answer, verdict)
+            });
+            marked.collect::<Vec<_>>().join(" / ")
+        });
+        let row = iter::once(format!("{day:02}"))
+            .chain(cells)
+            .collect::<Vec<_>>();
+        table.row(row);
+    }
+    (table, wrong)
+}
+
+/// The expected answers of every input and day, the first known answer of a
+/// part winning
+///
+/// Answers come from `answers.json` at the root of the workspace, then from
+/// `inputs/expected.json` in `dir`, then for the real inputs from the answers
+/// the site accepted.
+pub fn expected(dir: &Path) -> Result<Expected> {
+    let mut expected = Expected::new();
+    let files = [dir.join("../answers.json"), dir.join("expected.json")];
+    let known = files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok());
+    for content in known {
+        for (input, days) in serde_json::from_str::<Expected>(&content)? {
+            for (day, parts) in days {
+                let slots = (expected.entry(input.clone()).or_default())
+                    .entry(day)
+                    .or_default();
+                for (slot, answer) in slots.iter_mut().zip(parts) {
+                    if slot.is_none() {
+                        *slot = answer;
+                    }
+                }
+            }
+        }
+    }
+    for ((day, part), answer) in submit::correct_answers()? {
+        if let Some(slot) = (expected.entry(REAL.to_string()).or_default())
+            .entry(day)
+            .or_default()
+            .get_mut(usize::from(part) - 1)
+        {
+            slot.get_or_insert(answer);
+        }
+    }
+    Ok(expected)
+}
+
+/// `matrix [--format text|markdown]`: run every day on every input at hand
+/// and show the answers, failing if any is not the expected one
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["format"])?;
+    let format = flags.format()?;
+    let dir = get_workspace_root()?.join("inputs");
+    let days = solutions::all()
+        .iter()
+        .map(|solution| solution.day())
+        .collect::<Vec<_>>();
+    let expected = expected(&dir)?;
+    let inputs = inputs(&dir, &days)?;
+    // The example and the real inputs first, then the profiles by name
+    let mut columns = inputs.keys().cloned().collect::<Vec<_>>();
+    columns.sort_by_key(|column| (column != EXAMPLE, column != REAL, column.clone()));
+    // Panics are shown in the matrix, and their messages would bury it
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut results = BTreeMap::new();
+    for (column, days) in &inputs {
+        for (&day, input) in days {
+            results.insert(
+                (column.clone(), day),
+                run_day(day, input).map(|run| run.answers),
+            );
+        }
+    }
+    panic::set_hook(hook);
+    let (table, wrong) = matrix(&days, &columns, &results, &expected, format);
+    print!("{}", format.render(&table));
+    if wrong > 0 {
+        bail!("{wrong} answers are not the expected ones");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_matrix() {
+        let columns = ["example", "real", "alice"].map(String::from);
+        let answers = BTreeMap::from([
+            (
+                ("example".to_string(), 1),
+                Ok(["3".to_string(), "6".to_string()]),
+            ),
+            (
+                ("real".to_string(), 1),
+                Ok(["10".to_string(), "20".to_string()]),
+            ),
+            (
+                ("alice".to_string(), 1),
+                Ok(["11".to_string(), "21".to_string()]),
+            ),
+            (("example".to_string(), 2), Err(anyhow!("panicked"))),
+        ]);
+        let expected = Expected::from([
+            (
+                "example".to_string(),
+                BTreeMap::from([(1, [Some("3".to_string()), Some("6".to_string())])]),
+            ),
+            (
+                "alice".to_string(),
+                BTreeMap::from([(1, [Some("11".to_string()), Some("22".to_string())])]),
+            ),
+        ]);
+        let (table, wrong) = matrix(&[1, 2], &columns, &answers, &expected, Format::Markdown);
+        assert_eq!(wrong, 2);
+        assert_eq!(
+            table.to_markdown(),
+            "\
+| Day | example     | real    | alice           |
+| --: | ----------- | ------- | --------------- |
+|  01 | 3 ✓ / 6 ✓   | 10 / 20 | 11 ✓ / **21 ✗** |
+|  02 | **error ✗** |         |                 |
+"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Upload of the merged benchmarks of every machine to a dashboard, as one
+//! JSON POST authenticated with a bearer token.
+//!
+//! Nothing is sent unless asked to: the endpoint comes from `--url` or
+//! `AOC_RESULTS_URL`, and the token only from `AOC_RESULTS_TOKEN`, so that it
+//! stays out of the shell history.
+
+use std::{env, fs, path::PathBuf, thread, time::Duration};
+
+use anyhow::{Result, anyhow, bail};
+use ureq::Agent;
+use util::get_workspace_root;
+
+use crate::{Flags, results::Results};
+
+/// Environment variable holding the endpoint to push to
+const URL_ENV: &str = "AOC_RESULTS_URL";
+/// Environment variable holding the token sent along
+const TOKEN_ENV: &str = "AOC_RESULTS_TOKEN";
+/// Wait before the first retry, doubled before every further one
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Whether a failed request may succeed when sent again: the server was busy
+/// or broken, or the request never made it there
+const fn retryable(error: &ureq::Error) -> bool {
+    match error {
+        ureq::Error::StatusCode(status) => *status == 429 || *status >= 500,
+        ureq::Error::Io(_)
+        | ureq::Error::Timeout(_)
+        | ureq::Error::HostNotFound
+        | ureq::Error::ConnectionFailed => true,
+        _ => false,
+    }
+}
+
+/// `send` tried up to `retries` more times while it fails in a retryable way,
+/// calling `wait` with a doubling backoff in between
+fn with_retries<T>(
+    retries: u32,
+    mut send: impl FnMut() -> Result<T, ureq::Error>,
+    mut wait: impl FnMut(Duration),
+) -> Result<T> {
+    let mut backoff = FIRST_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        match send() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < retries && retryable(&e) => {
+                eprintln!("Push failed ({e}), retrying in {}s", backoff.as_secs());
+                wait(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) => bail!("Push failed after {} attempts: {e}", attempt + 1),
+        }
+    }
+}
+
+/// `push-results [--url URL] [--file PATH] [--retries N] [--dry-run]`:
+/// POST the merged benchmarks, `outputs/results.json` by default, to a
+/// dashboard
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["url", "file", "retries", "dry-run"])?;
+    let path = match flags.get::<PathBuf>("file")? {
+        Some(path) => path,
+        None => get_workspace_root()?.join("outputs/results.json"),
+    };
+    let body = fs::read_to_string(&path).map_err(|e| {
+        anyhow!(
+            "Failed to read {}, run `just bench-merge` first: {e}",
+            path.display()
+        )
+    })?;
+    // Refuse to push anything the dashboard would not understand
+    let machines = serde_json::from_str::<Vec<Results>>(&body)?;
+    let url = match flags.get::<String>("url")? {
+        Some(url) => url,
+        None => {
+            env::var(URL_ENV).map_err(|_| anyhow!("Missing --url, and {URL_ENV} is not set"))?
+        }
+    };
+    let labels = machines
+        .iter()
+        .map(|m| m.environment.label.as_str())
+        .collect::<Vec<_>>();
+    if flags.switch("dry-run")? {
+        println!(
+            "Would push {} bytes of results of {} to {url}",
+            body.len(),
+            labels.join(", ")
+        );
+        return Ok(());
+    }
+    let token = env::var(TOKEN_ENV).map_err(|_| anyhow!("{TOKEN_ENV} is not set"))?;
+    let agent: Agent = Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(30)))
+        .build()
+        .into();
+    let retries = flags.get("retries")?.unwrap_or(3);
+    let send = || {
+        agent
+            .post(&url)
+            .header("Authorization", &format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .send(body.as_str())
+    };
+    with_retries(retries, send, thread::sleep)?;
+    println!("Results of {} pushed to {url}", labels.join(", "));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_retries() {
+        let mut waits = Vec::new();
+        let mut failures = vec![ureq::Error::ConnectionFailed, ureq::Error::StatusCode(503)];
+        let sent = with_retries(
+            3,
+            || failures.pop().map_or(Ok("sent"), Err),
+            |backoff| waits.push(backoff.as_secs()),
+        );
+        assert_eq!(sent.ok(), Some("sent"));
+        assert_eq!(waits, [1, 2]);
+        // A rejected token is not worth sending again
+        let mut attempts = 0;
+        let rejected = with_retries(
+            3,
+            || -> Result<(), _> {
+                attempts += 1;
+                Err(ureq::Error::StatusCode(401))
+            },
+            |_| {},
+        );
+        assert!(rejected.is_err());
+        assert_eq!(attempts, 1);
+        // Nor is anything once the retries run out
+        let mut attempts = 0;
+        let exhausted = with_retries(
+            2,
+            || -> Result<(), _> {
+                attempts += 1;
+                Err(ureq::Error::StatusCode(500))
+            },
+            |_| {},
+        );
+        assert!(exhausted.is_err());
+        assert_eq!(attempts, 3);
+    }
+}
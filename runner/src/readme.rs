@@ -0,0 +1,200 @@
+//! The benchmark table of the README, generated from the benchmarks saved in
+//! `outputs` and written between the `<!-- bench:start -->` and
+//! `<!-- bench:end -->` markers, keeping the notes written by hand.
+
+use std::{collections::BTreeMap, fs, time::Duration};
+
+use anyhow::{Result, bail};
+use util::{
+    get_workspace_root,
+    timer::BenchmarkResult,
+    writer::{Align, Table},
+};
+
+use crate::{Flags, compare::Source, submit};
+
+/// Start of the generated table in the README
+const START: &str = "<!-- bench:start -->";
+/// End of the generated table in the README
+const END: &str = "<!-- bench:end -->";
+
+/// The phases every day benchmarks, which get a column each
+const PHASES: [&str; 3] = ["Parse", "Part 1", "Part 2"];
+
+/// The unit a duration reads best in, with how many seconds it stands for
+const fn unit(duration: Duration) -> (&'static str, f64) {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        ("ns", 1e-9)
+    } else if nanos < 1_000_000 {
+        ("µs", 1e-6)
+    } else if nanos < 1_000_000_000 {
+        ("ms", 1e-3)
+    } else {
+        ("s", 1.0)
+    }
+}
+
+/// `median ± mad` with the unit of the median, `N/A` for a missing phase
+fn timing(result: Option<&BenchmarkResult>) -> String {
+    result.map_or_else(
+        || "N/A".to_string(),
+        |result| {
+            let (unit, seconds) = unit(result.median);
+            format!(
+                "{:.3} $\\pm$ {:.3} {unit}",
+                result.median.as_secs_f64() / seconds,
+                result.mad.as_secs_f64() / seconds,
+            )
+        },
+    )
+}
+
+/// Notes of the days in the generated table of `readme`, by day
+fn notes(readme: &str) -> BTreeMap<u8, String> {
+    let Some((_, table)) = readme.split_once(START) else {
+        return BTreeMap::new();
+    };
+    let table = table.split_once(END).map_or(table, |(table, _)| table);
+    table
+        .lines()
+        .filter_map(|line| {
+            let cells = line.trim().trim_matches('|').split('|').collect::<Vec<_>>();
+            let day = cells.first()?.trim().parse().ok()?;
+            Some((day, cells.last()?.trim().to_string()))
+        })
+        .collect()
+}
+
+/// A row for every benchmarked day, with its stars, the timing of every phase,
+/// the total of their medians and its note
+fn table(
+    days: &BTreeMap<u8, Vec<BenchmarkResult>>,
+    stars: &BTreeMap<u8, usize>,
+    notes: &BTreeMap<u8, String>,
+) -> Table {
+    let mut table = Table::new([
+        "Day", "Stars", "Parsing", "Part 1", "Part 2", "Total", "Note",
+    ]);
+    for column in 2..=5 {
+        table = table.align(column, Align::Center);
+    }
+    for (&day, results) in days {
+        let phases = PHASES.map(|phase| results.iter().find(|r| r.name == phase));
+        let total = phases.iter().flatten().map(|r| r.median).sum::<Duration>();
+        let (unit, seconds) = unit(total);
+        let stars = stars.get(&day).copied().unwrap_or_default();
+        table.row(
+            [format!("{day:02}"), "★".repeat(stars)]
+                .into_iter()
+                .chain(phases.map(timing))
+                .chain([
+                    format!("{:.3} {unit}", total.as_secs_f64() / seconds),
+                    notes.get(&day).cloned().unwrap_or_default(),
+                ]),
+        );
+    }
+    table
+}
+
+/// `readme` with what is between the markers replaced by `table`
+fn splice(readme: &str, table: &str) -> Result<String> {
+    let (Some(start), Some(end)) = (readme.find(START), readme.find(END)) else {
+        bail!("Missing {START} or {END} in the README");
+    };
+    if end < start {
+        bail!("{END} comes before {START} in the README");
+    }
+    Ok(format!(
+        "{}\n\n{table}\n{}",
+        &readme[..start + START.len()],
+        &readme[end..]
+    ))
+}
+
+/// `bench-readme`: rewrite the benchmark table of the README from the saved
+/// benchmarks of every day
+pub fn run(args: &[String]) -> Result<()> {
+    Flags::parse(args, &[])?;
+    let root = get_workspace_root()?;
+    let outputs = Source::Dir(root.join("outputs"));
+    let mut days = BTreeMap::new();
+    for day in 1..=25 {
+        if let Some(results) = outputs.benchmarks(&root, day)? {
+            days.insert(day, results);
+        }
+    }
+    let path = root.join("README.md");
+    let readme = fs::read_to_string(&path)?;
+    let table = table(&days, &submit::stars()?, &notes(&readme));
+    fs::write(&path, splice(&readme, &table.to_markdown())?)?;
+    println!(
+        "Benchmarks of {} days written to {}",
+        days.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, median: Duration, mad: Duration) -> BenchmarkResult {
+        BenchmarkResult {
+            name: name.to_string(),
+            time_limit: Duration::from_secs(1),
+            iterations: 1000,
+            fastest: median,
+            slowest: median,
+            mean: median,
+            std_dev: mad,
+            median,
+            mad,
+            samples: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_readme() -> Result<()> {
+        let readme = format!(
+            "# Title\n\n{START}\n\n| Day | Note |\n| --- | --- |\n| 02  | Sorting |\n{END}\n\nMore\n"
+        );
+        let notes = notes(&readme);
+        assert_eq!(notes, BTreeMap::from([(2, "Sorting".to_string())]));
+        let (us, ms) = (Duration::from_micros, Duration::from_millis);
+        let days = BTreeMap::from([
+            (
+                1,
+                vec![
+                    result("Parse", us(12), us(1)),
+                    result("Part 1", ms(3), us(20)),
+                    result("Part 2", us(2), us(0)),
+                    result("Part 1 (Other)", ms(9), us(0)),
+                ],
+            ),
+            (
+                2,
+                vec![result("Parse", Duration::from_nanos(50), Duration::ZERO)],
+            ),
+        ]);
+        let stars = BTreeMap::from([(1, 2), (2, 1)]);
+        let table = table(&days, &stars, &notes).to_markdown();
+        let readme = splice(&readme, &table)?;
+        assert_eq!(
+            readme,
+            format!(
+                "# Title\n\n{START}\n\n\
+| Day | Stars |        Parsing        |        Part 1        |        Part 2        |   Total   | Note    |
+| --- | ----- | :-------------------: | :------------------: | :------------------: | :-------: | ------- |
+| 01  | ★★    | 12.000 $\\pm$ 1.000 µs | 3.000 $\\pm$ 0.020 ms | 2.000 $\\pm$ 0.000 µs | 3.014 ms  |         |
+| 02  | ★     | 50.000 $\\pm$ 0.000 ns |         N/A          |         N/A          | 50.000 ns | Sorting |
+\n{END}\n\nMore\n"
+            )
+        );
+        // Splicing again changes nothing
+        assert_eq!(splice(&readme, &table)?, readme);
+        assert!(splice("no markers", &table).is_err());
+        Ok(())
+    }
+}
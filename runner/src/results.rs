@@ -0,0 +1,306 @@
+//! Benchmarks of several machines in one place: every machine exports its
+//! saved benchmarks along with what it is, and merging the exports shows
+//! every machine side by side.
+//!
+//! A day that is much slower on one machine than its CPU would suggest is
+//! usually bound by memory rather than by computation.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::Path,
+    process::Command,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use util::{
+    get_workspace_root,
+    timer::BenchmarkResult,
+    writer::{Align, Table, output_file},
+};
+
+use crate::{Flags, compare::Source};
+
+/// The machine benchmarks were run on
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Environment {
+    /// What the machine is called in the comparison, its host name by default
+    pub label: String,
+    pub os: String,
+    pub arch: String,
+    pub cpus: usize,
+}
+
+impl Environment {
+    /// The machine this runs on, labelled `label` or by its host name
+    pub fn current(label: Option<String>) -> Self {
+        let hostname = || {
+            env::var("HOSTNAME")
+                .ok()
+                .or_else(|| {
+                    let output = Command::new("hostname").output().ok()?;
+                    String::from_utf8(output.stdout).ok()
+                })
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "unknown".to_string())
+        };
+        Self {
+            label: label.unwrap_or_else(hostname),
+            os: env::consts::OS.to_string(),
+            arch: env::consts::ARCH.to_string(),
+            cpus: thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+}
+
+/// A phase of a day as benchmarked, in nanoseconds
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timing {
+    pub name: String,
+    pub iterations: u128,
+    pub median: u64,
+    pub mad: u64,
+    pub mean: u64,
+    pub std_dev: u64,
+}
+
+impl From<&BenchmarkResult> for Timing {
+    fn from(result: &BenchmarkResult) -> Self {
+        let nanos = |d: Duration| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX);
+        Self {
+            name: result.name.clone(),
+            iterations: result.iterations,
+            median: nanos(result.median),
+            mad: nanos(result.mad),
+            mean: nanos(result.mean),
+            std_dev: nanos(result.std_dev),
+        }
+    }
+}
+
+/// The saved benchmarks of a machine, as exported
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Results {
+    pub environment: Environment,
+    /// When the export was made, in seconds since the Unix epoch
+    pub exported: u64,
+    pub days: BTreeMap<u8, Vec<Timing>>,
+}
+
+/// Exports of the same machine as one, the newest export of a day winning
+pub fn merge(exports: impl IntoIterator<Item = Results>) -> Vec<Results> {
+    let mut merged = BTreeMap::<Environment, Results>::new();
+    let mut exports = exports.into_iter().collect::<Vec<_>>();
+    exports.sort_by_key(|results| results.exported);
+    for results in exports {
+        match merged.get_mut(&results.environment) {
+            Some(machine) => {
+                machine.exported = results.exported;
+                machine.days.extend(results.days);
+            }
+            None => {
+                merged.insert(results.environment.clone(), results);
+            }
+        }
+    }
+    merged.into_values().collect()
+}
+
+/// Nanoseconds in the unit that reads best
+#[allow(clippy::cast_precision_loss)]
+fn readable(nanos: u64) -> String {
+    let (unit, scale) = match nanos {
+        ..1_000 => return format!("{nanos}ns"),
+        1_000..1_000_000 => ("µs", 1e3),
+        1_000_000..1_000_000_000 => ("ms", 1e6),
+        _ => ("s", 1e9),
+    };
+    format!("{:.3}{unit}", nanos as f64 / scale)
+}
+
+/// Medians of every phase of every day on every machine, with how many times
+/// slower the slowest machine is than the fastest
+#[allow(clippy::cast_precision_loss)]
+fn grid(machines: &[Results]) -> Table {
+    let labels = machines.iter().map(|m| m.environment.label.clone());
+    let headers = ["Day", "Phase"]
+        .map(String::from)
+        .into_iter()
+        .chain(labels)
+        .chain(["Spread".to_string()]);
+    let mut table = Table::new(headers).align(0, Align::Right);
+    for column in 2..=machines.len() + 2 {
+        table = table.align(column, Align::Right);
+    }
+    let mut phases = BTreeMap::<u8, Vec<&str>>::new();
+    for timing in machines.iter().flat_map(|m| &m.days) {
+        let names = phases.entry(*timing.0).or_default();
+        for phase in timing.1 {
+            if !names.contains(&phase.name.as_str()) {
+                names.push(&phase.name);
+            }
+        }
+    }
+    for (day, names) in phases {
+        for name in names {
+            let medians = machines
+                .iter()
+                .map(|m| {
+                    let timings = m.days.get(&day)?;
+                    Some(timings.iter().find(|t| t.name == name)?.median)
+                })
+                .collect::<Vec<_>>();
+            let known = medians.iter().flatten().collect::<Vec<_>>();
+            let spread = match (known.iter().min(), known.iter().max()) {
+                (Some(&&fastest), Some(&&slowest)) if fastest > 0 && known.len() > 1 => {
+                    format!("{:.2}x", slowest as f64 / fastest as f64)
+                }
+                _ => String::new(),
+            };
+            table.row(
+                [day.to_string(), name.to_string()]
+                    .into_iter()
+                    .chain(medians.iter().map(|m| m.map(readable).unwrap_or_default()))
+                    .chain([spread]),
+            );
+        }
+    }
+    table
+}
+
+/// `bench-export [--label NAME]`: save the benchmarks in `outputs` along with
+/// the machine they ran on to `outputs/results-NAME.json`
+pub fn export(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["label"])?;
+    let environment = Environment::current(flags.get("label")?);
+    let root = get_workspace_root()?;
+    let outputs = Source::Dir(root.join("outputs"));
+    let mut days = BTreeMap::new();
+    for day in 1..=25 {
+        if let Some(results) = outputs.benchmarks(&root, day)? {
+            days.insert(day, results.iter().map(Timing::from).collect());
+        }
+    }
+    if days.is_empty() {
+        bail!("No saved benchmarks to export, run `just bench` first");
+    }
+    let exported = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let path = output_file(&format!("results-{}.json", environment.label))?;
+    let results = Results {
+        environment,
+        exported,
+        days,
+    };
+    fs::write(&path, serde_json::to_string_pretty(&results)?)?;
+    println!(
+        "Benchmarks of {} days exported to {}",
+        results.days.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Every export in `dir`, as `results-*.json` files
+fn exports(dir: &Path) -> Result<Vec<Results>> {
+    let mut exports = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with("results-") && name.ends_with(".json") {
+            exports.push(serde_json::from_str(&fs::read_to_string(&path)?)?);
+        }
+    }
+    Ok(exports)
+}
+
+/// `bench-merge [--dir DIR] [--format text|markdown]`: merge the exports in
+/// `DIR`, `outputs` by default, into `outputs/results.json` and show every
+/// machine side by side
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["dir", "format"])?;
+    let format = flags.format()?;
+    let dir = match flags.get::<String>("dir")? {
+        Some(dir) => dir.into(),
+        None => get_workspace_root()?.join("outputs"),
+    };
+    let machines = merge(exports(&dir)?);
+    if machines.is_empty() {
+        bail!("No results-*.json exports in {}", dir.display());
+    }
+    let path = output_file("results.json")?;
+    fs::write(&path, serde_json::to_string_pretty(&machines)?)?;
+    print!("{}", format.render(&grid(&machines)));
+    println!(
+        "Results of {} machines merged into {}",
+        machines.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(label: &str, exported: u64, days: &[(u8, &str, u64)]) -> Results {
+        let mut by_day = BTreeMap::<u8, Vec<Timing>>::new();
+        for &(day, name, median) in days {
+            by_day.entry(day).or_default().push(Timing {
+                name: name.to_string(),
+                iterations: 100,
+                median,
+                mad: 0,
+                mean: median,
+                std_dev: 0,
+            });
+        }
+        Results {
+            environment: Environment {
+                label: label.to_string(),
+                os: "linux".to_string(),
+                arch: "x86_64".to_string(),
+                cpus: 8,
+            },
+            exported,
+            days: by_day,
+        }
+    }
+
+    #[test]
+    fn test_merge() -> Result<()> {
+        let machines = merge([
+            results("laptop", 2, &[(1, "Parse", 2_000), (2, "Parse", 900)]),
+            results(
+                "desktop",
+                1,
+                &[(1, "Parse", 1_000), (1, "Part 1", 5_000_000)],
+            ),
+            // An older export of the laptop, overridden where they overlap
+            results("laptop", 1, &[(1, "Parse", 9_999), (3, "Parse", 1_500)]),
+        ]);
+        assert_eq!(machines.len(), 2);
+        assert_eq!(machines[1].days[&1][0].median, 2_000);
+        assert_eq!(machines[1].days.len(), 3);
+        assert_eq!(
+            grid(&machines).to_text(),
+            "\
+Day  Phase   desktop   laptop  Spread
+---  ------  -------  -------  ------
+  1  Parse   1.000µs  2.000µs   2.00x
+  1  Part 1  5.000ms
+  2  Parse              900ns
+  3  Parse            1.500µs
+"
+        );
+        // Exports survive a round trip through JSON
+        let json = serde_json::to_string(&machines)?;
+        assert_eq!(serde_json::from_str::<Vec<Results>>(&json)?, machines);
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! What the input of a day looks like, and the reader call that would parse
+//! it, for starting a day from the right parsing code rather than a blank
+//! `new`.
+
+use anyhow::Result;
+use util::reader::{InputKind, Separator, read_file, sniff};
+
+use crate::Flags;
+
+/// Code parsing `source`, a `&str` expression, into `name`, as the lines of
+/// a `new` function of a day
+fn reader_call(kind: &InputKind, source: &str, name: &str) -> Vec<String> {
+    let parse = |call: String| vec![format!("let {name} = {call}?;")];
+    match kind {
+        InputKind::Empty => Vec::new(),
+        InputKind::CharGrid { chars, .. } if chars.iter().all(char::is_ascii_digit) => parse(
+            format!("parse_char_grid({source}, |c| c.to_digit(10).ok_or(\"Not a digit\"))"),
+        ),
+        InputKind::CharGrid { chars, .. } if chars.iter().all(char::is_ascii) => {
+            let cells = (0_u8..)
+                .zip(chars)
+                .map(|(value, c)| format!("{c:?} => {value}"));
+            let table = format!(
+                "static CELLS: CharMap<u8> = char_map!({});",
+                cells.collect::<Vec<_>>().join(", ")
+            );
+            let mut lines = vec![table];
+            lines.extend(parse(format!("parse_byte_grid({source}, &CELLS)")));
+            lines
+        }
+        InputKind::CharGrid { .. } => parse(format!(
+            "parse_char_grid({source}, |c| Ok::<_, anyhow::Error>(c))"
+        )),
+        InputKind::IntegerRows {
+            rows,
+            min_cols,
+            max_cols,
+            separator,
+        } => parse(match (separator, rows, min_cols == max_cols) {
+            (Separator::Whitespace, _, true) if *min_cols == 1 => {
+                format!("parse_lines({source}, str::parse::<i64>)")
+            }
+            (Separator::Whitespace, _, true) => format!("parse_grid({source}, str::parse::<i64>)"),
+            (Separator::Whitespace, _, false) => format!(
+                "parse_lines({source}, |line| parse_whitespace_separated(line, str::parse::<i64>))"
+            ),
+            (Separator::Comma, 1, _) => {
+                format!("parse_comma_separated({source}, str::parse::<i64>)")
+            }
+            (Separator::Comma, _, _) => format!(
+                "parse_lines({source}, |line| parse_comma_separated(line, str::parse::<i64>))"
+            ),
+            (Separator::Dash, _, _) => format!(
+                "parse_lines({source}, |line| {{ let (a, b) = line.split_once('-').ok_or(anyhow!(\"No dash\"))?; Ok::<_, anyhow::Error>((a.parse::<i64>()?, b.parse::<i64>()?)) }})"
+            ),
+        }),
+        InputKind::EdgeList { separator, .. } => parse(format!(
+            "parse_lines({source}, |line| line.split_once({separator:?}).map(|(from, to)| (from.trim().to_string(), to.split_whitespace().map(str::to_string).collect::<Vec<_>>())).ok_or(\"No separator\"))"
+        )),
+        InputKind::FixedWidth { widths, .. } => parse(format!(
+            "parse_padded_fixed_width_grid({source}, {widths:?}, |s| Ok::<_, anyhow::Error>(s.trim().to_string()))"
+        )),
+        InputKind::Sections(sections) => {
+            let source = source.trim_start_matches('&');
+            let mut lines = vec![format!(
+                "let sections = {source}.split(\"\\n\\n\").collect::<Vec<_>>();"
+            )];
+            for (i, section) in sections.iter().enumerate() {
+                lines.push(format!("// {section}"));
+                let source = format!("sections[{i}]");
+                let code = reader_call(section, &source, &format!("section{}", i + 1));
+                // Tables of cells are statics, which would clash otherwise
+                let code = code
+                    .into_iter()
+                    .map(|line| line.replace("CELLS", &format!("CELLS{}", i + 1)));
+                lines.extend(code);
+            }
+            lines
+        }
+        InputKind::Lines { .. } => {
+            vec![format!(
+                "let {name} = {source}.lines().collect::<Vec<_>>();"
+            )]
+        }
+    }
+}
+
+/// The start of the `new` function of a day for `input`: what it looks like
+/// as a comment, reading the input and parsing it as it seems to be, or
+/// nothing if it is empty
+pub fn skeleton(input: &str) -> Vec<String> {
+    let shape = sniff(input);
+    if shape.kind == InputKind::Empty {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        format!("// The input looks like {}", shape.kind),
+        "let input = read_file(Self::DAY, example)?;".to_string(),
+    ];
+    lines.extend(reader_call(&shape.kind, "&input", "parsed"));
+    lines
+}
+
+/// `sniff --day N [--example]`: tell what the input of a day looks
+/// like, what may trip its reader up, and the reader call that would parse
+/// it
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["day", "example"])?;
+    let day = flags.day()?;
+    let input = read_file(day, flags.switch("example")?)?;
+    let shape = sniff(&input);
+    println!("Day {day} input: {}", shape.kind);
+    for anomaly in &shape.anomalies {
+        println!("Warning: {anomaly}");
+    }
+    for line in skeleton(&input) {
+        println!("    {line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skeleton() {
+        assert!(skeleton("\n").is_empty());
+        assert_eq!(
+            skeleton("..@\n@.@\n"),
+            [
+                "// The input looks like a grid of 2 rows and 3 columns of '.', '@'",
+                "let input = read_file(Self::DAY, example)?;",
+                "static CELLS: CharMap<u8> = char_map!('.' => 0, '@' => 1);",
+                "let parsed = parse_byte_grid(&input, &CELLS)?;",
+            ]
+        );
+        let sections = skeleton("3-5\n10-14\n\n1\n17\n");
+        assert_eq!(
+            sections[2..4],
+            [
+                "let sections = input.split(\"\\n\\n\").collect::<Vec<_>>();",
+                "// 2 rows of 2 integers separated by dashes",
+            ]
+            .map(String::from)[..]
+        );
+        assert_eq!(
+            sections.last().map(String::as_str),
+            Some("let section2 = parse_lines(sections[1], str::parse::<i64>)?;")
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Puzzle statements kept offline as Markdown, next to the solutions.
+//!
+//! The text of the puzzles is not to be redistributed, so the `puzzles`
+//! directory is ignored by git.
+
+use std::{fmt::Write as _, fs};
+
+use anyhow::{Result, bail};
+use util::aoc_api::Client;
+
+use crate::Flags;
+
+/// Marks the heading of the second part, which only shows once it unlocks
+const PART_TWO: &str = "--- Part Two ---";
+
+/// Replace the few HTML entities the puzzle pages use
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The value of an attribute in the inside of an opening tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let (_, rest) = tag.split_once(&format!("{name}=\""))?;
+    rest.split_once('"').map(|(value, _)| value)
+}
+
+/// Markdown for the HTML of a puzzle description.
+///
+/// Only the handful of elements the puzzle pages use are handled: headings,
+/// paragraphs, emphasis, inline and block code, lists and links. Anything
+/// else keeps its text and loses its markup.
+fn to_markdown(html: &str, directory: &str) -> String {
+    let mut markdown = String::new();
+    let mut in_pre = false;
+    let mut links = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let text = unescape(&rest[..start]);
+        if in_pre {
+            markdown.push_str(&text);
+        } else {
+            // Line breaks in the source are just spaces in the text
+            markdown.push_str(&text.replace('\n', " "));
+        }
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+        let name = tag
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match name.as_str() {
+            "h2" => markdown.push_str("## "),
+            "/h2" | "/p" | "/ul" => markdown.push_str("\n\n"),
+            "pre" => {
+                in_pre = true;
+                markdown.push_str("```\n");
+            }
+            "/pre" => {
+                in_pre = false;
+                if !markdown.ends_with('\n') {
+                    markdown.push('\n');
+                }
+                markdown.push_str("```\n\n");
+            }
+            "code" | "/code" if !in_pre => markdown.push('`'),
+            "em" | "/em" if !in_pre => markdown.push('*'),
+            "li" => markdown.push_str("- "),
+            "/li" => markdown.push('\n'),
+            "a" => {
+                let href = attribute(tag, "href").unwrap_or_default();
+                links.push(match href {
+                    href if href.starts_with("http") => href.to_string(),
+                    href if href.starts_with('/') => format!("https://adventofcode.com{href}"),
+                    href => format!("{directory}/{href}"),
+                });
+                markdown.push('[');
+            }
+            "/a" => {
+                let href = links.pop().unwrap_or_default();
+                let _ = write!(markdown, "]({href})");
+            }
+            _ => {}
+        }
+    }
+    markdown.push_str(&unescape(rest));
+    // Tidy up the spaces left around line breaks by the markup, outside code
+    // blocks where they matter
+    let mut in_block = false;
+    let trimmed = markdown
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_block = !in_block;
+                line.trim()
+            } else if in_block {
+                line.trim_end()
+            } else {
+                line.trim()
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut tidy = trimmed.join("\n").trim().to_string();
+    while tidy.contains("\n\n\n") {
+        tidy = tidy.replace("\n\n\n", "\n\n");
+    }
+    tidy + "\n"
+}
+
+/// The puzzle descriptions of a page, one per unlocked part
+fn descriptions(page: &str) -> Vec<&str> {
+    page.split("<article class=\"day-desc\">")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("</article>").map(|(article, _)| article))
+        .collect()
+}
+
+/// The statement of a day as Markdown, with a link back to the puzzle
+fn statement(page: &str, year: u16, day: u8) -> Result<String> {
+    let base_url = format!("https://adventofcode.com/{year}/day/{day}");
+    // Relative links are relative to the directory of the page
+    let directory = format!("https://adventofcode.com/{year}/day");
+    let parts = descriptions(page);
+    if parts.is_empty() {
+        bail!("No puzzle description found on the page of day {day}");
+    }
+    let body = parts
+        .iter()
+        .map(|part| to_markdown(part, &directory))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(format!("<!-- {base_url} -->\n\n{body}"))
+}
+
+/// `statement --day N`: save the puzzle statement to `puzzles/dayXX.md`,
+/// fetching the page again while the saved one lacks part 2
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["day"])?;
+    let day = flags.day()?;
+    let client = Client::new()?;
+    let path = util::get_workspace_root()?.join(format!("puzzles/day{day:02}.md"));
+    if fs::read_to_string(&path).is_ok_and(|saved| saved.contains(PART_TWO)) {
+        println!("Statement already saved to {}", path.display());
+        return Ok(());
+    }
+    let mut page = client.puzzle_page(day)?;
+    if !page.contains(PART_TWO) && path.exists() {
+        // The cached page may predate solving part 1
+        client.forget_page(day)?;
+        page = client.puzzle_page(day)?;
+    }
+    let markdown = statement(&page, client.year(), day)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, markdown)?;
+    let parts = if page.contains(PART_TWO) {
+        "both parts"
+    } else {
+        "part 1"
+    };
+    println!("Statement with {parts} saved to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Test ---</h2><p>The <em>Elves</em> need
+your <a href="/2025/about">help</a> with <code>x &lt; 3</code>:</p>
+<pre><code>1 2
+<em>3</em> 4
+</code></pre>
+<ul>
+<li>One <a href="1/input" target="_blank">input</a>.</li>
+<li>Two.</li>
+</ul>
+</article>
+<p>Your puzzle answer was <code>7</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Again &amp; again.</p></article>
+</main>"#;
+
+    #[test]
+    fn test_statement() -> Result<()> {
+        assert_eq!(
+            statement(PAGE, 2025, 1)?,
+            "<!-- https://adventofcode.com/2025/day/1 -->
+
+## --- Day 1: Test ---
+
+The *Elves* need your [help](https://adventofcode.com/2025/about) with `x < 3`:
+
+```
+1 2
+3 4
+```
+
+- One [input](https://adventofcode.com/2025/day/1/input).
+- Two.
+
+## --- Part Two ---
+
+Again & again.
+"
+        );
+        assert!(statement("<main></main>", 2025, 1).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+//! A corpus of large generated inputs under `inputs/stress`, each with the
+//! seed and size it was generated from, to see how a day scales with the
+//! size of its input.
+//!
+//! Inputs are named `dayNN-SIZE-SEED.txt` and their metadata sits next to
+//! them as `dayNN-SIZE-SEED.json`. What the size counts depends on the day.
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use util::{
+    generate::ranges,
+    get_workspace_root,
+    rng::Rng,
+    writer::{Align, Table},
+};
+
+use crate::Flags;
+
+/// Days with a generator, and what the size of their inputs counts
+const DAYS: [(u8, &str); 4] = [
+    (1, "rotations"),
+    (3, "banks of 100 batteries"),
+    (4, "rows and columns"),
+    (5, "ranges and ids"),
+];
+
+/// How an input of the corpus was generated
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Meta {
+    day: u8,
+    seed: u64,
+    size: usize,
+    bytes: usize,
+}
+
+/// Where the corpus is kept
+fn corpus_dir() -> Result<PathBuf> {
+    Ok(get_workspace_root()?.join("inputs/stress"))
+}
+
+/// An input of `day` of the given size, the same for the same `seed`
+fn generate(day: u8, size: usize, seed: u64) -> Result<String> {
+    let rng = &mut Rng::new(seed);
+    let lines = match day {
+        1 => (0..size)
+            .map(|_| {
+                let direction = if rng.chance(0.5) { 'L' } else { 'R' };
+                format!("{direction}{}", rng.between(1, 999))
+            })
+            .collect::<Vec<_>>(),
+        3 => (0..size)
+            .map(|_| {
+                (0..100)
+                    .map(|_| char::from(b"123456789"[rng.below(9)]))
+                    .collect()
+            })
+            .collect(),
+        4 => (0..size)
+            .map(|_| {
+                // Mostly rolls of paper, as in the real input
+                (0..size)
+                    .map(|_| if rng.chance(0.6) { '@' } else { '.' })
+                    .collect()
+            })
+            .collect(),
+        5 => {
+            let bound = i64::try_from(size).unwrap_or(i64::MAX).saturating_mul(1000);
+            let ranges = ranges(rng, size, 1..=bound)
+                .into_iter()
+                .map(|(start, end)| format!("{start}-{end}"));
+            let ids = (0..size).map(|_| rng.between(1, bound).to_string());
+            ranges.chain([String::new()]).chain(ids).collect()
+        }
+        _ => bail!(
+            "Day {day} has no generator, only days {} do",
+            DAYS.map(|(day, _)| day.to_string()).join(", ")
+        ),
+    };
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The corpus of a day with the inputs themselves, smallest first
+fn corpus(day: u8) -> Result<Vec<(Meta, String)>> {
+    let dir = corpus_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let prefix = format!("day{day:02}-");
+    let mut corpus = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if !(name.starts_with(&prefix) && name.ends_with(".json")) {
+            continue;
+        }
+        let meta = serde_json::from_str::<Meta>(&fs::read_to_string(&path)?)?;
+        let input = fs::read_to_string(path.with_extension("txt"))
+            .map_err(|e| anyhow!("Missing the input of {}: {e}", path.display()))?;
+        corpus.push((meta, input));
+    }
+    corpus.sort_by_key(|(meta, _)| (meta.size, meta.seed));
+    Ok(corpus)
+}
+
+/// The exponent `k` of `time ~ size^k` that fits the points best, by least
+/// squares on their logarithms, if there are two sizes to fit
+#[allow(clippy::cast_precision_loss)]
+fn scaling(points: &[(usize, Duration)]) -> Option<f64> {
+    let logs = points
+        .iter()
+        .filter(|(size, time)| *size > 0 && !time.is_zero())
+        .map(|&(size, time)| ((size as f64).ln(), time.as_secs_f64().ln()))
+        .collect::<Vec<_>>();
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance = logs
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let variance = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/// `stress-gen --day N --size S [--seed X]`: add an input of size `S` to the
+/// corpus of a day
+pub fn generate_run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["day", "size", "seed"])?;
+    let day = flags.day()?;
+    let size = flags.require("size")?;
+    let seed = flags.get("seed")?.unwrap_or_default();
+    let input = generate(day, size, seed)?;
+    let dir = corpus_dir()?;
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("day{day:02}-{size}-{seed}.txt"));
+    let meta = Meta {
+        day,
+        seed,
+        size,
+        bytes: input.len(),
+    };
+    fs::write(&path, &input)?;
+    fs::write(
+        path.with_extension("json"),
+        serde_json::to_string_pretty(&meta)?,
+    )?;
+    println!(
+        "Stress input of {} bytes written to {}",
+        meta.bytes,
+        path.display()
+    );
+    Ok(())
+}
+
+/// `--stress --day N [--seconds S] [--format text|markdown]`: benchmark a day
+/// on every input of its corpus, for about `S` seconds per phase, and show how
+/// its time grows with the size of the input
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["day", "seconds", "format"])?;
+    let day = flags.day()?;
+    let format = flags.format()?;
+    let time_limit = Duration::try_from_secs_f64(flags.get("seconds")?.unwrap_or(0.5))?;
+    let corpus = corpus(day)?;
+    if corpus.is_empty() {
+        bail!("No stress inputs for day {day}, add some with `just stress-gen`");
+    }
+    let solution = solutions::find(day)?;
+    let mut table = Table::new([
+        "Size", "Seed", "Bytes", "Parse", "Part 1", "Part 2", "Total",
+    ]);
+    for column in [0, 2, 3, 4, 5, 6] {
+        table = table.align(column, Align::Right);
+    }
+    let mut totals = Vec::new();
+    for (meta, input) in corpus {
+        let results = solutions::with_input(&input, || solution.bench(time_limit));
+        let total = results.iter().map(|result| result.median).sum();
+        table.row(
+            [
+                meta.size.to_string(),
+                meta.seed.to_string(),
+                meta.bytes.to_string(),
+            ]
+            .into_iter()
+            .chain(
+                results
+                    .iter()
+                    .map(|result| result.human_readable_format()(result.median)),
+            )
+            .chain([format!("{total:.3?}")]),
+        );
+        totals.push((meta.size, total));
+    }
+    print!("{}", format.render(&table));
+    let unit = DAYS
+        .iter()
+        .find_map(|&(d, unit)| (d == day).then_some(unit))
+        .unwrap_or("size");
+    match scaling(&totals) {
+        Some(k) => println!("Total time grows as ({unit})^{k:.2}"),
+        None => println!("Stress inputs of more than one size are needed to see the scaling"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> Result<()> {
+        for (day, _) in DAYS {
+            let input = generate(day, 20, 7)?;
+            assert_eq!(input, generate(day, 20, 7)?);
+            // Every generated input is one the day accepts
+            solutions::parse(day, &input)?;
+        }
+        assert_eq!(generate(4, 20, 1)?.lines().count(), 20);
+        assert_eq!(generate(5, 20, 1)?.lines().count(), 41);
+        assert!(generate(2, 20, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scaling() -> Result<()> {
+        let quadratic =
+            [10, 20, 40, 80].map(|n| (n, Duration::from_nanos(3 * n as u64 * n as u64)));
+        let k = scaling(&quadratic).ok_or_else(|| anyhow!("No scaling"))?;
+        assert!((k - 2.0).abs() < 1e-6);
+        assert_eq!(scaling(&quadratic[..1]), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,470 @@
+//! Submitting answers, keeping a record of every attempt so that no answer
+//! known to be wrong, or for a part already solved, is ever sent again.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use util::{
+    aoc_api::{Client, YEAR},
+    writer::output_file,
+};
+
+use crate::{Flags, answers};
+
+/// What the site made of an answer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    /// Sent before the wait from an earlier attempt was over
+    TooSoon,
+    /// The part is already solved, or not unlocked yet
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    const fn is_wrong(self) -> bool {
+        matches!(self, Self::TooHigh | Self::TooLow | Self::Incorrect)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Correct => "Correct",
+            Self::TooHigh => "Too high",
+            Self::TooLow => "Too low",
+            Self::Incorrect => "Incorrect",
+            Self::TooSoon => "Too soon",
+            Self::WrongLevel => "Wrong level",
+            Self::Unknown => "Unknown",
+        })
+    }
+}
+
+/// One answer sent, as a line of the submissions file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Attempt {
+    /// Seconds since the Unix epoch
+    time: u64,
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: String,
+    verdict: Verdict,
+    /// When the site accepts another answer, if it asked to wait
+    retry_after: Option<u64>,
+    /// The text of the response
+    message: String,
+}
+
+/// Seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The text of the `<article>` of a response page, without tags and with
+/// whitespace collapsed
+fn message(page: &str) -> String {
+    // Past `<article`, the rest of the opening tag comes first
+    let (article, mut in_tag) = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or((page, false), |(article, _)| (article, true));
+    let mut text = String::new();
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// How long a message asks to wait, from either "You have 1m 3s left to
+/// wait" or "Please wait 5 minutes before trying again"
+fn wait(message: &str) -> Option<Duration> {
+    let lower = message.to_lowercase();
+    if let Some((before, _)) = lower.split_once(" left to wait") {
+        let seconds = before
+            .rsplit(' ')
+            .map_while(|token| {
+                let (number, unit) = token.split_at(token.len().checked_sub(1)?);
+                let number = number.parse::<u64>().ok()?;
+                match unit {
+                    "s" => Some(number),
+                    "m" => Some(number * 60),
+                    "h" => Some(number * 3600),
+                    _ => None,
+                }
+            })
+            .sum::<u64>();
+        return Some(Duration::from_secs(seconds));
+    }
+    let (_, after) = lower.split_once("please wait ")?;
+    let mut words = after.split_whitespace();
+    let count = match words.next()? {
+        "one" => 1,
+        number => number.parse().ok()?,
+    };
+    words
+        .next()?
+        .starts_with("minute")
+        .then(|| Duration::from_secs(count * 60))
+}
+
+/// The verdict of a response message
+fn judge(message: &str) -> Verdict {
+    if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("That's not the right answer") {
+        if message.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if message.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Incorrect
+        }
+    } else if message.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if message.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    }
+}
+
+/// Every attempt so far, kept as JSON lines in a file
+struct History {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl History {
+    fn load(path: &Path) -> Result<Self> {
+        let attempts = if path.exists() {
+            fs::read_to_string(path)?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<_, _>>()?
+        } else {
+            Vec::new()
+        };
+        tracing::debug!(attempts = attempts.len(), "submission history loaded");
+        Ok(Self {
+            path: path.to_path_buf(),
+            attempts,
+        })
+    }
+
+    fn record(&mut self, attempt: Attempt) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&attempt)?)?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    fn of(&self, year: u16, day: u8, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| (a.year, a.day, a.part) == (year, day, part))
+    }
+
+    /// Why sending anything for the part would be pointless, if it is solved
+    fn solved(&self, year: u16, day: u8, part: u8) -> Option<String> {
+        self.of(year, day, part)
+            .find(|a| a.verdict == Verdict::Correct)
+            .map(|a| format!("Day {day} part {part} is already solved with {}", a.answer))
+    }
+
+    /// Why sending `answer` would be pointless, if the history tells
+    fn refusal(&self, year: u16, day: u8, part: u8, answer: &str) -> Option<String> {
+        if let Some(reason) = self.solved(year, day, part) {
+            return Some(reason);
+        }
+        if let Some(tried) = self
+            .of(year, day, part)
+            .find(|a| a.answer == answer && a.verdict.is_wrong())
+        {
+            return Some(format!("{answer} was already rejected: {}", tried.message));
+        }
+        // Numbers past an answer known to be too high or too low are wrong too
+        let number = answer.parse::<i128>().ok()?;
+        self.of(year, day, part).find_map(|a| {
+            let bound = a.answer.parse::<i128>().ok()?;
+            match a.verdict {
+                Verdict::TooHigh if number >= bound => {
+                    Some(format!("{answer} is not below {bound}, which is too high"))
+                }
+                Verdict::TooLow if number <= bound => {
+                    Some(format!("{answer} is not above {bound}, which is too low"))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// When the site accepts another answer for the part
+    fn retry_after(&self, year: u16, day: u8, part: u8) -> Option<u64> {
+        self.of(year, day, part).filter_map(|a| a.retry_after).max()
+    }
+}
+
+/// Attempts at an answer the site keeps saying is sent too soon
+const MAX_ATTEMPTS: usize = 3;
+/// Shortest wait before sending again, whatever the site says is left
+const MIN_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Send `answer` with `send` until the site judges it, recording every
+/// attempt and waiting with `sleep` as long as the site asks in between
+///
+/// # Errors
+/// This function will return an error if sending fails, if the site says it
+/// is too soon without saying how long to wait, or still says so after
+/// [`MAX_ATTEMPTS`] attempts.
+fn send_until_judged(
+    history: &mut History,
+    day: u8,
+    part: u8,
+    answer: &str,
+    mut send: impl FnMut() -> Result<String>,
+    mut sleep: impl FnMut(Duration),
+) -> Result<Verdict> {
+    for attempt in 0..MAX_ATTEMPTS {
+        let left = (history.retry_after(YEAR, day, part))
+            .map_or(0, |retry_after| retry_after.saturating_sub(now()));
+        let mut left = Duration::from_secs(left);
+        if attempt > 0 {
+            left = left.max(MIN_BACKOFF);
+        }
+        if !left.is_zero() {
+            println!("Waiting {}s before submitting", left.as_secs());
+            sleep(left);
+        }
+        println!("Submitting {answer} for day {day} part {part}");
+        let message = message(&send()?);
+        let verdict = judge(&message);
+        let wait = wait(&message);
+        history.record(Attempt {
+            time: now(),
+            year: YEAR,
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict,
+            retry_after: wait.map(|wait| now() + wait.as_secs()),
+            message: message.clone(),
+        })?;
+        println!("{verdict}: {message}");
+        if verdict != Verdict::TooSoon {
+            return Ok(verdict);
+        }
+        if wait.is_none() {
+            bail!("Too soon, without saying how long to wait: {message}");
+        }
+    }
+    bail!("Still too soon for day {day} part {part} after {MAX_ATTEMPTS} attempts")
+}
+
+/// The accepted answer of every part solved this year by day and part, as
+/// the history records
+pub fn correct_answers() -> Result<BTreeMap<(u8, u8), String>> {
+    let history = History::load(&output_file("submissions.jsonl")?)?;
+    Ok((history.attempts.into_iter())
+        .filter(|a| a.year == YEAR && a.verdict == Verdict::Correct)
+        .map(|a| ((a.day, a.part), a.answer))
+        .collect())
+}
+
+/// Stars of every day with a part solved this year, as the history records
+pub fn stars() -> Result<BTreeMap<u8, usize>> {
+    let mut stars = BTreeMap::new();
+    for (day, _) in correct_answers()?.into_keys() {
+        *stars.entry(day).or_default() += 1;
+    }
+    Ok(stars)
+}
+
+/// `submit --day N --part P [--answer A]`: send the answer of a part, which
+/// is computed by running the day unless given
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["day", "part", "answer"])?;
+    let day = flags.day()?;
+    let part = flags.require("part")?;
+    if !(1..=2).contains(&part) {
+        bail!("Part must be 1 or 2");
+    }
+    let mut history = History::load(&output_file("submissions.jsonl")?)?;
+    if let Some(reason) = history.solved(YEAR, day, part) {
+        bail!(reason);
+    }
+    let answer = match flags.get::<String>("answer")? {
+        Some(answer) => answer,
+        None => answers(day)?[usize::from(part - 1)].clone(),
+    };
+    if answer.is_empty() || answer.contains("not implemented") {
+        bail!("No answer to submit for day {day} part {part}: {answer:?}");
+    }
+    if let Some(reason) = history.refusal(YEAR, day, part, &answer) {
+        bail!(reason);
+    }
+    let client = Client::new()?;
+    let verdict = send_until_judged(
+        &mut history,
+        day,
+        part,
+        &answer,
+        || Ok(client.submit(day, part, &answer)?),
+        thread::sleep,
+    )?;
+    if verdict == Verdict::Correct {
+        client.forget_page(day)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORRECT: &str = "<main>\n<article><p>That's the right answer!  You are <span \
+                           class=\"day-success\">one gold star</span> closer.</p></article>\n</main>";
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high. \
+                            Please wait one minute before trying again.</p></article>";
+    const TOO_SOON: &str = "<article><p>You gave an answer too recently; you have to wait \
+                            after submitting an answer before trying again.  You have 1m 3s \
+                            left to wait.</p></article>";
+
+    #[test]
+    fn test_judge() {
+        assert_eq!(
+            message(CORRECT),
+            "That's the right answer! You are one gold star closer."
+        );
+        let cases = [
+            (CORRECT, Verdict::Correct, None),
+            (TOO_HIGH, Verdict::TooHigh, Some(60)),
+            (TOO_SOON, Verdict::TooSoon, Some(63)),
+            (
+                "<article><p>That's not the right answer. Please wait 5 minutes before trying \
+                 again.</p></article>",
+                Verdict::Incorrect,
+                Some(300),
+            ),
+            (
+                "<article><p>You don't seem to be solving the right level.</p></article>",
+                Verdict::WrongLevel,
+                None,
+            ),
+        ];
+        for (page, verdict, seconds) in cases {
+            let message = message(page);
+            assert_eq!(judge(&message), verdict, "{message}");
+            assert_eq!(
+                wait(&message),
+                seconds.map(Duration::from_secs),
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_history() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("submissions-{}.jsonl", std::process::id()));
+        let attempt = |answer: &str, verdict, retry_after| Attempt {
+            time: 0,
+            year: 2025,
+            day: 1,
+            part: 1,
+            answer: answer.to_string(),
+            verdict,
+            retry_after,
+            message: String::new(),
+        };
+        let mut history = History::load(&path)?;
+        history.record(attempt("100", Verdict::TooHigh, Some(60)))?;
+        history.record(attempt("abc", Verdict::Incorrect, Some(120)))?;
+        history.record(attempt("10", Verdict::TooLow, None))?;
+        // Everything survives a round trip through the file
+        let mut history = History::load(&path)?;
+        assert_eq!(history.attempts.len(), 3);
+        assert_eq!(history.retry_after(2025, 1, 1), Some(120));
+        assert!(history.refusal(2025, 1, 1, "abc").is_some());
+        assert!(history.refusal(2025, 1, 1, "100").is_some());
+        assert!(history.refusal(2025, 1, 1, "250").is_some());
+        assert!(history.refusal(2025, 1, 1, "7").is_some());
+        assert_eq!(history.refusal(2025, 1, 1, "50"), None);
+        // Other parts are unaffected
+        assert_eq!(history.refusal(2025, 1, 2, "100"), None);
+        history.record(attempt("50", Verdict::Correct, None))?;
+        assert_eq!(
+            history.refusal(2025, 1, 1, "51"),
+            Some("Day 1 part 1 is already solved with 50".to_string())
+        );
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_too_soon() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("too-soon-{}.jsonl", std::process::id()));
+        let mut history = History::load(&path)?;
+        // Without a wait to go by, it stops rather than sending again
+        let (mut sent, mut slept) = (0, Vec::new());
+        let unparseable = "<article><p>You gave an answer too recently.</p></article>";
+        let result = send_until_judged(
+            &mut history,
+            1,
+            1,
+            "42",
+            || {
+                sent += 1;
+                Ok(unparseable.to_string())
+            },
+            |wait| slept.push(wait),
+        );
+        assert!(result.is_err());
+        assert_eq!((sent, slept.len()), (1, 0));
+        // With one, it waits at least the minimum, a few times at most
+        let result = send_until_judged(
+            &mut history,
+            1,
+            2,
+            "42",
+            || {
+                sent += 1;
+                Ok(TOO_SOON.to_string())
+            },
+            |wait| slept.push(wait),
+        );
+        assert!(result.is_err());
+        assert_eq!(sent, 1 + MAX_ATTEMPTS);
+        assert_eq!(slept.len(), MAX_ATTEMPTS - 1);
+        assert!(slept.iter().all(|&wait| wait >= MIN_BACKOFF));
+        assert_eq!(history.attempts.len(), 1 + MAX_ATTEMPTS);
+        fs::remove_file(path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+/* C interface of the solutions, from `cargo build -r -p solutions --features ffi`,
+ * which builds target/release/libsolutions.so (.dylib, .dll). */
+
+#ifndef AOC_H
+#define AOC_H
+
+#include <stddef.h>
+#include <stdint.h>
+#include <sys/types.h>
+
+/* The day is not solved, or the part is neither 1 nor 2 */
+#define AOC_UNKNOWN (-1)
+/* The input is not valid UTF-8 */
+#define AOC_NOT_UTF8 (-2)
+/* The input pointer is null while its length is not zero */
+#define AOC_NULL_INPUT (-3)
+/* The solution panicked, usually on a malformed input */
+#define AOC_PANICKED (-4)
+
+/* Solve a part of a day for an input of `len` bytes.
+ *
+ * The answer is written to `out_buf` as a NUL-terminated string if it fits in
+ * `out_cap` bytes with the NUL. Either way, its length without the NUL is
+ * returned, so that a call with a large enough buffer can follow; errors are
+ * the negative AOC_* codes above. */
+ssize_t aoc_solve(uint8_t day, uint8_t part, const uint8_t *input_ptr, size_t len,
+                  uint8_t *out_buf, size_t out_cap);
+
+#endif
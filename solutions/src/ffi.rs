@@ -0,0 +1,76 @@
+//! C functions for embedding the solutions in harnesses written in other
+//! languages, declared in `include/aoc.h`.
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    ptr, slice, str,
+};
+
+use crate::{find, with_input};
+
+/// The day is not solved, or the part is neither 1 nor 2
+const AOC_UNKNOWN: isize = -1;
+/// The input is not valid UTF-8
+const AOC_NOT_UTF8: isize = -2;
+/// The input pointer is null while its length is not zero
+const AOC_NULL_INPUT: isize = -3;
+/// The solution panicked, usually on a malformed input
+const AOC_PANICKED: isize = -4;
+
+/// Solve a part of a day for an input of `len` bytes.
+///
+/// The answer is written to `out_buf` as a NUL-terminated string if it fits
+/// in `out_cap` bytes with the NUL. Either way, its length without the NUL is
+/// returned, so that a call with a large enough buffer can follow; errors
+/// are negative:
+/// - `-1`: the day is not solved, or the part is neither 1 nor 2
+/// - `-2`: the input is not valid UTF-8
+/// - `-3`: `input_ptr` is null while `len` is not zero
+/// - `-4`: the solution panicked, usually on a malformed input
+///
+/// # Safety
+/// `input_ptr` must point to `len` readable bytes, and `out_buf` to
+/// `out_cap` writable bytes unless it is null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input_ptr: *const u8,
+    len: usize,
+    out_buf: *mut u8,
+    out_cap: usize,
+) -> isize {
+    let input = if len == 0 {
+        &[][..]
+    } else if input_ptr.is_null() {
+        return AOC_NULL_INPUT;
+    } else {
+        // SAFETY: the caller guarantees `len` readable bytes at `input_ptr`
+        unsafe { slice::from_raw_parts(input_ptr, len) }
+    };
+    let Ok(input) = str::from_utf8(input) else {
+        return AOC_NOT_UTF8;
+    };
+    let Ok(solution) = find(day) else {
+        return AOC_UNKNOWN;
+    };
+    // Unwinding into the caller is undefined behavior
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+        with_input(input, || solution.solve(false, part))
+    }));
+    let answer = match answer {
+        Ok(Some(answer)) => answer,
+        Ok(None) => return AOC_UNKNOWN,
+        Err(_) => return AOC_PANICKED,
+    };
+    let bytes = answer.as_bytes();
+    if !out_buf.is_null() && bytes.len() < out_cap {
+        // SAFETY: the caller guarantees `out_cap` writable bytes at `out_buf`,
+        // which is more than the answer and its NUL
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), out_buf, bytes.len());
+            out_buf.add(bytes.len()).write(0);
+        }
+    }
+    isize::try_from(bytes.len()).unwrap_or(isize::MAX)
+}
@@ -0,0 +1,94 @@
+//! Every day as a library, for running the solutions from elsewhere than
+//! their own binaries, such as the browser, Python or C.
+//!
+//! The `main.rs` of each day is included as it is, and its own `main` is
+//! never called. Inputs other than the files under `inputs` are handed to the
+//! days through [`with_input`].
+
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "python")]
+mod python;
+
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::{Result, anyhow, bail};
+use util::{DynSolution, reader::set_input_provider};
+
+macro_rules! days {
+    ($($day:ident),* $(,)?) => {
+        $(
+            #[allow(dead_code)]
+            mod $day {
+                include!(concat!("../../", stringify!($day), "/src/main.rs"));
+
+                pub fn solution() -> Box<dyn util::DynSolution> {
+                    util::Day::<Puzzle>::boxed()
+                }
+
+                pub fn parse_input(input: &str) -> anyhow::Result<()> {
+                    crate::with_input(input, || Puzzle::new(false)).map(drop)
+                }
+            }
+        )*
+
+        /// Parse `input` as the input of a day without solving anything,
+        /// e.g. to check that parsing never panics
+        ///
+        /// # Errors
+        /// This function will return an error if the day is not solved, or
+        /// if the input is invalid.
+        pub fn parse(day: u8, input: &str) -> Result<()> {
+            $(
+                if $day::solution().day() == day {
+                    return $day::parse_input(input);
+                }
+            )*
+            bail!("Day {day} is not solved")
+        }
+
+        /// The solutions of all days, in order
+        #[must_use]
+        pub fn all() -> Vec<Box<dyn DynSolution>> {
+            vec![$($day::solution()),*]
+        }
+    };
+}
+
+days!(
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12,
+);
+
+/// The solution of a day
+///
+/// # Errors
+/// This function will return an error if the day is not solved.
+pub fn find(day: u8) -> Result<Box<dyn DynSolution>> {
+    all()
+        .into_iter()
+        .find(|solution| solution.day() == day)
+        .ok_or_else(|| anyhow!("Day {day} is not solved"))
+}
+
+/// Held while the days read an input other than their files
+static INPUT: Mutex<()> = Mutex::new(());
+
+/// Run `f` with every day reading `input` instead of its files, which they
+/// read again afterwards, even if `f` panics.
+///
+/// Calls from several threads take turns, as the input is shared by all.
+pub fn with_input<T>(input: &str, f: impl FnOnce() -> T) -> T {
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            set_input_provider(None);
+        }
+    }
+
+    let _lock = INPUT.lock().unwrap_or_else(PoisonError::into_inner);
+    let input = input.to_string();
+    set_input_provider(Some(Box::new(move |_, _| Ok(input.clone()))));
+    let _reset = Reset;
+    f()
+}
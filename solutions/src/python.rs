@@ -0,0 +1,71 @@
+//! The `solutions` Python module, for driving the days from notebooks:
+//!
+//! ```python
+//! import solutions
+//! solutions.solve(1, 2, open("inputs/day01.txt").read())
+//! solutions.benchmark(1, 0.5)
+//! ```
+
+use std::{fmt::Display, time::Duration};
+
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+
+use crate::{find, with_input};
+
+fn value_error(e: &dyn Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// solve(day, part, input) -> str
+///
+/// The answer of a part of a day for the given input.
+#[pyfunction]
+fn solve(day: u8, part: u8, input: &str) -> PyResult<String> {
+    let solution = find(day).map_err(|e| value_error(&e))?;
+    with_input(input, || solution.solve(false, part))
+        .ok_or_else(|| value_error(&"Part must be 1 or 2"))
+}
+
+/// benchmark(day, seconds, input=None) -> list[dict]
+///
+/// Benchmark parsing and both parts of a day for about `seconds` each, on
+/// `input` or else on the input file of the day. Times are in seconds.
+#[pyfunction]
+#[pyo3(signature = (day, seconds, input = None))]
+fn benchmark<'py>(
+    py: Python<'py>,
+    day: u8,
+    seconds: f64,
+    input: Option<&str>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let solution = find(day).map_err(|e| value_error(&e))?;
+    let time_limit = Duration::try_from_secs_f64(seconds).map_err(|e| value_error(&e))?;
+    let bench = || solution.bench(time_limit);
+    let results = input.map_or_else(bench, |input| with_input(input, bench));
+    results
+        .iter()
+        .map(|result| {
+            let dict = PyDict::new(py);
+            dict.set_item("name", &result.name)?;
+            dict.set_item("iterations", result.iterations)?;
+            for (key, time) in [
+                ("fastest", result.fastest),
+                ("slowest", result.slowest),
+                ("mean", result.mean),
+                ("std_dev", result.std_dev),
+                ("median", result.median),
+                ("mad", result.mad),
+            ] {
+                dict.set_item(key, time.as_secs_f64())?;
+            }
+            Ok(dict)
+        })
+        .collect()
+}
+
+#[pymodule]
+fn solutions(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(solve, module)?)?;
+    module.add_function(wrap_pyfunction!(benchmark, module)?)?;
+    Ok(())
+}
@@ -1,5 +1,4 @@
-use anyhow::Result;
-use util::Solution;
+use util::prelude::*;
 
 struct Puzzle {}
 
@@ -26,9 +25,12 @@ impl Solution for Puzzle {
 }
 
 fn main() -> Result<()> {
-    let puzzle = Puzzle::new(false)?;
-    println!("Day {} Part 1: {}", Puzzle::DAY, puzzle.part1());
-    println!("Day {} Part 2: {}", Puzzle::DAY, puzzle.part2());
+    trace::init();
+    diagnostics::init();
+    let puzzle = trace::parse(Puzzle::DAY, || Puzzle::new(false))?;
+    diagnostics::report();
+    println!("Day {} Part 1: {}", Puzzle::DAY, trace::part(Puzzle::DAY, 1, || puzzle.part1()));
+    println!("Day {} Part 2: {}", Puzzle::DAY, trace::part(Puzzle::DAY, 2, || puzzle.part2()));
 
     Ok(())
 }
@@ -37,26 +39,22 @@ fn main() -> Result<()> {
 mod tests {
     use std::time::Duration;
 
-    use util::{Benchmark, Serializable};
+    use util::testing::assert_answer;
 
     use super::*;
 
     #[test]
-    fn test_part1() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part1(), "Part 1 not implemented");
-        Ok(())
+    fn test_part1() {
+        assert_answer!(Puzzle, part1, example);
     }
 
     #[test]
-    fn test_part2() -> Result<()> {
-        let puzzle = Puzzle::new(true)?;
-        assert_eq!(puzzle.part2(), "Part 2 not implemented");
-        Ok(())
+    fn test_part2() {
+        assert_answer!(Puzzle, part2, example);
     }
 
     #[test]
     fn benchmark() -> Result<()> {
-        Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)
+        Ok(Puzzle::bench_all(Duration::from_secs(1)).to_csv(Puzzle::DAY)?)
     }
 }
@@ -0,0 +1,232 @@
+//! What the dashboard knows about every day, updated as jobs report back.
+
+use std::{fs, path::Path, time::Duration};
+
+use anyhow::Result;
+use util::timer::BenchmarkResult;
+
+/// Phases every day benchmarks, in the order of their columns
+pub const PHASES: [&str; 3] = ["Parse", "Part 1", "Part 2"];
+
+/// Outcome of the example tests of a day
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Examples {
+    Unknown,
+    Running,
+    Passed,
+    Failed,
+}
+
+/// Something done to a day in the background
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Job {
+    /// Solve both parts on the actual input
+    Run,
+    /// Benchmark parsing and both parts on the actual input
+    Bench,
+    /// Run the example tests of the day
+    Test,
+}
+
+impl Job {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Run => "running",
+            Self::Bench => "benchmarking",
+            Self::Test => "testing",
+        }
+    }
+}
+
+/// What a job found out about a day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Update {
+    /// A job started on the day
+    Started(u8, Job),
+    /// Answers of both parts on the actual input
+    Ran(u8, [String; 2]),
+    /// Medians of parsing and both parts
+    Benched(u8, [Duration; 3]),
+    /// Whether the example tests passed
+    Tested(u8, bool),
+    /// The job panicked, with the message
+    Failed(u8, String),
+}
+
+impl Update {
+    const fn day(&self) -> u8 {
+        match self {
+            Self::Started(day, _)
+            | Self::Ran(day, _)
+            | Self::Benched(day, _)
+            | Self::Tested(day, _)
+            | Self::Failed(day, _) => *day,
+        }
+    }
+}
+
+/// A row of the dashboard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub day: u8,
+    pub answers: Option<[String; 2]>,
+    pub medians: [Option<Duration>; 3],
+    pub examples: Examples,
+    /// What is running for the day, or the last failure
+    pub status: String,
+}
+
+impl Row {
+    const fn new(day: u8) -> Self {
+        Self {
+            day,
+            answers: None,
+            medians: [None; 3],
+            examples: Examples::Unknown,
+            status: String::new(),
+        }
+    }
+
+    /// Medians of the benchmarks saved by the `benchmark` test of the day in
+    /// `outputs`, if there are any
+    fn load_medians(&mut self, outputs: &Path) -> Result<()> {
+        let path = outputs.join(format!("benchmark-day{:02}.csv", self.day));
+        if path.exists() {
+            let results = BenchmarkResult::from_csv(&fs::read_to_string(path)?)?;
+            self.medians = PHASES.map(|phase| {
+                let result = results.iter().find(|r| r.name == phase)?;
+                Some(result.median)
+            });
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Started(_, job) => {
+                if job == Job::Test {
+                    self.examples = Examples::Running;
+                }
+                self.status = format!("{}…", job.label());
+            }
+            Update::Ran(_, answers) => {
+                self.answers = Some(answers);
+                self.status.clear();
+            }
+            Update::Benched(_, medians) => {
+                self.medians = medians.map(Some);
+                self.status.clear();
+            }
+            Update::Tested(_, passed) => {
+                self.examples = if passed {
+                    Examples::Passed
+                } else {
+                    Examples::Failed
+                };
+                self.status.clear();
+            }
+            Update::Failed(_, message) => {
+                if self.examples == Examples::Running {
+                    self.examples = Examples::Unknown;
+                }
+                self.status = message;
+            }
+        }
+    }
+
+    /// Text of every column, as in [`HEADERS`]
+    pub fn cells(&self) -> [String; 8] {
+        let [part1, part2] = self
+            .answers
+            .clone()
+            .unwrap_or_else(|| [String::new(), String::new()]);
+        let duration = |d: Option<Duration>| d.map(|d| format!("{d:.2?}")).unwrap_or_default();
+        let [parse, time1, time2] = self.medians.map(duration);
+        let examples = match self.examples {
+            Examples::Unknown => "",
+            Examples::Running => "…",
+            Examples::Passed => "pass",
+            Examples::Failed => "FAIL",
+        };
+        [
+            format!("{:02}", self.day),
+            part1,
+            part2,
+            parse,
+            time1,
+            time2,
+            examples.to_string(),
+            self.status.clone(),
+        ]
+    }
+}
+
+/// Headers of the columns of [`Row::cells`]
+pub const HEADERS: [&str; 8] = [
+    "Day", "Part 1", "Part 2", "Parse", "Part 1", "Part 2", "Examples", "Status",
+];
+
+/// Every solved day, in order
+pub struct App {
+    pub rows: Vec<Row>,
+}
+
+impl App {
+    /// A row for each of `days`, with the medians saved in `outputs`
+    pub fn new(days: impl IntoIterator<Item = u8>, outputs: &Path) -> Result<Self> {
+        let mut rows = days.into_iter().map(Row::new).collect::<Vec<_>>();
+        for row in &mut rows {
+            row.load_medians(outputs)?;
+        }
+        Ok(Self { rows })
+    }
+
+    /// Take in what a job found out, ignoring days without a row
+    pub fn apply(&mut self, update: Update) {
+        let day = update.day();
+        if let Some(row) = self.rows.iter_mut().find(|row| row.day == day) {
+            row.apply(update);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() -> Result<()> {
+        let outputs = std::env::temp_dir().join(format!("tui-{}", std::process::id()));
+        fs::create_dir_all(&outputs)?;
+        fs::write(
+            outputs.join("benchmark-day02.csv"),
+            "name,iterations,time_limit,fastest,slowest,mean,std_dev,median,mad\n\
+             Parse,10,1s,1µs,3µs,2µs,1µs,2µs,1µs\n\
+             Part 2,10,1s,1ms,3ms,2ms,1ms,2ms,1ms\n",
+        )?;
+        let mut app = App::new([1, 2], &outputs)?;
+        fs::remove_dir_all(outputs)?;
+        assert_eq!(
+            app.rows[1].cells(),
+            ["02", "", "", "2.00µs", "", "2.00ms", "", ""].map(String::from)
+        );
+        app.apply(Update::Started(1, Job::Test));
+        assert_eq!(
+            app.rows[0].cells()[6..],
+            ["…", "testing…"].map(String::from)
+        );
+        app.apply(Update::Tested(1, false));
+        app.apply(Update::Ran(1, ["3".to_string(), "6".to_string()]));
+        app.apply(Update::Benched(1, [Duration::from_micros(5); 3]));
+        assert_eq!(
+            app.rows[0].cells(),
+            ["01", "3", "6", "5.00µs", "5.00µs", "5.00µs", "FAIL", ""].map(String::from)
+        );
+        app.apply(Update::Failed(2, "Failed to parse input".to_string()));
+        assert_eq!(app.rows[1].status, "Failed to parse input");
+        // Days without a row are left alone
+        app.apply(Update::Tested(25, true));
+        assert_eq!(app.rows.len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Jobs run on a thread of their own, one day after another, reporting every
+//! step back so that the dashboard updates as they go.
+
+use std::{
+    any::Any,
+    env,
+    panic::{self, AssertUnwindSafe},
+    process::{Command, Stdio},
+    sync::mpsc::Sender,
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+
+use crate::app::{Job, Update};
+
+/// How long each phase is benchmarked for, as in the `benchmark` test of
+/// every day
+const TIME_LIMIT: Duration = Duration::from_secs(1);
+
+/// The message a panic was raised with
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(ToString::to_string))
+        .unwrap_or_else(|| "panicked".to_string())
+}
+
+/// Do `job` to a day, through the solution registry or, for the example
+/// tests, through cargo
+fn work(job: Job, day: u8) -> Result<Update> {
+    Ok(match job {
+        Job::Run => Update::Ran(day, solutions::find(day)?.run(false).answers),
+        Job::Bench => {
+            let results = solutions::find(day)?.bench(TIME_LIMIT);
+            Update::Benched(day, results.map(|result| result.median))
+        }
+        Job::Test => {
+            let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+            let status = Command::new(cargo)
+                .args([
+                    "test",
+                    "--quiet",
+                    "-p",
+                    &format!("day{day:02}"),
+                    "test_part",
+                ])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()?;
+            Update::Tested(day, status.success())
+        }
+    })
+}
+
+/// Do `job` to each of `days` in turn on another thread, sending what it
+/// finds to `updates`
+pub fn spawn(job: Job, days: Vec<u8>, updates: Sender<Update>) {
+    thread::spawn(move || {
+        for day in days {
+            if updates.send(Update::Started(day, job)).is_err() {
+                // The dashboard is gone
+                return;
+            }
+            let update = match panic::catch_unwind(AssertUnwindSafe(|| work(job, day))) {
+                Ok(Ok(update)) => update,
+                Ok(Err(e)) => Update::Failed(day, e.to_string()),
+                Err(payload) => Update::Failed(day, panic_message(payload.as_ref())),
+            };
+            if updates.send(update).is_err() {
+                return;
+            }
+        }
+    });
+}
@@ -0,0 +1,133 @@
+//! A terminal dashboard of every solved day: its answers, the medians of its
+//! last benchmark and whether its example tests pass.
+//!
+//! All days are solved and tested in the background on start, and the table
+//! updates as they finish. The selected day can be run, benchmarked or tested
+//! again. Benchmarks taken here are shown but not saved, as `just bench` saves
+//! every variant of a day.
+//!
+//! ```text
+//! cargo run -r -p tui
+//! ```
+
+mod app;
+mod jobs;
+
+use std::{
+    panic,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use app::{App, HEADERS, Job, Update};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Row, Table, TableState},
+};
+use util::get_workspace_root;
+
+/// Keys and what they do, shown below the table
+const HELP: &str = "↑/↓ select · r run · b benchmark · t test · a test all · q quit";
+
+/// How long to wait for a key before taking in updates again
+const TICK: Duration = Duration::from_millis(100);
+
+fn draw(frame: &mut Frame, app: &App, state: &mut TableState) {
+    let [table_area, help_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let header = Row::new(HEADERS).style(Style::new().add_modifier(Modifier::BOLD));
+    let rows = app.rows.iter().map(|row| Row::new(row.cells()));
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Length(16),
+        Constraint::Length(16),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::bordered().title(" Advent of Code 2025 "))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, table_area, state);
+    frame.render_widget(Line::raw(HELP), help_area);
+}
+
+/// Handle keys until quit, taking in the updates of the jobs in between
+fn run(
+    terminal: &mut DefaultTerminal,
+    mut app: App,
+    sender: &Sender<Update>,
+    receiver: &Receiver<Update>,
+) -> Result<()> {
+    let mut state = TableState::new().with_selected(Some(0));
+    loop {
+        while let Ok(update) = receiver.try_recv() {
+            app.apply(update);
+        }
+        terminal.draw(|frame| draw(frame, &app, &mut state))?;
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let selected = state
+            .selected()
+            .and_then(|index| app.rows.get(index))
+            .map(|row| row.day);
+        let job = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => {
+                state.select_previous();
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                state.select_next();
+                None
+            }
+            KeyCode::Char('r') => Some(Job::Run),
+            KeyCode::Char('b') => Some(Job::Bench),
+            KeyCode::Char('t') => Some(Job::Test),
+            KeyCode::Char('a') => {
+                let days = app.rows.iter().map(|row| row.day).collect();
+                jobs::spawn(Job::Test, days, sender.clone());
+                None
+            }
+            _ => None,
+        };
+        if let (Some(job), Some(day)) = (job, selected) {
+            jobs::spawn(job, vec![day], sender.clone());
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    // Panics of the jobs are shown in the table, and would garble the screen
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().name() == Some("main") {
+            default_hook(info);
+        }
+    }));
+    let days = solutions::all()
+        .iter()
+        .map(|solution| solution.day())
+        .collect::<Vec<_>>();
+    let app = App::new(days.clone(), &get_workspace_root()?.join("outputs"))?;
+    let (sender, receiver) = mpsc::channel();
+    jobs::spawn(Job::Run, days.clone(), sender.clone());
+    jobs::spawn(Job::Test, days, sender.clone());
+    ratatui::run(|terminal| run(terminal, app, &sender, &receiver))
+}
@@ -0,0 +1,44 @@
+//! With the `cache` feature, a hash of the sources of `util` for the keys of
+//! cached puzzles, so that a puzzle parsed by older `util` code is parsed
+//! again rather than read back.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Every file under `dir`, in a stable order
+fn files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            found.extend(files(&path)?);
+        } else {
+            found.push(path);
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+fn main() -> io::Result<()> {
+    println!("cargo::rerun-if-changed=src");
+    if env::var_os("CARGO_FEATURE_CACHE").is_none() {
+        return Ok(());
+    }
+    // 64-bit FNV-1a, like `util::cache` itself
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    let src = Path::new("src");
+    for path in files(src)? {
+        let name = path.strip_prefix(src).unwrap_or(&path).to_string_lossy();
+        for part in [name.as_bytes(), &fs::read(&path)?] {
+            // The length keeps the boundaries between parts apart
+            for &byte in (part.len() as u64).to_le_bytes().iter().chain(part) {
+                hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+    }
+    println!("cargo::rustc-env=UTIL_SOURCE_HASH={hash:016x}");
+    Ok(())
+}
@@ -0,0 +1,25 @@
+//! The global allocator of every binary built on this crate, picked by
+//! feature: mimalloc with `mimalloc`, jemalloc with `jemalloc`, and the
+//! system allocator otherwise.
+//!
+//! Days building many small collections, such as the memoized searches of day
+//! 10 and the graphs of day 11, spend a good share of their time allocating.
+//! See `just bench-alloc` for how much another allocator changes that.
+
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+// mimalloc wins when both are enabled, e.g. with `--all-features`
+#[cfg(all(feature = "jemalloc", not(feature = "mimalloc")))]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+/// Name of the global allocator in use
+pub const NAME: &str = if cfg!(feature = "mimalloc") {
+    "mimalloc"
+} else if cfg!(feature = "jemalloc") {
+    "jemalloc"
+} else {
+    "system"
+};
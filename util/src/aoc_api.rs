@@ -0,0 +1,435 @@
+//! Client for the Advent of Code website, keeping everything it fetches in an
+//! on-disk cache so that repeated runs do not hit the servers again.
+//!
+//! Requests are authenticated with the session cookie of a logged-in browser,
+//! taken from the `AOC_SESSION` environment variable or from the
+//! `~/.config/aoc/session` file.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use ureq::Agent;
+
+use super::get_workspace_root;
+use crate::{Error, Result, reader::read_file};
+
+/// The event the puzzles are fetched from
+pub const YEAR: u16 = 2025;
+/// Environment variable holding the session cookie
+pub const SESSION_ENV: &str = "AOC_SESSION";
+/// Session file, relative to the home directory
+const SESSION_FILE: &str = ".config/aoc/session";
+const BASE_URL: &str = "https://adventofcode.com";
+/// Identifies the tool to the site maintainers, as they ask automated clients
+/// to do
+const USER_AGENT: &str = "github.com/TeddyHuang-00/AoC2025 util::aoc_api";
+/// Shown on the puzzle page once both parts are solved, after which it no
+/// longer changes
+const COMPLETE: &str = "Both parts of this puzzle are complete";
+
+/// How long a cached puzzle page is used before it is fetched again, as
+/// solving part 1 unlocks part 2 on the same page
+pub const PAGE_MAX_AGE: Duration = Duration::from_mins(15);
+
+/// How long a cached private leaderboard is used before it is fetched again,
+/// as the site asks not to request one more often than that
+pub const LEADERBOARD_MAX_AGE: Duration = Duration::from_mins(15);
+
+/// The session cookie from `AOC_SESSION`, or else from the session file in
+/// the home directory
+pub fn session() -> Result<String> {
+    read_session(
+        env::var(SESSION_ENV).ok(),
+        env::var_os("HOME").map(PathBuf::from),
+    )
+}
+
+fn read_session(from_env: Option<String>, home: Option<PathBuf>) -> Result<String> {
+    let session = match (from_env, home) {
+        (Some(session), _) => session,
+        (None, Some(home)) => {
+            let path = home.join(SESSION_FILE);
+            fs::read_to_string(&path).map_err(|e| {
+                Error::Session(format!(
+                    "No {SESSION_ENV} set and failed to read '{}': {e}",
+                    path.display()
+                ))
+            })?
+        }
+        (None, None) => {
+            return Err(Error::Session(format!(
+                "No {SESSION_ENV} set and no home directory to look in"
+            )));
+        }
+    };
+    let session = session.trim();
+    // Copied from the browser's cookie storage with the name sometimes
+    let session = session.strip_prefix("session=").unwrap_or(session);
+    if session.is_empty() {
+        return Err(Error::Session("The session cookie is empty".to_string()));
+    }
+    Ok(session.to_string())
+}
+
+/// Days from 1970-01-01 to the given date of the proleptic Gregorian calendar
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // Count years from March, so the leap day is the last day of a year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// When the puzzle of a day unlocks, at midnight in US Eastern Standard Time
+#[must_use]
+pub fn unlock_time(year: u16, day: u8) -> SystemTime {
+    let days = days_from_civil(year.into(), 12, day.into());
+    // Midnight EST is 5 in the morning UTC, and December is always after 1970
+    let seconds = u64::try_from(days * 86_400 + 5 * 3_600).unwrap_or_default();
+    SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+/// Time left until the puzzle of a day unlocks, or `None` once it has
+#[must_use]
+pub fn until_unlock(year: u16, day: u8) -> Option<Duration> {
+    unlock_time(year, day)
+        .duration_since(SystemTime::now())
+        .ok()
+        .filter(|left| !left.is_zero())
+}
+
+/// Files kept under a directory, each usable until it gets too old
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache in `.cache/aoc` at the root of the workspace
+    pub fn workspace() -> Result<Self> {
+        Ok(Self::new(get_workspace_root()?.join(".cache/aoc")))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+
+    /// The cached contents under `key` and how long ago they were stored, if
+    /// anything is
+    pub fn get(&self, key: &str) -> Result<Option<(String, Duration)>> {
+        let path = self.path(key);
+        if !path.exists() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(key, "cache miss");
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)?;
+        // A clock gone backwards makes the entry look brand new
+        let age = fs::metadata(&path)?
+            .modified()?
+            .elapsed()
+            .unwrap_or_default();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(key, age = age.as_secs(), "cache hit");
+        Ok(Some((contents, age)))
+    }
+
+    /// Store `contents` under `key`, replacing anything there
+    pub fn put(&self, key: &str, contents: &str) -> Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Forget anything stored under `key`
+    pub fn remove(&self, key: &str) -> Result<()> {
+        let path = self.path(key);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// A blocking client for one year of puzzles
+pub struct Client {
+    agent: Agent,
+    session: String,
+    cache: Cache,
+    year: u16,
+}
+
+impl Client {
+    /// A client for [`YEAR`], with the [`session`] cookie and the workspace
+    /// [`Cache`]
+    pub fn new() -> Result<Self> {
+        Ok(Self::with(session()?, Cache::workspace()?, YEAR))
+    }
+
+    #[must_use]
+    pub fn with(session: String, cache: Cache, year: u16) -> Self {
+        let agent = Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        Self {
+            agent,
+            session,
+            cache,
+            year,
+        }
+    }
+
+    #[must_use]
+    pub const fn year(&self) -> u16 {
+        self.year
+    }
+
+    #[must_use]
+    pub const fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Cache key of a file about a day
+    fn key(&self, day: u8, name: &str) -> String {
+        format!("{}/day{day:02}/{name}", self.year)
+    }
+
+    fn url(&self, day: u8, path: &str) -> String {
+        format!("{BASE_URL}/{}/day/{day}{path}", self.year)
+    }
+
+    fn check_unlocked(&self, day: u8) -> Result<()> {
+        if !(1..=25).contains(&day) {
+            return Err(Error::InvalidDay(day));
+        }
+        if let Some(left) = until_unlock(self.year, day) {
+            return Err(Error::Locked {
+                year: self.year,
+                day,
+                seconds: left.as_secs(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Fetch `url` with the session cookie
+    pub fn get(&self, url: &str) -> Result<String> {
+        #[cfg(feature = "tracing")]
+        tracing::info!(url, "fetching");
+        self.agent
+            .get(url)
+            .header("Cookie", &format!("session={}", self.session))
+            .call()
+            .map_err(|e| http_error("fetch", url, &e))?
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| http_error("read", url, &e))
+    }
+
+    /// Post a form to `url` with the session cookie
+    pub fn post(&self, url: &str, form: &[(&str, &str)]) -> Result<String> {
+        #[cfg(feature = "tracing")]
+        tracing::info!(url, "posting");
+        self.agent
+            .post(url)
+            .header("Cookie", &format!("session={}", self.session))
+            .send_form(form.iter().copied())
+            .map_err(|e| http_error("post to", url, &e))?
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| http_error("read", url, &e))
+    }
+
+    /// The puzzle input of a day, which never changes once fetched
+    pub fn input(&self, day: u8) -> Result<String> {
+        let key = self.key(day, "input.txt");
+        if let Some((input, _)) = self.cache.get(&key)? {
+            return Ok(input);
+        }
+        self.check_unlocked(day)?;
+        let input = self.get(&self.url(day, "/input"))?;
+        self.cache.put(&key, &input)?;
+        Ok(input)
+    }
+
+    /// Write the puzzle input of a day to `inputs/dayXX.txt` unless it is
+    /// already there, returning the path
+    pub fn download_input(&self, day: u8) -> Result<PathBuf> {
+        let path = get_workspace_root()?.join(format!("inputs/day{day:02}.txt"));
+        if !is_filled(&path) {
+            fs::write(&path, self.input(day)?)?;
+        }
+        Ok(path)
+    }
+
+    /// The HTML page of a day's puzzle, fetched again once it is older than
+    /// [`PAGE_MAX_AGE`] until both parts are solved
+    pub fn puzzle_page(&self, day: u8) -> Result<String> {
+        let key = self.key(day, "puzzle.html");
+        if let Some((page, age)) = self.cache.get(&key)?
+            && (page.contains(COMPLETE) || age < PAGE_MAX_AGE)
+        {
+            return Ok(page);
+        }
+        self.check_unlocked(day)?;
+        let page = self.get(&self.url(day, ""))?;
+        self.cache.put(&key, &page)?;
+        Ok(page)
+    }
+
+    /// Post the answer to a part of a day's puzzle, returning the response
+    /// page with the verdict
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> Result<String> {
+        self.check_unlocked(day)?;
+        let level = part.to_string();
+        self.post(
+            &self.url(day, "/answer"),
+            &[("level", &level), ("answer", answer)],
+        )
+    }
+
+    /// Drop the cached puzzle page of a day, e.g. after solving a part
+    pub fn forget_page(&self, day: u8) -> Result<()> {
+        self.cache.remove(&self.key(day, "puzzle.html"))
+    }
+
+    /// The JSON of a private leaderboard, fetched again only once the cached
+    /// copy is older than [`LEADERBOARD_MAX_AGE`]
+    pub fn leaderboard(&self, id: u64) -> Result<String> {
+        let key = format!("{}/leaderboard/{id}.json", self.year);
+        if let Some((json, age)) = self.cache.get(&key)?
+            && age < LEADERBOARD_MAX_AGE
+        {
+            return Ok(json);
+        }
+        let json = self.get(&format!(
+            "{BASE_URL}/{}/leaderboard/private/view/{id}.json",
+            self.year
+        ))?;
+        // Without a valid session the site answers with its login page
+        if !json.trim_start().starts_with('{') {
+            return Err(Error::Session(format!(
+                "Leaderboard {id} is not available, is the session still valid?"
+            )));
+        }
+        self.cache.put(&key, &json)?;
+        Ok(json)
+    }
+}
+
+/// The real input of a day, downloaded to `inputs` first when it is missing
+/// or still the empty file of the scaffold
+pub fn read_input(day: u8) -> Result<String> {
+    match read_file(day, false) {
+        Ok(input) if !input.is_empty() => return Ok(input),
+        Ok(_) | Err(Error::MissingInput { .. }) => {}
+        Err(e) => return Err(e),
+    }
+    Client::new()?.download_input(day)?;
+    read_file(day, false)
+}
+
+/// A failed request to `url`, keeping only the message of the `ureq` error
+fn http_error(action: &'static str, url: &str, error: &ureq::Error) -> Error {
+    Error::Http {
+        action,
+        url: url.to_string(),
+        message: error.to_string(),
+    }
+}
+
+/// Whether a file exists and has something in it, as the scaffold creates
+/// empty input files
+fn is_filled(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temporary directory
+    fn temp_dir(name: &str) -> Result<PathBuf> {
+        let dir = env::temp_dir().join(format!("aoc-api-{name}-{}", std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    #[test]
+    fn test_session() -> Result<()> {
+        let home = temp_dir("session")?;
+        assert_eq!(read_session(Some(" abc\n".into()), None)?, "abc");
+        assert!(read_session(None, Some(home.clone())).is_err());
+        fs::create_dir_all(home.join(".config/aoc"))?;
+        fs::write(home.join(SESSION_FILE), "session=def\n")?;
+        assert_eq!(read_session(None, Some(home.clone()))?, "def");
+        // The environment takes precedence
+        assert_eq!(read_session(Some("abc".into()), Some(home.clone()))?, "abc");
+        assert!(read_session(Some("\n".into()), None).is_err());
+        fs::remove_dir_all(home)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        let seconds = |time: SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default()
+        };
+        assert_eq!(seconds(unlock_time(2025, 1)), 1_764_565_200);
+        assert_eq!(seconds(unlock_time(2024, 25)), 1_735_102_800);
+        assert_eq!(until_unlock(2015, 1), None);
+    }
+
+    #[test]
+    fn test_cache() -> Result<()> {
+        let cache = Cache::new(temp_dir("cache")?);
+        assert_eq!(cache.get("2025/day01/input.txt")?, None);
+        cache.put("2025/day01/input.txt", "1 2 3")?;
+        let (contents, age) = cache.get("2025/day01/input.txt")?.unwrap_or_default();
+        assert_eq!(contents, "1 2 3");
+        assert!(age < PAGE_MAX_AGE);
+        cache.remove("2025/day01/input.txt")?;
+        assert_eq!(cache.get("2025/day01/input.txt")?, None);
+        fs::remove_dir_all(&cache.dir)?;
+        Ok(())
+    }
+
+    /// Cached entries are served without ever reaching the network, even for
+    /// days that are not unlocked yet
+    #[test]
+    fn test_cached_client() -> Result<()> {
+        let cache = Cache::new(temp_dir("client")?);
+        let client = Client::with("abc".into(), cache.clone(), 9999);
+        cache.put("9999/day01/input.txt", "1 2 3")?;
+        assert_eq!(client.input(1)?, "1 2 3");
+        cache.put("9999/day01/puzzle.html", "<main></main>")?;
+        assert_eq!(client.puzzle_page(1)?, "<main></main>");
+        client.forget_page(1)?;
+        let error = client.puzzle_page(1).map_err(|e| e.to_string());
+        assert!(error.is_err_and(|e| e.starts_with("Day 1 of 9999 unlocks in")));
+        assert!(matches!(client.input(26), Err(Error::InvalidDay(26))));
+        cache.put("9999/leaderboard/42.json", "{}")?;
+        assert_eq!(client.leaderboard(42)?, "{}");
+        fs::remove_dir_all(&cache.dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+//! Bitsets for state masks wider than a machine word
+
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A set of small non-negative integers stored as a bitmask over `u64` words.
+///
+/// The storage is either a `Vec<u64>` (the default, sized at runtime) or a
+/// `[u64; N]` array for a fixed capacity known at compile time, which avoids
+/// heap allocation.
+///
+/// Sets are compared and hashed by their words, so they can be used directly
+/// as keys in maps for bitmask DP. Binary operations require both operands to
+/// have the same number of words.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BitSet<S = Vec<u64>> {
+    words: S,
+}
+
+impl BitSet<Vec<u64>> {
+    /// Create an empty set able to hold the values `0..bits`
+    #[must_use]
+    pub fn new(bits: usize) -> Self {
+        Self {
+            words: vec![0; bits.div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Create a set able to hold the values `0..bits`, containing `indices`
+    pub fn from_indices(bits: usize, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut set = Self::new(bits);
+        for i in indices {
+            set.insert(i);
+        }
+        set
+    }
+}
+
+impl<const N: usize> BitSet<[u64; N]> {
+    /// Create an empty set able to hold the values `0..N * 64`
+    #[must_use]
+    pub const fn new_fixed() -> Self {
+        Self { words: [0; N] }
+    }
+}
+
+impl<S: AsRef<[u64]> + AsMut<[u64]>> BitSet<S> {
+    /// Number of values the set is able to hold
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.words.as_ref().len() * WORD_BITS
+    }
+
+    /// The underlying words, least significant bits first
+    #[must_use]
+    pub fn words(&self) -> &[u64] {
+        self.words.as_ref()
+    }
+
+    /// Whether `i` is in the set
+    #[must_use]
+    pub fn contains(&self, i: usize) -> bool {
+        self.words
+            .as_ref()
+            .get(i / WORD_BITS)
+            .is_some_and(|w| w >> (i % WORD_BITS) & 1 == 1)
+    }
+
+    /// Add `i` to the set, returning whether it was newly inserted
+    pub fn insert(&mut self, i: usize) -> bool {
+        let word = &mut self.words.as_mut()[i / WORD_BITS];
+        let mask = 1 << (i % WORD_BITS);
+        let inserted = *word & mask == 0;
+        *word |= mask;
+        inserted
+    }
+
+    /// Remove `i` from the set, returning whether it was present
+    pub fn remove(&mut self, i: usize) -> bool {
+        let word = &mut self.words.as_mut()[i / WORD_BITS];
+        let mask = 1 << (i % WORD_BITS);
+        let removed = *word & mask != 0;
+        *word &= !mask;
+        removed
+    }
+
+    /// Flip the membership of `i`
+    pub fn toggle(&mut self, i: usize) {
+        self.words.as_mut()[i / WORD_BITS] ^= 1 << (i % WORD_BITS);
+    }
+
+    /// Remove all values
+    pub fn clear(&mut self) {
+        self.words.as_mut().fill(0);
+    }
+
+    /// Number of values in the set
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .as_ref()
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    /// Whether the set contains no values
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.words.as_ref().iter().all(|&w| w == 0)
+    }
+
+    /// Iterate over the values in the set in ascending order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .as_ref()
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, &word)| {
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(idx * WORD_BITS + bit)
+                })
+            })
+    }
+
+    /// Add `i + shift` for every `i` already in the set, dropping values
+    /// beyond the capacity. This is the core step of bitset subset-sum DP.
+    pub fn or_shifted(&mut self, shift: usize) {
+        let words = self.words.as_mut();
+        let (word_shift, bit_shift) = (shift / WORD_BITS, shift % WORD_BITS);
+        // Go downwards so that every source word is read before it changes
+        for i in (word_shift..words.len()).rev() {
+            let src = i - word_shift;
+            let mut shifted = words[src] << bit_shift;
+            if bit_shift > 0 && src > 0 {
+                shifted |= words[src - 1] >> (WORD_BITS - bit_shift);
+            }
+            words[i] |= shifted;
+        }
+    }
+
+    fn zip_assign(&mut self, rhs: &Self, op: impl Fn(&mut u64, u64)) {
+        let (lhs, rhs) = (self.words.as_mut(), rhs.words.as_ref());
+        assert_eq!(lhs.len(), rhs.len(), "Bitsets must have the same size");
+        lhs.iter_mut().zip(rhs).for_each(|(a, &b)| op(a, b));
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $apply:expr) => {
+        impl<S: AsRef<[u64]> + AsMut<[u64]>> $op_assign<&Self> for BitSet<S> {
+            fn $method_assign(&mut self, rhs: &Self) {
+                self.zip_assign(rhs, $apply);
+            }
+        }
+
+        impl<S: AsRef<[u64]> + AsMut<[u64]>> $op<&Self> for BitSet<S> {
+            type Output = Self;
+
+            fn $method(mut self, rhs: &Self) -> Self::Output {
+                self.zip_assign(rhs, $apply);
+                self
+            }
+        }
+
+        impl<S: AsRef<[u64]> + AsMut<[u64]> + Clone> $op for &BitSet<S> {
+            type Output = BitSet<S>;
+
+            fn $method(self, rhs: Self) -> Self::Output {
+                self.clone().$method(rhs)
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| *a ^= b);
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| *a &= b);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| *a |= b);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_membership() {
+        let mut set = BitSet::new(130);
+        assert_eq!(set.capacity(), 192);
+        assert!(set.is_empty());
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(129));
+        assert!(!set.insert(129));
+        assert!(set.contains(64));
+        assert!(!set.contains(63));
+        assert!(!set.contains(1000));
+        assert_eq!(set.count_ones(), 3);
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), vec![0, 64, 129]);
+        assert!(set.remove(64));
+        assert!(!set.remove(64));
+        set.toggle(5);
+        set.toggle(0);
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), vec![5, 129]);
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_operations() {
+        let a = BitSet::from_indices(200, [1, 70, 150, 199]);
+        let b = BitSet::from_indices(200, [1, 71, 150]);
+        assert_eq!((&a ^ &b).iter_ones().collect::<Vec<_>>(), vec![70, 71, 199]);
+        assert_eq!((&a & &b).iter_ones().collect::<Vec<_>>(), vec![1, 150]);
+        assert_eq!(
+            (&a | &b).iter_ones().collect::<Vec<_>>(),
+            vec![1, 70, 71, 150, 199]
+        );
+        let mut c = a.clone();
+        c ^= &a;
+        assert!(c.is_empty());
+        assert_eq!(a.clone() & &b, &a & &b);
+    }
+
+    #[test]
+    fn test_or_shifted() {
+        let mut set = BitSet::from_indices(200, [0, 3, 63]);
+        set.or_shifted(1);
+        assert_eq!(
+            set.iter_ones().collect::<Vec<_>>(),
+            vec![0, 1, 3, 4, 63, 64]
+        );
+        set.or_shifted(130);
+        assert_eq!(
+            set.iter_ones().collect::<Vec<_>>(),
+            vec![0, 1, 3, 4, 63, 64, 130, 131, 133, 134, 193, 194]
+        );
+        set.or_shifted(1000);
+        assert_eq!(set.count_ones(), 12);
+    }
+
+    #[test]
+    fn test_fixed_storage() {
+        let mut a = BitSet::<[u64; 2]>::new_fixed();
+        let mut b = BitSet::<[u64; 2]>::new_fixed();
+        a.insert(3);
+        a.insert(100);
+        b.insert(100);
+        assert_eq!(a.capacity(), 128);
+        assert_eq!((&a ^ &b).iter_ones().collect::<Vec<_>>(), vec![3]);
+        // Hashing and equality work on the contents
+        let set = HashSet::from([a.clone(), b, a]);
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,138 @@
+//! Parsed puzzles kept on disk between runs, so that a day whose parsing is
+//! heavy goes straight to solving when neither its input nor its source
+//! changed.
+//!
+//! A day opts in by deriving `Serialize` and `Deserialize` for its puzzle and
+//! parsing through [`cached`], with its own source as
+//! `include_str!("main.rs")`. Puzzles are stored as JSON under
+//! `outputs/cache`, one file per input, along with a hash of the input, the
+//! source of the day and the sources of `util`, hashed by its build script:
+//! when any of them changes, the stale puzzle is parsed again and replaced.
+//! With `--no-cache` on the command line, the cache is neither read nor
+//! written, and [`args`] leaves it out of the arguments of the day.
+
+use std::{env, fs, path::Path};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{Error, reader::read_file, writer::output_file};
+
+/// Command line flag parsing the input without the cache
+pub const NO_CACHE_FLAG: &str = "--no-cache";
+
+/// Hash of the sources of `util`, which the parsing of the days relies on
+const UTIL_SOURCE_HASH: &str = env!("UTIL_SOURCE_HASH");
+
+/// A parsed puzzle and the hash of what it was parsed from
+#[derive(Serialize, Deserialize)]
+struct Entry<P> {
+    key: u64,
+    puzzle: P,
+}
+
+/// 64-bit FNV-1a hash of `parts`, which unlike the hasher of the standard
+/// library is the same from one build to the next
+fn fingerprint(parts: &[&[u8]]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for part in parts {
+        // The length keeps the boundaries between parts apart
+        for &byte in (part.len() as u64).to_le_bytes().iter().chain(*part) {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// The puzzle stored at `path`, if there is one parsed from what `key` is
+/// the hash of
+fn load<P: DeserializeOwned>(path: &Path, key: u64) -> Option<P> {
+    let entry = serde_json::from_str::<Entry<P>>(&fs::read_to_string(path).ok()?).ok()?;
+    (entry.key == key).then_some(entry.puzzle)
+}
+
+/// Store `puzzle` at `path`, in place of any older one
+fn store<P: Serialize>(path: &Path, key: u64, puzzle: &P) -> crate::Result<()> {
+    let json = serde_json::to_string(&Entry { key, puzzle })
+        .map_err(|e| Error::Other(anyhow::anyhow!("Failed to serialize the puzzle: {e}")))?;
+    Ok(fs::write(path, json)?)
+}
+
+/// Whether `--no-cache` is not among the arguments of the program
+#[must_use]
+pub fn enabled() -> bool {
+    !env::args().skip(1).any(|arg| arg == NO_CACHE_FLAG)
+}
+
+/// `args` but `--no-cache`, which is for the cache rather than the day, e.g.
+/// on the arguments of [`diagnostics::args`](crate::diagnostics::args)
+#[must_use]
+pub fn args(args: Vec<String>) -> Vec<String> {
+    args.into_iter()
+        .filter(|arg| arg != NO_CACHE_FLAG)
+        .collect()
+}
+
+/// The puzzle of a day as `parse` returns it, or as it was stored by an
+/// earlier run on the same input with the same `source` and `util`
+///
+/// Failing to store the puzzle only prints a warning, as the run itself does
+/// not need the cache.
+///
+/// # Errors
+/// This function will return an error if the input cannot be read, or if
+/// `parse` fails.
+pub fn cached<P, E>(
+    day: u8,
+    example: bool,
+    source: &str,
+    parse: impl FnOnce() -> Result<P, E>,
+) -> Result<P, E>
+where
+    P: Serialize + DeserializeOwned,
+    E: From<Error>,
+{
+    if !enabled() {
+        return parse();
+    }
+    let input = read_file(day, example)?;
+    let key = fingerprint(&[
+        UTIL_SOURCE_HASH.as_bytes(),
+        source.as_bytes(),
+        input.as_bytes(),
+    ]);
+    let suffix = if example { "-example" } else { "" };
+    let path = output_file(&format!("cache/day{day:02}{suffix}.json"))?;
+    if let Some(puzzle) = load(&path, key) {
+        return Ok(puzzle);
+    }
+    let puzzle = parse()?;
+    if let Err(e) = store(&path, key, &puzzle) {
+        eprintln!("Warning: failed to cache the puzzle of day {day}: {e}");
+    }
+    Ok(puzzle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache() {
+        // Known FNV-1a hashes would not hold with the lengths mixed in, so
+        // only check that both parts and their boundary matter
+        let key = fingerprint(&[b"source", b"input"]);
+        assert_eq!(key, fingerprint(&[b"source", b"input"]));
+        assert_ne!(key, fingerprint(&[b"source", b"other input"]));
+        assert_ne!(key, fingerprint(&[b"sourc", b"einput"]));
+        let path = env::temp_dir().join(format!("aoc-cache-{}.json", std::process::id()));
+        assert_eq!(load::<Vec<u32>>(&path, key), None);
+        assert!(store(&path, key, &vec![1_u32, 2, 3]).is_ok());
+        assert_eq!(load::<Vec<u32>>(&path, key), Some(vec![1, 2, 3]));
+        // Stale, or of another shape
+        assert_eq!(load::<Vec<u32>>(&path, key + 1), None);
+        assert_eq!(load::<String>(&path, key), None);
+        let _ = fs::remove_file(&path);
+        let given = ["--no-cache", "--paths", "3"].map(String::from);
+        assert_eq!(args(given.to_vec()), ["--paths", "3"]);
+    }
+}
@@ -0,0 +1,5 @@
+//! General-purpose containers missing from the standard library
+
+mod counter;
+
+pub use counter::Counter;
@@ -0,0 +1,273 @@
+//! Multisets that count how many times each item occurs.
+
+use std::{
+    collections::{HashMap, hash_map},
+    hash::Hash,
+    ops::{Add, AddAssign, BitAnd, BitOr, Sub, SubAssign},
+};
+
+/// A multiset counting occurrences of hashable items.
+///
+/// Only positive counts are stored, so an item is either counted at least
+/// once or absent. Arithmetic follows multiset semantics: `+` adds counts, `-`
+/// subtracts them (dropping items that reach zero), `|` takes the larger and
+/// `&` the smaller count of each item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    /// Create an empty counter
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Count one more occurrence of `item`
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    /// Count `n` more occurrences of `item`
+    pub fn add_n(&mut self, item: T, n: usize) {
+        if n > 0 {
+            *self.counts.entry(item).or_default() += n;
+        }
+    }
+
+    /// Remove up to `n` occurrences of `item`, returning how many were removed
+    pub fn remove_n(&mut self, item: &T, n: usize) -> usize {
+        let Some(count) = self.counts.get_mut(item) else {
+            return 0;
+        };
+        let removed = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        removed
+    }
+
+    /// Number of occurrences of `item`
+    #[must_use]
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or_default()
+    }
+
+    /// Number of distinct items
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Whether nothing has been counted
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Total number of occurrences of all items
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Iterate over `(item, count)` in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// The `n` most common items with their counts, from most to least common
+    /// and ties broken by the smaller item
+    #[must_use]
+    pub fn most_common(&self, n: usize) -> Vec<(&T, usize)>
+    where
+        T: Ord,
+    {
+        let mut items = self.iter().collect::<Vec<_>>();
+        let by_count = |&(a, x): &(&T, usize), &(b, y): &(&T, usize)| y.cmp(&x).then(a.cmp(b));
+        if n < items.len() {
+            items.select_nth_unstable_by(n, by_count);
+            items.truncate(n);
+        }
+        items.sort_unstable_by(by_count);
+        items
+    }
+
+    /// Fraction of all occurrences that are `item`, or 0 when empty
+    #[must_use]
+    pub fn frequency(&self, item: &T) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => to_f64(self.get(item)) / to_f64(total),
+        }
+    }
+
+    /// Shannon entropy of the item distribution, in bits
+    #[must_use]
+    pub fn entropy(&self) -> f64 {
+        let total = to_f64(self.total());
+        self.counts
+            .values()
+            .map(|&count| {
+                let p = to_f64(count) / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
+
+/// Counts are far below 2^52 in practice, so the conversion is exact
+#[allow(clippy::cast_precision_loss)]
+const fn to_f64(count: usize) -> f64 {
+    count as f64
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Eq + Hash> IntoIterator for Counter<T> {
+    type IntoIter = hash_map::IntoIter<T, usize>;
+    type Item = (T, usize);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<T: Eq + Hash + Clone> AddAssign<&Self> for Counter<T> {
+    fn add_assign(&mut self, rhs: &Self) {
+        for (item, count) in rhs.iter() {
+            self.add_n(item.clone(), count);
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> SubAssign<&Self> for Counter<T> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        for (item, count) in rhs.iter() {
+            self.remove_n(item, count);
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> Add for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result += rhs;
+        result
+    }
+}
+
+impl<T: Eq + Hash + Clone> Sub for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result -= rhs;
+        result
+    }
+}
+
+impl<T: Eq + Hash + Clone> BitOr for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        for (item, count) in rhs.iter() {
+            let current = result.get(item);
+            result.add_n(item.clone(), count.saturating_sub(current));
+        }
+        result
+    }
+}
+
+impl<T: Eq + Hash + Clone> BitAnd for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let counts = self
+            .iter()
+            .filter_map(|(item, count)| {
+                let common = count.min(rhs.get(item));
+                (common > 0).then(|| (item.clone(), common))
+            })
+            .collect();
+        Counter { counts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting() {
+        let mut counter = "abracadabra".chars().collect::<Counter<_>>();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!(counter.len(), 5);
+        assert_eq!(counter.total(), 11);
+        assert_eq!(
+            counter.most_common(3),
+            vec![(&'a', 5), (&'b', 2), (&'r', 2)]
+        );
+        assert_eq!(counter.most_common(10).len(), 5);
+        assert_eq!(counter.remove_n(&'b', 5), 2);
+        assert_eq!(counter.get(&'b'), 0);
+        assert_eq!(counter.len(), 4);
+        counter.add_n('z', 0);
+        assert_eq!(counter.len(), 4);
+        assert!(Counter::<u8>::default().is_empty());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = [1, 1, 1, 2, 3].into_iter().collect::<Counter<_>>();
+        let b = [1, 2, 2, 4].into_iter().collect::<Counter<_>>();
+        let sorted = |c: Counter<i32>| {
+            let mut items = c.into_iter().collect::<Vec<_>>();
+            items.sort_unstable();
+            items
+        };
+        assert_eq!(sorted(&a + &b), vec![(1, 4), (2, 3), (3, 1), (4, 1)]);
+        assert_eq!(sorted(&a - &b), vec![(1, 2), (3, 1)]);
+        assert_eq!(sorted(&a | &b), vec![(1, 3), (2, 2), (3, 1), (4, 1)]);
+        assert_eq!(sorted(&a & &b), vec![(1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let uniform = (0..8).collect::<Counter<_>>();
+        assert!((uniform.entropy() - 3.0).abs() < 1e-12);
+        assert!((uniform.frequency(&3) - 0.125).abs() < f64::EPSILON);
+        let single = std::iter::repeat_n('x', 10).collect::<Counter<_>>();
+        assert!(single.entropy().abs() < f64::EPSILON);
+        assert!(Counter::<u8>::new().entropy().abs() < f64::EPSILON);
+        assert!(Counter::<u8>::new().frequency(&1).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,101 @@
+//! Tunables of the days, read from `config/dayXX.toml` at the root of the
+//! workspace, so that what a day lets one tweak is data rather than a
+//! constant to edit.
+//!
+//! Each day deserializes its file into a struct of its own, whose `Default`
+//! is what the puzzle says, so that the file of a day and every key in it are
+//! optional. With `#[serde(default, deny_unknown_fields)]` on the struct, a
+//! missing key falls back to the default and a misspelled one is an error.
+//!
+//! ```toml
+//! # config/day03.toml
+//! length = 16
+//! ```
+
+use std::{fs, io};
+
+use serde::de::DeserializeOwned;
+
+use crate::{Error, Result, get_workspace_root};
+
+/// Parse the text of the configuration file of a day
+///
+/// # Errors
+/// This function will return an error if the text is not valid TOML, or does
+/// not describe a `T`.
+pub fn parse<T: DeserializeOwned>(day: u8, text: &str) -> Result<T> {
+    toml::from_str(text).map_err(|e| {
+        let message = e.message().trim_end().to_string();
+        let message = match e.span() {
+            Some(span) => {
+                let line = text[..span.start].matches('\n').count() + 1;
+                format!("Line {line}: {message}")
+            }
+            None => message,
+        };
+        Error::InvalidConfig { day, message }
+    })
+}
+
+/// The configuration of a day from `config/dayXX.toml`, or the default one if
+/// there is no such file
+///
+/// # Errors
+/// This function will return an error if the file cannot be read or parsed.
+pub fn load<T: DeserializeOwned + Default>(day: u8) -> Result<T> {
+    let path = get_workspace_root()?.join(format!("config/day{day:02}.toml"));
+    match fs::read_to_string(path) {
+        Ok(text) => parse(day, &text),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(default, deny_unknown_fields)]
+    struct Tunables {
+        checkpoints: Vec<String>,
+        limit: u8,
+        scale: f64,
+    }
+
+    impl Default for Tunables {
+        fn default() -> Self {
+            Self {
+                checkpoints: vec!["dac".to_string(), "fft".to_string()],
+                limit: 10,
+                scale: 1.0,
+            }
+        }
+    }
+
+    #[test]
+    fn test_config() -> Result<()> {
+        let config = parse::<Tunables>(
+            11,
+            "# Paths of part 2\ncheckpoints = [\"dac\", \"f#t\",]  # renamed\n\nscale = 2.5\n",
+        )?;
+        assert_eq!(config.checkpoints, ["dac", "f#t"]);
+        assert_eq!(config.limit, 10);
+        assert!((config.scale - 2.5).abs() < f64::EPSILON);
+        assert_eq!(parse::<Tunables>(11, "")?, Tunables::default());
+        let Err(error) = parse::<Tunables>(11, "scale = 1.0\nlimit = 1000\n") else {
+            panic!("1000 does not fit in a u8");
+        };
+        assert!(
+            error.to_string().starts_with("config/day11.toml: Line 2: "),
+            "{error}"
+        );
+        assert!(parse::<Tunables>(11, "limit = 1\nlimit = 2").is_err());
+        assert!(parse::<Tunables>(11, "just text").is_err());
+        assert!(parse::<Tunables>(11, "name = \"unclosed").is_err());
+        assert!(parse::<Tunables>(11, "limits = 1").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,334 @@
+//! Constraint satisfaction over finite domains.
+//!
+//! Binary constraints take part in arc consistency (AC-3) propagation, both
+//! up front and after every assignment during the backtracking search.
+//! Constraints over arbitrary scopes are checked as soon as all of their
+//! variables are assigned.
+
+use std::collections::VecDeque;
+
+type BinaryPredicate<'a, V> = Box<dyn Fn(&V, &V) -> bool + 'a>;
+type GlobalPredicate<'a, V> = Box<dyn Fn(&[&V]) -> bool + 'a>;
+
+/// Strategy for the order in which variables and values are tried.
+pub trait Heuristic<V> {
+    /// Pick the next variable to assign among those not yet `assigned`, or
+    /// `None` when every variable is assigned.
+    fn select_variable(&self, domains: &[Vec<V>], assigned: &[bool]) -> Option<usize>;
+
+    /// Order in which to try the values of `var`'s current domain
+    fn order_values(&self, var: usize, domain: &[V]) -> Vec<V>
+    where
+        V: Clone,
+    {
+        let _ = var;
+        domain.to_vec()
+    }
+}
+
+/// Assign variables in the order they were added
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InOrder;
+
+impl<V> Heuristic<V> for InOrder {
+    fn select_variable(&self, _domains: &[Vec<V>], assigned: &[bool]) -> Option<usize> {
+        assigned.iter().position(|&a| !a)
+    }
+}
+
+/// Assign the variable with the fewest remaining values first ("fail first")
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinRemainingValues;
+
+impl<V> Heuristic<V> for MinRemainingValues {
+    fn select_variable(&self, domains: &[Vec<V>], assigned: &[bool]) -> Option<usize> {
+        (0..domains.len())
+            .filter(|&var| !assigned[var])
+            .min_by_key(|&var| domains[var].len())
+    }
+}
+
+/// A constraint satisfaction problem over variables with values of type `V`.
+pub struct Csp<'a, V> {
+    domains: Vec<Vec<V>>,
+    binary: Vec<(usize, usize, BinaryPredicate<'a, V>)>,
+    global: Vec<(Vec<usize>, GlobalPredicate<'a, V>)>,
+    /// Binary constraints involving each variable
+    binary_of: Vec<Vec<usize>>,
+    /// Global constraints involving each variable
+    global_of: Vec<Vec<usize>>,
+}
+
+impl<V> Default for Csp<'_, V> {
+    fn default() -> Self {
+        Self {
+            domains: Vec::new(),
+            binary: Vec::new(),
+            global: Vec::new(),
+            binary_of: Vec::new(),
+            global_of: Vec::new(),
+        }
+    }
+}
+
+impl<'a, V: Clone> Csp<'a, V> {
+    /// Create a problem without variables
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a variable taking values from `domain`, returning its index
+    pub fn add_variable(&mut self, domain: impl IntoIterator<Item = V>) -> usize {
+        self.domains.push(domain.into_iter().collect());
+        self.binary_of.push(Vec::new());
+        self.global_of.push(Vec::new());
+        self.domains.len() - 1
+    }
+
+    /// Number of variables
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    /// Whether the problem has no variables
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+
+    /// Current domain of `var`, which shrinks after [`Self::propagate`]
+    #[must_use]
+    pub fn domain(&self, var: usize) -> &[V] {
+        &self.domains[var]
+    }
+
+    /// Require `allowed(value of a, value of b)` to hold
+    pub fn add_binary(&mut self, a: usize, b: usize, allowed: impl Fn(&V, &V) -> bool + 'a) {
+        if a == b {
+            // A constraint on a single variable simply filters its domain
+            self.domains[a].retain(|v| allowed(v, v));
+            return;
+        }
+        let id = self.binary.len();
+        self.binary.push((a, b, Box::new(allowed)));
+        self.binary_of[a].push(id);
+        self.binary_of[b].push(id);
+    }
+
+    /// Require `allowed` to hold on the values of `scope`, given in the same
+    /// order. It is only checked once all variables of the scope are assigned.
+    pub fn add_constraint(&mut self, scope: &[usize], allowed: impl Fn(&[&V]) -> bool + 'a) {
+        let id = self.global.len();
+        self.global.push((scope.to_vec(), Box::new(allowed)));
+        for &var in scope {
+            self.global_of[var].push(id);
+        }
+    }
+
+    /// Require all variables in `vars` to take pairwise different values
+    pub fn add_all_different(&mut self, vars: &[usize])
+    where
+        V: PartialEq,
+    {
+        for (i, &a) in vars.iter().enumerate() {
+            for &b in &vars[i + 1..] {
+                self.add_binary(a, b, |x, y| x != y);
+            }
+        }
+    }
+
+    /// Remove values without support under the binary constraints, returning
+    /// `false` if some domain became empty (the problem is unsatisfiable).
+    pub fn propagate(&mut self) -> bool {
+        let mut domains = std::mem::take(&mut self.domains);
+        let queue = self.all_arcs();
+        let consistent = self.ac3(&mut domains, queue);
+        self.domains = domains;
+        consistent
+    }
+
+    /// Find a satisfying assignment with the [`MinRemainingValues`] heuristic
+    #[must_use]
+    pub fn solve(&self) -> Option<Vec<V>> {
+        self.solve_with(&MinRemainingValues)
+    }
+
+    /// Find a satisfying assignment, one value per variable, trying
+    /// variables and values in the order given by `heuristic`.
+    #[must_use]
+    pub fn solve_with(&self, heuristic: &impl Heuristic<V>) -> Option<Vec<V>> {
+        let mut domains = self.domains.clone();
+        let queue = self.all_arcs();
+        if !self.ac3(&mut domains, queue) {
+            return None;
+        }
+        let mut assigned = vec![false; domains.len()];
+        self.search(domains, &mut assigned, heuristic)
+    }
+
+    fn search(
+        &self,
+        domains: Vec<Vec<V>>,
+        assigned: &mut [bool],
+        heuristic: &impl Heuristic<V>,
+    ) -> Option<Vec<V>> {
+        let Some(var) = heuristic.select_variable(&domains, assigned) else {
+            return domains
+                .into_iter()
+                .map(|domain| domain.into_iter().next())
+                .collect();
+        };
+        assigned[var] = true;
+        for value in heuristic.order_values(var, &domains[var]) {
+            let mut next = domains.clone();
+            next[var] = vec![value];
+            let queue = self.binary_of[var]
+                .iter()
+                .map(|&id| (self.other(id, var), id))
+                .collect();
+            if self.ac3(&mut next, queue)
+                && self.globals_hold(&next, assigned, var)
+                && let Some(solution) = self.search(next, assigned, heuristic)
+            {
+                return Some(solution);
+            }
+        }
+        assigned[var] = false;
+        None
+    }
+
+    /// Whether the global constraints on `var` hold, among those whose scope
+    /// is fully assigned
+    fn globals_hold(&self, domains: &[Vec<V>], assigned: &[bool], var: usize) -> bool {
+        self.global_of[var].iter().all(|&id| {
+            let (scope, allowed) = &self.global[id];
+            !scope.iter().all(|&v| assigned[v])
+                || allowed(&scope.iter().map(|&v| &domains[v][0]).collect::<Vec<_>>())
+        })
+    }
+
+    /// Both directions of every binary constraint, as `(variable, constraint)`
+    fn all_arcs(&self) -> VecDeque<(usize, usize)> {
+        self.binary
+            .iter()
+            .enumerate()
+            .flat_map(|(id, &(a, b, _))| [(a, id), (b, id)])
+            .collect()
+    }
+
+    /// The variable on the other side of binary constraint `id`
+    fn other(&self, id: usize, var: usize) -> usize {
+        let (a, b, _) = &self.binary[id];
+        if *a == var { *b } else { *a }
+    }
+
+    /// Run AC-3 starting from the `(variable, constraint)` arcs in `queue`
+    fn ac3(&self, domains: &mut [Vec<V>], mut queue: VecDeque<(usize, usize)>) -> bool {
+        while let Some((var, id)) = queue.pop_front() {
+            if !self.revise(domains, var, id) {
+                continue;
+            }
+            if domains[var].is_empty() {
+                return false;
+            }
+            queue.extend(
+                self.binary_of[var]
+                    .iter()
+                    .filter(|&&other_id| other_id != id)
+                    .map(|&other_id| (self.other(other_id, var), other_id)),
+            );
+        }
+        true
+    }
+
+    /// Remove values of `var` without a supporting value on the other side of
+    /// constraint `id`, returning whether anything was removed
+    fn revise(&self, domains: &mut [Vec<V>], var: usize, id: usize) -> bool {
+        let (a, b, allowed) = &self.binary[id];
+        let other = if *a == var { *b } else { *a };
+        let (mut domain, support) = (std::mem::take(&mut domains[var]), &domains[other]);
+        let before = domain.len();
+        if *a == var {
+            domain.retain(|x| support.iter().any(|y| allowed(x, y)));
+        } else {
+            domain.retain(|y| support.iter().any(|x| allowed(x, y)));
+        }
+        let revised = domain.len() != before;
+        domains[var] = domain;
+        revised
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queens(n: usize) -> Csp<'static, usize> {
+        let mut csp = Csp::new();
+        let rows = (0..n).map(|_| csp.add_variable(0..n)).collect::<Vec<_>>();
+        for (i, &a) in rows.iter().enumerate() {
+            for (j, &b) in rows.iter().enumerate().skip(i + 1) {
+                let dist = j - i;
+                csp.add_binary(a, b, move |&x, &y| x != y && x.abs_diff(y) != dist);
+            }
+        }
+        csp
+    }
+
+    #[test]
+    fn test_queens() {
+        for n in [1, 4, 8, 12] {
+            let solution = queens(n)
+                .solve()
+                .unwrap_or_else(|| panic!("{n} queens should be solvable"));
+            for i in 0..n {
+                for j in i + 1..n {
+                    assert_ne!(solution[i], solution[j]);
+                    assert_ne!(solution[i].abs_diff(solution[j]), j - i);
+                }
+            }
+        }
+        assert_eq!(queens(3).solve(), None);
+        assert_eq!(queens(6).solve_with(&InOrder).map(|s| s.len()), Some(6));
+    }
+
+    #[test]
+    fn test_propagate() {
+        // x < y < z over 0..3 forces a single assignment
+        let mut csp = Csp::new();
+        let vars = (0..3).map(|_| csp.add_variable(0..3)).collect::<Vec<_>>();
+        csp.add_binary(vars[0], vars[1], |x, y| x < y);
+        csp.add_binary(vars[1], vars[2], |x, y| x < y);
+        assert!(csp.propagate());
+        assert_eq!(csp.domain(vars[0]), &[0]);
+        assert_eq!(csp.domain(vars[1]), &[1]);
+        assert_eq!(csp.domain(vars[2]), &[2]);
+
+        // Pigeonhole: 3 pairwise different values from 2 is unsatisfiable
+        let mut csp = Csp::new();
+        let vars = (0..3).map(|_| csp.add_variable([0, 1])).collect::<Vec<_>>();
+        csp.add_all_different(&vars);
+        assert_eq!(csp.solve(), None);
+    }
+
+    #[test]
+    fn test_global_constraint() {
+        // Pick distinct digits summing to 20 with the first one even
+        let mut csp = Csp::new();
+        let vars = (0..3).map(|_| csp.add_variable(1..=9)).collect::<Vec<_>>();
+        csp.add_all_different(&vars);
+        csp.add_binary(vars[0], vars[0], |x, _| x % 2 == 0);
+        csp.add_constraint(&vars, |values| values.iter().copied().sum::<i32>() == 20);
+        let solution = csp
+            .solve_with(&InOrder)
+            .unwrap_or_else(|| panic!("Should be solvable"));
+        assert_eq!(solution.iter().sum::<i32>(), 20);
+        assert_eq!(solution[0] % 2, 0);
+        assert!(solution[0] != solution[1] && solution[1] != solution[2]);
+        // Sum too large for any assignment
+        csp.add_constraint(&vars, |values| values.iter().copied().sum::<i32>() > 24);
+        assert_eq!(csp.solve(), None);
+    }
+}
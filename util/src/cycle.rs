@@ -0,0 +1,196 @@
+//! Cycle detection for iterated functions.
+//!
+//! Puzzles asking for the state "after 1,000,000,000 iterations" almost always
+//! fall into a loop long before that. Once the loop is known, any far-away step
+//! maps back onto one that has already been seen.
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    hash::Hash,
+};
+
+/// The shape of the sequence `x0, f(x0), f(f(x0)), ...` once it repeats: the
+/// first `start` steps form a tail, after which every `period` steps repeat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest step whose state is the same as at step `n`
+    #[must_use]
+    pub const fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Find the cycle of the sequence starting at `initial` with Floyd's
+/// tortoise-and-hare algorithm, using constant memory.
+///
+/// Never returns if the sequence does not repeat.
+pub fn floyd<T: Clone + PartialEq>(initial: T, mut f: impl FnMut(&T) -> T) -> Cycle {
+    // Meet somewhere inside the cycle
+    let mut tortoise = f(&initial);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        hare = f(&hare);
+    }
+    // The meeting point is a multiple of the period away from the start
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+    let mut period = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        period += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Find the cycle of the sequence starting at `initial` with Brent's
+/// algorithm, which uses constant memory and fewer calls to `f` than
+/// [`floyd`].
+///
+/// Never returns if the sequence does not repeat.
+pub fn brent<T: Clone + PartialEq>(initial: T, mut f: impl FnMut(&T) -> T) -> Cycle {
+    // Find the period by teleporting the tortoise at powers of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = f(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = f(&hare);
+        period += 1;
+    }
+    // Walk two pointers a period apart until they meet at the start
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..period {
+        hare = f(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+/// The state after applying `f` to `initial` `n` times, skipping ahead as soon
+/// as a state repeats.
+pub fn nth_state<T: Clone + Eq + Hash>(initial: T, n: usize, f: impl FnMut(&T) -> T) -> T {
+    nth_state_by_key(initial, n, T::clone, f)
+}
+
+/// Like [`nth_state`], but states are compared by `key`, e.g. to hash a compact
+/// summary instead of the whole state.
+///
+/// When a repeat is found, the earliest state with the same key as step `n` is
+/// returned as-is, including any data that the key leaves out.
+///
+/// Every state is kept until a repeat is found, so this trades memory for not
+/// having to re-run the sequence like [`floyd`] and [`brent`] do.
+pub fn nth_state_by_key<T, K: Eq + Hash>(
+    initial: T,
+    n: usize,
+    key: impl Fn(&T) -> K,
+    mut f: impl FnMut(&T) -> T,
+) -> T {
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    for step in 0..n {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                let start = *entry.get();
+                let cycle = Cycle {
+                    start,
+                    period: step - start,
+                };
+                return history.swap_remove(cycle.reduce(n));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(step);
+            }
+        }
+        let next = f(&state);
+        history.push(state);
+        state = next;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tail of 3 steps followed by a loop of 7: 0 -> 1 -> 2 -> 3 -> ... -> 9
+    /// -> 3
+    const fn step(x: u32) -> u32 {
+        if x == 9 { 3 } else { x + 1 }
+    }
+
+    #[test]
+    fn test_detection() {
+        let expected = Cycle {
+            start: 3,
+            period: 7,
+        };
+        assert_eq!(floyd(0, |&x| step(x)), expected);
+        assert_eq!(brent(0, |&x| step(x)), expected);
+        // Pure loops and fixed points
+        assert_eq!(
+            floyd(5, |&x| step(x)),
+            Cycle {
+                start: 0,
+                period: 7
+            }
+        );
+        assert_eq!(
+            brent(1u8, |&x| x),
+            Cycle {
+                start: 0,
+                period: 1
+            }
+        );
+        assert_eq!(expected.reduce(2), 2);
+        assert_eq!(expected.reduce(10), 3);
+        assert_eq!(expected.reduce(1_000_000_000), 3 + (1_000_000_000 - 3) % 7);
+    }
+
+    #[test]
+    fn test_nth_state() {
+        let simulate = |n| (0..n).fold(0, |x, _| step(x));
+        for n in 0..30 {
+            assert_eq!(nth_state(0, n, |&x| step(x)), simulate(n));
+        }
+        let n = 1_000_000_000_000;
+        let expected = 3 + (n - 3) % 7;
+        assert_eq!(
+            nth_state(0, n, |&x| step(x)),
+            u32::try_from(expected).unwrap_or_default()
+        );
+        // The counter is left out of the key, so it comes from the earliest
+        // matching step rather than step 25
+        let state = nth_state_by_key((0, 0), 25, |&(x, _)| x, |&(x, c)| (step(x), c + 1));
+        assert_eq!(state, (simulate(25), 4));
+    }
+}
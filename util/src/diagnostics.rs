@@ -0,0 +1,141 @@
+//! Warnings about inputs that parse, but not quite as written, such as a
+//! grid whose lines lost their trailing spaces in an editor.
+//!
+//! Parsers [`warn`] and go on, and the binary of a day prints what was
+//! collected with [`report`] once parsing is done. With `--strict` on the
+//! command line, [`init`] makes every warning an [`Error::Warning`] instead,
+//! failing the parse, and [`args`] leaves it out of the other arguments.
+//! Warnings are kept per thread, like the parsing that raises them, while
+//! `--strict` holds for every thread of the program.
+
+use std::{
+    cell::RefCell,
+    env, fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::{Error, Result};
+
+/// Something about an input that was dealt with rather than rejected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    /// Line of the input, counted from 1, if it is about a single one
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Where the warnings of a thread are collected
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    pub warnings: Vec<Warning>,
+}
+
+impl Diagnostics {
+    /// Collect a warning, or return it as an error when `strict`
+    pub fn warn(&mut self, warning: Warning, strict: bool) -> Result<()> {
+        if strict {
+            return Err(Error::Warning(warning));
+        }
+        self.warnings.push(warning);
+        Ok(())
+    }
+}
+
+/// Whether warnings are errors, on every thread, so that parsing spread over
+/// threads is as strict as the main one
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static DIAGNOSTICS: RefCell<Diagnostics> = RefCell::default();
+}
+
+/// Collect a warning about a line of the input, or about all of it with
+/// `None`
+///
+/// # Errors
+/// This function will return the warning as [`Error::Warning`] when warnings
+/// are errors.
+pub fn warn(line: Option<usize>, message: impl Into<String>) -> Result<()> {
+    let warning = Warning {
+        line,
+        message: message.into(),
+    };
+    DIAGNOSTICS.with_borrow_mut(|diagnostics| diagnostics.warn(warning, strict()))
+}
+
+/// Make warnings errors, or collect them again with `false`
+pub fn set_strict(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+/// Whether warnings are errors
+#[must_use]
+pub fn strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
+
+/// The warnings collected so far, which are forgotten
+#[must_use]
+pub fn take() -> Vec<Warning> {
+    DIAGNOSTICS.with_borrow_mut(|diagnostics| std::mem::take(&mut diagnostics.warnings))
+}
+
+/// Make warnings errors if `--strict` is among the arguments of the program
+pub fn init() {
+    set_strict(env::args().skip(1).any(|arg| arg == "--strict"));
+}
+
+/// The arguments of the program but `--strict`, without the name of the
+/// program
+#[must_use]
+pub fn args() -> Vec<String> {
+    env::args()
+        .skip(1)
+        .filter(|arg| arg != "--strict")
+        .collect()
+}
+
+/// Print the warnings collected so far to stderr
+pub fn report() {
+    for warning in take() {
+        eprintln!("Warning: {warning}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics() {
+        assert!(warn(Some(3), "trailing spaces ignored").is_ok());
+        assert!(warn(None, "no newline at the end").is_ok());
+        let warnings = take();
+        assert_eq!(
+            warnings.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["Line 3: trailing spaces ignored", "no newline at the end"]
+        );
+        assert!(take().is_empty());
+        // Not through `set_strict`, which would fail the warnings of the
+        // tests running alongside
+        let mut diagnostics = Diagnostics::default();
+        let warning = Warning {
+            line: Some(1),
+            message: "padded".to_string(),
+        };
+        assert!(matches!(
+            diagnostics.warn(warning, true),
+            Err(Error::Warning(Warning { line: Some(1), .. }))
+        ));
+        assert!(diagnostics.warnings.is_empty());
+    }
+}
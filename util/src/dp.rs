@@ -0,0 +1,181 @@
+//! Knapsack-style dynamic programming over items with integer weights.
+//!
+//! The knapsack helpers are generic over the DP state: `take` extends a state
+//! with one more item, and `choose` keeps the better of two states reaching
+//! the same weight. Plain maximization is `choose = max`, but states can also
+//! count solutions, build numbers digit by digit, and so on.
+
+use crate::bits::BitSet;
+
+/// Merge `candidate` into the best state so far at some weight
+fn relax<S>(best: &mut Option<S>, candidate: S, choose: &impl Fn(S, S) -> S) {
+    let merged = match best.take() {
+        Some(current) => choose(current, candidate),
+        None => candidate,
+    };
+    *best = Some(merged);
+}
+
+/// 0/1 knapsack: every item is taken at most once, in the given order.
+///
+/// Returns, for every total weight `0..=capacity`, the best state among the
+/// selections with exactly that weight, or `None` if no selection has it.
+/// The empty selection has state `empty`. Runs in `O(items * capacity)`.
+pub fn knapsack<I, S: Clone>(
+    items: &[I],
+    capacity: usize,
+    empty: S,
+    weight: impl Fn(&I) -> usize,
+    take: impl Fn(&S, &I) -> S,
+    choose: impl Fn(S, S) -> S,
+) -> Vec<Option<S>> {
+    let mut best = vec![None; capacity + 1];
+    best[0] = Some(empty);
+    for item in items {
+        let w = weight(item);
+        // Downwards, so that every item extends only selections without it
+        for total in (w..=capacity).rev() {
+            let Some(state) = &best[total - w] else {
+                continue;
+            };
+            let candidate = take(state, item);
+            relax(&mut best[total], candidate, &choose);
+        }
+    }
+    best
+}
+
+/// Bounded knapsack: each item comes with a number of available copies.
+///
+/// Like [`knapsack`], but item `i` can be taken up to `items[i].1` times,
+/// applying `take` once per copy. Runs in `O(copies * capacity)`.
+pub fn bounded_knapsack<I, S: Clone>(
+    items: &[(I, usize)],
+    capacity: usize,
+    empty: S,
+    weight: impl Fn(&I) -> usize,
+    take: impl Fn(&S, &I) -> S,
+    choose: impl Fn(S, S) -> S,
+) -> Vec<Option<S>> {
+    let mut best = vec![None; capacity + 1];
+    best[0] = Some(empty);
+    for (item, copies) in items {
+        let w = weight(item);
+        let previous = best.clone();
+        for (base, state) in previous.into_iter().enumerate() {
+            let Some(mut state) = state else { continue };
+            // Take 1, 2, ... copies on top of each selection without the item
+            for count in 1..=*copies {
+                let total = base + count * w;
+                if total > capacity {
+                    break;
+                }
+                state = take(&state, item);
+                relax(&mut best[total], state.clone(), &choose);
+            }
+        }
+    }
+    best
+}
+
+/// All sums `0..=max` reachable by adding up a subset of `values`, as a
+/// bitset. Each value is used at most once.
+///
+/// Shifts whole machine words at a time, in `O(values * max / 64)`.
+#[must_use]
+pub fn subset_sums(values: &[usize], max: usize) -> BitSet {
+    let mut reachable = BitSet::new(max + 1);
+    reachable.insert(0);
+    for &value in values {
+        if value <= max {
+            reachable.or_shifted(value);
+        }
+    }
+    // Capacity is rounded up to whole words, which may hold sums beyond `max`
+    for sum in max + 1..reachable.capacity() {
+        reachable.remove(sum);
+    }
+    reachable
+}
+
+/// Whether some subset of `values` adds up to exactly `target`
+#[must_use]
+pub fn subset_sum(values: &[usize], target: usize) -> bool {
+    subset_sums(values, target).contains(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_knapsack() {
+        // (weight, value) pairs, maximizing value
+        let items = [(1, 1), (3, 4), (4, 5), (5, 7)];
+        let best = knapsack(&items, 7, 0, |&(w, _)| w, |s, &(_, v)| s + v, u32::max);
+        assert_eq!(
+            best,
+            vec![
+                Some(0),
+                Some(1),
+                None,
+                Some(4),
+                Some(5),
+                Some(7),
+                Some(8),
+                Some(9)
+            ]
+        );
+        // Counting selections instead: subsets of 1..=6 by their sum
+        let count = knapsack(
+            &[1, 2, 3, 4, 5, 6],
+            21,
+            1u32,
+            |&w| w,
+            |&s, _| s,
+            |a, b| a + b,
+        );
+        assert_eq!(count[10], Some(5));
+        assert_eq!(count[21], Some(1));
+        assert_eq!(count.iter().flatten().sum::<u32>(), 64);
+    }
+
+    #[test]
+    fn test_take_or_skip() {
+        // Largest 3-digit subsequence, taking digits in order
+        let digits = [8, 1, 9, 2, 7, 3];
+        let best = knapsack(&digits, 3, 0, |_| 1, |&s, &d| s * 10 + d, u32::max);
+        assert_eq!(best[3], Some(973));
+    }
+
+    #[test]
+    fn test_bounded_knapsack() {
+        let items = [((2, 3), 3), ((3, 5), 1)];
+        let best = bounded_knapsack(&items, 8, 0, |&(w, _)| w, |s, &(_, v)| s + v, u32::max);
+        assert_eq!(best[6], Some(9));
+        assert_eq!(best[7], Some(11));
+        assert_eq!(best[8], None);
+        // Matches a 0/1 knapsack over the expanded copies
+        let expanded = [(2, 3), (2, 3), (2, 3), (3, 5)];
+        let expected = knapsack(&expanded, 8, 0, |&(w, _)| w, |s, &(_, v)| s + v, u32::max);
+        assert_eq!(best, expected);
+    }
+
+    #[test]
+    fn test_subset_sums() {
+        let values = [3, 34, 4, 12, 5, 2];
+        assert!(subset_sum(&values, 9));
+        assert!(!subset_sum(&values, 30));
+        let sums = subset_sums(&values, 100);
+        assert_eq!(
+            sums.count_ones(),
+            sums.iter_ones().filter(|&s| s <= 100).count()
+        );
+        // Agrees with the generic knapsack counting reachable weights
+        let reachable = knapsack(&values, 100, (), |&v| v, |(), _| (), |(), ()| ());
+        for (sum, state) in reachable.iter().enumerate() {
+            assert_eq!(sums.contains(sum), state.is_some(), "sum {sum}");
+        }
+        assert_eq!(subset_sums(&[], 0).iter_ones().collect::<Vec<_>>(), vec![0]);
+    }
+}
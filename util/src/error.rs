@@ -0,0 +1,139 @@
+//! Errors of reading inputs, parsing them and writing outputs, as an enum so
+//! that callers can tell them apart, e.g. to download an input that is
+//! missing rather than give up.
+//!
+//! [`Error`] converts into `anyhow::Error` through `?` like any other error,
+//! and an `anyhow::Error` converts back into [`Error::Other`].
+
+use std::{io, path::PathBuf};
+
+use crate::{diagnostics::Warning, grid::Point};
+
+/// An error of a parser function, whatever its type
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// `Result` with [`Error`] as the default error
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// A graph that should be acyclic has a cycle, through the nodes of
+    /// `path`
+    #[error("Graph has a cycle: {path}")]
+    Cycle { path: String },
+    /// An instruction of a program failed, at position `pc`
+    #[error("Instruction {pc} failed: {message}")]
+    Execution { pc: usize, message: String },
+    /// Encoding an animated GIF failed
+    #[cfg(feature = "gif")]
+    #[error(transparent)]
+    Gif(#[from] gif::EncodingError),
+    /// A request of the Advent of Code client to `url` failed
+    #[error("Failed to {action} {url}: {message}")]
+    Http {
+        action: &'static str,
+        url: String,
+        message: String,
+    },
+    /// The integer linear program has no optimal solution
+    #[cfg(feature = "ilp")]
+    #[error("Failed to solve ILP: {0}")]
+    Ilp(#[from] microlp::Error),
+    /// An animated GIF would be more than `u16::MAX` pixels across
+    #[error("Animation of {pixels} pixels across is too large for a GIF")]
+    ImageTooLarge { pixels: usize },
+    /// The configuration file of a day is invalid, or a value in it
+    #[error("config/day{day:02}.toml: {message}")]
+    InvalidConfig { day: u8, message: String },
+    /// The day is not between 1 and 25
+    #[error("Day must be between 1 and 25, not {0}")]
+    InvalidDay(u8),
+    /// A range is empty, or overlaps one already there
+    #[error("Invalid range: {0}")]
+    InvalidRange(&'static str),
+    /// A day is not unlocked yet, for `seconds` more
+    #[error("Day {day} of {year} unlocks in {seconds}s")]
+    Locked { year: u16, day: u8, seconds: u64 },
+    /// There is no input file at `path`, as for a day not downloaded yet
+    #[error("Missing input file '{}'", path.display())]
+    MissingInput { path: PathBuf },
+    /// An animation to write has no frames
+    #[error("No frames to write")]
+    NoFrames,
+    /// A segment that should be horizontal or vertical is neither
+    #[error("Segment from {from:?} to {to:?} is not axis-aligned")]
+    NotAxisAligned { from: Point, to: Point },
+    /// There is no `Cargo.lock` in the current directory or any parent
+    #[error("Could not find workspace root")]
+    NoWorkspaceRoot,
+    /// `text` is not a valid `what`, e.g. a number or a duration
+    #[error("Invalid {what} '{text}': {reason}")]
+    Parse {
+        what: &'static str,
+        text: String,
+        reason: String,
+    },
+    /// The parser function failed on a line, counted from 1
+    #[error("Failed to parse line {line}: {error}")]
+    ParseLine { line: usize, error: BoxError },
+    /// The Advent of Code session cookie is missing, empty or no longer
+    /// valid
+    #[error("{0}")]
+    Session(String),
+    /// A line of a grid, counted from 1, has another number of columns than
+    /// the first one
+    #[error("Line {line} has {found} columns, but the first line has {expected}")]
+    ShapeMismatch {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// There are `found` of `what` where `expected` are needed, such as the
+    /// columns of a row of a square matrix
+    #[error("Expected {expected} {what}, found {found}")]
+    SizeMismatch {
+        what: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// A warning about the input, as warnings are errors
+    #[error("{0}")]
+    Warning(Warning),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        let error = Error::ParseLine {
+            line: 3,
+            error: "invalid digit".into(),
+        };
+        assert_eq!(error.to_string(), "Failed to parse line 3: invalid digit");
+        let error = Error::Parse {
+            what: "duration",
+            text: "3 parsecs".to_string(),
+            reason: "missing unit".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Invalid duration '3 parsecs': missing unit"
+        );
+        // Both ways through anyhow keep the kind of the error
+        let error = anyhow::Error::from(Error::InvalidDay(26));
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::InvalidDay(26))
+        ));
+        let error = Error::from(anyhow::anyhow!("elsewhere"));
+        assert!(matches!(&error, Error::Other(_)));
+        assert_eq!(error.to_string(), "elsewhere");
+    }
+}
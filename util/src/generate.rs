@@ -0,0 +1,337 @@
+//! Random puzzle-shaped inputs and a stress tester comparing solutions.
+//!
+//! Generators take an [`Rng`] so that every case is reproducible from its
+//! seed. [`stress_test`] runs a fast solution against a brute-force reference
+//! on many generated inputs, and shrinks the first mismatch it finds to a
+//! minimal failing input via [`Shrink`].
+
+use std::ops::RangeInclusive;
+
+use ndarray::Array2;
+
+use crate::{parallel::prelude::*, rng::Rng};
+
+/// A random length within the inclusive `len` range
+fn length(rng: &mut Rng, len: &RangeInclusive<usize>) -> usize {
+    len.start() + rng.below(len.end() - len.start() + 1)
+}
+
+/// A list of `len` integers drawn from `values`
+pub fn int_list(
+    rng: &mut Rng,
+    len: RangeInclusive<usize>,
+    values: RangeInclusive<i64>,
+) -> Vec<i64> {
+    let len = length(rng, &len);
+    (0..len)
+        .map(|_| rng.between(*values.start(), *values.end()))
+        .collect()
+}
+
+/// A grid with `rows` by `cols` cells drawn uniformly from `alphabet`
+///
+/// # Panics
+/// Panics if `alphabet` is empty.
+pub fn grid<T: Clone>(
+    rng: &mut Rng,
+    rows: RangeInclusive<usize>,
+    cols: RangeInclusive<usize>,
+    alphabet: &[T],
+) -> Array2<T> {
+    let shape = (length(rng, &rows), length(rng, &cols));
+    Array2::from_shape_simple_fn(shape, || alphabet[rng.below(alphabet.len())].clone())
+}
+
+/// `edges` random directed `(from, to, weight)` edges between `nodes` nodes,
+/// possibly with self-loops and duplicates
+///
+/// # Panics
+/// Panics if `nodes` is zero while `edges` is not.
+pub fn edge_list(
+    rng: &mut Rng,
+    nodes: usize,
+    edges: usize,
+    weights: RangeInclusive<i64>,
+) -> Vec<(usize, usize, i64)> {
+    (0..edges)
+        .map(|_| {
+            let (from, to) = (rng.below(nodes), rng.below(nodes));
+            (from, to, rng.between(*weights.start(), *weights.end()))
+        })
+        .collect()
+}
+
+/// Random edges of a DAG, always pointing from a smaller to a larger node, so
+/// that `0..nodes` is a topological order
+pub fn dag_edges(rng: &mut Rng, nodes: usize, edges: usize) -> Vec<(usize, usize)> {
+    if nodes < 2 {
+        return Vec::new();
+    }
+    (0..edges)
+        .map(|_| {
+            let from = rng.below(nodes - 1);
+            (from, from + 1 + rng.below(nodes - from - 1))
+        })
+        .collect()
+}
+
+/// `count` non-empty inclusive ranges within `bounds`, possibly overlapping
+pub fn ranges(rng: &mut Rng, count: usize, bounds: RangeInclusive<i64>) -> Vec<(i64, i64)> {
+    (0..count)
+        .map(|_| {
+            let a = rng.between(*bounds.start(), *bounds.end());
+            let b = rng.between(*bounds.start(), *bounds.end());
+            (a.min(b), a.max(b))
+        })
+        .collect()
+}
+
+/// Values that can be simplified towards a minimal counterexample
+pub trait Shrink: Sized {
+    /// Strictly simpler variants of `self`, most aggressive first
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! impl_shrink_int {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                // Towards zero: zero itself, then halfway, then one step
+                let x = *self;
+                let mut candidates = Vec::new();
+                for candidate in [0, x / 2, x - x.signum()] {
+                    if candidate != x && !candidates.contains(&candidate) {
+                        candidates.push(candidate);
+                    }
+                }
+                candidates
+            }
+        }
+    )*};
+}
+
+impl_shrink_int!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_shrink_uint {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let x = *self;
+                let mut candidates = Vec::new();
+                for candidate in [0, x / 2, x.saturating_sub(1)] {
+                    if candidate != x && !candidates.contains(&candidate) {
+                        candidates.push(candidate);
+                    }
+                }
+                candidates
+            }
+        }
+    )*};
+}
+
+impl_shrink_uint!(u8, u16, u32, u64, u128, usize);
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self { vec![false] } else { Vec::new() }
+    }
+}
+
+impl Shrink for char {
+    fn shrink(&self) -> Vec<Self> {
+        if *self == 'a' { Vec::new() } else { vec!['a'] }
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b) = self;
+        let firsts = a.shrink().into_iter().map(|a| (a, b.clone()));
+        let seconds = b.shrink().into_iter().map(|b| (a.clone(), b));
+        firsts.chain(seconds).collect()
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone, C: Shrink + Clone> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b, c) = self;
+        let firsts = a.shrink().into_iter().map(|a| (a, b.clone(), c.clone()));
+        let seconds = b.shrink().into_iter().map(|b| (a.clone(), b, c.clone()));
+        let thirds = c.shrink().into_iter().map(|c| (a.clone(), b.clone(), c));
+        firsts.chain(seconds).chain(thirds).collect()
+    }
+}
+
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        // Drop chunks of halving size, then simplify single elements
+        let mut chunk = self.len();
+        while chunk > 0 {
+            for start in (0..self.len()).step_by(chunk) {
+                let mut smaller = self[..start].to_vec();
+                smaller.extend_from_slice(&self[(start + chunk).min(self.len())..]);
+                candidates.push(smaller);
+            }
+            chunk /= 2;
+        }
+        for (i, item) in self.iter().enumerate() {
+            for simpler in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl<T: Shrink + Clone> Shrink for Array2<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let (rows, cols) = self.dim();
+        let mut candidates = Vec::new();
+        // Drop the last row or column, then simplify single cells
+        if rows > 0 {
+            candidates.push(self.slice(ndarray::s![..rows - 1, ..]).to_owned());
+        }
+        if cols > 0 {
+            candidates.push(self.slice(ndarray::s![.., ..cols - 1]).to_owned());
+        }
+        for (pos, cell) in self.indexed_iter() {
+            for simpler in cell.shrink() {
+                let mut candidate = self.clone();
+                candidate[pos] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+/// An input on which two solutions disagree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counterexample<I, O> {
+    /// Seed of the generated case that first failed
+    pub seed: u64,
+    /// The failing input, after shrinking
+    pub input: I,
+    /// Output of the reference solution
+    pub expected: O,
+    /// Output of the solution under test
+    pub actual: O,
+}
+
+/// Compare `fast` against the reference `brute` on `n` inputs from `generate`,
+/// seeded with `0..n`.
+///
+/// Cases run in parallel. The failing case with the smallest seed is shrunk
+/// greedily while it keeps failing and returned, or `None` if all cases
+/// agree.
+pub fn stress_test<I, O>(
+    brute: impl Fn(&I) -> O + Sync,
+    fast: impl Fn(&I) -> O + Sync,
+    generate: impl Fn(&mut Rng) -> I + Sync,
+    n: u64,
+) -> Option<Counterexample<I, O>>
+where
+    I: Shrink + Send,
+    O: PartialEq + Send,
+{
+    let (seed, mut input) = (0..n).maybe_into_par_iter().find_map_first(|seed| {
+        let input = generate(&mut Rng::new(seed));
+        (brute(&input) != fast(&input)).then_some((seed, input))
+    })?;
+    // Move to the first simpler input that still fails, until none does
+    while let Some(simpler) = input
+        .shrink()
+        .into_iter()
+        .find(|candidate| brute(candidate) != fast(candidate))
+    {
+        input = simpler;
+    }
+    Some(Counterexample {
+        seed,
+        expected: brute(&input),
+        actual: fast(&input),
+        input,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generators() {
+        let mut rng = Rng::new(1);
+        let list = int_list(&mut rng, 3..=5, -2..=2);
+        assert!((3..=5).contains(&list.len()));
+        assert!(list.iter().all(|x| (-2..=2).contains(x)));
+        let grid = grid(&mut rng, 2..=2, 1..=4, &['.', '#']);
+        assert_eq!(grid.nrows(), 2);
+        assert!(grid.iter().all(|c| ".#".contains(*c)));
+        let edges = edge_list(&mut rng, 4, 10, 1..=9);
+        assert_eq!(edges.len(), 10);
+        assert!(edges.iter().all(|&(u, v, w)| u < 4 && v < 4 && w > 0));
+        assert!(
+            dag_edges(&mut rng, 5, 20)
+                .iter()
+                .all(|&(u, v)| u < v && v < 5)
+        );
+        assert!(dag_edges(&mut rng, 1, 20).is_empty());
+        assert!(ranges(&mut rng, 10, 0..=9).iter().all(|&(a, b)| a <= b));
+        // Same seed, same input
+        assert_eq!(
+            int_list(&mut Rng::new(7), 0..=9, 0..=9),
+            int_list(&mut Rng::new(7), 0..=9, 0..=9)
+        );
+    }
+
+    #[test]
+    fn test_shrink() {
+        assert_eq!(10i32.shrink(), vec![0, 5, 9]);
+        assert_eq!((-1i64).shrink(), vec![0]);
+        assert!(0u8.shrink().is_empty());
+        assert_eq!(vec![1u8, 2].shrink()[0], Vec::<u8>::new());
+        assert!(Vec::<u8>::new().shrink().is_empty());
+    }
+
+    #[test]
+    fn test_stress_test() {
+        // Maximum subarray sum: Kadane's algorithm against all subarrays
+        let brute = |xs: &Vec<i64>| {
+            (0..xs.len())
+                .flat_map(|i| (i + 1..=xs.len()).map(move |j| xs[i..j].iter().sum::<i64>()))
+                .max()
+                .unwrap_or(0)
+                .max(0)
+        };
+        let kadane = |xs: &Vec<i64>| {
+            xs.iter()
+                .scan(0, |run, &x| {
+                    *run = (*run + x).max(0);
+                    Some(*run)
+                })
+                .max()
+                .unwrap_or(0)
+        };
+        let generate = |rng: &mut Rng| int_list(rng, 0..=12, -20..=20);
+        assert_eq!(stress_test(brute, kadane, generate, 300), None);
+        // A buggy version that forgets to reset the running sum
+        let buggy = |xs: &Vec<i64>| {
+            xs.iter()
+                .scan(0, |run, &x| {
+                    *run += x;
+                    Some(*run)
+                })
+                .max()
+                .unwrap_or(0)
+                .max(0)
+        };
+        let failure = stress_test(brute, buggy, generate, 300)
+            .unwrap_or_else(|| panic!("Bug should be found"));
+        // Shrunk down to a negative number followed by a positive one
+        assert_eq!(failure.input, vec![-1, 1]);
+        assert_eq!((failure.expected, failure.actual), (1, 0));
+    }
+}
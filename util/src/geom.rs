@@ -0,0 +1,303 @@
+//! Computational geometry on integer lattice points.
+//!
+//! Polygons are given as their vertices in order (either orientation), with
+//! the closing edge from the last vertex back to the first left implicit.
+//! Coordinates reuse [`Point<i64>`], whose `row` and `col` simply name the two
+//! axes here. Points in space use [`Vec3`].
+
+mod segment_index;
+mod vec3;
+
+pub use segment_index::SegmentIndex;
+pub use vec3::Vec3;
+
+use crate::{
+    grid::{Direction, Point},
+    math::gcd,
+};
+
+/// Position of a point relative to a polygon
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Iterate over the edges of a polygon as pairs of consecutive vertices,
+/// including the closing edge
+pub fn edges(polygon: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Twice the signed area of a polygon by the shoelace formula, positive when
+/// the vertices go counter-clockwise in `(row, col)` axes
+#[must_use]
+pub fn signed_double_area(polygon: &[Point]) -> i64 {
+    edges(polygon)
+        .map(|(a, b)| a.row * b.col - b.row * a.col)
+        .sum()
+}
+
+/// Twice the area of a polygon, which is always an integer for lattice points
+#[must_use]
+pub fn double_area(polygon: &[Point]) -> u64 {
+    signed_double_area(polygon).unsigned_abs()
+}
+
+/// Number of lattice points on the boundary of a polygon
+#[must_use]
+pub fn boundary_points(polygon: &[Point]) -> u64 {
+    edges(polygon)
+        .map(|(a, b)| {
+            gcd(
+                (b.row - a.row).unsigned_abs(),
+                (b.col - a.col).unsigned_abs(),
+            )
+        })
+        .sum()
+}
+
+/// Number of lattice points strictly inside a polygon, by Pick's theorem:
+/// `A = I + B / 2 - 1`
+#[must_use]
+pub fn interior_points(polygon: &[Point]) -> u64 {
+    (double_area(polygon) + 2 - boundary_points(polygon)) / 2
+}
+
+/// Number of lattice points inside or on the boundary of a polygon, e.g. the
+/// number of cells dug out by a trench and its interior
+#[must_use]
+pub fn enclosed_points(polygon: &[Point]) -> u64 {
+    interior_points(polygon) + boundary_points(polygon)
+}
+
+/// Locate `point` relative to a simple polygon by casting a ray along the
+/// `col` axis and counting edge crossings
+#[must_use]
+pub fn point_in_polygon(point: Point, polygon: &[Point]) -> Containment {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        // Collinear and within the bounding box means on the edge
+        let cross = (b.row - a.row) * (point.col - a.col) - (b.col - a.col) * (point.row - a.row);
+        if cross == 0
+            && (a.row.min(b.row)..=a.row.max(b.row)).contains(&point.row)
+            && (a.col.min(b.col)..=a.col.max(b.col)).contains(&point.col)
+        {
+            return Containment::Boundary;
+        }
+        // Half-open rule so that vertices on the ray are counted once
+        if (a.row > point.row) != (b.row > point.row) {
+            // The crossing lies to the right when the cross product has the
+            // same sign as the edge direction along `row`
+            let right = if b.row > a.row { cross > 0 } else { cross < 0 };
+            if right {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+/// Trace a rectilinear path from `start`, moving `length` steps in each
+/// direction, and return the vertices visited (including `start`, but not
+/// the final point).
+///
+/// When the moves form a closed loop, the result is the vertex list of the
+/// polygon it encloses.
+pub fn walk_perimeter(
+    start: Point,
+    moves: impl IntoIterator<Item = (Direction, i64)>,
+) -> Vec<Point> {
+    let mut vertices = vec![start];
+    let mut current = start;
+    for (direction, length) in moves {
+        current = current + direction.offset() * length;
+        vertices.push(current);
+    }
+    vertices.pop();
+    vertices
+}
+
+/// An axis-aligned rectangle of lattice points, with inclusive bounds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rect {
+    /// The smallest rectangle containing both (opposite) corners
+    #[must_use]
+    pub fn from_corners(a: Point, b: Point) -> Self {
+        Self {
+            min: Point::new(a.row.min(b.row), a.col.min(b.col)),
+            max: Point::new(a.row.max(b.row), a.col.max(b.col)),
+        }
+    }
+
+    /// Number of lattice points covered, boundary included
+    #[must_use]
+    pub const fn area(&self) -> u64 {
+        (self.max.row.abs_diff(self.min.row) + 1) * (self.max.col.abs_diff(self.min.col) + 1)
+    }
+
+    /// Whether `point` lies inside or on the boundary
+    #[must_use]
+    pub const fn contains(&self, point: Point) -> bool {
+        self.min.row <= point.row
+            && point.row <= self.max.row
+            && self.min.col <= point.col
+            && point.col <= self.max.col
+    }
+
+    /// The lattice points covered by both rectangles, if any
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point::new(
+            self.min.row.max(other.min.row),
+            self.min.col.max(other.min.col),
+        );
+        let max = Point::new(
+            self.max.row.min(other.max.row),
+            self.max.col.min(other.max.col),
+        );
+        (min.row <= max.row && min.col <= max.col).then_some(Self { min, max })
+    }
+
+    /// Whether `other` reaches strictly inside this rectangle, i.e. overlaps it
+    /// by more than touching the boundary
+    #[must_use]
+    pub const fn overlaps_interior(&self, other: &Self) -> bool {
+        other.min.row < self.max.row
+            && other.max.row > self.min.row
+            && other.min.col < self.max.col
+            && other.max.col > self.min.col
+    }
+
+    /// Whether the axis-aligned segment from `a` to `b` passes through the
+    /// interior of the rectangle, rather than staying outside or on its
+    /// boundary
+    #[must_use]
+    pub fn crossed_by(&self, a: Point, b: Point) -> bool {
+        self.overlaps_interior(&Self::from_corners(a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(i64, i64)]) -> Vec<Point> {
+        vertices.iter().map(|&v| v.into()).collect()
+    }
+
+    #[test]
+    fn test_area_and_pick() {
+        let square = polygon(&[(0, 0), (0, 4), (4, 4), (4, 0)]);
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(signed_double_area(&square), -32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(enclosed_points(&square), 25);
+
+        let triangle = polygon(&[(0, 0), (3, 0), (0, 3)]);
+        assert_eq!(double_area(&triangle), 9);
+        assert_eq!(boundary_points(&triangle), 9);
+        assert_eq!(interior_points(&triangle), 1);
+    }
+
+    #[test]
+    fn test_walk_perimeter() {
+        // An L-shaped trench, counted cell by cell
+        let moves = [
+            (Direction::Right, 6),
+            (Direction::Down, 2),
+            (Direction::Left, 3),
+            (Direction::Down, 3),
+            (Direction::Left, 3),
+            (Direction::Up, 5),
+        ];
+        let vertices = walk_perimeter(Point::default(), moves);
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(vertices[1], Point::new(0, 6));
+        let cells = (0..=5)
+            .flat_map(|r| (0..=6).map(move |c| Point::new(r, c)))
+            .filter(|&p| point_in_polygon(p, &vertices) != Containment::Outside)
+            .count();
+        assert_eq!(
+            enclosed_points(&vertices),
+            u64::try_from(cells).unwrap_or_default()
+        );
+        assert_eq!(enclosed_points(&vertices), 7 * 3 + 4 * 3);
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let shape = polygon(&[(0, 0), (0, 4), (2, 2), (4, 4), (4, 0)]);
+        assert_eq!(
+            point_in_polygon(Point::new(1, 1), &shape),
+            Containment::Inside
+        );
+        assert_eq!(
+            point_in_polygon(Point::new(2, 0), &shape),
+            Containment::Boundary
+        );
+        assert_eq!(
+            point_in_polygon(Point::new(1, 3), &shape),
+            Containment::Boundary
+        );
+        assert_eq!(
+            point_in_polygon(Point::new(2, 3), &shape),
+            Containment::Outside
+        );
+        assert_eq!(
+            point_in_polygon(Point::new(2, 2), &shape),
+            Containment::Boundary
+        );
+        assert_eq!(
+            point_in_polygon(Point::new(5, 1), &shape),
+            Containment::Outside
+        );
+        // Ray passing exactly through a vertex
+        assert_eq!(
+            point_in_polygon(Point::new(2, -1), &shape),
+            Containment::Outside
+        );
+        assert_eq!(
+            point_in_polygon(Point::new(2, 1), &shape),
+            Containment::Inside
+        );
+    }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::from_corners(Point::new(4, 1), Point::new(0, 5));
+        assert_eq!(rect.min, Point::new(0, 1));
+        assert_eq!(rect.area(), 25);
+        assert!(rect.contains(Point::new(4, 5)));
+        assert!(!rect.contains(Point::new(5, 5)));
+        let other = Rect::from_corners(Point::new(3, 4), Point::new(10, 10));
+        assert_eq!(
+            rect.intersection(&other),
+            Some(Rect::from_corners(Point::new(3, 4), Point::new(4, 5)))
+        );
+        assert_eq!(
+            rect.intersection(&Rect::from_corners(Point::new(5, 0), Point::new(6, 0))),
+            None
+        );
+        // Segments along the boundary do not cross, those through it do
+        assert!(!rect.crossed_by(Point::new(0, -3), Point::new(0, 8)));
+        assert!(!rect.crossed_by(Point::new(2, 5), Point::new(2, 9)));
+        assert!(rect.crossed_by(Point::new(2, 4), Point::new(2, 9)));
+        assert!(rect.crossed_by(Point::new(-1, 3), Point::new(9, 3)));
+        assert!(!rect.crossed_by(Point::new(-1, 3), Point::new(0, 3)));
+    }
+}
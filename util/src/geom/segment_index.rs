@@ -0,0 +1,187 @@
+//! Static index over axis-aligned segments, answering which rectangles they
+//! pass through.
+
+use super::{Rect, edges};
+use crate::{Error, Result, grid::Point};
+
+/// Segments along one axis, each fixed at a `key` on the other axis and
+/// spanning `(start, end)` along this one.
+///
+/// This is a merge sort tree: a segment tree over the segments sorted by key,
+/// where every node lists the spans of its segments sorted by start, along
+/// with the largest end among each prefix of that list.
+#[derive(Clone, Debug)]
+struct AxisTree {
+    keys: Vec<i64>,
+    /// `(start, largest end so far)` of the segments below each node
+    nodes: Vec<Vec<(i64, i64)>>,
+}
+
+impl AxisTree {
+    fn new(mut segments: Vec<(i64, (i64, i64))>) -> Self {
+        segments.sort_unstable();
+        let mut tree = Self {
+            keys: segments.iter().map(|&(key, _)| key).collect(),
+            nodes: vec![Vec::new(); 4 * segments.len().max(1)],
+        };
+        let spans = segments.iter().map(|&(_, span)| span).collect::<Vec<_>>();
+        tree.build(1, &spans);
+        tree
+    }
+
+    fn build(&mut self, node: usize, spans: &[(i64, i64)]) {
+        let mut sorted = spans.to_vec();
+        sorted.sort_unstable();
+        let mut reach = i64::MIN;
+        self.nodes[node] = sorted
+            .into_iter()
+            .map(|(start, end)| {
+                reach = reach.max(end);
+                (start, reach)
+            })
+            .collect();
+        if spans.len() > 1 {
+            let (left, right) = spans.split_at(spans.len() / 2);
+            self.build(2 * node, left);
+            self.build(2 * node + 1, right);
+        }
+    }
+
+    /// Whether any segment with a key strictly between `keys` has a span
+    /// reaching strictly inside `span`
+    fn any_within(&self, keys: (i64, i64), span: (i64, i64)) -> bool {
+        let from = self.keys.partition_point(|&k| k <= keys.0);
+        let to = self.keys.partition_point(|&k| k < keys.1);
+        from < to && self.query(1, (0, self.keys.len()), (from, to), span)
+    }
+
+    fn query(
+        &self,
+        node: usize,
+        (lo, hi): (usize, usize),
+        (from, to): (usize, usize),
+        span: (i64, i64),
+    ) -> bool {
+        if to <= lo || hi <= from {
+            return false;
+        }
+        if from <= lo && hi <= to {
+            // Among the spans starting before `span` ends, the one reaching
+            // furthest decides whether any overlaps it
+            let list = &self.nodes[node];
+            let starting = list.partition_point(|&(start, _)| start < span.1);
+            return starting > 0 && list[starting - 1].1 > span.0;
+        }
+        let mid = lo + (hi - lo) / 2;
+        self.query(2 * node, (lo, mid), (from, to), span)
+            || self.query(2 * node + 1, (mid, hi), (from, to), span)
+    }
+}
+
+/// A static set of axis-aligned segments, such as the edges of a rectilinear
+/// polygon, indexed to tell in `O(log^2 n)` whether any of them passes
+/// through the interior of a rectangle.
+///
+/// This answers the same question as [`Rect::crossed_by`] for every segment
+/// at once, after `O(n log^2 n)` preprocessing.
+#[derive(Clone, Debug)]
+pub struct SegmentIndex {
+    /// Segments along `col`, keyed by their row
+    horizontal: AxisTree,
+    /// Segments along `row`, keyed by their col
+    vertical: AxisTree,
+}
+
+impl SegmentIndex {
+    /// Index segments given by their endpoints, failing if any of them is not
+    /// axis-aligned
+    pub fn new(segments: impl IntoIterator<Item = (Point, Point)>) -> Result<Self> {
+        let (mut horizontal, mut vertical) = (Vec::new(), Vec::new());
+        for (a, b) in segments {
+            if a.row == b.row {
+                horizontal.push((a.row, (a.col.min(b.col), a.col.max(b.col))));
+            } else if a.col == b.col {
+                vertical.push((a.col, (a.row.min(b.row), a.row.max(b.row))));
+            } else {
+                return Err(Error::NotAxisAligned { from: a, to: b });
+            }
+        }
+        Ok(Self {
+            horizontal: AxisTree::new(horizontal),
+            vertical: AxisTree::new(vertical),
+        })
+    }
+
+    /// Index the edges of a rectilinear polygon
+    pub fn from_polygon(polygon: &[Point]) -> Result<Self> {
+        Self::new(edges(polygon))
+    }
+
+    /// Whether any segment passes through the interior of `rect`, rather than
+    /// staying outside or on its boundary
+    #[must_use]
+    pub fn crosses(&self, rect: &Rect) -> bool {
+        let rows = (rect.min.row, rect.max.row);
+        let cols = (rect.min.col, rect.max.col);
+        self.horizontal.any_within(rows, cols) || self.vertical.any_within(cols, rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_matches_crossed_by() -> Result<()> {
+        let mut rng = Rng::new(9);
+        let mut coord = || Point::new(rng.between(0, 12), rng.between(0, 12));
+        for _ in 0..50 {
+            let segments = (0..20)
+                .map(|_| {
+                    let (a, b) = (coord(), coord());
+                    // Keep one coordinate of `a` to make the segment axis-aligned
+                    let b = if b.row % 2 == 0 {
+                        Point::new(a.row, b.col)
+                    } else {
+                        Point::new(b.row, a.col)
+                    };
+                    (a, b)
+                })
+                .collect::<Vec<_>>();
+            let index = SegmentIndex::new(segments.iter().copied())?;
+            for _ in 0..50 {
+                let rect = Rect::from_corners(coord(), coord());
+                let expected = segments.iter().any(|&(a, b)| rect.crossed_by(a, b));
+                assert_eq!(index.crosses(&rect), expected, "{rect:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_polygon() -> Result<()> {
+        // A U shape, open at the top between columns 2 and 4
+        let polygon = [
+            (0, 0),
+            (0, 2),
+            (4, 2),
+            (4, 4),
+            (0, 4),
+            (0, 6),
+            (6, 6),
+            (6, 0),
+        ]
+        .map(Point::from);
+        let index = SegmentIndex::from_polygon(&polygon)?;
+        assert!(!index.crosses(&Rect::from_corners(Point::new(4, 0), Point::new(6, 6))));
+        assert!(index.crosses(&Rect::from_corners(Point::new(3, 0), Point::new(6, 6))));
+        assert!(!index.crosses(&Rect::from_corners(Point::new(0, 0), Point::new(6, 2))));
+        assert!(SegmentIndex::new([(Point::new(0, 0), Point::new(1, 1))]).is_err());
+        assert!(
+            !SegmentIndex::new([])?
+                .crosses(&Rect::from_corners(Point::new(0, 0), Point::new(1, 1)))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! Vectors and points in 3D space.
+
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign, Index, Mul, Neg, Sub, SubAssign},
+};
+
+/// A vector or point in 3D space, stored as `(x, y, z)`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Vec3<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Apply `f` to every coordinate
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Vec3<U> {
+        Vec3::new(f(self.x), f(self.y), f(self.z))
+    }
+
+    /// Iterate over the coordinates in `x, y, z` order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        [&self.x, &self.y, &self.z].into_iter()
+    }
+}
+
+impl Vec3<i64> {
+    /// The origin
+    pub const ZERO: Self = Self::new(0, 0, 0);
+
+    /// Unit vectors along the `x`, `y` and `z` axes
+    pub const AXES: [Self; 3] = [Self::new(1, 0, 0), Self::new(0, 1, 0), Self::new(0, 0, 1)];
+
+    /// Manhattan (taxicab) norm, the sum of absolute coordinates
+    #[must_use]
+    pub const fn manhattan(self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    /// Chebyshev norm, the largest absolute coordinate
+    #[must_use]
+    pub fn chebyshev(self) -> u64 {
+        self.x
+            .unsigned_abs()
+            .max(self.y.unsigned_abs())
+            .max(self.z.unsigned_abs())
+    }
+
+    /// Squared Euclidean norm, which orders vectors like the Euclidean norm
+    /// while staying exact
+    #[must_use]
+    pub const fn norm2(self) -> i64 {
+        self.dot(self)
+    }
+
+    /// Dot product
+    #[must_use]
+    pub const fn dot(self, rhs: Self) -> i64 {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    /// Cross product
+    #[must_use]
+    pub const fn cross(self, rhs: Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+
+    /// The 6 orthogonal neighbors, i.e. those at Manhattan distance 1
+    #[must_use]
+    pub fn neighbors6(self) -> [Self; 6] {
+        let [x, y, z] = Self::AXES;
+        [self + x, self - x, self + y, self - y, self + z, self - z]
+    }
+}
+
+impl<T> From<[T; 3]> for Vec3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Vec3<T>> for [T; 3] {
+    fn from(v: Vec3<T>) -> Self {
+        [v.x, v.y, v.z]
+    }
+}
+
+impl<T> Index<usize> for Vec3<T> {
+    type Output = T;
+
+    /// Coordinate along axis `0`, `1` or `2` for `x`, `y` or `z`
+    fn index(&self, axis: usize) -> &T {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Axis {axis} out of range for a 3D vector"),
+        }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Vec3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Vec3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Vec3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vec3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Vec3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Vec3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Add<Output = T> + Default> Sum for Vec3<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, v| acc + v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Vec3::new(1, -2, 3);
+        let b = Vec3::from([4, 5, -6]);
+        assert_eq!(a + b, Vec3::new(5, 3, -3));
+        assert_eq!(a - b, Vec3::new(-3, -7, 9));
+        assert_eq!(a * 2, Vec3::new(2, -4, 6));
+        assert_eq!(-a, Vec3::new(-1, 2, -3));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        assert_eq!([a, b, b].into_iter().sum::<Vec3>(), Vec3::new(9, 8, -9));
+        assert_eq!(a.dot(b), -24);
+        assert_eq!(a.cross(b), Vec3::new(-3, 18, 13));
+        assert_eq!(a.cross(b).dot(a), 0);
+        assert_eq!(<[i64; 3]>::from(a), [1, -2, 3]);
+    }
+
+    #[test]
+    fn test_norms_and_axes() {
+        let v = Vec3::new(3, -4, 12);
+        assert_eq!(v.manhattan(), 19);
+        assert_eq!(v.chebyshev(), 12);
+        assert_eq!(v.norm2(), 169);
+        assert_eq!((v[0], v[1], v[2]), (3, -4, 12));
+        assert_eq!(v.iter().copied().collect::<Vec<_>>(), vec![3, -4, 12]);
+        assert_eq!(v.map(i64::unsigned_abs), Vec3::new(3, 4, 12));
+        let neighbors = Vec3::ZERO.neighbors6();
+        assert!(neighbors.iter().all(|n| n.manhattan() == 1));
+        assert_eq!(neighbors.into_iter().sum::<Vec3>(), Vec3::ZERO);
+    }
+}
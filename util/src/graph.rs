@@ -0,0 +1,13 @@
+//! Graph algorithms shared across puzzles
+
+mod csr;
+mod dag_dp;
+mod scc;
+mod shortest_paths;
+mod toposort;
+
+pub use csr::Csr;
+pub use dag_dp::{dag_dp, dag_dp_all};
+pub use scc::{Condensation, condense, strongly_connected_components};
+pub use shortest_paths::{bellman_ford, floyd_warshall};
+pub use toposort::{toposort, toposort_with_names};
@@ -0,0 +1,201 @@
+//! Compressed sparse row storage for large, static graphs.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A directed graph in compressed sparse row (CSR) form, with optional edge
+/// weights of type `W`.
+///
+/// The edges leaving node `u` are stored contiguously at
+/// `offsets[u]..offsets[u + 1]` of the `targets` and `weights` arrays, so the
+/// whole graph takes three flat allocations instead of one per node, and
+/// neighbor scans are cache friendly. The graph cannot be modified once
+/// built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Csr<W = ()> {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Vec<W>,
+}
+
+impl<W> Csr<W> {
+    /// Build a graph with `n` nodes from `(from, to, weight)` edges. Edges
+    /// leaving the same node keep their relative order.
+    ///
+    /// # Panics
+    /// Panics if an edge refers to a node `>= n`.
+    #[must_use]
+    pub fn from_edges(n: usize, edges: impl IntoIterator<Item = (usize, usize, W)>) -> Self {
+        let mut edges = edges.into_iter().collect::<Vec<_>>();
+        // Stable, so that edges of each node keep their input order
+        edges.sort_by_key(|&(from, _, _)| from);
+        let mut offsets = vec![0; n + 1];
+        for &(from, to, _) in &edges {
+            assert!(from < n && to < n, "Edge {from} -> {to} out of range");
+            offsets[from + 1] += 1;
+        }
+        for u in 0..n {
+            offsets[u + 1] += offsets[u];
+        }
+        let (targets, weights) = edges.into_iter().map(|(_, to, w)| (to, w)).unzip();
+        Self {
+            offsets,
+            targets,
+            weights,
+        }
+    }
+
+    /// Build a graph from weighted adjacency lists, where `adjacency[u]` holds
+    /// the `(to, weight)` pairs of the edges leaving `u`
+    #[must_use]
+    pub fn from_weighted_adjacency(adjacency: &[Vec<(usize, W)>]) -> Self
+    where
+        W: Clone,
+    {
+        Self::from_edges(
+            adjacency.len(),
+            adjacency
+                .iter()
+                .enumerate()
+                .flat_map(|(from, edges)| edges.iter().map(move |(to, w)| (from, *to, w.clone()))),
+        )
+    }
+
+    /// Number of nodes
+    #[must_use]
+    pub const fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Number of edges
+    #[must_use]
+    pub const fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Number of edges leaving `u`
+    #[must_use]
+    pub fn degree(&self, u: usize) -> usize {
+        self.offsets[u + 1] - self.offsets[u]
+    }
+
+    /// Targets of the edges leaving `u`
+    #[must_use]
+    pub fn neighbors(&self, u: usize) -> &[usize] {
+        &self.targets[self.offsets[u]..self.offsets[u + 1]]
+    }
+
+    /// Weights of the edges leaving `u`, in the same order as
+    /// [`Self::neighbors`]
+    #[must_use]
+    pub fn weights(&self, u: usize) -> &[W] {
+        &self.weights[self.offsets[u]..self.offsets[u + 1]]
+    }
+
+    /// Iterate over the edges leaving `u` as `(to, weight)` pairs
+    pub fn edges(&self, u: usize) -> impl Iterator<Item = (usize, &W)> {
+        self.neighbors(u).iter().copied().zip(self.weights(u))
+    }
+
+    /// Iterate over all nodes in parallel, as `(u, neighbors, weights)`
+    #[cfg(feature = "parallel")]
+    #[must_use]
+    pub fn par_nodes(&self) -> impl IndexedParallelIterator<Item = (usize, &[usize], &[W])>
+    where
+        W: Sync,
+    {
+        (0..self.node_count())
+            .into_par_iter()
+            .map(|u| (u, self.neighbors(u), self.weights(u)))
+    }
+
+    /// The graph with every edge reversed
+    #[must_use]
+    pub fn transpose(&self) -> Self
+    where
+        W: Clone,
+    {
+        Self::from_edges(
+            self.node_count(),
+            (0..self.node_count()).flat_map(|u| self.edges(u).map(move |(v, w)| (v, u, w.clone()))),
+        )
+    }
+
+    /// Unweighted adjacency lists, e.g. for [`dag_dp`](super::dag_dp)
+    #[must_use]
+    pub fn to_adjacency(&self) -> Vec<Vec<usize>> {
+        (0..self.node_count())
+            .map(|u| self.neighbors(u).to_vec())
+            .collect()
+    }
+}
+
+impl Csr {
+    /// Build an unweighted graph from adjacency lists, where `adjacency[u]`
+    /// holds the targets of the edges leaving `u`
+    #[must_use]
+    pub fn from_adjacency(adjacency: &[Vec<usize>]) -> Self {
+        let mut offsets = Vec::with_capacity(adjacency.len() + 1);
+        offsets.push(0);
+        for targets in adjacency {
+            offsets.push(offsets[offsets.len() - 1] + targets.len());
+        }
+        let targets = adjacency.concat();
+        Self {
+            weights: vec![(); targets.len()],
+            offsets,
+            targets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_edges() {
+        let graph = Csr::from_edges(4, [(2, 0, 5), (0, 1, 1), (0, 2, 4), (1, 2, 2), (2, 3, 1)]);
+        assert_eq!((graph.node_count(), graph.edge_count()), (4, 5));
+        assert_eq!(graph.neighbors(0), &[1, 2]);
+        assert_eq!(graph.weights(0), &[1, 4]);
+        assert_eq!(graph.edges(2).collect::<Vec<_>>(), vec![(0, &5), (3, &1)]);
+        assert_eq!(graph.degree(3), 0);
+        assert!(graph.neighbors(3).is_empty());
+        let reversed = graph.transpose();
+        assert_eq!(reversed.neighbors(2), &[0, 1]);
+        assert_eq!(reversed.weights(2), &[4, 2]);
+        assert_eq!(reversed.transpose(), graph);
+    }
+
+    #[test]
+    fn test_adjacency_round_trip() {
+        let adjacency = vec![vec![1, 2], vec![], vec![0, 1, 3], vec![3]];
+        let graph = Csr::from_adjacency(&adjacency);
+        assert_eq!(graph.to_adjacency(), adjacency);
+        let edges = adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(u, vs)| vs.iter().map(move |&v| (u, v, ())));
+        assert_eq!(Csr::from_edges(4, edges), graph);
+        let weighted = Csr::from_weighted_adjacency(&[vec![(1, 'a')], vec![(0, 'b'), (1, 'c')]]);
+        assert_eq!(weighted.weights(1), &['b', 'c']);
+        assert_eq!(Csr::<()>::from_edges(0, []).node_count(), 0);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_par_nodes() {
+        // A cycle over many nodes, with every degree summed in parallel
+        let n = 10_000;
+        let graph = Csr::from_edges(n, (0..n).map(|u| (u, (u + 1) % n, u)));
+        let total = graph
+            .par_nodes()
+            .map(|(u, targets, weights)| {
+                assert_eq!(targets, &[(u + 1) % n]);
+                weights.iter().sum::<usize>()
+            })
+            .sum::<usize>();
+        assert_eq!(total, n * (n - 1) / 2);
+    }
+}
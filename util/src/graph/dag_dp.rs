@@ -0,0 +1,254 @@
+//! Dynamic programming over directed acyclic graphs.
+
+use std::collections::VecDeque;
+
+/// Borrow the states of two distinct nodes, one immutably and one mutably.
+fn split_pair<T>(states: &mut [T], from: usize, to: usize) -> (&T, &mut T) {
+    assert_ne!(from, to, "Self-loops cannot be propagated");
+    if from < to {
+        let (left, right) = states.split_at_mut(to);
+        (&left[from], &mut right[0])
+    } else {
+        let (left, right) = states.split_at_mut(from);
+        (&right[0], &mut left[to])
+    }
+}
+
+/// Generalized topology dynamic programming framework for DAGs.
+///
+/// It does a topological traversal of the DAG described by `out_nodes` (the
+/// children of each node), from `start` to `goal`. Every node starts with
+/// `default_state`, except `start` which starts with `start_state`.
+///
+/// The states form a semigroup over the graph:
+/// - `update` finalizes the state of a node once all of its parents have been
+///   merged into it, e.g. to mark that paths have visited a special node.
+/// - `merge` folds the finalized state of a parent into the state of a child.
+///
+/// Both functions work in place, so the state does not need to be `Copy` and
+/// heavier states (`Vec`, big integers) only pay for the clones they need.
+/// Parents are tracked by counting the edges left into each node, so the
+/// traversal takes time linear in the size of the graph.
+///
+/// Returns the final state at the `goal` node.
+///
+/// This is fail-safe when the graph is not a DAG: traversal will just be stuck
+/// when there is a cycle and no new nodes can be added to the frontier. The
+/// algorithm will terminate, and the contribution from the cycle will just not
+/// be counted.
+pub fn dag_dp<T, FM, FU>(
+    out_nodes: &[Vec<usize>],
+    start: usize,
+    goal: usize,
+    default_state: T,
+    start_state: T,
+    merge: FM,
+    update: FU,
+) -> T
+where
+    T: Clone,
+    FM: Fn(&mut T, &T),
+    FU: Fn(&mut T, usize),
+{
+    let mut states = dag_dp_all(
+        out_nodes,
+        start,
+        goal,
+        default_state,
+        start_state,
+        merge,
+        update,
+    );
+    states.swap_remove(goal)
+}
+
+/// Same as [`dag_dp`], but returns the states of all nodes, e.g. to walk back
+/// from the goal through the parents that contributed to it.
+///
+/// The traversal still stops at `goal`, so only the states of `goal` and of
+/// the nodes that have a path to it are guaranteed to be final.
+pub fn dag_dp_all<T, FM, FU>(
+    out_nodes: &[Vec<usize>],
+    start: usize,
+    goal: usize,
+    default_state: T,
+    start_state: T,
+    merge: FM,
+    update: FU,
+) -> Vec<T>
+where
+    T: Clone,
+    FM: Fn(&mut T, &T),
+    FU: Fn(&mut T, usize),
+{
+    // Edges left into each node, with a node ready once all of its parents
+    // have been merged into it
+    let mut in_degree = vec![0usize; out_nodes.len()];
+    for &to in out_nodes.iter().flatten() {
+        in_degree[to] += 1;
+    }
+    let mut states = vec![default_state; out_nodes.len()];
+    states[start] = start_state;
+    let mut ready = (0..out_nodes.len())
+        .filter(|&i| in_degree[i] == 0)
+        .collect::<VecDeque<_>>();
+    while let Some(node) = ready.pop_front() {
+        // Update and finalize the state of the node
+        update(&mut states[node], node);
+        // Early exit if we have reached the goal
+        if node == goal {
+            break;
+        }
+        // Propagate the state to outgoing nodes. A node with a self-loop is
+        // never ready, so targets never alias the node.
+        for &to in &out_nodes[node] {
+            let (state, target) = split_pair(&mut states, node, to);
+            merge(target, state);
+            in_degree[to] -= 1;
+            if in_degree[to] == 0 {
+                ready.push_back(to);
+            }
+        }
+    }
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::{generate::dag_edges, rng::Rng};
+
+    /// The traversal [`dag_dp_all`] used before counting edges, which keeps
+    /// the parents left of each node in a set and rescans every node for the
+    /// next frontier
+    fn frontier_dag_dp_all(out_nodes: &[Vec<usize>], start: usize, goal: usize) -> Vec<u64> {
+        let mut in_nodes = vec![BTreeSet::new(); out_nodes.len()];
+        for (from, outs) in out_nodes.iter().enumerate() {
+            for &to in outs {
+                in_nodes[to].insert(from);
+            }
+        }
+        let mut states = vec![0u64; out_nodes.len()];
+        states[start] = 1;
+        let mut frontier = (0..out_nodes.len())
+            .filter(|&i| in_nodes[i].is_empty())
+            .collect::<Vec<_>>();
+        let mut visited = BTreeSet::<usize>::new();
+        while !frontier.is_empty() {
+            visited.extend(&frontier);
+            if visited.contains(&goal) {
+                break;
+            }
+            for &from in &frontier {
+                for &to in &out_nodes[from] {
+                    states[to] = states[to].wrapping_add(states[from]);
+                    in_nodes[to].remove(&from);
+                }
+            }
+            frontier = (0..out_nodes.len())
+                .filter(|&i| in_nodes[i].is_empty() && !visited.contains(&i))
+                .collect();
+        }
+        states
+    }
+
+    /// A random DAG of `nodes` nodes and `edges` edges, as children of each
+    /// node
+    fn random_dag(rng: &mut Rng, nodes: usize, edges: usize) -> Vec<Vec<usize>> {
+        let mut out_nodes = vec![Vec::new(); nodes];
+        for (from, to) in dag_edges(rng, nodes, edges) {
+            out_nodes[from].push(to);
+        }
+        out_nodes
+    }
+
+    /// Paths from `start` to every node, wrapping on overflow
+    fn count_paths(out_nodes: &[Vec<usize>], start: usize, goal: usize) -> Vec<u64> {
+        dag_dp_all(
+            out_nodes,
+            start,
+            goal,
+            0u64,
+            1,
+            |a, b| *a = a.wrapping_add(*b),
+            |_, _| {},
+        )
+    }
+
+    /// A small diamond-shaped DAG with a tail:
+    /// ```raw
+    ///   0 -> 1 -> 3 -> 4
+    ///   0 -> 2 -> 3
+    ///   2 -> 4
+    /// ```
+    fn diamond() -> Vec<Vec<usize>> {
+        vec![vec![1, 2], vec![3], vec![3, 4], vec![4], vec![]]
+    }
+
+    #[test]
+    fn test_dag_dp_path_count() {
+        let graph = diamond();
+        let count = dag_dp(&graph, 0, 4, 0u64, 1, |a, b| *a += b, |_, _| {});
+        assert_eq!(count, 3);
+        let count = dag_dp(&graph, 1, 4, 0u64, 1, |a, b| *a += b, |_, _| {});
+        assert_eq!(count, 1);
+        // Unreachable goal keeps the default state
+        let count = dag_dp(&graph, 4, 0, 0u64, 1, |a, b| *a += b, |_, _| {});
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_dag_dp_non_copy_state() {
+        // Track the counts of paths by whether they have visited node 3
+        let graph = diamond();
+        let counts = dag_dp(
+            &graph,
+            0,
+            4,
+            vec![0u64; 2],
+            vec![1, 0],
+            |a, b| a.iter_mut().zip(b).for_each(|(x, y)| *x += y),
+            |state, node| {
+                if node == 3 {
+                    *state = vec![0, state.iter().sum()];
+                }
+            },
+        );
+        assert_eq!(counts, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_dag_dp_all() {
+        let graph = diamond();
+        let counts = dag_dp_all(&graph, 0, 4, 0u64, 1, |a, b| *a += b, |_, _| {});
+        assert_eq!(counts, vec![1, 1, 1, 2, 3]);
+        // Node 4 is past the goal, so it only got the paths through node 2
+        let counts = dag_dp_all(&graph, 0, 3, 0u64, 1, |a, b| *a += b, |_, _| {});
+        assert_eq!(counts[..4], [1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_dag_dp_cycle_is_ignored() {
+        // 0 -> 1 -> 2 -> 1 forms a cycle, 0 -> 3 does not
+        let graph = vec![vec![1, 3], vec![2], vec![1, 3], vec![]];
+        let count = dag_dp(&graph, 0, 3, 0u64, 1, |a, b| *a += b, |_, _| {});
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_dag_dp_matches_frontier() {
+        let mut rng = Rng::new(11);
+        for _ in 0..50 {
+            let nodes = 2 + rng.below(40);
+            let edges = rng.below(nodes * 3);
+            let graph = random_dag(&mut rng, nodes, edges);
+            let (start, goal) = (rng.below(nodes), rng.below(nodes));
+            assert_eq!(
+                count_paths(&graph, start, goal)[goal],
+                frontier_dag_dp_all(&graph, start, goal)[goal]
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Strongly connected components, and condensing them into a DAG.
+
+use super::toposort::shortest_cycle;
+
+/// The strongly connected components of the graph described by `out_nodes`
+/// (the children of each node), each with its nodes in ascending order.
+///
+/// Components are listed in topological order, so that every edge between
+/// two components goes from an earlier one to a later one. Runs Tarjan's
+/// algorithm in `O(V + E)` with an explicit stack.
+#[must_use]
+pub fn strongly_connected_components(out_nodes: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let n = out_nodes.len();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut counter = 0;
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // Simulated call stack of nodes and the position of their next child
+        let mut calls = vec![(root, 0)];
+        index[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some((node, child)) = calls.last_mut() {
+            let node = *node;
+            if let Some(&to) = out_nodes[node].get(*child) {
+                *child += 1;
+                if index[to] == UNVISITED {
+                    index[to] = counter;
+                    low[to] = counter;
+                    counter += 1;
+                    stack.push(to);
+                    on_stack[to] = true;
+                    calls.push((to, 0));
+                } else if on_stack[to] {
+                    low[node] = low[node].min(index[to]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            // A node that cannot reach further back roots its component
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    // Tarjan's algorithm finishes components from the sinks upwards
+    components.reverse();
+    components
+}
+
+/// A graph with each strongly connected component merged into a single node,
+/// which always leaves a DAG.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condensation {
+    /// Component of each original node
+    pub component: Vec<usize>,
+    /// Original nodes of each component, in topological order
+    pub members: Vec<Vec<usize>>,
+    /// Children of each component, without duplicates or self-loops
+    pub out_nodes: Vec<Vec<usize>>,
+    /// Whether each component contains a cycle, i.e. has several nodes or a
+    /// self-loop
+    pub cyclic: Vec<bool>,
+}
+
+impl Condensation {
+    /// A shortest cycle among the nodes of `component`, or `None` if it is
+    /// not cyclic. `out_nodes` must be the graph this was condensed from.
+    #[must_use]
+    pub fn cycle(&self, out_nodes: &[Vec<usize>], component: usize) -> Option<Vec<usize>> {
+        if !self.cyclic[component] {
+            return None;
+        }
+        let within = self
+            .component
+            .iter()
+            .map(|&c| c == component)
+            .collect::<Vec<_>>();
+        shortest_cycle(out_nodes, &within)
+    }
+}
+
+/// Condense the strongly connected components of the graph described by
+/// `out_nodes` into single nodes, numbered in topological order.
+#[must_use]
+pub fn condense(out_nodes: &[Vec<usize>]) -> Condensation {
+    let members = strongly_connected_components(out_nodes);
+    let mut component = vec![0; out_nodes.len()];
+    for (c, nodes) in members.iter().enumerate() {
+        for &node in nodes {
+            component[node] = c;
+        }
+    }
+    let mut cyclic = members
+        .iter()
+        .map(|nodes| nodes.len() > 1)
+        .collect::<Vec<_>>();
+    let mut condensed = vec![Vec::new(); members.len()];
+    for (from, children) in out_nodes.iter().enumerate() {
+        for &to in children {
+            let (from, to) = (component[from], component[to]);
+            if from == to {
+                cyclic[from] = true;
+            } else {
+                condensed[from].push(to);
+            }
+        }
+    }
+    for children in &mut condensed {
+        children.sort_unstable();
+        children.dedup();
+    }
+    Condensation {
+        component,
+        members,
+        out_nodes: condensed,
+        cyclic,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::graph::toposort;
+
+    #[test]
+    fn test_components() {
+        // Two cycles {0, 1, 2} and {3, 4} joined by an edge, a self-loop on 5
+        // and a lone node 6 feeding into the first cycle
+        let graph = vec![
+            vec![1],
+            vec![2],
+            vec![0, 3],
+            vec![4],
+            vec![3, 5],
+            vec![5],
+            vec![0],
+        ];
+        assert_eq!(
+            strongly_connected_components(&graph),
+            vec![vec![6], vec![0, 1, 2], vec![3, 4], vec![5]]
+        );
+        assert!(strongly_connected_components(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_condense() -> Result<()> {
+        let graph = vec![
+            vec![1],
+            vec![2],
+            vec![0, 3],
+            vec![4],
+            vec![3, 5],
+            vec![5],
+            vec![0],
+        ];
+        let condensed = condense(&graph);
+        assert_eq!(condensed.component, vec![1, 1, 1, 2, 2, 3, 0]);
+        assert_eq!(condensed.out_nodes, vec![vec![1], vec![2], vec![3], vec![]]);
+        assert_eq!(condensed.cyclic, vec![false, true, true, true]);
+        assert_eq!(toposort(&condensed.out_nodes)?, vec![0, 1, 2, 3]);
+        assert_eq!(condensed.cycle(&graph, 0), None);
+        assert_eq!(condensed.cycle(&graph, 1), Some(vec![0, 1, 2]));
+        assert_eq!(condensed.cycle(&graph, 3), Some(vec![5]));
+        // A long path stays within the explicit stack
+        let path = (0..100_000)
+            .map(|i| vec![i + 1])
+            .chain([vec![]])
+            .collect::<Vec<_>>();
+        assert_eq!(condense(&path).members.len(), 100_001);
+        Ok(())
+    }
+}
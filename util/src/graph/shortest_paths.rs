@@ -0,0 +1,152 @@
+//! Shortest paths over explicit graphs, allowing negative edge weights.
+//!
+//! Missing edges and unreachable nodes are `None`, so any ordered numeric type
+//! can be used as the weight without reserving an "infinity" value.
+
+use std::ops::Add;
+
+use ndarray::{Axis, prelude::*};
+
+use crate::parallel::prelude::*;
+
+/// Distances between all pairs of nodes, given the matrix of edge `weights`
+/// where `weights[(i, j)]` is the weight of the edge from `i` to `j`.
+///
+/// Runs the Floyd–Warshall algorithm in `O(n^3)`, updating rows in parallel.
+/// The distance from a node to itself is zero unless a cheaper cycle exists.
+///
+/// Returns `None` if the graph contains a negative cycle.
+///
+/// # Panics
+/// Panics if `weights` is not square.
+#[must_use]
+pub fn floyd_warshall<C>(weights: &Array2<Option<C>>) -> Option<Array2<Option<C>>>
+where
+    C: Copy + Ord + Default + Add<Output = C> + Send + Sync,
+{
+    let n = weights.nrows();
+    assert_eq!(n, weights.ncols(), "Weight matrix must be square");
+    let zero = C::default();
+    let mut dist = weights.clone();
+    for i in 0..n {
+        dist[(i, i)] = Some(dist[(i, i)].map_or(zero, |d| d.min(zero)));
+    }
+    for k in 0..n {
+        // Snapshot row `k`, which no longer changes without negative cycles
+        let through = dist.row(k).to_owned();
+        dist.axis_iter_mut(Axis(0))
+            .maybe_into_par_iter()
+            .for_each(|mut row| {
+                let Some(to_k) = row[k] else { return };
+                for (d, &from_k) in row.iter_mut().zip(&through) {
+                    if let Some(from_k) = from_k {
+                        let candidate = to_k + from_k;
+                        if d.is_none_or(|d| candidate < d) {
+                            *d = Some(candidate);
+                        }
+                    }
+                }
+            });
+    }
+    // A negative cycle through a node shows up as a negative distance to itself
+    (0..n)
+        .all(|i| dist[(i, i)].is_none_or(|d| d >= zero))
+        .then_some(dist)
+}
+
+/// Distances from `source` to every node of a graph with `n` nodes and
+/// directed, weighted `edges` given as `(from, to, weight)`.
+///
+/// Runs the Bellman–Ford algorithm in `O(n * edges)`, stopping early once
+/// distances settle.
+///
+/// Returns `None` if a negative cycle is reachable from `source`, as
+/// distances are then unbounded.
+#[must_use]
+pub fn bellman_ford<C>(
+    n: usize,
+    edges: &[(usize, usize, C)],
+    source: usize,
+) -> Option<Vec<Option<C>>>
+where
+    C: Copy + Ord + Default + Add<Output = C>,
+{
+    let mut dist = vec![None; n];
+    dist[source] = Some(C::default());
+    // Without negative cycles, every shortest path has at most n - 1 edges, so
+    // anything still improving in round n is on or behind a negative cycle
+    for _ in 0..n {
+        let mut changed = false;
+        for &(from, to, weight) in edges {
+            let Some(d) = dist[from] else { continue };
+            let candidate = d + weight;
+            if dist[to].is_none_or(|current| candidate < current) {
+                dist[to] = Some(candidate);
+                changed = true;
+            }
+        }
+        if !changed {
+            return Some(dist);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small graph with a negative edge but no negative cycle
+    const EDGES: [(usize, usize, i64); 6] = [
+        (0, 1, 4),
+        (0, 2, 1),
+        (2, 1, -2),
+        (1, 3, 3),
+        (2, 3, 5),
+        (3, 4, -1),
+    ];
+
+    fn matrix(n: usize, edges: &[(usize, usize, i64)]) -> Array2<Option<i64>> {
+        let mut weights = Array2::from_elem((n, n), None);
+        for &(from, to, weight) in edges {
+            weights[(from, to)] = Some(weight);
+        }
+        weights
+    }
+
+    #[test]
+    fn test_floyd_warshall() {
+        let dist =
+            floyd_warshall(&matrix(5, &EDGES)).unwrap_or_else(|| panic!("No negative cycle"));
+        assert_eq!(
+            dist.row(0).to_vec(),
+            vec![Some(0), Some(-1), Some(1), Some(2), Some(1)]
+        );
+        assert_eq!(dist[(4, 0)], None);
+        assert_eq!(dist[(2, 4)], Some(0));
+        // Agrees with Bellman-Ford from every source
+        for source in 0..5 {
+            let expected =
+                bellman_ford(5, &EDGES, source).unwrap_or_else(|| panic!("No negative cycle"));
+            assert_eq!(dist.row(source).to_vec(), expected);
+        }
+        let cycle = [(0, 1, 1), (1, 2, -3), (2, 0, 1)];
+        assert_eq!(floyd_warshall(&matrix(3, &cycle)), None);
+    }
+
+    #[test]
+    fn test_bellman_ford() {
+        assert_eq!(
+            bellman_ford(5, &EDGES, 0),
+            Some(vec![Some(0), Some(-1), Some(1), Some(2), Some(1)])
+        );
+        // Negative cycles only matter when reachable
+        let edges = [(0, 1, 1), (2, 3, -1), (3, 2, -1)];
+        assert_eq!(
+            bellman_ford(4, &edges, 0),
+            Some(vec![Some(0), Some(1), None, None])
+        );
+        assert_eq!(bellman_ford(4, &edges, 2), None);
+        assert_eq!(bellman_ford::<u32>(1, &[], 0), Some(vec![Some(0)]));
+    }
+}
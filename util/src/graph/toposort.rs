@@ -0,0 +1,125 @@
+//! Topological ordering with cycle diagnostics.
+
+use std::{collections::VecDeque, fmt::Display};
+
+use crate::{Error, Result};
+
+/// A topological order of the graph described by `out_nodes` (the children of
+/// each node), so that every edge goes from an earlier node to a later one.
+///
+/// Ties are broken by node index, so the order is deterministic. Fails with
+/// an error listing the nodes of a shortest cycle if the graph is not a DAG.
+pub fn toposort(out_nodes: &[Vec<usize>]) -> Result<Vec<usize>> {
+    toposort_with_names(out_nodes, |node| node)
+}
+
+/// Like [`toposort`], but naming the nodes of a cycle with `name` in the
+/// error message, e.g. to show the labels from the puzzle input.
+pub fn toposort_with_names<D: Display>(
+    out_nodes: &[Vec<usize>],
+    name: impl Fn(usize) -> D,
+) -> Result<Vec<usize>> {
+    let mut in_degree = vec![0usize; out_nodes.len()];
+    for &to in out_nodes.iter().flatten() {
+        in_degree[to] += 1;
+    }
+    // Kahn's algorithm: repeatedly emit nodes without remaining parents
+    let mut queue = (0..out_nodes.len())
+        .filter(|&node| in_degree[node] == 0)
+        .collect::<VecDeque<_>>();
+    let mut order = Vec::with_capacity(out_nodes.len());
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for &to in &out_nodes[node] {
+            in_degree[to] -= 1;
+            if in_degree[to] == 0 {
+                queue.push_back(to);
+            }
+        }
+    }
+    if order.len() == out_nodes.len() {
+        return Ok(order);
+    }
+    // Nodes left with parents are on a cycle or downstream of one
+    let stuck = in_degree.iter().map(|&d| d > 0).collect::<Vec<_>>();
+    let Some(cycle) = shortest_cycle(out_nodes, &stuck) else {
+        unreachable!("Kahn's algorithm only gets stuck on cycles")
+    };
+    let path = cycle
+        .iter()
+        .chain(cycle.first())
+        .map(|&node| name(node).to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    Err(Error::Cycle { path })
+}
+
+/// A shortest cycle among the nodes flagged in `within`, starting from its
+/// smallest possible first node, found by a BFS from every candidate node
+pub(super) fn shortest_cycle(out_nodes: &[Vec<usize>], within: &[bool]) -> Option<Vec<usize>> {
+    let mut best: Option<Vec<usize>> = None;
+    let mut parent = vec![None; out_nodes.len()];
+    for start in (0..out_nodes.len()).filter(|&node| within[node]) {
+        parent.fill(None);
+        let mut queue = VecDeque::from([start]);
+        let mut closing = None;
+        'bfs: while let Some(node) = queue.pop_front() {
+            for &to in &out_nodes[node] {
+                if to == start {
+                    closing = Some(node);
+                    break 'bfs;
+                }
+                if within[to] && parent[to].is_none() {
+                    parent[to] = Some(node);
+                    queue.push_back(to);
+                }
+            }
+        }
+        let Some(mut node) = closing else { continue };
+        let mut cycle = vec![node];
+        while let Some(prev) = parent[node] {
+            cycle.push(prev);
+            node = prev;
+        }
+        if node != start {
+            cycle.push(start);
+        }
+        cycle.reverse();
+        if best.as_ref().is_none_or(|best| cycle.len() < best.len()) {
+            best = Some(cycle);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order() -> Result<()> {
+        let graph = vec![vec![1, 2], vec![3], vec![3, 4], vec![4], vec![]];
+        let order = toposort(&graph)?;
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+        let graph = vec![vec![], vec![0], vec![1, 0]];
+        assert_eq!(toposort(&graph)?, vec![2, 1, 0]);
+        assert!(toposort(&[])?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle_errors() {
+        // A long cycle 0 -> 1 -> 2 -> 3 -> 0 and a short one 2 -> 4 -> 2,
+        // with node 5 hanging off the cycles
+        let graph = vec![vec![1], vec![2], vec![3, 4], vec![0], vec![2, 5], vec![]];
+        let Err(error) = toposort(&graph) else {
+            panic!("Cyclic graph should not be sorted");
+        };
+        assert_eq!(error.to_string(), "Graph has a cycle: 2 -> 4 -> 2");
+        let names = ["you", "svr", "dac", "fft", "out", "end"];
+        let Err(error) = toposort_with_names(&[vec![0, 1], vec![]], |node| names[node]) else {
+            panic!("Self-loops are cycles");
+        };
+        assert_eq!(error.to_string(), "Graph has a cycle: you -> you");
+    }
+}
@@ -0,0 +1,22 @@
+//! Grid geometry and helpers for `Array2`-based puzzles
+
+mod automaton;
+mod path;
+mod point;
+mod prefix_sum;
+mod regions;
+mod stencil;
+mod transform;
+mod walker;
+
+pub use automaton::{Automaton, population};
+pub use path::{Moves, shortest_path};
+pub use point::{Direction, Point, UPoint, neighbors4, neighbors8};
+pub use prefix_sum::PrefixSum2D;
+pub use regions::{Components, flood_fill, label_components};
+pub use stencil::{convolve_count, kernel4, kernel8};
+pub use transform::{
+    bounding_box, crop, flip_horizontal, flip_vertical, rotate90, rotate180, rotate270, symmetries,
+    tile, transpose,
+};
+pub use walker::{Bounds, Turn, Walker};
@@ -0,0 +1,218 @@
+//! Life-like cellular automata on 0/1 grids, where each cell looks at a
+//! weighted count of its neighbors to decide whether it is occupied next.
+
+use ndarray::{Zip, prelude::*};
+
+use super::{convolve_count, kernel8};
+use crate::parallel::prelude::*;
+
+/// Number of occupied cells in a 0/1 grid
+#[must_use]
+pub fn population(grid: &Array2<u8>) -> usize {
+    grid.maybe_par_iter().filter(|&&v| v > 0).count()
+}
+
+/// A rule counting neighbors with a kernel, as in [`convolve_count`], and
+/// listing the counts for which an empty cell becomes occupied or an
+/// occupied cell stays occupied. Every other cell ends up empty.
+///
+/// Kernel weights are `u8` and must sum to at most 255, so that every count
+/// fits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Automaton {
+    kernel: Array2<u8>,
+    /// Whether an empty cell with each neighbor count becomes occupied
+    born: Vec<bool>,
+    /// Whether an occupied cell with each neighbor count stays occupied
+    survive: Vec<bool>,
+}
+
+impl Automaton {
+    /// A rule counting neighbors with `kernel`, where nothing is born or
+    /// survives until [`Self::born`] or [`Self::survive`] says so
+    ///
+    /// # Panics
+    /// Panics if either dimension of the kernel is even, or if its weights sum
+    /// to more than 255.
+    #[must_use]
+    pub fn new(kernel: Array2<u8>) -> Self {
+        let (rows, cols) = kernel.dim();
+        assert!(
+            rows % 2 == 1 && cols % 2 == 1,
+            "Kernel dimensions must be odd"
+        );
+        let max = kernel.iter().map(|&w| usize::from(w)).sum::<usize>();
+        assert!(
+            u8::try_from(max).is_ok(),
+            "Kernel weights must sum to at most 255"
+        );
+        Self {
+            kernel,
+            born: vec![false; max + 1],
+            survive: vec![false; max + 1],
+        }
+    }
+
+    /// Conway's Game of Life: born with 3 of the 8 neighbors, surviving with 2
+    /// or 3
+    #[must_use]
+    pub fn life() -> Self {
+        Self::new(kernel8()).born([3]).survive([2, 3])
+    }
+
+    /// Also make empty cells with any of these counts occupied
+    #[must_use]
+    pub fn born(mut self, counts: impl IntoIterator<Item = u8>) -> Self {
+        for count in counts {
+            if let Some(born) = self.born.get_mut(usize::from(count)) {
+                *born = true;
+            }
+        }
+        self
+    }
+
+    /// Also keep occupied cells with any of these counts
+    #[must_use]
+    pub fn survive(mut self, counts: impl IntoIterator<Item = u8>) -> Self {
+        for count in counts {
+            if let Some(survive) = self.survive.get_mut(usize::from(count)) {
+                *survive = true;
+            }
+        }
+        self
+    }
+
+    /// Whether a cell is occupied next, given whether it is now and its count
+    fn next(&self, occupied: bool, count: u8) -> bool {
+        let table = if occupied { &self.survive } else { &self.born };
+        table.get(usize::from(count)).is_some_and(|&next| next)
+    }
+
+    /// One step, updating every cell at once from the counts of `grid`
+    #[must_use]
+    pub fn step(&self, grid: &Array2<u8>) -> Array2<u8> {
+        let count = convolve_count(grid, &self.kernel);
+        let mut next = grid.clone();
+        next.axis_iter_mut(Axis(0))
+            .maybe_into_par_iter()
+            .zip(count.axis_iter(Axis(0)))
+            .for_each(|(mut row, counts)| {
+                for (v, &count) in row.iter_mut().zip(counts) {
+                    *v = u8::from(self.next(*v > 0, count));
+                }
+            });
+        next
+    }
+
+    /// Step until the grid stops changing, returning the final grid and the
+    /// number of steps that changed something.
+    ///
+    /// This never returns if the grid keeps oscillating or growing.
+    #[must_use]
+    pub fn settle(&self, grid: &Array2<u8>) -> (Array2<u8>, usize) {
+        let mut grid = grid.clone();
+        let mut steps = 0;
+        loop {
+            let next = self.step(&grid);
+            if next == grid {
+                return (grid, steps);
+            }
+            grid = next;
+            steps += 1;
+        }
+    }
+
+    /// The grid [`Self::settle`] ends at, for rules under which cells only
+    /// ever disappear.
+    ///
+    /// Rather than recomputing every count each step, the counts are computed
+    /// once and updated as cells go: removing a cell only lowers the counts of
+    /// the cells its kernel reaches, so those are the only ones that can go
+    /// next. Each cell is queued at most once, when it is removed, for
+    /// `O(cells + removals * kernel)` work overall. The order of removals
+    /// doesn't matter, as removing a cell never makes another one stay.
+    ///
+    /// # Panics
+    /// Panics if the rule can make cells appear, or if a cell that stays with
+    /// some count would go with a higher one.
+    #[must_use]
+    pub fn erode(&self, grid: &Array2<u8>) -> Array2<u8> {
+        assert!(
+            self.born.iter().all(|&born| !born),
+            "Eroding rules cannot make cells appear"
+        );
+        assert!(
+            self.survive.windows(2).all(|w| !w[0] || w[1]),
+            "Eroding rules must keep cells with more neighbors"
+        );
+        let mut grid = grid.mapv(|v| u8::from(v > 0));
+        let mut count = convolve_count(&grid, &self.kernel);
+        let (rows, cols) = grid.dim();
+        let (k_rows, k_cols) = self.kernel.dim();
+        let mut queue =
+            Zip::indexed(&count)
+                .and(&grid)
+                .fold(Vec::new(), |mut queue, pos, &cnt, &v| {
+                    if v > 0 && !self.next(true, cnt) {
+                        queue.push(pos);
+                    }
+                    queue
+                });
+        for &pos in &queue {
+            grid[pos] = 0;
+        }
+        while let Some((row, col)) = queue.pop() {
+            // The cell counts towards every cell the kernel reaches it from
+            for ((dr, dc), &weight) in self.kernel.indexed_iter() {
+                let (Some(r), Some(c)) = (
+                    (row + k_rows / 2).checked_sub(dr),
+                    (col + k_cols / 2).checked_sub(dc),
+                ) else {
+                    continue;
+                };
+                if weight == 0 || r >= rows || c >= cols || grid[(r, c)] == 0 {
+                    continue;
+                }
+                count[(r, c)] -= weight;
+                // Only dropping out of the surviving counts removes it, so
+                // each cell is queued exactly once
+                if !self.next(true, count[(r, c)]) {
+                    grid[(r, c)] = 0;
+                    queue.push((r, c));
+                }
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, rng::Rng};
+
+    #[test]
+    fn test_life() {
+        let life = Automaton::life();
+        let blinker = array![[0u8, 0, 0], [1, 1, 1], [0, 0, 0]];
+        assert_eq!(life.step(&blinker), blinker.t());
+        assert_eq!(life.step(&life.step(&blinker)), blinker);
+        let block = array![[0u8, 0, 0, 0], [0, 1, 1, 0], [0, 1, 1, 0], [0, 0, 0, 0]];
+        assert_eq!(life.settle(&block), (block.clone(), 0));
+        // Three cells in an L grow into a block
+        let corner = array![[1u8, 1], [1, 0]];
+        assert_eq!(life.settle(&corner), (array![[1, 1], [1, 1]], 1));
+    }
+
+    #[test]
+    fn test_erode() {
+        let kernel = array![[0, 1, 0], [1, 0, 2], [0, 1, 0]];
+        let rule = Automaton::new(kernel).survive(2..=5);
+        for seed in 0..100 {
+            let grid = generate::grid(&mut Rng::new(seed), 1..=12, 1..=12, &[0, 1, 1]);
+            let (settled, _) = rule.settle(&grid);
+            assert_eq!(rule.erode(&grid), settled, "{grid:?}");
+            assert!(population(&settled) <= population(&grid));
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Shortest paths between cells of `Array2` grids, without turning the grid
+//! into a graph first.
+//!
+//! This is [`dijkstra`] over the cells, stepping to their orthogonal or to
+//! all their surrounding neighbors, with the cost of each step given by the
+//! values of the two cells.
+
+use std::ops::Add;
+
+use ndarray::Array2;
+
+use super::{UPoint, neighbors4, neighbors8};
+use crate::search::dijkstra;
+
+/// The neighbors a step of [`shortest_path`] can move to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Moves {
+    /// The 4 orthogonal neighbors
+    Orthogonal,
+    /// The 8 surrounding neighbors, diagonals included
+    All,
+}
+
+impl Moves {
+    /// The cells one step away from `pos` within a grid of `shape`
+    fn from(self, pos: UPoint, shape: (usize, usize)) -> Vec<UPoint> {
+        match self {
+            Self::Orthogonal => neighbors4(pos, shape).collect(),
+            Self::All => neighbors8(pos, shape).collect(),
+        }
+    }
+}
+
+/// The cheapest path from `start` to `goal`, as its cost and its cells, both
+/// ends included, or `None` if `goal` cannot be reached.
+///
+/// `cost` gives the non-negative cost of stepping from a cell onto one of
+/// its neighbors given their values, or `None` when the step is not allowed,
+/// e.g. onto a wall.
+pub fn shortest_path<T, C>(
+    grid: &Array2<T>,
+    start: impl Into<UPoint>,
+    goal: impl Into<UPoint>,
+    mut cost: impl FnMut(&T, &T) -> Option<C>,
+    moves: Moves,
+) -> Option<(C, Vec<UPoint>)>
+where
+    C: Copy + Ord + Default + Add<Output = C>,
+{
+    let shape = grid.dim();
+    let goal = goal.into();
+    let neighbors = |&pos: &UPoint| {
+        (moves.from(pos, shape).into_iter())
+            .filter_map(|next| Some((next, cost(&grid[pos.as_index()], &grid[next.as_index()])?)))
+            .collect::<Vec<_>>()
+    };
+    let result = dijkstra(start.into(), neighbors, |&pos| pos == goal)?;
+    Some((result.cost, result.path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_shortest_path() {
+        let grid = array![[1, 1, 1, 1], [0, 0, 9, 1], [1, 1, 1, 1]];
+        // Walls are 0, any other cell costs its value to step onto
+        let cost = |_: &u32, &to: &u32| (to > 0).then_some(to);
+        let (total, path) = shortest_path(&grid, (0, 0), (2, 0), cost, Moves::Orthogonal)
+            .unwrap_or_else(|| panic!("Goal should be reachable"));
+        // Around the expensive cell rather than through it
+        assert_eq!(total, 8);
+        assert_eq!(path.len(), 9);
+        assert_eq!(path.first(), Some(&UPoint::new(0, 0)));
+        assert_eq!(path.last(), Some(&UPoint::new(2, 0)));
+        let (total, path) = shortest_path(&grid, (0, 0), (2, 0), cost, Moves::All)
+            .unwrap_or_else(|| panic!("Goal should be reachable"));
+        // Cutting the corners around the expensive cell
+        assert_eq!((total, path.len()), (6, 7));
+        let walled = array![[1, 0, 1]];
+        assert_eq!(
+            shortest_path(&walled, (0, 0), (0, 2), cost, Moves::All),
+            None
+        );
+        assert_eq!(
+            shortest_path(&walled, (0, 0), (0, 0), cost, Moves::All),
+            Some((0, vec![UPoint::new(0, 0)]))
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Points, directions, and neighborhoods on 2D grids.
+//!
+//! Coordinates are `(row, col)` to match the indexing of `Array2`, with rows
+//! growing downwards.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A point on a 2D grid, stored as `(row, col)`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point<T = i64> {
+    pub row: T,
+    pub col: T,
+}
+
+/// A point that is directly usable as an `Array2` index.
+pub type UPoint = Point<usize>;
+
+/// Offsets of the 4 orthogonal neighbors, clockwise from up
+const OFFSETS_4: [(i64, i64); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+/// Offsets of the 8 surrounding neighbors, clockwise from up
+const OFFSETS_8: [(i64, i64); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+impl<T> Point<T> {
+    pub const fn new(row: T, col: T) -> Self {
+        Self { row, col }
+    }
+}
+
+impl Point<i64> {
+    /// Manhattan distance from the origin
+    #[must_use]
+    pub const fn manhattan(self) -> u64 {
+        self.row.unsigned_abs() + self.col.unsigned_abs()
+    }
+
+    /// Convert into an index of a grid with the given `(rows, cols)` shape, if
+    /// it falls within the bounds.
+    #[must_use]
+    pub fn to_index(self, shape: (usize, usize)) -> Option<UPoint> {
+        let row = usize::try_from(self.row).ok()?;
+        let col = usize::try_from(self.col).ok()?;
+        (row < shape.0 && col < shape.1).then_some(Point { row, col })
+    }
+}
+
+impl UPoint {
+    /// The `(row, col)` tuple used to index an `Array2`
+    #[must_use]
+    pub const fn as_index(self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    /// Move by `delta`, returning `None` when leaving a grid with the given
+    /// `(rows, cols)` shape.
+    #[must_use]
+    pub fn offset(self, delta: Point<i64>, shape: (usize, usize)) -> Option<Self> {
+        let row = self
+            .row
+            .checked_add_signed(isize::try_from(delta.row).ok()?)?;
+        let col = self
+            .col
+            .checked_add_signed(isize::try_from(delta.col).ok()?)?;
+        (row < shape.0 && col < shape.1).then_some(Self { row, col })
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((row, col): (T, T)) -> Self {
+        Self { row, col }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(point: Point<T>) -> Self {
+        (point.row, point.col)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.row * rhs, self.col * rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.row, -self.col)
+    }
+}
+
+/// One of the 4 orthogonal directions on a grid.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise from up
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// Unit step in this direction as a `(row, col)` delta
+    #[must_use]
+    pub const fn offset(self) -> Point<i64> {
+        let (row, col) = OFFSETS_4[self as usize];
+        Point { row, col }
+    }
+
+    /// Rotate 90 degrees counter-clockwise
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    /// Rotate 90 degrees clockwise
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// Rotate 180 degrees
+    #[must_use]
+    pub const fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Take one step from `pos` in this direction, returning `None` when
+    /// leaving a grid with the given `(rows, cols)` shape.
+    #[must_use]
+    pub fn step(self, pos: UPoint, shape: (usize, usize)) -> Option<UPoint> {
+        pos.offset(self.offset(), shape)
+    }
+}
+
+/// Iterate over the in-bounds orthogonal neighbors of `pos` on a grid with the
+/// given `(rows, cols)` shape, clockwise from up.
+pub fn neighbors4(pos: impl Into<UPoint>, shape: (usize, usize)) -> impl Iterator<Item = UPoint> {
+    let pos = pos.into();
+    OFFSETS_4
+        .into_iter()
+        .filter_map(move |delta| pos.offset(delta.into(), shape))
+}
+
+/// Iterate over the in-bounds orthogonal and diagonal neighbors of `pos` on a
+/// grid with the given `(rows, cols)` shape, clockwise from up.
+pub fn neighbors8(pos: impl Into<UPoint>, shape: (usize, usize)) -> impl Iterator<Item = UPoint> {
+    let pos = pos.into();
+    OFFSETS_8
+        .into_iter()
+        .filter_map(move |delta| pos.offset(delta.into(), shape))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point::new(1, -2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a + b, Point::new(-2, 3));
+        assert_eq!(a - b, Point::new(4, -7));
+        assert_eq!(a * 3, Point::new(3, -6));
+        assert_eq!(-a, Point::new(-1, 2));
+        assert_eq!((a - b).manhattan(), 11);
+        assert_eq!(<(i64, i64)>::from(a), (1, -2));
+    }
+
+    #[test]
+    fn test_point_bounds() {
+        assert_eq!(Point::new(1, 2).to_index((2, 3)), Some(Point::new(1, 2)));
+        assert_eq!(Point::new(2, 2).to_index((2, 3)), None);
+        assert_eq!(Point::new(-1, 0).to_index((2, 3)), None);
+
+        let origin = UPoint::new(0, 0);
+        assert_eq!(origin.offset(Point::new(0, -1), (2, 2)), None);
+        assert_eq!(
+            origin.offset(Point::new(1, 1), (2, 2)),
+            Some(UPoint::new(1, 1))
+        );
+        assert_eq!(origin.offset(Point::new(2, 0), (2, 2)), None);
+        assert_eq!(UPoint::new(1, 0).as_index(), (1, 0));
+    }
+
+    #[test]
+    fn test_direction() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+            assert_eq!(dir.offset() + dir.reverse().offset(), Point::default());
+            assert_eq!(dir.offset().manhattan(), 1);
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Down.offset(), Point::new(1, 0));
+        assert_eq!(
+            Direction::Left.step(UPoint::new(0, 1), (1, 2)),
+            Some(UPoint::new(0, 0))
+        );
+        assert_eq!(Direction::Left.step(UPoint::new(0, 0), (1, 2)), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let shape = (3, 4);
+        assert_eq!(neighbors4((1, 1), shape).count(), 4);
+        assert_eq!(neighbors8((1, 1), shape).count(), 8);
+        assert_eq!(
+            neighbors4((0, 0), shape).collect::<Vec<_>>(),
+            vec![UPoint::new(0, 1), UPoint::new(1, 0)]
+        );
+        assert_eq!(neighbors8((0, 0), shape).count(), 3);
+        assert_eq!(neighbors8((2, 3), shape).count(), 3);
+        assert_eq!(neighbors8((1, 3), shape).count(), 5);
+        assert!(neighbors8((2, 3), shape).all(|p| p.row < shape.0 && p.col < shape.1));
+    }
+}
@@ -0,0 +1,96 @@
+//! Summed-area tables for constant-time rectangle sums.
+
+use std::ops::{Add, Sub};
+
+use ndarray::Array2;
+
+use super::UPoint;
+
+/// Prefix sums of a grid, answering the sum over any rectangle in `O(1)`.
+///
+/// Values are assumed non-negative when `T` is unsigned, so that intermediate
+/// results never underflow. Use a signed type for grids with negative values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrefixSum2D<T> {
+    /// `sums[(r, c)]` is the sum over all cells above and left of `(r, c)`,
+    /// exclusive, so the first row and column are all zero
+    sums: Array2<T>,
+}
+
+impl<T> PrefixSum2D<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Build the table in `O(rows * cols)`
+    #[must_use]
+    pub fn new(grid: &Array2<T>) -> Self {
+        let (rows, cols) = grid.dim();
+        let mut sums = Array2::from_elem((rows + 1, cols + 1), T::default());
+        for ((r, c), &value) in grid.indexed_iter() {
+            sums[(r + 1, c + 1)] = value + sums[(r, c + 1)] + sums[(r + 1, c)] - sums[(r, c)];
+        }
+        Self { sums }
+    }
+
+    /// Shape of the original grid as `(rows, cols)`
+    #[must_use]
+    pub fn dim(&self) -> (usize, usize) {
+        let (rows, cols) = self.sums.dim();
+        (rows - 1, cols - 1)
+    }
+
+    /// Sum over the rectangle with inclusive corners `min` and `max`, or zero
+    /// when it is empty (`max` before `min` along either axis).
+    ///
+    /// # Panics
+    /// Panics if `max` lies outside the grid.
+    #[must_use]
+    pub fn sum(&self, min: UPoint, max: UPoint) -> T {
+        if min.row > max.row || min.col > max.col {
+            return T::default();
+        }
+        let (r0, c0) = min.as_index();
+        let (r1, c1) = (max.row + 1, max.col + 1);
+        // Add before subtracting so that unsigned sums never underflow
+        self.sums[(r1, c1)] + self.sums[(r0, c0)] - self.sums[(r0, c1)] - self.sums[(r1, c0)]
+    }
+
+    /// Sum over the whole grid
+    #[must_use]
+    pub fn total(&self) -> T {
+        let (rows, cols) = self.sums.dim();
+        self.sums[(rows - 1, cols - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_rectangle_sums() {
+        let grid = array![[1u32, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]];
+        let table = PrefixSum2D::new(&grid);
+        assert_eq!(table.dim(), (3, 4));
+        assert_eq!(table.total(), 78);
+        for r0 in 0..3 {
+            for c0 in 0..4 {
+                for r1 in r0..3 {
+                    for c1 in c0..4 {
+                        let expected = grid.slice(ndarray::s![r0..=r1, c0..=c1]).sum();
+                        let sum = table.sum(UPoint::new(r0, c0), UPoint::new(r1, c1));
+                        assert_eq!(sum, expected);
+                    }
+                }
+            }
+        }
+        assert_eq!(table.sum(UPoint::new(2, 0), UPoint::new(1, 3)), 0);
+        // Negative values with a signed type
+        let signed = PrefixSum2D::new(&array![[-1i64, 2], [3, -4]]);
+        assert_eq!(signed.sum(UPoint::new(0, 0), UPoint::new(1, 1)), 0);
+        assert_eq!(signed.sum(UPoint::new(0, 1), UPoint::new(1, 1)), -2);
+        assert_eq!(PrefixSum2D::new(&Array2::<u8>::zeros((0, 0))).total(), 0);
+    }
+}
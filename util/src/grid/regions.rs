@@ -0,0 +1,127 @@
+//! Flood fill and connected regions on `Array2` grids.
+//!
+//! Cells are connected through their orthogonal neighbors.
+
+use ndarray::Array2;
+
+use super::{UPoint, neighbors4};
+
+/// Connected regions of a grid, as computed by [`label_components`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Components {
+    /// The region label of every cell, dense from 0 in row-major order of
+    /// discovery
+    pub labels: Array2<u32>,
+    /// Number of cells in each region, indexed by label
+    pub sizes: Vec<usize>,
+}
+
+impl Components {
+    /// Number of regions
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Whether there are no regions, i.e. the grid is empty
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+}
+
+/// Mark every cell reachable from `start` by only stepping onto `passable`
+/// cells. Nothing is marked when `start` itself is not passable.
+pub fn flood_fill<T>(
+    grid: &Array2<T>,
+    start: impl Into<UPoint>,
+    passable: impl Fn(&T) -> bool,
+) -> Array2<bool> {
+    let shape = grid.dim();
+    let mut filled = Array2::from_elem(shape, false);
+    let start = start.into();
+    if !passable(&grid[start.as_index()]) {
+        return filled;
+    }
+    filled[start.as_index()] = true;
+    let mut stack = vec![start];
+    while let Some(pos) = stack.pop() {
+        for next in neighbors4(pos, shape) {
+            if !filled[next.as_index()] && passable(&grid[next.as_index()]) {
+                filled[next.as_index()] = true;
+                stack.push(next);
+            }
+        }
+    }
+    filled
+}
+
+/// Split the whole grid into connected regions, where neighboring cells belong
+/// to the same region when `same_region` holds for their values (e.g. equal
+/// plants in a garden, or both being walls).
+///
+/// `same_region` is expected to be symmetric.
+pub fn label_components<T>(grid: &Array2<T>, same_region: impl Fn(&T, &T) -> bool) -> Components {
+    let shape = grid.dim();
+    let mut labels = Array2::from_elem(shape, u32::MAX);
+    let mut sizes = Vec::new();
+    let mut label = 0;
+    let mut stack = Vec::new();
+    for ((row, col), _) in grid.indexed_iter() {
+        if labels[(row, col)] != u32::MAX {
+            continue;
+        }
+        labels[(row, col)] = label;
+        stack.push(UPoint::new(row, col));
+        let mut size = 0;
+        while let Some(pos) = stack.pop() {
+            size += 1;
+            for next in neighbors4(pos, shape) {
+                if labels[next.as_index()] == u32::MAX
+                    && same_region(&grid[pos.as_index()], &grid[next.as_index()])
+                {
+                    labels[next.as_index()] = label;
+                    stack.push(next);
+                }
+            }
+        }
+        sizes.push(size);
+        label += 1;
+    }
+    Components { labels, sizes }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = array![[0, 0, 1, 0], [1, 0, 1, 0], [0, 0, 0, 1], [0, 1, 1, 0]];
+        let filled = flood_fill(&grid, (0, 0), |&v| v == 0);
+        assert_eq!(filled.iter().filter(|&&f| f).count(), 7);
+        assert!(filled[(3, 0)]);
+        assert!(!filled[(0, 3)]);
+        assert!(!filled[(3, 3)]);
+        assert!(!flood_fill(&grid, (0, 2), |&v| v == 0).iter().any(|&f| f));
+    }
+
+    #[test]
+    fn test_label_components() {
+        let grid = array![
+            ['A', 'A', 'B', 'B'],
+            ['A', 'C', 'C', 'B'],
+            ['D', 'C', 'A', 'A'],
+        ];
+        let components = label_components(&grid, PartialEq::eq);
+        assert_eq!(components.len(), 5);
+        assert_eq!(components.sizes, vec![3, 3, 3, 1, 2]);
+        assert_eq!(
+            components.labels,
+            array![[0, 0, 1, 1], [0, 2, 2, 1], [3, 2, 4, 4]]
+        );
+        assert!(label_components(&Array2::<u8>::zeros((0, 3)), PartialEq::eq).is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+//! Stencils for counting weighted neighbors of every cell at once.
+
+use ndarray::{LinalgScalar, Zip, prelude::*};
+
+use crate::parallel::prelude::*;
+
+/// Kernel counting the 4 orthogonal neighbors of a cell
+#[must_use]
+pub fn kernel4<A: LinalgScalar>() -> Array2<A> {
+    let (o, i) = (A::zero(), A::one());
+    array![[o, i, o], [i, o, i], [o, i, o]]
+}
+
+/// Kernel counting the 8 orthogonal and diagonal neighbors of a cell
+#[must_use]
+pub fn kernel8<A: LinalgScalar>() -> Array2<A> {
+    let mut kernel = Array2::from_elem((3, 3), A::one());
+    kernel[(1, 1)] = A::zero();
+    kernel
+}
+
+/// Weighted sum of the neighborhood of every cell, with the `kernel` centered
+/// on the cell and everything outside the grid counting as zero.
+///
+/// With a 0/1 grid and one of [`kernel4`] or [`kernel8`], this counts the
+/// occupied neighbors of every cell, which is most of a cellular automaton
+/// update rule.
+///
+/// # Panics
+/// Panics if either dimension of the kernel is even, as it has no center.
+#[must_use]
+pub fn convolve_count<A>(grid: &Array2<A>, kernel: &Array2<A>) -> Array2<A>
+where
+    A: LinalgScalar + Send + Sync,
+{
+    let (rows, cols) = grid.dim();
+    let (k_rows, k_cols) = kernel.dim();
+    assert!(
+        k_rows % 2 == 1 && k_cols % 2 == 1,
+        "Kernel dimensions must be odd"
+    );
+    let (pad_rows, pad_cols) = (k_rows / 2, k_cols / 2);
+    let mut padded = Array2::zeros((rows + 2 * pad_rows, cols + 2 * pad_cols));
+    padded
+        .slice_mut(s![pad_rows..pad_rows + rows, pad_cols..pad_cols + cols])
+        .assign(grid);
+    let mut count = Array2::zeros((rows, cols));
+    // Add one shifted copy of the grid per non-zero kernel entry
+    for ((dr, dc), &weight) in kernel.indexed_iter() {
+        if weight.is_zero() {
+            continue;
+        }
+        let shifted = padded.slice(s![dr..dr + rows, dc..dc + cols]);
+        Zip::from(&mut count)
+            .and(&shifted)
+            .maybe_par_for_each(|acc, &v| *acc = *acc + weight * v);
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbor_counts() {
+        let grid = array![[1u8, 1, 0], [0, 1, 0], [1, 0, 1]];
+        assert_eq!(
+            convolve_count(&grid, &kernel8()),
+            array![[2, 2, 2], [4, 4, 3], [1, 3, 1]]
+        );
+        assert_eq!(
+            convolve_count(&grid, &kernel4()),
+            array![[1, 2, 1], [3, 1, 2], [0, 3, 0]]
+        );
+        // Asymmetric kernels are not flipped: this one looks one cell right
+        let right = array![[0, 0, 0], [0, 0, 1], [0, 0, 0]];
+        assert_eq!(
+            convolve_count(&grid, &right),
+            array![[1, 0, 0], [1, 0, 0], [0, 1, 0]]
+        );
+        let wide = Array2::from_elem((1, 5), 1i32);
+        assert_eq!(
+            convolve_count(&array![[1, 2, 3, 4]], &wide),
+            array![[6, 10, 10, 9]]
+        );
+    }
+}
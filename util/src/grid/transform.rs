@@ -0,0 +1,164 @@
+//! Rotations, reflections, cropping and tiling of whole grids.
+//!
+//! Rotations are clockwise, matching
+//! [`Polyomino::rotate`](crate::poly::Polyomino::rotate).
+
+use ndarray::{Array2, s};
+
+use super::UPoint;
+
+/// Rotate 90 degrees clockwise, so that the first column becomes the first
+/// row read bottom to top
+#[must_use]
+pub fn rotate90<T: Clone>(grid: &Array2<T>) -> Array2<T> {
+    grid.slice(s![..;-1, ..])
+        .t()
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// Rotate 180 degrees
+#[must_use]
+pub fn rotate180<T: Clone>(grid: &Array2<T>) -> Array2<T> {
+    grid.slice(s![..;-1, ..;-1])
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// Rotate 270 degrees clockwise, i.e. 90 degrees counter-clockwise
+#[must_use]
+pub fn rotate270<T: Clone>(grid: &Array2<T>) -> Array2<T> {
+    grid.t()
+        .slice(s![..;-1, ..])
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// Mirror left to right, reversing every row
+#[must_use]
+pub fn flip_horizontal<T: Clone>(grid: &Array2<T>) -> Array2<T> {
+    grid.slice(s![.., ..;-1]).as_standard_layout().into_owned()
+}
+
+/// Mirror top to bottom, reversing every column
+#[must_use]
+pub fn flip_vertical<T: Clone>(grid: &Array2<T>) -> Array2<T> {
+    grid.slice(s![..;-1, ..]).as_standard_layout().into_owned()
+}
+
+/// Swap rows and columns, as an owned grid in standard layout
+#[must_use]
+pub fn transpose<T: Clone>(grid: &Array2<T>) -> Array2<T> {
+    grid.t().as_standard_layout().into_owned()
+}
+
+/// The distinct grids among the 8 rotations and reflections of `grid`, in the
+/// order: 4 rotations, then 4 rotations of the horizontal mirror
+#[must_use]
+pub fn symmetries<T: Clone + PartialEq>(grid: &Array2<T>) -> Vec<Array2<T>> {
+    let mut found: Vec<Array2<T>> = Vec::with_capacity(8);
+    for mut current in [grid.clone(), flip_horizontal(grid)] {
+        for _ in 0..4 {
+            let next = rotate90(&current);
+            if !found.contains(&current) {
+                found.push(current);
+            }
+            current = next;
+        }
+    }
+    found
+}
+
+/// Inclusive corners of the smallest rectangle containing every cell
+/// matching `predicate`, or `None` if no cell does
+pub fn bounding_box<T>(
+    grid: &Array2<T>,
+    mut predicate: impl FnMut(&T) -> bool,
+) -> Option<(UPoint, UPoint)> {
+    grid.indexed_iter()
+        .filter(|&(_, value)| predicate(value))
+        .map(|(pos, _)| UPoint::from(pos))
+        .fold(None, |bounds, pos| {
+            let (min, max) = bounds.unwrap_or((pos, pos));
+            Some((
+                UPoint::new(min.row.min(pos.row), min.col.min(pos.col)),
+                UPoint::new(max.row.max(pos.row), max.col.max(pos.col)),
+            ))
+        })
+}
+
+/// Cut `grid` down to the [`bounding_box`] of `predicate`, returning the
+/// offset of the cropped grid within the original along with it
+pub fn crop<T: Clone>(
+    grid: &Array2<T>,
+    predicate: impl FnMut(&T) -> bool,
+) -> Option<(UPoint, Array2<T>)> {
+    let (min, max) = bounding_box(grid, predicate)?;
+    let cropped = grid
+        .slice(s![min.row..=max.row, min.col..=max.col])
+        .as_standard_layout()
+        .into_owned();
+    Some((min, cropped))
+}
+
+/// Repeat `grid` `rows` times downwards and `cols` times to the right
+#[must_use]
+pub fn tile<T: Clone>(grid: &Array2<T>, rows: usize, cols: usize) -> Array2<T> {
+    let (height, width) = grid.dim();
+    Array2::from_shape_fn((height * rows, width * cols), |(r, c)| {
+        grid[(r % height, c % width)].clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_rotations_and_flips() {
+        let grid = array![[1, 2, 3], [4, 5, 6]];
+        assert_eq!(rotate90(&grid), array![[4, 1], [5, 2], [6, 3]]);
+        assert_eq!(rotate180(&grid), array![[6, 5, 4], [3, 2, 1]]);
+        assert_eq!(rotate270(&grid), array![[3, 6], [2, 5], [1, 4]]);
+        assert_eq!(rotate90(&rotate90(&grid)), rotate180(&grid));
+        assert_eq!(rotate90(&rotate270(&grid)), grid);
+        assert_eq!(flip_horizontal(&grid), array![[3, 2, 1], [6, 5, 4]]);
+        assert_eq!(flip_vertical(&grid), array![[4, 5, 6], [1, 2, 3]]);
+        assert_eq!(transpose(&grid), array![[1, 4], [2, 5], [3, 6]]);
+        assert!(transpose(&grid).is_standard_layout());
+        assert!(rotate90(&grid).is_standard_layout());
+        assert_eq!(flip_vertical(&flip_horizontal(&grid)), rotate180(&grid));
+    }
+
+    #[test]
+    fn test_symmetries() {
+        let asymmetric = array![[1, 1], [1, 0], [1, 0]];
+        assert_eq!(symmetries(&asymmetric).len(), 8);
+        let square = array![[1, 1], [1, 1]];
+        assert_eq!(symmetries(&square), vec![square]);
+        // An S shape only has two distinct rotations, each its own mirror's
+        let s_shape = array![[0, 1, 1], [1, 1, 0]];
+        assert_eq!(symmetries(&s_shape).len(), 4);
+    }
+
+    #[test]
+    fn test_crop_and_tile() {
+        let grid = array![[0, 0, 0, 0], [0, 1, 0, 0], [0, 0, 2, 0]];
+        assert_eq!(
+            bounding_box(&grid, |&v| v > 0),
+            Some((UPoint::new(1, 1), UPoint::new(2, 2)))
+        );
+        assert_eq!(
+            crop(&grid, |&v| v > 0),
+            Some((UPoint::new(1, 1), array![[1, 0], [0, 2]]))
+        );
+        assert_eq!(crop(&grid, |&v| v > 5), None);
+        assert_eq!(
+            tile(&array![[1, 2]], 2, 2),
+            array![[1, 2, 1, 2], [1, 2, 1, 2]]
+        );
+        assert_eq!(tile(&grid, 0, 3).dim(), (0, 12));
+    }
+}
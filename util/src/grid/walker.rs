@@ -0,0 +1,263 @@
+//! A turtle that follows movement instructions on a grid or dial.
+
+use super::{Direction, Point, UPoint};
+
+/// A turn relative to the current heading
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+/// What happens when a [`Walker`] reaches the edge of its world
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Bounds {
+    /// The plane is infinite
+    Unbounded,
+    /// Coordinates wrap around a torus with the given `(rows, cols)` shape. A
+    /// dial with `n` positions is a `(1, n)` torus.
+    Wrap((usize, usize)),
+    /// Movement stops at the edge of a grid with the given `(rows, cols)` shape
+    Stop((usize, usize)),
+}
+
+/// A position and heading, moved by turning and walking forward.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Walker {
+    pos: Point<i64>,
+    heading: Direction,
+    bounds: Bounds,
+}
+
+impl Walker {
+    /// Start at `pos` facing `heading` on an unbounded plane
+    #[must_use]
+    pub const fn new(pos: Point<i64>, heading: Direction) -> Self {
+        Self {
+            pos,
+            heading,
+            bounds: Bounds::Unbounded,
+        }
+    }
+
+    /// Restrict movement to the given bounds
+    ///
+    /// # Panics
+    /// This function panics if wrapping bounds have no cell to wrap to.
+    #[must_use]
+    pub const fn with_bounds(mut self, bounds: Bounds) -> Self {
+        if let Bounds::Wrap((rows, cols)) = bounds {
+            assert!(rows > 0 && cols > 0, "Cannot wrap around an empty grid");
+        }
+        self.bounds = bounds;
+        self
+    }
+
+    /// Current position
+    #[must_use]
+    pub const fn pos(&self) -> Point<i64> {
+        self.pos
+    }
+
+    /// Current position as an index of a grid with the given shape, if it
+    /// falls within the bounds.
+    #[must_use]
+    pub fn index(&self, shape: (usize, usize)) -> Option<UPoint> {
+        self.pos.to_index(shape)
+    }
+
+    /// Current heading
+    #[must_use]
+    pub const fn heading(&self) -> Direction {
+        self.heading
+    }
+
+    /// Rotate 90 degrees in place
+    pub const fn turn(&mut self, turn: Turn) {
+        self.heading = match turn {
+            Turn::Left => self.heading.turn_left(),
+            Turn::Right => self.heading.turn_right(),
+        };
+    }
+
+    /// Face `heading` regardless of the current one
+    pub const fn face(&mut self, heading: Direction) {
+        self.heading = heading;
+    }
+
+    /// Unit step and number of steps for moving `n` cells forward, where
+    /// negative `n` walks backwards. With [`Bounds::Stop`] the steps are cut
+    /// short at the edge.
+    fn plan(&self, n: i64) -> (Point<i64>, i64) {
+        let unit = self.heading.offset() * n.signum();
+        let mut steps = n.abs();
+        if let Bounds::Stop((rows, cols)) = self.bounds {
+            for (d, coord, size) in [
+                (unit.row, self.pos.row, rows),
+                (unit.col, self.pos.col, cols),
+            ] {
+                let size = i64::try_from(size).unwrap_or(i64::MAX);
+                let room = match d.signum() {
+                    1 => size - 1 - coord,
+                    -1 => coord,
+                    _ => continue,
+                };
+                steps = steps.min(room.max(0));
+            }
+        }
+        (unit, steps)
+    }
+
+    /// Bring `pos` back within wrapping bounds
+    fn wrap(&self, pos: Point<i64>) -> Point<i64> {
+        match self.bounds {
+            Bounds::Wrap((rows, cols)) => {
+                let rows = i64::try_from(rows).unwrap_or(i64::MAX);
+                let cols = i64::try_from(cols).unwrap_or(i64::MAX);
+                Point::new(pos.row.rem_euclid(rows), pos.col.rem_euclid(cols))
+            }
+            Bounds::Unbounded | Bounds::Stop(_) => pos,
+        }
+    }
+
+    /// Walk `n` cells forward, or backwards when negative, in `O(1)`.
+    /// Returns the number of steps actually taken, which is less than `|n|`
+    /// only when stopped by the edge.
+    pub fn forward(&mut self, n: i64) -> u64 {
+        let (unit, steps) = self.plan(n);
+        self.pos = self.wrap(self.pos + unit * steps);
+        steps.unsigned_abs()
+    }
+
+    /// Like [`Self::forward`], but call `visit` with every cell entered along
+    /// the way, including the last one.
+    pub fn forward_with(&mut self, n: i64, mut visit: impl FnMut(Point<i64>)) -> u64 {
+        let (unit, steps) = self.plan(n);
+        for _ in 0..steps {
+            self.pos = self.wrap(self.pos + unit);
+            visit(self.pos);
+        }
+        steps.unsigned_abs()
+    }
+
+    /// Like [`Self::forward`], but return how many times the walker enters
+    /// `target` along the way, including where it stops but not where it
+    /// starts. Computed in `O(1)` even when wrapping around many times.
+    pub fn forward_counting(&mut self, n: i64, target: Point<i64>) -> u64 {
+        let (unit, steps) = self.plan(n);
+        let start = self.pos;
+        self.pos = self.wrap(start + unit * steps);
+        // Split into the coordinate along the movement and the one across it
+        let (lap, from, to, sign) = if unit.row == 0 {
+            if start.row != target.row {
+                return 0;
+            }
+            (self.laps().1, start.col, target.col, unit.col)
+        } else {
+            if start.col != target.col {
+                return 0;
+            }
+            (self.laps().0, start.row, target.row, unit.row)
+        };
+        let distance = (to - from) * sign;
+        let Some(lap) = lap else {
+            return u64::from((1..=steps).contains(&distance));
+        };
+        // Steps until the first hit, after which it recurs every lap
+        let first = match distance.rem_euclid(lap) {
+            0 => lap,
+            d => d,
+        };
+        if steps < first {
+            0
+        } else {
+            ((steps - first) / lap + 1).unsigned_abs()
+        }
+    }
+
+    /// Wrapping period along each axis, if any
+    fn laps(&self) -> (Option<i64>, Option<i64>) {
+        match self.bounds {
+            Bounds::Wrap((rows, cols)) => (i64::try_from(rows).ok(), i64::try_from(cols).ok()),
+            Bounds::Unbounded | Bounds::Stop(_) => (None, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turtle() {
+        let mut walker = Walker::new(Point::new(0, 0), Direction::Up);
+        // Walk a square, ending back at the start
+        for _ in 0..4 {
+            assert_eq!(walker.forward(3), 3);
+            walker.turn(Turn::Right);
+        }
+        assert_eq!(walker.pos(), Point::new(0, 0));
+        assert_eq!(walker.heading(), Direction::Up);
+        walker.turn(Turn::Left);
+        walker.forward(-2);
+        assert_eq!(walker.pos(), Point::new(0, 2));
+        let mut visited = Vec::new();
+        walker.face(Direction::Down);
+        walker.forward_with(3, |pos| visited.push(pos));
+        assert_eq!(
+            visited,
+            vec![Point::new(1, 2), Point::new(2, 2), Point::new(3, 2)]
+        );
+        assert_eq!(walker.index((4, 4)), Some(UPoint::new(3, 2)));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut walker =
+            Walker::new(Point::new(1, 1), Direction::Right).with_bounds(Bounds::Stop((3, 4)));
+        assert_eq!(walker.forward(10), 2);
+        assert_eq!(walker.pos(), Point::new(1, 3));
+        assert_eq!(walker.forward(-10), 3);
+        assert_eq!(walker.pos(), Point::new(1, 0));
+        let mut walker =
+            Walker::new(Point::new(1, 1), Direction::Up).with_bounds(Bounds::Wrap((3, 4)));
+        assert_eq!(walker.forward(2), 2);
+        assert_eq!(walker.pos(), Point::new(2, 1));
+        assert_eq!(walker.forward(-7), 7);
+        assert_eq!(walker.pos(), Point::new(0, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot wrap around an empty grid")]
+    fn test_empty_wrap() {
+        let _ = Walker::new(Point::new(0, 0), Direction::Up).with_bounds(Bounds::Wrap((1, 0)));
+    }
+
+    #[test]
+    fn test_counting_matches_visits() {
+        for bounds in [
+            Bounds::Unbounded,
+            Bounds::Wrap((1, 7)),
+            Bounds::Stop((1, 7)),
+        ] {
+            for start in 0..7 {
+                for n in -20..=20 {
+                    for target in 0..7 {
+                        let target = Point::new(0, target);
+                        let mut walker =
+                            Walker::new(Point::new(0, start), Direction::Right).with_bounds(bounds);
+                        let mut expected = 0;
+                        walker.forward_with(n, |pos| expected += u64::from(pos == target));
+                        let mut counting =
+                            Walker::new(Point::new(0, start), Direction::Right).with_bounds(bounds);
+                        assert_eq!(counting.forward_counting(n, target), expected);
+                        assert_eq!(counting, walker);
+                    }
+                }
+            }
+        }
+        // Targets off the line of movement are never entered
+        let mut walker = Walker::new(Point::new(0, 0), Direction::Down);
+        assert_eq!(walker.forward_counting(5, Point::new(3, 1)), 0);
+    }
+}
@@ -0,0 +1,297 @@
+//! Hashing helpers for "find the lowest number whose hash ..." puzzles.
+//!
+//! Includes a small MD5 implementation, which is broken as a cryptographic
+//! hash but keeps showing up in puzzles. It is only available with the `hash`
+//! feature.
+
+use std::fmt::Write;
+
+use crate::parallel::prelude::*;
+
+/// Per-round left rotation amounts
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, //
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, //
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, //
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+/// Per-round additive constants, `floor(|sin(i + 1)| * 2^32)`
+const CONSTANTS: [u32; 64] = [
+    0xd76a_a478,
+    0xe8c7_b756,
+    0x2420_70db,
+    0xc1bd_ceee,
+    0xf57c_0faf,
+    0x4787_c62a,
+    0xa830_4613,
+    0xfd46_9501,
+    0x6980_98d8,
+    0x8b44_f7af,
+    0xffff_5bb1,
+    0x895c_d7be,
+    0x6b90_1122,
+    0xfd98_7193,
+    0xa679_438e,
+    0x49b4_0821,
+    0xf61e_2562,
+    0xc040_b340,
+    0x265e_5a51,
+    0xe9b6_c7aa,
+    0xd62f_105d,
+    0x0244_1453,
+    0xd8a1_e681,
+    0xe7d3_fbc8,
+    0x21e1_cde6,
+    0xc337_07d6,
+    0xf4d5_0d87,
+    0x455a_14ed,
+    0xa9e3_e905,
+    0xfcef_a3f8,
+    0x676f_02d9,
+    0x8d2a_4c8a,
+    0xfffa_3942,
+    0x8771_f681,
+    0x6d9d_6122,
+    0xfde5_380c,
+    0xa4be_ea44,
+    0x4bde_cfa9,
+    0xf6bb_4b60,
+    0xbebf_bc70,
+    0x289b_7ec6,
+    0xeaa1_27fa,
+    0xd4ef_3085,
+    0x0488_1d05,
+    0xd9d4_d039,
+    0xe6db_99e5,
+    0x1fa2_7cf8,
+    0xc4ac_5665,
+    0xf429_2244,
+    0x432a_ff97,
+    0xab94_23a7,
+    0xfc93_a039,
+    0x655b_59c3,
+    0x8f0c_cc92,
+    0xffef_f47d,
+    0x8584_5dd1,
+    0x6fa8_7e4f,
+    0xfe2c_e6e0,
+    0xa301_4314,
+    0x4e08_11a1,
+    0xf753_7e82,
+    0xbd3a_f235,
+    0x2ad7_d2bb,
+    0xeb86_d391,
+];
+
+/// Incremental MD5 hasher, for hashing input that arrives in pieces
+#[derive(Clone, Debug)]
+pub struct Md5 {
+    state: [u32; 4],
+    /// Bytes of the current, incomplete block
+    block: [u8; 64],
+    /// Total number of bytes hashed so far
+    len: u64,
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Md5 {
+    /// A hasher that has not seen any input yet
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476],
+            block: [0; 64],
+            len: 0,
+        }
+    }
+
+    /// Feed more input
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let filled = (self.len % 64) as usize;
+            let n = data.len().min(64 - filled);
+            self.block[filled..filled + n].copy_from_slice(&data[..n]);
+            self.len += n as u64;
+            data = &data[n..];
+            if filled + n == 64 {
+                self.compress();
+            }
+        }
+    }
+
+    /// Mix the current full block into the state
+    fn compress(&mut self) {
+        let mut words = [0u32; 16];
+        for (word, bytes) in words.iter_mut().zip(self.block.chunks_exact(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for i in 0..64 {
+            let (mix, word) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(mix)
+                .wrapping_add(CONSTANTS[i])
+                .wrapping_add(words[word])
+                .rotate_left(SHIFTS[i]);
+            (a, b, c, d) = (d, b.wrapping_add(rotated), b, c);
+        }
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    /// Pad the input and return the 16-byte digest
+    #[must_use]
+    pub fn finalize(mut self) -> [u8; 16] {
+        let bits = self.len.wrapping_mul(8);
+        self.update(&[0x80]);
+        while self.len % 64 != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_le_bytes());
+        let mut digest = [0; 16];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+/// MD5 digest of `data`
+#[must_use]
+pub fn md5(data: &[u8]) -> [u8; 16] {
+    let mut hasher = Md5::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// MD5 digest of `data` as 32 lowercase hex digits
+#[must_use]
+pub fn md5_hex(data: &[u8]) -> String {
+    to_hex(&md5(data))
+}
+
+/// MD5 digest of `prefix` followed by the decimal digits of `n`, the usual
+/// shape of "secret key plus counter" puzzles
+#[must_use]
+pub fn md5_counter(prefix: &[u8], n: u64) -> [u8; 16] {
+    let mut hasher = Md5::new();
+    hasher.update(prefix);
+    hasher.update(n.to_string().as_bytes());
+    hasher.finalize()
+}
+
+/// Lowercase hex representation of `bytes`
+#[must_use]
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Number of leading zero hex digits of `digest`
+#[must_use]
+pub fn leading_zero_nibbles(digest: &[u8]) -> u32 {
+    leading_zero_bits(digest) / 4
+}
+
+/// Number of leading zero bits of `digest`
+#[must_use]
+pub fn leading_zero_bits(digest: &[u8]) -> u32 {
+    let zero_bytes = digest.iter().take_while(|&&byte| byte == 0).count();
+    let rest = digest
+        .get(zero_bytes)
+        .map_or(0, |byte| byte.leading_zeros());
+    u32::try_from(zero_bytes).unwrap_or(u32::MAX) * 8 + rest
+}
+
+/// Numbers checked per parallel batch while searching
+const BATCH: u64 = 1 << 14;
+
+/// The lowest `n >= start` satisfying `predicate`, checking batches of
+/// candidates in parallel. Loops forever if there is none.
+pub fn find_lowest(start: u64, predicate: impl Fn(u64) -> bool + Sync) -> u64 {
+    let mut base = start;
+    loop {
+        let end = base.saturating_add(BATCH);
+        if let Some(n) = (base..end)
+            .maybe_into_par_iter()
+            .find_first(|&n| predicate(n))
+        {
+            return n;
+        }
+        base = end;
+    }
+}
+
+/// The `count` lowest numbers `n >= start` satisfying `predicate`, in
+/// increasing order, checking batches of candidates in parallel
+pub fn find_lowest_n(start: u64, count: usize, predicate: impl Fn(u64) -> bool + Sync) -> Vec<u64> {
+    let mut found = Vec::with_capacity(count);
+    let mut base = start;
+    while found.len() < count {
+        let end = base.saturating_add(BATCH);
+        let batch = (base..end)
+            .maybe_into_par_iter()
+            .filter(|&n| predicate(n))
+            .collect::<Vec<_>>();
+        found.extend(batch.into_iter().take(count - found.len()));
+        base = end;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_md5() {
+        assert_eq!(md5_hex(b""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(md5_hex(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            md5_hex(b"The quick brown fox jumps over the lazy dog"),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+        // Input spanning several blocks, fed in uneven pieces
+        let data = (0..200u8).collect::<Vec<_>>();
+        let mut hasher = Md5::new();
+        for chunk in data.chunks(37) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), md5(&data));
+        assert_eq!(md5_counter(b"abc", 123), md5(b"abc123"));
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        assert_eq!(leading_zero_nibbles(&[0x00, 0x00, 0x0f]), 5);
+        assert_eq!(leading_zero_bits(&[0x00, 0x00, 0x0f]), 20);
+        assert_eq!(leading_zero_bits(&[0x80]), 0);
+        assert_eq!(leading_zero_bits(&[0, 0]), 16);
+    }
+
+    #[test]
+    fn test_search() {
+        // 2015 day 4: the lowest counter giving five leading zeros
+        let n = find_lowest(1, |n| leading_zero_nibbles(&md5_counter(b"abcdef", n)) >= 5);
+        assert_eq!(n, 609_043);
+        let multiples = find_lowest_n(1, 5, |n| n % 7_000 == 0);
+        assert_eq!(multiples, vec![7_000, 14_000, 21_000, 28_000, 35_000]);
+        assert!(find_lowest_n(0, 0, |_| true).is_empty());
+    }
+}
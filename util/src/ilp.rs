@@ -0,0 +1,171 @@
+//! Integer linear programming, for "minimum cost subject to linear constraints"
+//! puzzles.
+//!
+//! This is a thin builder over `microlp`, which solves the LP relaxation with
+//! the simplex method and branches on fractional integer variables. It is only
+//! available with the `ilp` feature to keep the solver out of default builds.
+
+use std::ops::RangeInclusive;
+
+use crate::Result;
+
+/// A variable of an [`Ilp`] model
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Var(microlp::Variable);
+
+/// Comparison between a linear expression and its right-hand side
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cmp {
+    Eq,
+    Le,
+    Ge,
+}
+
+/// An integer linear program under construction.
+///
+/// Variables are added with their objective coefficient, then constrained by
+/// linear (in)equalities over `(variable, coefficient)` terms.
+pub struct Ilp {
+    problem: microlp::Problem,
+}
+
+/// An optimal assignment of an [`Ilp`] model
+pub struct IlpSolution {
+    solution: microlp::Solution,
+}
+
+impl Ilp {
+    /// Create an empty model that minimizes its objective
+    #[must_use]
+    pub fn minimize() -> Self {
+        Self {
+            problem: microlp::Problem::new(microlp::OptimizationDirection::Minimize),
+        }
+    }
+
+    /// Create an empty model that maximizes its objective
+    #[must_use]
+    pub fn maximize() -> Self {
+        Self {
+            problem: microlp::Problem::new(microlp::OptimizationDirection::Maximize),
+        }
+    }
+
+    /// Add an integer variable within `bounds`, contributing `cost` per unit to
+    /// the objective.
+    pub fn integer_var(&mut self, cost: f64, bounds: RangeInclusive<i32>) -> Var {
+        Var(self
+            .problem
+            .add_integer_var(cost, (*bounds.start(), *bounds.end())))
+    }
+
+    /// Add a real-valued variable within `(min, max)`, contributing `cost` per
+    /// unit to the objective. Use infinities for unbounded sides.
+    pub fn continuous_var(&mut self, cost: f64, bounds: (f64, f64)) -> Var {
+        Var(self.problem.add_var(cost, bounds))
+    }
+
+    /// Add a variable restricted to 0 or 1, contributing `cost` when set
+    pub fn binary_var(&mut self, cost: f64) -> Var {
+        Var(self.problem.add_binary_var(cost))
+    }
+
+    /// Require `sum(coeff * var) cmp rhs` over the given terms
+    pub fn constraint(&mut self, terms: impl IntoIterator<Item = (Var, f64)>, cmp: Cmp, rhs: f64) {
+        let mut expr = microlp::LinearExpr::empty();
+        for (Var(var), coeff) in terms {
+            expr.add(var, coeff);
+        }
+        let cmp = match cmp {
+            Cmp::Eq => microlp::ComparisonOp::Eq,
+            Cmp::Le => microlp::ComparisonOp::Le,
+            Cmp::Ge => microlp::ComparisonOp::Ge,
+        };
+        self.problem.add_constraint(expr, cmp, rhs);
+    }
+
+    /// Find an optimal assignment, failing if the model is infeasible or
+    /// unbounded.
+    pub fn solve(&self) -> Result<IlpSolution> {
+        let solution = self.problem.solve()?;
+        Ok(IlpSolution { solution })
+    }
+}
+
+impl IlpSolution {
+    /// Value of the objective at the optimum
+    #[must_use]
+    pub fn objective(&self) -> f64 {
+        self.solution.objective()
+    }
+
+    /// Value of `var` at the optimum
+    #[must_use]
+    pub fn value(&self, var: Var) -> f64 {
+        *self.solution.var_value(var.0)
+    }
+
+    /// Value of the integer variable `var` at the optimum, rounded to cancel
+    /// out floating point noise
+    #[must_use]
+    pub fn int_value(&self, var: Var) -> i64 {
+        // Integer variables are bounded by `i32`, so this never truncates
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            self.value(var).round() as i64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_presses() -> Result<()> {
+        // Buttons toggling counters, each counter must reach its target exactly
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let targets = [3.0, 5.0, 4.0, 7.0];
+        let mut model = Ilp::minimize();
+        let presses = buttons
+            .iter()
+            .map(|_| model.integer_var(1.0, 0..=100))
+            .collect::<Vec<_>>();
+        for (counter, &target) in targets.iter().enumerate() {
+            let terms = buttons
+                .iter()
+                .zip(&presses)
+                .filter(|(button, _)| button.contains(&counter))
+                .map(|(_, &var)| (var, 1.0));
+            model.constraint(terms, Cmp::Eq, target);
+        }
+        let solution = model.solve()?;
+        let total = presses
+            .iter()
+            .map(|&var| solution.int_value(var))
+            .sum::<i64>();
+        assert_eq!(total, 10);
+        assert!((solution.objective() - 10.0).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_integrality() -> Result<()> {
+        // The LP relaxation optimum (x = 2.5) is not integral
+        let mut model = Ilp::maximize();
+        let x = model.integer_var(1.0, 0..=10);
+        let y = model.binary_var(0.5);
+        model.constraint([(x, 2.0), (y, 1.0)], Cmp::Le, 5.0);
+        let solution = model.solve()?;
+        assert_eq!((solution.int_value(x), solution.int_value(y)), (2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_infeasible() {
+        let mut model = Ilp::minimize();
+        let x = model.integer_var(1.0, 0..=3);
+        model.constraint([(x, 1.0)], Cmp::Ge, 5.0);
+        assert!(model.solve().is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Iterator helpers, sequential and parallel.
+
+#[cfg(feature = "parallel")]
+use rayon::iter::{
+    IndexedParallelIterator, ParallelIterator,
+    plumbing::{Consumer, Producer, ProducerCallback, UnindexedConsumer, bridge},
+};
+
+/// Number of pairs `(i, j)` with `j > i` that come before row `i`
+const fn row_start(n: usize, i: usize) -> usize {
+    i * (2 * n).saturating_sub(i + 1) / 2
+}
+
+/// The pair at position `rank` in row-major order, or one past the last pair
+/// when `rank` is the total number of pairs
+const fn unrank(n: usize, rank: usize) -> (usize, usize) {
+    // Largest row starting at or before `rank`
+    let (mut lo, mut hi) = (0, n.saturating_sub(1));
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if row_start(n, mid) <= rank {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    (lo, lo + 1 + rank - row_start(n, lo))
+}
+
+/// All pairs of indices `(i, j)` with `0 <= i < j < n`, i.e. the strict upper
+/// triangle of an `n` by `n` matrix, in row-major order
+#[must_use]
+pub const fn pairs(n: usize) -> Pairs {
+    let len = n * n.saturating_sub(1) / 2;
+    Pairs {
+        n,
+        front: unrank(n, 0),
+        back: unrank(n, len),
+        len,
+    }
+}
+
+/// The parallel version of [`pairs`], splitting the triangle evenly between
+/// threads without collecting it first
+#[cfg(feature = "parallel")]
+#[must_use]
+pub const fn par_pairs(n: usize) -> ParPairs {
+    ParPairs { n }
+}
+
+/// Iterator returned by [`pairs`]
+#[derive(Clone, Debug)]
+pub struct Pairs {
+    n: usize,
+    /// Next pair to yield from the front
+    front: (usize, usize),
+    /// One past the next pair to yield from the back
+    back: (usize, usize),
+    len: usize,
+}
+
+impl Iterator for Pairs {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let pair = self.front;
+        self.front = if pair.1 + 1 < self.n {
+            (pair.0, pair.1 + 1)
+        } else {
+            (pair.0 + 1, pair.0 + 2)
+        };
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl DoubleEndedIterator for Pairs {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let (i, j) = self.back;
+        self.back = if j > i + 1 {
+            (i, j - 1)
+        } else {
+            (i - 1, self.n - 1)
+        };
+        Some(self.back)
+    }
+}
+
+impl ExactSizeIterator for Pairs {}
+
+#[cfg(feature = "parallel")]
+impl Producer for Pairs {
+    type IntoIter = Self;
+    type Item = (usize, usize);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (i, j) = self.front;
+        let mid = unrank(self.n, row_start(self.n, i) + j - i - 1 + index);
+        let left = Self {
+            back: mid,
+            len: index,
+            ..self
+        };
+        let right = Self {
+            front: mid,
+            len: self.len - index,
+            ..self
+        };
+        (left, right)
+    }
+}
+
+/// Parallel iterator returned by [`par_pairs`]
+#[cfg(feature = "parallel")]
+#[derive(Clone, Copy, Debug)]
+pub struct ParPairs {
+    n: usize,
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelIterator for ParPairs {
+    type Item = (usize, usize);
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl IndexedParallelIterator for ParPairs {
+    fn len(&self) -> usize {
+        pairs(self.n).len
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        callback.callback(pairs(self.n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nested(n: usize) -> Vec<(usize, usize)> {
+        (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect()
+    }
+
+    #[test]
+    fn test_pairs() {
+        for n in 0..8 {
+            let expected = nested(n);
+            assert_eq!(pairs(n).len(), expected.len());
+            assert_eq!(pairs(n).collect::<Vec<_>>(), expected);
+            let mut reversed = pairs(n).rev().collect::<Vec<_>>();
+            reversed.reverse();
+            assert_eq!(reversed, expected);
+        }
+        // Meet in the middle from both ends
+        let mut iter = pairs(4);
+        assert_eq!(iter.next(), Some((0, 1)));
+        assert_eq!(iter.next_back(), Some((2, 3)));
+        assert_eq!(iter.len(), 4);
+        assert_eq!(
+            iter.collect::<Vec<_>>(),
+            vec![(0, 2), (0, 3), (1, 2), (1, 3)]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_par_pairs() {
+        for n in [0, 1, 2, 7, 100] {
+            let expected = nested(n);
+            assert_eq!(par_pairs(n).len(), expected.len());
+            assert_eq!(par_pairs(n).collect::<Vec<_>>(), expected);
+            assert_eq!(
+                par_pairs(n).with_min_len(3).rev().collect::<Vec<_>>(),
+                expected.into_iter().rev().collect::<Vec<_>>()
+            );
+        }
+        // Every split point yields the two halves of the sequence
+        let expected = nested(9);
+        for k in 0..=expected.len() {
+            let (left, right) = pairs(9).split_at(k);
+            assert_eq!(left.collect::<Vec<_>>(), expected[..k]);
+            let mut right = right.rev().collect::<Vec<_>>();
+            right.reverse();
+            assert_eq!(right, expected[k..]);
+        }
+    }
+}
@@ -1,15 +1,58 @@
 //! Utilities for Advent of Code challenges
 
+pub mod allocator;
+#[cfg(feature = "api")]
+pub mod aoc_api;
+pub mod bits;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod collections;
+pub mod config;
+pub mod csp;
+pub mod cycle;
+pub mod diagnostics;
+pub mod dp;
+pub mod error;
+pub mod generate;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+#[cfg(feature = "hash")]
+pub mod hash;
+#[cfg(feature = "ilp")]
+pub mod ilp;
+pub mod iter;
+pub mod math;
+pub mod memo;
+pub mod optim;
+pub mod parallel;
+pub mod poly;
+pub mod prelude;
+pub mod ranges;
 pub mod reader;
+pub mod rng;
+#[cfg(feature = "sat")]
+pub mod sat;
+pub mod search;
+pub mod spatial;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+pub mod strings;
+pub mod term;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod timer;
+pub mod trace;
+pub mod vm;
 pub mod writer;
 
-use std::time::Duration;
+use std::{marker::PhantomData, time::Duration};
 
-use anyhow::Result;
-
-use crate::timer::{BenchmarkResult, measure_many};
-pub use crate::writer::Serializable;
+use crate::timer::{BenchmarkResult, measure_many, timed};
+pub use crate::{
+    error::{Error, Result},
+    writer::Serializable,
+};
 
 /// Get the root directory of the workspace by looking for Cargo.lock
 ///
@@ -18,13 +61,13 @@ pub use crate::writer::Serializable;
 /// # Errors
 /// This function will return an error if it cannot find the workspace root in
 /// any parent directory.
-fn get_workspace_root() -> Result<std::path::PathBuf> {
+pub fn get_workspace_root() -> Result<std::path::PathBuf> {
     let mut dir = std::env::current_dir()?;
     // Traverse up the directory tree until we find Cargo.lock,
     // which indicates the workspace root
     while !dir.join("Cargo.lock").exists() {
         if !dir.pop() {
-            anyhow::bail!("Could not find workspace root");
+            return Err(Error::NoWorkspaceRoot);
         }
     }
     Ok(dir)
@@ -78,3 +121,98 @@ impl<T: Solution> Benchmark for T {
         measure_many("Part 2", time_limit, move || puzzle.part2())
     }
 }
+
+/// Answers of both parts of a day, with how long parsing and each part took
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub answers: [String; 2],
+    /// Time spent parsing, on part 1 and on part 2
+    pub timings: [Duration; 3],
+}
+
+/// An object-safe view of a [`Solution`], so that days picked at runtime,
+/// e.g. from a web page, can be run through the same `dyn` value.
+pub trait DynSolution {
+    fn day(&self) -> u8;
+
+    /// Parse the input and solve both parts, timing every step
+    fn run(&self, example: bool) -> Run;
+
+    /// Parse the input and solve one part, or `None` for parts other than 1
+    /// and 2
+    fn solve(&self, example: bool, part: u8) -> Option<String>;
+
+    /// Benchmark parsing and both parts on the actual input
+    fn bench(&self, time_limit: Duration) -> [BenchmarkResult; 3];
+}
+
+/// The [`DynSolution`] of a [`Solution`] type
+pub struct Day<T>(PhantomData<T>);
+
+impl<T: Solution + 'static> Day<T> {
+    #[must_use]
+    pub fn boxed() -> Box<dyn DynSolution> {
+        Box::new(Self(PhantomData))
+    }
+}
+
+impl<T: Solution> DynSolution for Day<T> {
+    fn day(&self) -> u8 {
+        T::DAY
+    }
+
+    fn run(&self, example: bool) -> Run {
+        let (puzzle, parse) = timed(|| trace::parse(T::DAY, || T::parse(example)));
+        let (part1, time1) = timed(|| trace::part(T::DAY, 1, || puzzle.part1()));
+        let (part2, time2) = timed(|| trace::part(T::DAY, 2, || puzzle.part2()));
+        Run {
+            answers: [part1, part2],
+            timings: [parse, time1, time2],
+        }
+    }
+
+    fn solve(&self, example: bool, part: u8) -> Option<String> {
+        match part {
+            1 => Some(T::parse(example).part1()),
+            2 => Some(T::parse(example).part2()),
+            _ => None,
+        }
+    }
+
+    fn bench(&self, time_limit: Duration) -> [BenchmarkResult; 3] {
+        T::bench_all(time_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo(String);
+
+    impl Solution for Echo {
+        const DAY: u8 = 7;
+
+        fn parse(example: bool) -> Self {
+            Self(if example { "example" } else { "input" }.to_string())
+        }
+
+        fn part1(&self) -> String {
+            self.0.clone()
+        }
+
+        fn part2(&self) -> String {
+            self.0.to_uppercase()
+        }
+    }
+
+    #[test]
+    fn test_dyn_solution() {
+        let solutions = [Day::<Echo>::boxed()];
+        assert_eq!(solutions[0].day(), 7);
+        let run = solutions[0].run(true);
+        assert_eq!(run.answers, ["example", "EXAMPLE"]);
+        assert_eq!(solutions[0].solve(false, 2), Some("INPUT".to_string()));
+        assert_eq!(solutions[0].solve(false, 3), None);
+    }
+}
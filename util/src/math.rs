@@ -0,0 +1,11 @@
+//! Mathematical building blocks that show up every year
+
+pub mod gf2;
+pub mod matrix;
+mod modint;
+mod number_theory;
+mod u256;
+
+pub use modint::ModInt;
+pub use number_theory::{crt, extended_gcd, gcd, lcm, mod_inverse, mod_pow};
+pub use u256::U256;
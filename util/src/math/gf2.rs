@@ -0,0 +1,267 @@
+//! Linear algebra over GF(2), where addition is XOR and multiplication is AND.
+//!
+//! Systems like "which switches toggle the lights into this pattern" are
+//! linear over GF(2), so Gaussian elimination solves them directly instead of
+//! searching over subsets.
+
+use crate::bits::BitSet;
+
+/// A dense matrix over GF(2), stored as one bitset per row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitMatrix {
+    cols: usize,
+    rows: Vec<BitSet>,
+}
+
+/// The set of all solutions of a linear system: a particular solution plus any
+/// combination of the null space basis vectors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AffineSpace {
+    pub particular: BitSet,
+    pub basis: Vec<BitSet>,
+}
+
+/// Reduce `rows` to reduced row echelon form in place, considering only the
+/// first `cols` columns as pivot candidates.
+///
+/// Returns the pivot column of each of the leading rows, so its length is the
+/// rank of the matrix.
+fn eliminate(rows: &mut [BitSet], cols: usize) -> Vec<usize> {
+    let mut pivots = Vec::new();
+    for col in 0..cols {
+        let rank = pivots.len();
+        if rank == rows.len() {
+            break;
+        }
+        let Some(found) = (rank..rows.len()).find(|&r| rows[r].contains(col)) else {
+            continue;
+        };
+        rows.swap(rank, found);
+        let pivot = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && row.contains(col) {
+                *row ^= &pivot;
+            }
+        }
+        pivots.push(col);
+    }
+    pivots
+}
+
+/// Read the null space basis off a matrix in reduced row echelon form: one
+/// vector per free column.
+fn kernel(rows: &[BitSet], pivots: &[usize], cols: usize) -> Vec<BitSet> {
+    let mut is_pivot = vec![false; cols];
+    for &p in pivots {
+        is_pivot[p] = true;
+    }
+    (0..cols)
+        .filter(|&free| !is_pivot[free])
+        .map(|free| {
+            let mut v = BitSet::new(cols);
+            v.insert(free);
+            for (row, &p) in rows.iter().zip(pivots) {
+                if row.contains(free) {
+                    v.insert(p);
+                }
+            }
+            v
+        })
+        .collect()
+}
+
+impl BitMatrix {
+    /// Create a zero matrix of the given size
+    #[must_use]
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            cols,
+            rows: vec![BitSet::new(cols); rows],
+        }
+    }
+
+    /// Create a matrix whose entry at `(r, c)` is `f(r, c)`
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> bool) -> Self {
+        let mut matrix = Self::new(rows, cols);
+        for (r, row) in matrix.rows.iter_mut().enumerate() {
+            for c in (0..cols).filter(|&c| f(r, c)) {
+                row.insert(c);
+            }
+        }
+        matrix
+    }
+
+    /// Number of rows
+    #[must_use]
+    pub const fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Number of columns
+    #[must_use]
+    pub const fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The entry at `(r, c)`
+    #[must_use]
+    pub fn get(&self, r: usize, c: usize) -> bool {
+        self.rows[r].contains(c)
+    }
+
+    /// Set the entry at `(r, c)`
+    pub fn set(&mut self, r: usize, c: usize, value: bool) {
+        if value {
+            self.rows[r].insert(c);
+        } else {
+            self.rows[r].remove(c);
+        }
+    }
+
+    /// Multiply by the column vector `x`, which must have `cols` bits
+    #[must_use]
+    pub fn mul_vec(&self, x: &BitSet) -> BitSet {
+        let mut result = BitSet::new(self.rows.len());
+        for (r, row) in self.rows.iter().enumerate() {
+            if (row & x).count_ones() % 2 == 1 {
+                result.insert(r);
+            }
+        }
+        result
+    }
+
+    /// Rank of the matrix
+    #[must_use]
+    pub fn rank(&self) -> usize {
+        eliminate(&mut self.rows.clone(), self.cols).len()
+    }
+
+    /// A basis of the null space, i.e. all `x` such that `A x = 0`
+    #[must_use]
+    pub fn null_space(&self) -> Vec<BitSet> {
+        let mut rows = self.rows.clone();
+        let pivots = eliminate(&mut rows, self.cols);
+        kernel(&rows, &pivots, self.cols)
+    }
+
+    /// Solve `A x = b` where `b` has one bit per row.
+    ///
+    /// Returns `None` if the system is inconsistent, otherwise the full space
+    /// of solutions.
+    #[must_use]
+    pub fn solve(&self, b: &BitSet) -> Option<AffineSpace> {
+        // Augment each row with its right-hand side bit in column `cols`
+        let mut rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                let mut augmented = BitSet::from_indices(self.cols + 1, row.iter_ones());
+                if b.contains(r) {
+                    augmented.insert(self.cols);
+                }
+                augmented
+            })
+            .collect::<Vec<_>>();
+        let pivots = eliminate(&mut rows, self.cols);
+        // A zero row with a non-zero right-hand side means 0 = 1
+        if rows[pivots.len()..]
+            .iter()
+            .any(|row| row.contains(self.cols))
+        {
+            return None;
+        }
+        let mut particular = BitSet::new(self.cols);
+        for (row, &p) in rows.iter().zip(&pivots) {
+            if row.contains(self.cols) {
+                particular.insert(p);
+            }
+        }
+        let basis = kernel(&rows, &pivots, self.cols);
+        Some(AffineSpace { particular, basis })
+    }
+}
+
+impl AffineSpace {
+    /// Dimension of the solution space, so there are `2^dimension` solutions
+    #[must_use]
+    pub const fn dimension(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// Enumerate all solutions in Gray code order, so each one differs from the
+    /// previous by a single basis vector.
+    ///
+    /// This yields `2^dimension` items, so it is only practical for small null
+    /// spaces.
+    pub fn iter(&self) -> impl Iterator<Item = BitSet> + '_ {
+        let mut current = self.particular.clone();
+        let total = 1_usize
+            .checked_shl(u32::try_from(self.basis.len()).unwrap_or(u32::MAX))
+            .unwrap_or_else(|| panic!("Too many solutions to enumerate"));
+        (0..total).map(move |i| {
+            if i > 0 {
+                current ^= &self.basis[i.trailing_zeros() as usize];
+            }
+            current.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Matrix with one column per switch, each toggling the given rows
+    fn switches(rows: usize, columns: &[&[usize]]) -> BitMatrix {
+        BitMatrix::from_fn(rows, columns.len(), |r, c| columns[c].contains(&r))
+    }
+
+    #[test]
+    fn test_rank_and_null_space() {
+        let identity = BitMatrix::from_fn(70, 70, |r, c| r == c);
+        assert_eq!(identity.rank(), 70);
+        assert!(identity.null_space().is_empty());
+        assert_eq!(BitMatrix::new(3, 5).rank(), 0);
+
+        // The third column is the sum of the first two
+        let m = switches(3, &[&[0, 1], &[1, 2], &[0, 2], &[2]]);
+        assert_eq!(m.rank(), 3);
+        let kernel = m.null_space();
+        assert_eq!(kernel.len(), 1);
+        assert_eq!(kernel[0].iter_ones().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(m.mul_vec(&kernel[0]).is_empty());
+    }
+
+    #[test]
+    fn test_solve() {
+        let m = switches(4, &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]]);
+        let b = BitSet::from_indices(4, [1, 2]);
+        let solutions = m
+            .solve(&b)
+            .unwrap_or_else(|| panic!("System should be consistent"));
+        assert_eq!(solutions.dimension(), 6 - m.rank());
+        let all = solutions.iter().collect::<Vec<_>>();
+        assert_eq!(all.len(), 4);
+        assert!(all.iter().all(|x| m.mul_vec(x) == b));
+        // Brute force over all subsets of columns agrees on the count
+        let brute = (0..1_usize << 6)
+            .filter(|mask| {
+                m.mul_vec(&BitSet::from_indices(
+                    6,
+                    (0..6).filter(|i| mask >> i & 1 == 1),
+                )) == b
+            })
+            .count();
+        assert_eq!(brute, all.len());
+        // Minimum number of switches is 2, e.g. toggling rows 1, 3 and 2, 3
+        assert_eq!(all.iter().map(BitSet::count_ones).min(), Some(2));
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        let m = switches(2, &[&[0, 1], &[0, 1]]);
+        assert!(m.solve(&BitSet::from_indices(2, [0])).is_none());
+        assert!(m.solve(&BitSet::from_indices(2, [0, 1])).is_some());
+    }
+}
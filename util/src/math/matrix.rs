@@ -0,0 +1,359 @@
+//! Square matrices with fast exponentiation, and linear recurrences.
+//!
+//! Simulating a linear process for an astronomical number of steps boils down
+//! to raising its transition matrix to a power, or, for a single linear
+//! recurrence, to Kitamasa's method which avoids the matrix altogether.
+
+use std::{
+    num::Wrapping,
+    ops::{Add, Index, IndexMut, Mul},
+};
+
+use super::{ModInt, U256};
+use crate::{Error, Result};
+
+/// The arithmetic needed for matrix products: addition, multiplication and
+/// their identities.
+///
+/// Plain integers panic on overflow in debug builds, so use [`ModInt`] or
+/// [`Wrapping`] when values grow large.
+pub trait Ring: Copy + PartialEq + Add<Output = Self> + Mul<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_ring {
+    ($($t:ty),*) => {
+        $(
+            impl Ring for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+
+            impl Ring for Wrapping<$t> {
+                const ZERO: Self = Wrapping(0);
+                const ONE: Self = Wrapping(1);
+            }
+        )*
+    };
+}
+
+impl_ring!(u32, u64, u128, i32, i64, i128);
+
+impl<const M: u64> Ring for ModInt<M> {
+    const ONE: Self = Self::new(1);
+    const ZERO: Self = Self::new(0);
+}
+
+impl Ring for U256 {
+    const ONE: Self = Self::ONE;
+    const ZERO: Self = Self::ZERO;
+}
+
+/// A square matrix of size `N` known at compile time, stored inline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SquareMatrix<T, const N: usize>(pub [[T; N]; N]);
+
+impl<T: Ring, const N: usize> SquareMatrix<T, N> {
+    /// The all-zero matrix
+    #[must_use]
+    pub const fn zero() -> Self {
+        Self([[T::ZERO; N]; N])
+    }
+
+    /// The identity matrix
+    #[must_use]
+    pub fn identity() -> Self {
+        let mut m = Self::zero();
+        for i in 0..N {
+            m.0[i][i] = T::ONE;
+        }
+        m
+    }
+
+    /// Raise to the power `exp` by repeated squaring
+    #[must_use]
+    pub fn pow(self, exp: u64) -> Self {
+        pow(self, Self::identity(), exp)
+    }
+
+    /// Multiply by the column vector `v`
+    #[must_use]
+    pub fn mul_vec(&self, v: &[T; N]) -> [T; N] {
+        self.0
+            .map(|row| row.iter().zip(v).fold(T::ZERO, |acc, (&a, &b)| acc + a * b))
+    }
+}
+
+impl<T: Ring, const N: usize> Mul for SquareMatrix<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = Self::zero();
+        for i in 0..N {
+            for k in 0..N {
+                let a = self.0[i][k];
+                for j in 0..N {
+                    result.0[i][j] = result.0[i][j] + a * rhs.0[k][j];
+                }
+            }
+        }
+        result
+    }
+}
+
+/// A square matrix whose size is only known at runtime, stored row-major
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    size: usize,
+    data: Vec<T>,
+}
+
+impl<T: Ring> Matrix<T> {
+    /// The all-zero matrix of the given size
+    #[must_use]
+    pub fn zero(size: usize) -> Self {
+        Self {
+            size,
+            data: vec![T::ZERO; size * size],
+        }
+    }
+
+    /// The identity matrix of the given size
+    #[must_use]
+    pub fn identity(size: usize) -> Self {
+        let mut m = Self::zero(size);
+        for i in 0..size {
+            m[(i, i)] = T::ONE;
+        }
+        m
+    }
+
+    /// Build a matrix from its rows, which must form a square
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let size = rows.len();
+        if let Some(row) = rows.iter().find(|row| row.len() != size) {
+            return Err(Error::SizeMismatch {
+                what: "columns in a row of a square matrix",
+                expected: size,
+                found: row.len(),
+            });
+        }
+        Ok(Self {
+            size,
+            data: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Number of rows (and columns)
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Raise to the power `exp` by repeated squaring
+    #[must_use]
+    pub fn pow(&self, exp: u64) -> Self {
+        pow(self.clone(), Self::identity(self.size), exp)
+    }
+
+    /// Multiply by the column vector `v`, which must have `size` entries
+    #[must_use]
+    pub fn mul_vec(&self, v: &[T]) -> Vec<T> {
+        self.data
+            .chunks(self.size.max(1))
+            .take(self.size)
+            .map(|row| row.iter().zip(v).fold(T::ZERO, |acc, (&a, &b)| acc + a * b))
+            .collect()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        &self.data[r * self.size + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Self::Output {
+        &mut self.data[r * self.size + c]
+    }
+}
+
+impl<T: Ring> Mul for Matrix<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T: Ring> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.size, rhs.size, "Matrix sizes must match");
+        let n = self.size;
+        let mut result = Matrix::zero(n);
+        for i in 0..n {
+            for k in 0..n {
+                let a = self[(i, k)];
+                for j in 0..n {
+                    result[(i, j)] = result[(i, j)] + a * rhs[(k, j)];
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Binary exponentiation starting from the multiplicative identity
+fn pow<M: Clone + Mul<Output = M>>(mut base: M, identity: M, mut exp: u64) -> M {
+    let mut result = identity;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base.clone();
+        }
+        exp >>= 1;
+        // Skip the last squaring, which could overflow plain integers
+        if exp > 0 {
+            base = base.clone() * base;
+        }
+    }
+    result
+}
+
+/// Compute the `n`-th term (0-based) of the linear recurrence
+/// `a[k + d] = coeffs[0] * a[k + d - 1] + ... + coeffs[d - 1] * a[k]`, whose
+/// first `d` terms are `initial`.
+///
+/// Uses Kitamasa's method: reduce `x^n` modulo the characteristic polynomial,
+/// then combine the initial terms with the remainder's coefficients. This takes
+/// `O(d^2 log n)` instead of the `O(d^3 log n)` of matrix exponentiation.
+pub fn linear_recurrence<T: Ring>(coeffs: &[T], initial: &[T], n: u64) -> Result<T> {
+    let d = coeffs.len();
+    if initial.len() != d {
+        return Err(Error::SizeMismatch {
+            what: "initial terms",
+            expected: d,
+            found: initial.len(),
+        });
+    }
+    if let Some(&term) = usize::try_from(n).ok().and_then(|n| initial.get(n)) {
+        return Ok(term);
+    }
+    if d == 0 {
+        return Ok(T::ZERO);
+    }
+    // Reduce a polynomial modulo x^d - coeffs[0] x^(d-1) - ... - coeffs[d-1]
+    let reduce = |mut poly: Vec<T>| {
+        for k in (d..poly.len()).rev() {
+            let top = poly[k];
+            for (i, &c) in coeffs.iter().enumerate() {
+                poly[k - 1 - i] = poly[k - 1 - i] + top * c;
+            }
+        }
+        poly.truncate(d);
+        poly.resize(d, T::ZERO);
+        poly
+    };
+    let mul_mod = |a: &[T], b: &[T]| {
+        let mut product = vec![T::ZERO; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                product[i + j] = product[i + j] + x * y;
+            }
+        }
+        reduce(product)
+    };
+    let mut result = reduce(vec![T::ONE]);
+    let mut base = reduce(vec![T::ZERO, T::ONE]);
+    let mut exp = n;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(&result, &base);
+        }
+        base = mul_mod(&base, &base);
+        exp >>= 1;
+    }
+    Ok(result
+        .iter()
+        .zip(initial)
+        .fold(T::ZERO, |acc, (&r, &a)| acc + r * a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Mod = ModInt<1_000_000_007>;
+
+    #[test]
+    fn test_fixed_matrix() {
+        let fib = SquareMatrix([[1u64, 1], [1, 0]]);
+        assert_eq!(fib.pow(0), SquareMatrix::identity());
+        assert_eq!(fib.pow(10).0[0][1], 55);
+        assert_eq!(fib.pow(90).mul_vec(&[1, 0])[1], 2_880_067_194_370_816_120);
+        let a = SquareMatrix([[1, 2], [3, 4]]);
+        let b = SquareMatrix([[0, 1], [1, 0]]);
+        assert_eq!(a * b, SquareMatrix([[2, 1], [4, 3]]));
+        // Wrapping arithmetic for values past u64::MAX
+        let wrapping = SquareMatrix([[Wrapping(1u64), Wrapping(1)], [Wrapping(1), Wrapping(0)]]);
+        assert_eq!(
+            wrapping.pow(100).0[0][1],
+            Wrapping(3_736_710_778_780_434_371)
+        );
+    }
+
+    #[test]
+    fn test_dynamic_matrix() -> Result<()> {
+        let m = Matrix::from_rows(vec![
+            vec![Mod::new(1), Mod::new(1)],
+            vec![Mod::new(1), Mod::new(0)],
+        ])?;
+        assert_eq!(m.size(), 2);
+        assert_eq!(m.pow(10)[(0, 1)], Mod::new(55));
+        assert_eq!(&m.pow(3) * &m.pow(4), m.pow(7));
+        assert_eq!(
+            m.pow(5).mul_vec(&[Mod::new(1), Mod::new(0)])[1],
+            Mod::new(5)
+        );
+        assert!(Matrix::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+        assert_eq!(
+            Matrix::<u64>::identity(3).mul_vec(&[4, 5, 6]),
+            vec![4, 5, 6]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_linear_recurrence() -> Result<()> {
+        // Fibonacci
+        let (coeffs, initial) = ([1u64, 1], [0, 1]);
+        for n in 0..50 {
+            let expected = SquareMatrix([[1, 1], [1, 0]]).pow(n).0[0][1];
+            assert_eq!(linear_recurrence(&coeffs, &initial, n)?, expected);
+        }
+        // Tribonacci against direct simulation
+        let mut terms = vec![0u64, 0, 1];
+        while terms.len() < 60 {
+            let k = terms.len();
+            terms.push(terms[k - 1] + terms[k - 2] + terms[k - 3]);
+        }
+        for (n, &term) in (0..).zip(&terms) {
+            assert_eq!(linear_recurrence(&[1, 1, 1], &[0, 0, 1], n)?, term);
+        }
+        // Huge index agrees with matrix exponentiation
+        let n = 1_000_000_000_000_000;
+        let one = Mod::new(1);
+        let fib = SquareMatrix([[one, one], [one, Mod::new(0)]]).pow(n).0[0][1];
+        assert_eq!(linear_recurrence(&[one, one], &[Mod::new(0), one], n)?, fib);
+        // Degenerate recurrences
+        assert_eq!(linear_recurrence(&[3u64], &[2], 4)?, 162);
+        assert_eq!(linear_recurrence::<u64>(&[], &[], 4)?, 0);
+        assert!(linear_recurrence(&[1u64, 1], &[0], 4).is_err());
+        Ok(())
+    }
+}
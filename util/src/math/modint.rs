@@ -0,0 +1,143 @@
+//! Integers modulo a compile-time constant.
+
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use super::number_theory::{mod_inverse, mod_pow};
+
+/// An integer modulo `M`, always kept reduced to `[0, M)`.
+///
+/// Products are computed in `u128`, so any modulus up to `u64::MAX` is safe.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    /// Reduce `value` modulo `M`
+    #[must_use]
+    pub const fn new(value: u64) -> Self {
+        Self(value % M)
+    }
+
+    /// The reduced value in `[0, M)`
+    #[must_use]
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Raise to the power `exp`
+    #[must_use]
+    pub const fn pow(self, exp: u64) -> Self {
+        Self(mod_pow(self.0, exp, M))
+    }
+
+    /// Multiplicative inverse, if the value is coprime with `M`
+    #[must_use]
+    pub fn inverse(self) -> Option<Self> {
+        let inv = mod_inverse(self.0.into(), M.into())?;
+        u64::try_from(inv).ok().map(Self)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        // Subtract instead of adding to avoid overflowing for large moduli
+        if self.0 >= M - rhs.0 {
+            Self(self.0 - (M - rhs.0))
+        } else {
+            Self(self.0 + rhs.0)
+        }
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        if self.0 == 0 { self } else { Self(M - self.0) }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let product = u128::from(self.0) * u128::from(rhs.0) % u128::from(M);
+        // The product is reduced modulo a u64, so it always fits
+        #[allow(clippy::cast_possible_truncation)]
+        Self(product as u64)
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Mod7 = ModInt<7>;
+    type Big = ModInt<{ u64::MAX - 58 }>;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Mod7::new(12);
+        assert_eq!(a.value(), 5);
+        assert_eq!(a + Mod7::new(4), Mod7::new(2));
+        assert_eq!(a - Mod7::new(6), Mod7::new(6));
+        assert_eq!(a * Mod7::new(3), Mod7::new(1));
+        assert_eq!(-Mod7::new(0), Mod7::new(0));
+        assert_eq!(a.pow(6), Mod7::new(1));
+        assert_eq!(a.inverse(), Some(Mod7::new(3)));
+        assert_eq!(ModInt::<4>::new(2).inverse(), None);
+        assert_eq!(a.to_string(), "5");
+
+        // No overflow close to u64::MAX
+        let big = Big::new(u64::MAX - 59);
+        assert_eq!(big + big, Big::new(u64::MAX - 60));
+        assert_eq!(big * big, Big::new(1));
+        let mut acc = big;
+        acc -= Big::new(1);
+        acc *= Big::new(2);
+        acc += Big::new(4);
+        assert_eq!(acc, Big::new(0));
+    }
+}
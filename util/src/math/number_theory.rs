@@ -0,0 +1,226 @@
+//! Elementary number theory: divisibility, modular arithmetic, and the Chinese
+//! Remainder Theorem.
+
+/// Greatest common divisor, with `gcd(0, 0) = 0`
+#[must_use]
+pub const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it does not fit in a `u64`
+///
+/// `lcm(0, x)` is defined as 0.
+#[must_use]
+pub const fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Extended Euclidean algorithm.
+///
+/// Returns `(g, x, y)` such that `a * x + b * y = g`, where `g` is the
+/// (non-negative) greatest common divisor of `a` and `b`.
+#[must_use]
+pub const fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut prev_rem, mut rem) = (a, b);
+    let (mut prev_coef_a, mut coef_a) = (1, 0);
+    let (mut prev_coef_b, mut coef_b) = (0, 1);
+    while rem != 0 {
+        let quot = prev_rem / rem;
+        (prev_rem, rem) = (rem, prev_rem - quot * rem);
+        (prev_coef_a, coef_a) = (coef_a, prev_coef_a - quot * coef_a);
+        (prev_coef_b, coef_b) = (coef_b, prev_coef_b - quot * coef_b);
+    }
+    if prev_rem < 0 {
+        (-prev_rem, -prev_coef_a, -prev_coef_b)
+    } else {
+        (prev_rem, prev_coef_a, prev_coef_b)
+    }
+}
+
+/// Multiplicative inverse of `a` modulo `modulus`, in `[0, modulus)`.
+///
+/// Returns `None` if `modulus` is not positive or `a` is not coprime with it.
+#[must_use]
+pub const fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g == 1 {
+        Some(x.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+/// Compute `base^exp mod modulus` by repeated squaring.
+///
+/// Intermediate products are computed in `u128`, so any `u64` modulus is safe.
+#[must_use]
+pub const fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "Modulus must be positive");
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    // The result is reduced modulo a u64, so it always fits
+    #[allow(clippy::cast_possible_truncation)]
+    {
+        result as u64
+    }
+}
+
+/// Solve a system of congruences `x = r_i (mod m_i)` given as `(r_i, m_i)`
+/// pairs, using the Chinese Remainder Theorem.
+///
+/// The moduli do not need to be pairwise coprime. Returns `(x, m)` where `m`
+/// is the least common multiple of all moduli and `x` is the unique solution
+/// in `[0, m)`, or `None` if the system is inconsistent, a modulus is not
+/// positive, or `m` overflows an `i128`.
+#[must_use]
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |(x, m): (i128, i128), &(residue, modulus)| {
+            if modulus <= 0 {
+                return None;
+            }
+            // Find the shift such that x + m * shift = residue (mod modulus)
+            let diff = residue.rem_euclid(modulus) - x;
+            let (g, inv, _) = extended_gcd(m, modulus);
+            if diff % g != 0 {
+                return None;
+            }
+            let step = modulus / g;
+            let shift = (diff / g % step).checked_mul(inv % step)?.rem_euclid(step);
+            let lcm = m.checked_mul(step)?;
+            let x = m.checked_mul(shift)?.checked_add(x)?.rem_euclid(lcm);
+            Some((x, lcm))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm(1 << 40, 1 << 50), Some(1 << 50));
+        for a in 0..60 {
+            for b in 0..60 {
+                let g = gcd(a, b);
+                assert_eq!(g, gcd(b, a));
+                if g > 0 {
+                    assert_eq!(a % g, 0);
+                    assert_eq!(b % g, 0);
+                    // No larger common divisor exists
+                    assert!(((g + 1)..=a.max(b)).all(|d| a % d != 0 || b % d != 0));
+                }
+                if let Some(l) = lcm(a, b) {
+                    assert_eq!(g * l, a * b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for a in -40i64..40 {
+            for b in -40i64..40 {
+                let (g, x, y) = extended_gcd(a.into(), b.into());
+                assert_eq!(i128::from(a) * x + i128::from(b) * y, g);
+                assert_eq!(
+                    u64::try_from(g).ok(),
+                    Some(gcd(a.unsigned_abs(), b.unsigned_abs()))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(2, 4), None);
+        assert_eq!(mod_inverse(2, 0), None);
+        for m in 1..50 {
+            for a in 0..m {
+                match mod_inverse(a, m) {
+                    Some(inv) => assert_eq!(a * inv % m, 1 % m),
+                    None => assert_ne!(extended_gcd(a, m).0, 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(5, 0, 7), 1);
+        // Fermat's little theorem on a large prime
+        let p = 1_000_000_007;
+        for a in 1..50 {
+            assert_eq!(mod_pow(a, p - 1, p), 1);
+        }
+        // Does not overflow with moduli close to u64::MAX
+        let m = u64::MAX - 58;
+        assert_eq!(mod_pow(m - 1, 2, m), 1);
+        for base in 0..12 {
+            for exp in 0..12 {
+                let naive = (0..exp).fold(1, |acc, _| acc * base % 97);
+                assert_eq!(mod_pow(base, exp, 97), naive);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Non-coprime moduli, consistent and inconsistent
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(1, 0)]), None);
+        // Negative residues are normalized
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        // Overflowing moduli
+        assert_eq!(crt(&[(0, i128::MAX), (0, i128::MAX - 1)]), None);
+        // Brute-force cross check
+        for m1 in 1u64..12 {
+            for m2 in 1u64..12 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let brute = (0..m1 * m2).find(|x| x % m1 == r1 && x % m2 == r2);
+                        let congruences = [(r1.into(), m1.into()), (r2.into(), m2.into())];
+                        match crt(&congruences) {
+                            Some((x, m)) => {
+                                assert_eq!(u64::try_from(x).ok(), brute);
+                                assert_eq!(u64::try_from(m).ok(), lcm(m1, m2));
+                            }
+                            None => assert_eq!(brute, None),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
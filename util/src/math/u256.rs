@@ -0,0 +1,245 @@
+//! A fixed-width 256-bit unsigned integer for counts that outgrow `u128`.
+
+use std::{
+    fmt::{self, Write},
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign},
+    str::FromStr,
+};
+
+use crate::{Error, Result};
+
+/// A 256-bit unsigned integer, stored as little-endian `u64` limbs.
+///
+/// Arithmetic panics on overflow like the primitive integers do in debug
+/// builds; use the `checked_*` methods to handle it instead.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct U256([u64; 4]);
+
+/// Largest power of 10 that fits in a `u64`, used to print 19 digits at a time
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+
+impl U256 {
+    pub const MAX: Self = Self([u64::MAX; 4]);
+    pub const ONE: Self = Self([1, 0, 0, 0]);
+    pub const ZERO: Self = Self([0; 4]);
+
+    /// Whether the value is zero
+    #[must_use]
+    pub fn is_zero(self) -> bool {
+        self == Self::ZERO
+    }
+
+    /// Addition, or `None` on overflow
+    #[must_use]
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let mut limbs = [0; 4];
+        let mut carry = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, c2) = sum.overflowing_add(u64::from(carry));
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        (!carry).then_some(Self(limbs))
+    }
+
+    /// Multiplication, or `None` on overflow
+    #[must_use]
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let mut limbs = [0; 4];
+        for (i, &a) in self.0.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in rhs.0.iter().enumerate() {
+                let product = u128::from(a) * u128::from(b) + carry;
+                if i + j < 4 {
+                    let sum = u128::from(limbs[i + j]) + (product & u128::from(u64::MAX));
+                    limbs[i + j] = low(sum);
+                    carry = (product >> 64) + (sum >> 64);
+                } else if product != 0 {
+                    return None;
+                } else {
+                    carry = 0;
+                }
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(Self(limbs))
+    }
+
+    /// Divide by a small divisor, returning the quotient and remainder
+    #[must_use]
+    pub fn div_rem_u64(self, divisor: u64) -> (Self, u64) {
+        assert_ne!(divisor, 0, "Division by zero");
+        let mut limbs = [0; 4];
+        let mut rem = 0u128;
+        for i in (0..4).rev() {
+            let current = (rem << 64) | u128::from(self.0[i]);
+            limbs[i] = low(current / u128::from(divisor));
+            rem = current % u128::from(divisor);
+        }
+        (Self(limbs), low(rem))
+    }
+}
+
+/// The lower 64 bits of a `u128`
+#[allow(clippy::cast_possible_truncation)]
+const fn low(value: u128) -> u64 {
+    value as u64
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        Self([value, 0, 0, 0])
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        Self([low(value), low(value >> 64), 0, 0])
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Compare from the most significant limb
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for U256 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .unwrap_or_else(|| panic!("U256 addition overflowed"))
+    }
+}
+
+impl Mul for U256 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .unwrap_or_else(|| panic!("U256 multiplication overflowed"))
+    }
+}
+
+impl AddAssign for U256 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl MulAssign for U256 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Sum for U256 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut chunks = Vec::new();
+        let mut rest = *self;
+        loop {
+            let (quot, rem) = rest.div_rem_u64(DECIMAL_CHUNK);
+            chunks.push(rem);
+            rest = quot;
+            if rest.is_zero() {
+                break;
+            }
+        }
+        let mut chunks = chunks.iter().rev();
+        let mut digits = chunks.next().map(ToString::to_string).unwrap_or_default();
+        for chunk in chunks {
+            write!(digits, "{chunk:019}")?;
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl FromStr for U256 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: String| Error::Parse {
+            what: "U256",
+            text: s.to_string(),
+            reason,
+        };
+        if s.is_empty() {
+            return Err(invalid("empty string".to_string()));
+        }
+        s.chars().try_fold(Self::ZERO, |acc, c| {
+            let digit = c
+                .to_digit(10)
+                .ok_or_else(|| invalid(format!("invalid digit {c}")))?;
+            acc.checked_mul(10u64.into())
+                .and_then(|acc| acc.checked_add(u64::from(digit).into()))
+                .ok_or_else(|| invalid("out of range".to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = U256::from(u128::MAX);
+        let b = a + U256::ONE;
+        assert_eq!(b.0, [0, 0, 1, 0]);
+        assert_eq!(
+            (a * a).to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(U256::MAX.checked_add(U256::ONE), None);
+        assert_eq!(b.checked_mul(b), None);
+        assert_eq!(U256::MAX.checked_mul(U256::ONE), Some(U256::MAX));
+        assert_eq!(U256::ZERO.checked_mul(U256::MAX), Some(U256::ZERO));
+        let mut c = U256::from(3u64);
+        c *= U256::from(5u64);
+        c += U256::ONE;
+        assert_eq!(c, U256::from(16u64));
+        assert_eq!([c, c, c].into_iter().sum::<U256>(), U256::from(48u64));
+        assert!(U256::from(u128::MAX) < b);
+        assert!(U256([0, 0, 0, 1]) > U256([u64::MAX, u64::MAX, u64::MAX, 0]));
+    }
+
+    #[test]
+    fn test_display_and_parse() -> Result<()> {
+        assert_eq!(U256::ZERO.to_string(), "0");
+        assert_eq!(
+            U256::from(DECIMAL_CHUNK).to_string(),
+            "10000000000000000000"
+        );
+        assert_eq!(format!("{:>5}", U256::from(42u64)), "   42");
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(U256::MAX.to_string(), max);
+        assert_eq!(max.parse::<U256>()?, U256::MAX);
+        // Agree with u128 on its whole range
+        for v in [0, 1, 9, 10, u128::from(u64::MAX), u128::MAX / 3, u128::MAX] {
+            assert_eq!(U256::from(v).to_string(), v.to_string());
+            assert_eq!(v.to_string().parse::<U256>()?, U256::from(v));
+        }
+        assert!("".parse::<U256>().is_err());
+        assert!("12a".parse::<U256>().is_err());
+        assert!(format!("{max}0").parse::<U256>().is_err());
+        Ok(())
+    }
+}
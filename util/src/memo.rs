@@ -0,0 +1,243 @@
+//! Memoization for recursive solvers
+
+mod lru;
+
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+pub use lru::LruCache;
+
+/// Storage backing a [`Memo`] table
+pub trait Cache<K, V> {
+    /// Look up `key` as a use of the entry, e.g. refreshing its recency
+    fn lookup(&mut self, key: &K) -> Option<&V>;
+
+    /// Look up `key` without counting it as a use
+    fn peek(&self, key: &K) -> Option<&V>;
+
+    /// Cache `value` under `key`, possibly evicting other entries
+    fn store(&mut self, key: K, value: V);
+
+    /// Number of cached entries
+    fn len(&self) -> usize;
+
+    /// Whether nothing is cached
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all cached entries
+    fn clear(&mut self);
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Cache<K, V> for HashMap<K, V, S> {
+    fn lookup(&mut self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+}
+
+/// A memoization table for a function of `A`, cached under keys of type `K`.
+///
+/// The key is extracted from the argument by a user-provided function, which
+/// allows compressing heavy arguments (e.g. packing a slice of small counts
+/// into a single integer) instead of hashing them as-is.
+///
+/// Values are kept in a [`HashMap`] by default, which grows without bound.
+/// Use [`Memo::with_cache`] with an [`LruCache`] to bound memory instead.
+pub struct Memo<A: ?Sized, K, V, C = HashMap<K, V>> {
+    key: fn(&A) -> K,
+    cache: C,
+    capacity: Option<usize>,
+    _value: PhantomData<V>,
+}
+
+impl<A: ?Sized, K: Eq + Hash, V> Memo<A, K, V> {
+    /// Create an empty, unbounded memo table using `key` to extract cache keys
+    #[must_use]
+    pub fn new(key: fn(&A) -> K) -> Self {
+        Self::with_cache(key, HashMap::new())
+    }
+}
+
+impl<A: ?Sized, K, V, C: Cache<K, V>> Memo<A, K, V, C> {
+    /// Create a memo table storing values in `cache`, using `key` to extract
+    /// cache keys
+    #[must_use]
+    pub const fn with_cache(key: fn(&A) -> K, cache: C) -> Self {
+        Self {
+            key,
+            cache,
+            capacity: None,
+            _value: PhantomData,
+        }
+    }
+
+    /// The underlying cache, e.g. to inspect its statistics
+    #[must_use]
+    pub const fn cache(&self) -> &C {
+        &self.cache
+    }
+
+    /// Limit the number of cached entries. Once the limit is reached, newly
+    /// computed values are returned but no longer cached.
+    #[must_use]
+    pub const fn with_capacity_limit(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Number of cached entries
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Whether nothing has been cached yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Drop all cached entries
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Look up the cached value for `arg`
+    pub fn get(&self, arg: &A) -> Option<&V> {
+        self.cache.peek(&(self.key)(arg))
+    }
+
+    /// Cache `value` as the result for `arg`, unless the capacity limit has
+    /// been reached.
+    pub fn insert(&mut self, arg: &A, value: V) {
+        let key = (self.key)(arg);
+        self.store(key, value);
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        if self
+            .capacity
+            .is_none_or(|cap| self.cache.len() < cap || self.cache.peek(&key).is_some())
+        {
+            self.cache.store(key, value);
+        }
+    }
+
+    /// Return the cached value for `arg`, or compute and cache it.
+    ///
+    /// `compute` receives the memo table itself, so it can recurse into
+    /// subproblems through the same cache.
+    pub fn get_or_compute(&mut self, arg: &A, compute: impl FnOnce(&mut Self) -> V) -> V
+    where
+        V: Clone,
+    {
+        let key = (self.key)(arg);
+        if let Some(value) = self.cache.lookup(&key) {
+            return value.clone();
+        }
+        let value = compute(self);
+        self.store(key, value.clone());
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci(memo: &mut Memo<u64, u64, u64>, n: u64) -> u64 {
+        memo.get_or_compute(&n, |memo| {
+            if n < 2 {
+                n
+            } else {
+                fibonacci(memo, n - 1) + fibonacci(memo, n - 2)
+            }
+        })
+    }
+
+    #[test]
+    fn test_memo_recursion() {
+        let mut memo = Memo::new(Clone::clone);
+        assert_eq!(fibonacci(&mut memo, 90), 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(memo.get(&10), Some(&55));
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+
+    #[test]
+    fn test_memo_custom_key() {
+        // Slices of digits are packed into a single integer key
+        let mut memo: Memo<[u8], u64, usize> =
+            Memo::new(|digits| digits.iter().fold(0, |acc, &d| acc * 10 + u64::from(d)));
+        memo.insert(&[1, 2, 3], 6);
+        assert_eq!(memo.get(&[1, 2, 3]), Some(&6));
+        assert_eq!(memo.get(&[0, 1, 2, 3]), Some(&6));
+        assert_eq!(memo.get(&[3, 2, 1]), None);
+        let mut calls = 0;
+        let value = memo.get_or_compute(&[3, 2, 1], |_| {
+            calls += 1;
+            6
+        });
+        assert_eq!((value, calls), (6, 1));
+        let value = memo.get_or_compute(&[3, 2, 1], |_| {
+            calls += 1;
+            0
+        });
+        assert_eq!((value, calls), (6, 1));
+    }
+
+    #[test]
+    fn test_memo_capacity_limit() {
+        let mut memo = Memo::new(Clone::clone).with_capacity_limit(10);
+        assert_eq!(fibonacci(&mut memo, 30), 832_040);
+        assert_eq!(memo.len(), 10);
+        // Existing entries can still be updated when full
+        let key = (0..=30).find(|k| memo.get(k).is_some()).unwrap_or_default();
+        memo.insert(&key, 0);
+        assert_eq!(memo.get(&key), Some(&0));
+        memo.insert(&1000, 0);
+        assert_eq!(memo.get(&1000), None);
+    }
+
+    #[test]
+    fn test_memo_lru_cache() {
+        let mut memo = Memo::with_cache(Clone::clone, LruCache::new(4));
+        assert_eq!(fibonacci_lru(&mut memo, 90), 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 4);
+        // Only the most recent results are kept, and every `n - 2` was a hit
+        assert_eq!(memo.get(&90), Some(&2_880_067_194_370_816_120));
+        assert_eq!(memo.get(&10), None);
+        assert_eq!(memo.cache().hits(), 88);
+    }
+
+    fn fibonacci_lru(memo: &mut Memo<u64, u64, u64, LruCache<u64, u64>>, n: u64) -> u64 {
+        memo.get_or_compute(&n, |memo| {
+            if n < 2 {
+                n
+            } else {
+                fibonacci_lru(memo, n - 1) + fibonacci_lru(memo, n - 2)
+            }
+        })
+    }
+}
@@ -0,0 +1,275 @@
+//! A bounded cache evicting the least recently used entry.
+
+use std::{collections::HashMap, hash::Hash};
+
+use super::Cache;
+
+/// Marks the absence of a neighbor in the recency list
+const NIL: usize = usize::MAX;
+
+/// An entry of the recency list, linked by slot index
+#[derive(Clone, Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    /// More recently used neighbor
+    prev: usize,
+    /// Less recently used neighbor
+    next: usize,
+}
+
+/// A cache holding at most `capacity` entries, evicting the least recently
+/// used one to make room.
+///
+/// Entries live in a slab of slots forming a doubly linked list ordered by
+/// recency, with a hash map from keys to slots, so that every operation takes
+/// `O(1)`. Lookups through [`LruCache::get`] are counted as hits or misses, to
+/// help tuning the capacity.
+#[derive(Clone, Debug)]
+pub struct LruCache<K, V> {
+    slots: HashMap<K, usize>,
+    nodes: Vec<Node<K, V>>,
+    /// Most recently used slot
+    head: usize,
+    /// Least recently used slot
+    tail: usize,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    /// An empty cache holding up to `capacity` entries. A zero capacity
+    /// caches nothing.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: HashMap::new(),
+            nodes: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            capacity,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Maximum number of entries
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached entries
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Whether nothing is cached
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Number of lookups that found an entry
+    #[must_use]
+    pub const fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of lookups that found nothing
+    #[must_use]
+    pub const fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Report the statistics of the cache under `name`, as a debug event when
+    /// tracing
+    pub fn report(&self, name: &str) {
+        crate::trace::cache_stats(name, self.hits, self.misses, self.len());
+    }
+
+    /// Detach `slot` from the recency list
+    fn unlink(&mut self, slot: usize) {
+        let Node { prev, next, .. } = self.nodes[slot];
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+
+    /// Attach `slot` as the most recently used entry
+    fn push_front(&mut self, slot: usize) {
+        self.nodes[slot].prev = NIL;
+        self.nodes[slot].next = self.head;
+        match self.head {
+            NIL => self.tail = slot,
+            head => self.nodes[head].prev = slot,
+        }
+        self.head = slot;
+    }
+
+    /// Look up `key`, marking it as most recently used
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let Some(&slot) = self.slots.get(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        if slot != self.head {
+            self.unlink(slot);
+            self.push_front(slot);
+        }
+        Some(&self.nodes[slot].value)
+    }
+
+    /// Look up `key` without affecting recency or statistics
+    #[must_use]
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.slots.get(key).map(|&slot| &self.nodes[slot].value)
+    }
+
+    /// Whether `key` is cached, without affecting recency or statistics
+    #[must_use]
+    pub fn contains(&self, key: &K) -> bool {
+        self.slots.contains_key(key)
+    }
+
+    /// Cache `value` under `key` as the most recently used entry, returning
+    /// the entry evicted to make room, if any
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(&slot) = self.slots.get(&key) {
+            self.nodes[slot].value = value;
+            self.unlink(slot);
+            self.push_front(slot);
+            return None;
+        }
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        if self.nodes.len() < self.capacity {
+            self.slots.insert(key.clone(), self.nodes.len());
+            self.nodes.push(Node {
+                key,
+                value,
+                prev: NIL,
+                next: NIL,
+            });
+            self.push_front(self.nodes.len() - 1);
+            return None;
+        }
+        // Reuse the slot of the least recently used entry
+        let slot = self.tail;
+        self.unlink(slot);
+        self.slots.insert(key.clone(), slot);
+        let old_key = std::mem::replace(&mut self.nodes[slot].key, key);
+        let old_value = std::mem::replace(&mut self.nodes[slot].value, value);
+        self.slots.remove(&old_key);
+        self.push_front(slot);
+        Some((old_key, old_value))
+    }
+
+    /// Drop all entries, keeping the statistics
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.nodes.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Iterate over the entries from most to least recently used
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        std::iter::successors((self.head != NIL).then_some(self.head), |&slot| {
+            let next = self.nodes[slot].next;
+            (next != NIL).then_some(next)
+        })
+        .map(|slot| (&self.nodes[slot].key, &self.nodes[slot].value))
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> Cache<K, V> for LruCache<K, V> {
+    fn lookup(&mut self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.peek(key)
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eviction_order() {
+        let mut cache = LruCache::new(3);
+        for i in 0..3 {
+            assert_eq!(cache.insert(i, i * 10), None);
+        }
+        // Touching 0 makes 1 the least recently used
+        assert_eq!(cache.get(&0), Some(&0));
+        assert_eq!(cache.insert(3, 30), Some((1, 10)));
+        assert_eq!(cache.get(&1), None);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        // Updating an entry refreshes it without evicting anything
+        assert_eq!(cache.insert(2, 21), None);
+        assert_eq!(cache.insert(4, 40), Some((0, 0)));
+        let entries = cache.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>();
+        assert_eq!(entries, vec![(4, 40), (2, 21), (3, 30)]);
+        // Peeking does not count as a use
+        assert_eq!(cache.peek(&3), Some(&30));
+        assert_eq!(cache.insert(5, 50), Some((3, 30)));
+        assert_eq!(cache.len(), 3);
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.iter().count(), 0);
+        assert_eq!(cache.insert(6, 60), None);
+    }
+
+    #[test]
+    fn test_matches_naive_model() {
+        // Compare against a vector ordered by recency, on a deterministic
+        // pseudo-random workload
+        let mut cache = LruCache::new(5);
+        let mut model: Vec<(u32, u32)> = Vec::new();
+        let mut state = 12_345_u32;
+        for step in 0..2000 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let key = (state >> 16) % 9;
+            if step % 3 == 0 {
+                let position = model.iter().position(|&(k, _)| k == key);
+                let expected = position.map(|i| model.remove(i));
+                if let Some(entry) = expected {
+                    model.insert(0, entry);
+                }
+                assert_eq!(cache.get(&key), expected.map(|(_, v)| v).as_ref());
+            } else {
+                model.retain(|&(k, _)| k != key);
+                model.insert(0, (key, step));
+                let evicted = (model.len() > 5).then(|| model.remove(5));
+                assert_eq!(cache.insert(key, step), evicted);
+            }
+        }
+        let entries = cache.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>();
+        assert_eq!(entries, model);
+        assert_eq!(LruCache::<u8, u8>::new(0).insert(1, 1), Some((1, 1)));
+    }
+}
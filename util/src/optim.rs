@@ -0,0 +1,26 @@
+//! Local search metaheuristics for problems too large to solve exactly.
+//!
+//! A [`Problem`] describes how to create, score and perturb candidate states.
+//! The searches minimize its energy and are fully deterministic for a given
+//! seed, so that answers can be reproduced across runs.
+
+mod local_search;
+
+pub use local_search::{Best, Schedule, anneal, hill_climb, restarts};
+
+pub use crate::rng::Rng;
+
+/// An optimization problem over states of type [`Problem::State`]
+pub trait Problem {
+    /// A candidate solution
+    type State: Clone;
+
+    /// A state to start the search from
+    fn initial(&self, rng: &mut Rng) -> Self::State;
+
+    /// Score of `state`, where lower is better
+    fn energy(&self, state: &Self::State) -> f64;
+
+    /// A random small change of `state`
+    fn neighbor(&self, state: &Self::State, rng: &mut Rng) -> Self::State;
+}
@@ -0,0 +1,198 @@
+//! Hill climbing and simulated annealing.
+
+use super::{Problem, Rng};
+use crate::parallel::prelude::*;
+
+/// The best state found by a search
+#[derive(Clone, Debug, PartialEq)]
+pub struct Best<S> {
+    pub state: S,
+    pub energy: f64,
+}
+
+/// How the temperature of simulated annealing decreases over time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    /// Decrease by the same amount every iteration
+    Linear { start: f64, end: f64 },
+    /// Decrease by the same factor every iteration. Both temperatures must be
+    /// positive.
+    Geometric { start: f64, end: f64 },
+}
+
+impl Schedule {
+    /// Temperature after the given fraction of the iterations, in `[0, 1]`
+    #[must_use]
+    pub fn temperature(self, progress: f64) -> f64 {
+        match self {
+            Self::Linear { start, end } => (end - start).mul_add(progress, start),
+            Self::Geometric { start, end } => start * (end / start).powf(progress),
+        }
+    }
+}
+
+/// Greedily move to neighbors that are no worse, for `iterations` proposals.
+///
+/// Accepting equal energies lets the search drift across plateaus, but it
+/// still gets stuck in local minima; combine with [`restarts`] to escape them.
+pub fn hill_climb<P: Problem>(problem: &P, seed: u64, iterations: usize) -> Best<P::State> {
+    let mut rng = Rng::new(seed);
+    let mut state = problem.initial(&mut rng);
+    let mut energy = problem.energy(&state);
+    for _ in 0..iterations {
+        let candidate = problem.neighbor(&state, &mut rng);
+        let candidate_energy = problem.energy(&candidate);
+        if candidate_energy <= energy {
+            state = candidate;
+            energy = candidate_energy;
+        }
+    }
+    Best { state, energy }
+}
+
+/// Simulated annealing for `iterations` proposals, cooling as per `schedule`.
+///
+/// A worse neighbor is accepted with probability `exp(-delta / temperature)`,
+/// so that the search roams freely while hot and settles down as it cools.
+/// Returns the best state seen at any point, not the final one.
+#[allow(clippy::cast_precision_loss)]
+pub fn anneal<P: Problem>(
+    problem: &P,
+    seed: u64,
+    iterations: usize,
+    schedule: Schedule,
+) -> Best<P::State> {
+    let mut rng = Rng::new(seed);
+    let mut state = problem.initial(&mut rng);
+    let mut energy = problem.energy(&state);
+    let mut best = Best {
+        state: state.clone(),
+        energy,
+    };
+    for i in 0..iterations {
+        let temperature = schedule.temperature(i as f64 / iterations as f64);
+        let candidate = problem.neighbor(&state, &mut rng);
+        let candidate_energy = problem.energy(&candidate);
+        let delta = candidate_energy - energy;
+        let accept = delta <= 0.0 || temperature > 0.0 && rng.chance((-delta / temperature).exp());
+        if accept {
+            state = candidate;
+            energy = candidate_energy;
+            if energy < best.energy {
+                best = Best {
+                    state: state.clone(),
+                    energy,
+                };
+            }
+        }
+    }
+    best
+}
+
+/// Run `search` from `n` independent seeds derived from `seed` in parallel,
+/// keeping the best result. Ties go to the earliest restart, so the outcome
+/// does not depend on thread scheduling.
+///
+/// Returns `None` when `n` is zero.
+pub fn restarts<S: Send>(
+    n: usize,
+    seed: u64,
+    search: impl Fn(u64) -> Best<S> + Sync,
+) -> Option<Best<S>> {
+    let mut rng = Rng::new(seed);
+    let seeds = (0..n).map(|_| rng.next_u64()).collect::<Vec<_>>();
+    seeds
+        .maybe_into_par_iter()
+        .enumerate()
+        .map(|(i, seed)| (i, search(seed)))
+        .min_by(|(i, a), (j, b)| a.energy.total_cmp(&b.energy).then(i.cmp(j)))
+        .map(|(_, best)| best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Integers in `0..100` with a local minimum at every `10k + 5`, and the
+    /// global one at 35
+    struct Valleys;
+
+    impl Problem for Valleys {
+        type State = i64;
+
+        fn initial(&self, rng: &mut Rng) -> i64 {
+            i64::try_from(rng.below(100)).unwrap_or_default()
+        }
+
+        fn energy(&self, &x: &i64) -> f64 {
+            let e = (x % 10 - 5).abs() * 10 + (x / 10 - 3).abs();
+            f64::from(i32::try_from(e).unwrap_or(i32::MAX))
+        }
+
+        fn neighbor(&self, &x: &i64, rng: &mut Rng) -> i64 {
+            if rng.chance(0.5) {
+                (x + 1).min(99)
+            } else {
+                (x - 1).max(0)
+            }
+        }
+    }
+
+    /// Permutations of `0..n`, scored by how far each item is from its place
+    struct Sorting(usize);
+
+    impl Problem for Sorting {
+        type State = Vec<usize>;
+
+        fn initial(&self, rng: &mut Rng) -> Vec<usize> {
+            let mut items = (0..self.0).collect::<Vec<_>>();
+            rng.shuffle(&mut items);
+            items
+        }
+
+        fn energy(&self, state: &Vec<usize>) -> f64 {
+            let displacement = state
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| i.abs_diff(x))
+                .sum::<usize>();
+            f64::from(u32::try_from(displacement).unwrap_or(u32::MAX))
+        }
+
+        fn neighbor(&self, state: &Vec<usize>, rng: &mut Rng) -> Vec<usize> {
+            let mut state = state.clone();
+            let (i, j) = (rng.below(self.0), rng.below(self.0));
+            state.swap(i, j);
+            state
+        }
+    }
+
+    #[test]
+    fn test_hill_climb() {
+        let best = hill_climb(&Valleys, 1, 1000);
+        assert_eq!(best.state % 10, 5);
+        assert_eq!(best, hill_climb(&Valleys, 1, 1000));
+        let best = restarts(64, 7, |seed| hill_climb(&Valleys, seed, 1000))
+            .unwrap_or_else(|| panic!("At least one restart"));
+        assert_eq!(best.state, 35);
+        assert!(best.energy.abs() < f64::EPSILON);
+        assert!(restarts(0, 7, |seed| hill_climb(&Valleys, seed, 10)).is_none());
+    }
+
+    #[test]
+    fn test_anneal() {
+        let schedule = Schedule::Geometric {
+            start: 4.0,
+            end: 0.05,
+        };
+        let best = anneal(&Sorting(12), 3, 20_000, schedule);
+        assert_eq!(best.state, (0..12).collect::<Vec<_>>());
+        assert_eq!(best, anneal(&Sorting(12), 3, 20_000, schedule));
+        let linear = Schedule::Linear {
+            start: 2.0,
+            end: 0.0,
+        };
+        assert!((linear.temperature(0.5) - 1.0).abs() < 1e-12);
+        assert!((schedule.temperature(1.0) - 0.05).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,341 @@
+//! Iterators that run on rayon's thread pool with the `parallel` feature and
+//! sequentially without it, with the same code at the call site.
+//!
+//! Some days are so small that spreading them over threads costs more than it
+//! saves, and some targets have no threads at all. `use
+//! util::parallel::prelude::*` brings in the `maybe_*` methods, along with
+//! rayon's traits or, without the feature, sequential stand-ins for the
+//! rayon-only methods the days use.
+
+use ndarray::{Dimension, NdProducer, Zip};
+#[cfg(feature = "parallel")]
+use rayon::{iter::IterBridge, prelude::*};
+
+#[cfg(not(feature = "parallel"))]
+use crate::iter::{Pairs, pairs};
+#[cfg(feature = "parallel")]
+use crate::iter::{ParPairs, par_pairs};
+
+/// Whether the `maybe_*` iterators run in parallel
+pub const PARALLEL: bool = cfg!(feature = "parallel");
+
+/// `ParallelIterator` with the `parallel` feature, `Iterator` without it, to
+/// name what the `maybe_*` methods give
+#[cfg(feature = "parallel")]
+pub trait MaybeParallelIterator: ParallelIterator {}
+
+#[cfg(feature = "parallel")]
+impl<T: ParallelIterator> MaybeParallelIterator for T {}
+
+/// `ParallelIterator` with the `parallel` feature, `Iterator` without it, to
+/// name what the `maybe_*` methods give
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeParallelIterator: Iterator {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T: Iterator> MaybeParallelIterator for T {}
+
+/// `into_par_iter` with the `parallel` feature, `into_iter` without it
+pub trait MaybeIntoParIter {
+    type Iter;
+
+    fn maybe_into_par_iter(self) -> Self::Iter;
+}
+
+#[cfg(feature = "parallel")]
+impl<T: IntoParallelIterator> MaybeIntoParIter for T {
+    type Iter = T::Iter;
+
+    fn maybe_into_par_iter(self) -> Self::Iter {
+        self.into_par_iter()
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<T: IntoIterator> MaybeIntoParIter for T {
+    type Iter = T::IntoIter;
+
+    fn maybe_into_par_iter(self) -> Self::Iter {
+        self.into_iter()
+    }
+}
+
+/// `par_iter` with the `parallel` feature, `iter` without it
+pub trait MaybeParIter<'a> {
+    type Iter;
+
+    fn maybe_par_iter(&'a self) -> Self::Iter;
+}
+
+#[cfg(feature = "parallel")]
+impl<'a, T: 'a + ?Sized> MaybeParIter<'a> for T
+where
+    &'a T: IntoParallelIterator,
+{
+    type Iter = <&'a T as IntoParallelIterator>::Iter;
+
+    fn maybe_par_iter(&'a self) -> Self::Iter {
+        self.into_par_iter()
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<'a, T: 'a + ?Sized> MaybeParIter<'a> for T
+where
+    &'a T: IntoIterator,
+{
+    type Iter = <&'a T as IntoIterator>::IntoIter;
+
+    fn maybe_par_iter(&'a self) -> Self::Iter {
+        self.into_iter()
+    }
+}
+
+/// `par_bridge` with the `parallel` feature, the iterator itself without it
+pub trait MaybeParBridge: Iterator + Sized {
+    type Iter;
+
+    fn maybe_par_bridge(self) -> Self::Iter;
+}
+
+#[cfg(feature = "parallel")]
+impl<T: Iterator + Send> MaybeParBridge for T
+where
+    T::Item: Send,
+{
+    type Iter = IterBridge<T>;
+
+    fn maybe_par_bridge(self) -> Self::Iter {
+        self.par_bridge()
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<T: Iterator> MaybeParBridge for T {
+    type Iter = T;
+
+    fn maybe_par_bridge(self) -> Self::Iter {
+        self
+    }
+}
+
+/// `par_for_each` with the `parallel` feature, `for_each` without it, on an
+/// ndarray [`Zip`] of two producers
+pub trait MaybeParZip<A, B> {
+    fn maybe_par_for_each(self, f: impl Fn(A, B) + Sync + Send);
+}
+
+#[cfg(feature = "parallel")]
+impl<P1, P2, D> MaybeParZip<P1::Item, P2::Item> for Zip<(P1, P2), D>
+where
+    D: Dimension,
+    P1: NdProducer<Dim = D> + Send,
+    P2: NdProducer<Dim = D> + Send,
+    P1::Item: Send,
+    P2::Item: Send,
+{
+    fn maybe_par_for_each(self, f: impl Fn(P1::Item, P2::Item) + Sync + Send) {
+        self.par_for_each(f);
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<P1, P2, D> MaybeParZip<P1::Item, P2::Item> for Zip<(P1, P2), D>
+where
+    D: Dimension,
+    P1: NdProducer<Dim = D>,
+    P2: NdProducer<Dim = D>,
+{
+    fn maybe_par_for_each(self, f: impl Fn(P1::Item, P2::Item) + Sync + Send) {
+        self.for_each(f);
+    }
+}
+
+/// Rayon-only methods of parallel iterators, under the same names, for the
+/// sequential iterators the `maybe_*` methods give without the `parallel`
+/// feature
+#[cfg(not(feature = "parallel"))]
+pub trait SequentialExt: Iterator + Sized {
+    /// [`Iterator::find`], which already finds the first match
+    fn find_first(mut self, predicate: impl FnMut(&Self::Item) -> bool) -> Option<Self::Item> {
+        self.find(predicate)
+    }
+
+    /// [`Iterator::find`], as any match is the first one
+    fn find_any(mut self, predicate: impl FnMut(&Self::Item) -> bool) -> Option<Self::Item> {
+        self.find(predicate)
+    }
+
+    /// [`Iterator::find_map`], which already finds the first match
+    fn find_map_first<R>(mut self, f: impl FnMut(Self::Item) -> Option<R>) -> Option<R> {
+        self.find_map(f)
+    }
+
+    /// [`Iterator::find_map`], as any match is the first one
+    fn find_map_any<R>(mut self, f: impl FnMut(Self::Item) -> Option<R>) -> Option<R> {
+        self.find_map(f)
+    }
+
+    /// [`Iterator::flat_map`], as every inner iterator is sequential anyway
+    fn flat_map_iter<U, F>(self, f: F) -> std::iter::FlatMap<Self, U, F>
+    where
+        U: IntoIterator,
+        F: FnMut(Self::Item) -> U,
+    {
+        self.flat_map(f)
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<T: Iterator> SequentialExt for T {}
+
+/// `iter::par_pairs` with the `parallel` feature, `iter::pairs` without it
+#[cfg(feature = "parallel")]
+#[must_use]
+pub const fn maybe_par_pairs(n: usize) -> ParPairs {
+    par_pairs(n)
+}
+
+/// `iter::par_pairs` with the `parallel` feature, `iter::pairs` without it
+#[cfg(not(feature = "parallel"))]
+#[must_use]
+pub const fn maybe_par_pairs(n: usize) -> Pairs {
+    pairs(n)
+}
+
+/// Fold every chunk of `chunk_size` items from `identity()`, in parallel with
+/// the `parallel` feature, then combine the results of the chunks from first
+/// to last.
+///
+/// Rayon's `fold` and `reduce` group items however the work was split, which
+/// changes floating point sums and which of equal items wins from run to run.
+/// Here the grouping only depends on `chunk_size`, so the result is the same
+/// whatever the number of threads, and with or without the feature.
+pub fn ordered_fold<T, A>(
+    items: &[T],
+    chunk_size: usize,
+    identity: impl Fn() -> A + Sync + Send,
+    fold: impl Fn(A, &T) -> A + Sync + Send,
+    combine: impl Fn(A, A) -> A,
+) -> A
+where
+    T: Sync,
+    A: Send,
+{
+    let fold_chunk = |chunk: &[T]| chunk.iter().fold(identity(), &fold);
+    #[cfg(feature = "parallel")]
+    let folded = items
+        .par_chunks(chunk_size.max(1))
+        .map(fold_chunk)
+        .collect::<Vec<_>>();
+    #[cfg(not(feature = "parallel"))]
+    let folded = items.chunks(chunk_size.max(1)).map(fold_chunk);
+    folded.into_iter().reduce(combine).unwrap_or_else(identity)
+}
+
+/// [`ordered_fold`] with the same operation within and across chunks, such
+/// as a sum
+pub fn ordered_reduce<T>(
+    items: &[T],
+    chunk_size: usize,
+    identity: &T,
+    op: impl Fn(T, T) -> T + Sync + Send,
+) -> T
+where
+    T: Clone + Send + Sync,
+{
+    ordered_fold(
+        items,
+        chunk_size,
+        || identity.clone(),
+        |acc, item| op(acc, item.clone()),
+        &op,
+    )
+}
+
+/// Everything needed to call the `maybe_*` methods and what follows them
+pub mod prelude {
+    #[cfg(feature = "parallel")]
+    pub use rayon::prelude::*;
+
+    #[cfg(not(feature = "parallel"))]
+    pub use super::SequentialExt;
+    pub use super::{
+        MaybeIntoParIter, MaybeParBridge, MaybeParIter, MaybeParZip, MaybeParallelIterator,
+        maybe_par_pairs, ordered_fold, ordered_reduce,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prelude::*;
+
+    #[test]
+    fn test_maybe_par_iter() {
+        let values = (1..=100).collect::<Vec<u64>>();
+        assert_eq!(values.maybe_par_iter().sum::<u64>(), 5050);
+        assert_eq!(
+            values
+                .clone()
+                .maybe_into_par_iter()
+                .filter(|v| v % 7 == 0)
+                .collect::<Vec<_>>(),
+            (7..=98).step_by(7).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            (0..1000u64)
+                .maybe_into_par_iter()
+                .find_first(|&v| v > 10 && v % 13 == 0),
+            Some(13)
+        );
+        assert_eq!(
+            values
+                .iter()
+                .maybe_par_bridge()
+                .find_map_any(|&v| (v == 42).then_some(v)),
+            Some(42)
+        );
+        assert_eq!(
+            (0..4usize)
+                .maybe_into_par_iter()
+                .flat_map_iter(|i| 0..i)
+                .count(),
+            6
+        );
+        assert_eq!(maybe_par_pairs(5).count(), 10);
+        assert_eq!(super::PARALLEL, cfg!(feature = "parallel"));
+    }
+
+    #[test]
+    fn test_ordered_reduce() {
+        // Terms of very different magnitudes, whose float sum depends on the
+        // order they are added in
+        let terms = (0..100_000)
+            .map(|i| f64::from(i % 1000).powi(3) * if i % 7 == 0 { 1e-9 } else { -1.0 })
+            .collect::<Vec<_>>();
+        let sum = || ordered_reduce(&terms, 256, &0.0, |a, b| a + b);
+        let expected = terms
+            .chunks(256)
+            .map(|chunk| chunk.iter().fold(0.0, |a, b| a + b))
+            .fold(0.0, |a, b| a + b);
+        assert_eq!(sum().to_bits(), expected.to_bits());
+        #[cfg(feature = "parallel")]
+        for threads in [1, 2, 3, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap_or_else(|e| panic!("Failed to build a thread pool: {e}"));
+            assert_eq!(pool.install(sum).to_bits(), expected.to_bits());
+        }
+        let longest = ordered_fold(
+            &["ab", "cde", "fg", "hij"],
+            1,
+            String::new,
+            |_, s| (*s).to_string(),
+            |a, b| if b.len() > a.len() { b } else { a },
+        );
+        // The first of the longest, as chunks are combined in order
+        assert_eq!(longest, "cde");
+        assert_eq!(ordered_reduce(&[] as &[u64], 4, &0, |a, b| a + b), 0);
+    }
+}
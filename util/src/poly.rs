@@ -0,0 +1,204 @@
+//! Polyominoes for tiling and packing puzzles.
+//!
+//! A piece is kept as the sorted list of its cells, shifted so that its
+//! bounding box starts at the origin. Two pieces are then equal exactly when
+//! they have the same shape at the same orientation.
+
+use ndarray::Array2;
+
+use crate::grid::UPoint;
+
+/// A set of grid cells, normalized to touch the top and left edges
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Polyomino {
+    cells: Vec<UPoint>,
+}
+
+impl Polyomino {
+    /// Build a piece from its cells, in any order and at any offset
+    #[must_use]
+    pub fn new(cells: impl IntoIterator<Item = UPoint>) -> Self {
+        let mut cells = cells.into_iter().collect::<Vec<_>>();
+        let min_row = cells.iter().map(|p| p.row).min().unwrap_or_default();
+        let min_col = cells.iter().map(|p| p.col).min().unwrap_or_default();
+        for cell in &mut cells {
+            *cell = UPoint::new(cell.row - min_row, cell.col - min_col);
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        Self { cells }
+    }
+
+    /// Build a piece from the non-zero cells of `grid`
+    #[must_use]
+    pub fn from_grid(grid: &Array2<u8>) -> Self {
+        Self::new(
+            grid.indexed_iter()
+                .filter(|&(_, &v)| v != 0)
+                .map(|(idx, _)| idx.into()),
+        )
+    }
+
+    /// Render the piece on its bounding box, with 1 for covered cells
+    #[must_use]
+    pub fn to_grid(&self) -> Array2<u8> {
+        let mut grid = Array2::zeros(self.dim());
+        for cell in &self.cells {
+            grid[cell.as_index()] = 1;
+        }
+        grid
+    }
+
+    /// Offsets of the covered cells from the top-left corner of the bounding
+    /// box, in row-major order
+    #[must_use]
+    pub fn cells(&self) -> &[UPoint] {
+        &self.cells
+    }
+
+    /// Number of covered cells
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether the piece covers no cells
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Size of the bounding box as `(rows, cols)`
+    #[must_use]
+    pub fn dim(&self) -> (usize, usize) {
+        let rows = self
+            .cells
+            .iter()
+            .map(|p| p.row + 1)
+            .max()
+            .unwrap_or_default();
+        let cols = self
+            .cells
+            .iter()
+            .map(|p| p.col + 1)
+            .max()
+            .unwrap_or_default();
+        (rows, cols)
+    }
+
+    /// Rotate 90 degrees clockwise
+    #[must_use]
+    pub fn rotate(&self) -> Self {
+        let (rows, _) = self.dim();
+        Self::new(
+            self.cells
+                .iter()
+                .map(|p| UPoint::new(p.col, rows - 1 - p.row)),
+        )
+    }
+
+    /// Mirror left to right
+    #[must_use]
+    pub fn flip(&self) -> Self {
+        let (_, cols) = self.dim();
+        Self::new(
+            self.cells
+                .iter()
+                .map(|p| UPoint::new(p.row, cols - 1 - p.col)),
+        )
+    }
+
+    /// All distinct rotations and reflections of the piece, at most 8
+    #[must_use]
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        for mut piece in [self.clone(), self.flip()] {
+            for _ in 0..4 {
+                let next = piece.rotate();
+                orientations.push(piece);
+                piece = next;
+            }
+        }
+        orientations.sort_unstable();
+        orientations.dedup();
+        orientations
+    }
+
+    /// A representative shared by all orientations of the piece, so that
+    /// pieces can be deduplicated regardless of how they are turned
+    #[must_use]
+    pub fn canonical(&self) -> Self {
+        self.orientations().swap_remove(0)
+    }
+
+    /// The cells covered when the top-left corner of the bounding box is
+    /// placed at `origin`
+    pub fn cells_at(&self, origin: UPoint) -> impl Iterator<Item = UPoint> + '_ {
+        self.cells.iter().map(move |&p| p + origin)
+    }
+
+    /// Whether the piece placed at `origin` stays within `occupied` without
+    /// covering any occupied cell
+    #[must_use]
+    pub fn fits(&self, occupied: &Array2<bool>, origin: UPoint) -> bool {
+        self.cells_at(origin)
+            .all(|p| occupied.get(p.as_index()) == Some(&false))
+    }
+
+    /// Mark the cells covered by the piece at `origin` as `value`, e.g. to
+    /// place it and later remove it again while backtracking
+    ///
+    /// # Panics
+    /// Panics if the piece does not fit within the grid at `origin`.
+    pub fn mark(&self, occupied: &mut Array2<bool>, origin: UPoint, value: bool) {
+        for p in self.cells_at(origin) {
+            occupied[p.as_index()] = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_orientations() {
+        let l = Polyomino::from_grid(&array![[1, 0], [1, 0], [1, 1]]);
+        assert_eq!(l.len(), 4);
+        assert_eq!(l.dim(), (3, 2));
+        assert_eq!(l.rotate().to_grid(), array![[1, 1, 1], [1, 0, 0]]);
+        assert_eq!(l.flip().to_grid(), array![[0, 1], [0, 1], [1, 1]]);
+        assert_eq!(l.rotate().rotate().rotate().rotate(), l);
+        assert_eq!(l.orientations().len(), 8);
+        // Symmetric pieces have fewer distinct orientations
+        let square = Polyomino::from_grid(&array![[1, 1], [1, 1]]);
+        assert_eq!(square.orientations(), vec![square.clone()]);
+        let s = Polyomino::from_grid(&array![[0, 1, 1], [1, 1, 0]]);
+        assert_eq!(s.orientations().len(), 4);
+        let plus = Polyomino::from_grid(&array![[0, 1, 0], [1, 1, 1], [0, 1, 0]]);
+        assert_eq!(plus.orientations().len(), 1);
+        // Canonical forms identify turned and mirrored copies
+        assert_eq!(l.flip().rotate().canonical(), l.canonical());
+        assert_ne!(s.canonical(), l.canonical());
+        // Offsets and empty margins are ignored
+        let padded = Polyomino::from_grid(&array![[0, 0, 0], [0, 1, 1], [0, 1, 1]]);
+        assert_eq!(padded, square);
+    }
+
+    #[test]
+    fn test_placement() {
+        let l = Polyomino::from_grid(&array![[1, 0], [1, 1]]);
+        let mut occupied = Array2::from_elem((3, 3), false);
+        occupied[(0, 0)] = true;
+        assert!(!l.fits(&occupied, UPoint::new(0, 0)));
+        assert!(l.fits(&occupied, UPoint::new(1, 1)));
+        assert!(!l.fits(&occupied, UPoint::new(2, 1)));
+        l.mark(&mut occupied, UPoint::new(1, 1), true);
+        assert_eq!(occupied.iter().filter(|&&o| o).count(), 4);
+        assert!(!l.fits(&occupied, UPoint::new(1, 0)));
+        l.mark(&mut occupied, UPoint::new(1, 1), false);
+        assert!(l.fits(&occupied, UPoint::new(1, 0)));
+    }
+}
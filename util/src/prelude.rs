@@ -0,0 +1,19 @@
+//! What most days use, in one import: `use util::prelude::*;`
+//!
+//! Brings in [`Solution`] and what benchmarks it, the reader functions, the
+//! common grid and geometry types, and the `maybe_*` iterators of
+//! [`parallel`](crate::parallel). Anything else, `anyhow` included, is
+//! imported from its own crate or module as before.
+
+pub use crate::{
+    Benchmark, Serializable, Solution, char_map, diagnostics,
+    geom::{Rect, Vec3},
+    grid::{Direction, Point, UPoint},
+    parallel::prelude::*,
+    reader::{
+        CharMap, open_file, parse_byte_grid, parse_char_grid, parse_comma_separated,
+        parse_fixed_width_grid, parse_grid, parse_lines, parse_padded_fixed_width_grid,
+        parse_whitespace_separated, read_file,
+    },
+    trace,
+};
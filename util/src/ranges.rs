@@ -0,0 +1,49 @@
+//! Integer ranges and interval data structures
+
+mod interval_set;
+mod interval_tree;
+mod range_map;
+
+use std::ops::{Add, Sub};
+
+pub use interval_set::IntervalSet;
+pub use interval_tree::IntervalTree;
+pub use range_map::RangeMap;
+
+/// Primitive integers usable as interval bounds.
+pub trait Integer: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Addition returning `None` on overflow
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Average of two values rounded towards negative infinity, without
+    /// overflowing
+    #[must_use]
+    fn floor_midpoint(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn floor_midpoint(self, rhs: Self) -> Self {
+                    // Shared bits plus half of the differing ones
+                    (self & rhs) + ((self ^ rhs) >> 1)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
@@ -0,0 +1,270 @@
+//! Sets of integers stored as sorted, disjoint, inclusive ranges.
+
+use super::Integer;
+
+/// A set of integers represented by sorted, disjoint, inclusive `(start, end)`
+/// ranges.
+///
+/// Overlapping or contiguous ranges are always merged, so the representation
+/// of a set is unique and iteration yields the minimal list of ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+/// Whether a range ending at `end` touches or overlaps a range starting at
+/// `start`, i.e. `start <= end + 1` without overflowing.
+fn touches<T: Integer>(end: T, start: T) -> bool {
+    end.checked_add(T::ONE).is_none_or(|next| start <= next)
+}
+
+impl<T: Integer> IntervalSet<T> {
+    /// Create an empty set
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Number of disjoint ranges in the set
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Whether the set contains no values
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The disjoint ranges in ascending order
+    #[must_use]
+    pub fn as_slice(&self) -> &[(T, T)] {
+        &self.ranges
+    }
+
+    /// Iterate over the disjoint ranges in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    /// Add the inclusive `range` to the set, merging it with any overlapping
+    /// or contiguous ranges. Empty ranges (`start > end`) are ignored.
+    pub fn insert(&mut self, range: (T, T)) {
+        let (mut start, mut end) = range;
+        if start > end {
+            return;
+        }
+        // Ranges strictly before the new range, with a gap in between
+        let lo = self.ranges.partition_point(|&(_, e)| !touches(e, start));
+        // Ranges touching or overlapping the new range end before `hi`
+        let hi = self.ranges.partition_point(|&(s, _)| touches(end, s));
+        if lo < hi {
+            start = start.min(self.ranges[lo].0);
+            end = end.max(self.ranges[hi - 1].1);
+        }
+        self.ranges.splice(lo..hi, [(start, end)]);
+    }
+
+    /// Whether `value` is covered by any range in the set
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.ranges.partition_point(|&(_, end)| end < value);
+        self.ranges
+            .get(idx)
+            .is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// Total number of values covered by the set
+    ///
+    /// This will overflow if the set covers the whole domain of `T`.
+    #[must_use]
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |acc, &(start, end)| acc + (end - start) + T::ONE)
+    }
+
+    /// All values covered by either set
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    /// All values covered by both sets
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // Advance whichever range finishes first
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// All values covered by this set but not by `other`
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for &(start, end) in &self.ranges {
+            // Skip ranges of `other` that end before the current range
+            while other.ranges.get(j).is_some_and(|&(_, e)| e < start) {
+                j += 1;
+            }
+            // `None` means the rest of the range has been cut away
+            let mut rest = Some(start);
+            for &(cut_start, cut_end) in &other.ranges[j..] {
+                let Some(s) = rest else { break };
+                if cut_start > end {
+                    break;
+                }
+                if cut_start > s {
+                    ranges.push((s, cut_start - T::ONE));
+                }
+                rest = (cut_end < end).then(|| cut_end + T::ONE);
+            }
+            if let Some(s) = rest {
+                ranges.push((s, end));
+            }
+        }
+        Self { ranges }
+    }
+}
+
+impl<T: Integer> FromIterator<(T, T)> for IntervalSet<T> {
+    /// Build the set by sorting the ranges and merging overlapping or
+    /// contiguous ones. Empty ranges (`start > end`) are ignored.
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|&(start, end)| start <= end)
+            .collect::<Vec<_>>();
+        ranges.sort_unstable();
+        let ranges = ranges
+            .into_iter()
+            .fold(vec![], |mut acc: Vec<(T, T)>, curr| {
+                if let Some(last) = acc.last_mut()
+                    && touches(last.1, curr.0)
+                {
+                    last.1 = last.1.max(curr.1);
+                    return acc;
+                }
+                acc.push(curr);
+                acc
+            });
+        Self { ranges }
+    }
+}
+
+impl<T: Integer> Extend<(T, T)> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<'a, T: Integer> IntoIterator for &'a IntervalSet<T> {
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, (T, T)>>;
+    type Item = (T, T);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(u64, u64)]) -> IntervalSet<u64> {
+        ranges.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_from_iter_merges() {
+        let s = set(&[(16, 20), (3, 5), (12, 18), (10, 14), (6, 6), (30, 29)]);
+        assert_eq!(s.as_slice(), &[(3, 6), (10, 20)]);
+        assert_eq!(s.len(), 2);
+        assert_eq!(s.total_len(), 15);
+        assert!(set(&[]).is_empty());
+        // Contiguity at the end of the domain must not overflow
+        let s: IntervalSet<u8> = [(250, 255), (0, 3), (4, 4)].into_iter().collect();
+        assert_eq!(s.as_slice(), &[(0, 4), (250, 255)]);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut s = IntervalSet::new();
+        s.insert((10, 12));
+        s.insert((1, 2));
+        s.insert((20, 25));
+        assert_eq!(s.as_slice(), &[(1, 2), (10, 12), (20, 25)]);
+        // Contiguous with the first range
+        s.insert((3, 4));
+        assert_eq!(s.as_slice(), &[(1, 4), (10, 12), (20, 25)]);
+        // Bridging two ranges
+        s.insert((11, 19));
+        assert_eq!(s.as_slice(), &[(1, 4), (10, 25)]);
+        // Swallowing everything
+        s.insert((0, 100));
+        assert_eq!(s.as_slice(), &[(0, 100)]);
+        // Empty range is ignored
+        s.insert((200, 150));
+        assert_eq!(s.as_slice(), &[(0, 100)]);
+
+        let mut extended = IntervalSet::new();
+        extended.extend([(16, 20), (3, 5), (12, 18), (10, 14)]);
+        assert_eq!(extended, set(&[(3, 5), (10, 14), (16, 20), (12, 18)]));
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = set(&[(3, 5), (10, 20)]);
+        for v in 0..25 {
+            let expected = (3..=5).contains(&v) || (10..=20).contains(&v);
+            assert_eq!(s.contains(v), expected, "value {v}");
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 5), (10, 15), (20, 30)]);
+        let b = set(&[(4, 11), (14, 22), (30, 40)]);
+        assert_eq!(a.union(&b).as_slice(), &[(1, 40)]);
+        assert_eq!(
+            a.intersection(&b).as_slice(),
+            &[(4, 5), (10, 11), (14, 15), (20, 22), (30, 30)]
+        );
+        assert_eq!(a.difference(&b).as_slice(), &[(1, 3), (12, 13), (23, 29)]);
+        assert_eq!(b.difference(&a).as_slice(), &[(6, 9), (16, 19), (31, 40)]);
+        assert!(a.difference(&a).is_empty());
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+
+        // Cross-check against brute force membership
+        for v in 0..45 {
+            assert_eq!(a.union(&b).contains(v), a.contains(v) || b.contains(v));
+            assert_eq!(
+                a.intersection(&b).contains(v),
+                a.contains(v) && b.contains(v)
+            );
+            assert_eq!(
+                a.difference(&b).contains(v),
+                a.contains(v) && !b.contains(v)
+            );
+        }
+    }
+}
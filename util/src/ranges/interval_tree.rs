@@ -0,0 +1,180 @@
+//! A static interval tree over possibly overlapping inclusive ranges.
+
+use super::Integer;
+
+/// Inclusive ranges with attached values, answering which of them contain a
+/// point or overlap a range.
+///
+/// Unlike [`IntervalSet`](super::IntervalSet), ranges are kept as given, even
+/// when they overlap. The tree is built once: entries are sorted by start and
+/// laid out as an implicit balanced tree, where every node also records the
+/// largest end within its subtree to prune queries.
+#[derive(Clone, Debug)]
+pub struct IntervalTree<T, V = ()> {
+    entries: Vec<((T, T), V)>,
+    /// Largest end among the entries of the subtree rooted at each index
+    max_end: Vec<T>,
+}
+
+impl<T: Integer, V> IntervalTree<T, V> {
+    /// Build the tree over `entries` in `O(n log n)`. Empty ranges
+    /// (`start > end`) are dropped.
+    #[must_use]
+    pub fn new(entries: impl IntoIterator<Item = ((T, T), V)>) -> Self {
+        let mut entries = entries
+            .into_iter()
+            .filter(|&((start, end), _)| start <= end)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|&(range, _)| range);
+        let mut max_end = entries.iter().map(|&((_, end), _)| end).collect::<Vec<_>>();
+        Self::build(&mut max_end, 0, entries.len());
+        Self { entries, max_end }
+    }
+
+    /// Fill in subtree maxima for the entries in `lo..hi`, returning the
+    /// largest end among them
+    fn build(max_end: &mut [T], lo: usize, hi: usize) -> Option<T> {
+        if lo >= hi {
+            return None;
+        }
+        let mid = lo.midpoint(hi);
+        let left = Self::build(max_end, lo, mid);
+        let right = Self::build(max_end, mid + 1, hi);
+        for end in [left, right].into_iter().flatten() {
+            max_end[mid] = max_end[mid].max(end);
+        }
+        Some(max_end[mid])
+    }
+
+    /// Number of ranges in the tree
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the tree has no ranges
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over all ranges and their values, ordered by range
+    pub fn iter(&self) -> impl Iterator<Item = &((T, T), V)> {
+        self.entries.iter()
+    }
+
+    /// Collect the entries in `lo..hi` overlapping `query` into `found`, in
+    /// order
+    fn visit<'a>(&'a self, lo: usize, hi: usize, query: (T, T), found: &mut Vec<&'a ((T, T), V)>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo.midpoint(hi);
+        // Nothing in this subtree reaches the query
+        if self.max_end[mid] < query.0 {
+            return;
+        }
+        self.visit(lo, mid, query, found);
+        let entry = &self.entries[mid];
+        let (start, end) = entry.0;
+        // Everything to the right starts even later
+        if start > query.1 {
+            return;
+        }
+        if end >= query.0 {
+            found.push(entry);
+        }
+        self.visit(mid + 1, hi, query, found);
+    }
+
+    /// All ranges overlapping the inclusive `range`, ordered by range
+    #[must_use]
+    pub fn overlapping(&self, range: (T, T)) -> Vec<&((T, T), V)> {
+        let mut found = Vec::new();
+        if range.0 <= range.1 {
+            self.visit(0, self.entries.len(), range, &mut found);
+        }
+        found
+    }
+
+    /// All ranges containing `point` (a "stabbing" query), ordered by range
+    #[must_use]
+    pub fn stab(&self, point: T) -> Vec<&((T, T), V)> {
+        self.overlapping((point, point))
+    }
+}
+
+impl<T: Integer, V> FromIterator<((T, T), V)> for IntervalTree<T, V> {
+    fn from_iter<I: IntoIterator<Item = ((T, T), V)>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+impl<T: Integer> FromIterator<(T, T)> for IntervalTree<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        Self::new(iter.into_iter().map(|range| (range, ())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random ranges, many of them overlapping
+    fn ranges(n: usize) -> Vec<(u32, u32)> {
+        let mut state = 0x2545_f491_u32;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state % 100
+        };
+        (0..n)
+            .map(|_| {
+                let start = next();
+                (start, start + next() % 15)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let ranges = ranges(200);
+        let tree = ranges
+            .iter()
+            .enumerate()
+            .map(|(i, &range)| (range, i))
+            .collect::<IntervalTree<_, _>>();
+        assert_eq!(tree.len(), 200);
+        let brute_force = |query: (u32, u32)| {
+            let mut found = ranges
+                .iter()
+                .enumerate()
+                .filter(|&(_, &(s, e))| s <= query.1 && e >= query.0)
+                .map(|(i, &range)| (range, i))
+                .collect::<Vec<_>>();
+            found.sort_unstable();
+            found
+        };
+        let found =
+            |entries: Vec<&((u32, u32), usize)>| entries.into_iter().copied().collect::<Vec<_>>();
+        for point in 0..120 {
+            assert_eq!(found(tree.stab(point)), brute_force((point, point)));
+        }
+        for query in [(0, 0), (10, 20), (95, 200), (0, 200)] {
+            assert_eq!(found(tree.overlapping(query)), brute_force(query));
+        }
+        assert!(tree.overlapping((50, 49)).is_empty());
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let tree = [(5u8, 3), (0, 255), (10, 10)]
+            .into_iter()
+            .collect::<IntervalTree<_>>();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.stab(255).len(), 1);
+        assert_eq!(tree.stab(10).len(), 2);
+        assert!(IntervalTree::<u8>::new([]).stab(0).is_empty());
+    }
+}
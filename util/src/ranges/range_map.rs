@@ -0,0 +1,209 @@
+//! Piecewise translations of integers, given as disjoint source ranges.
+
+use super::{Integer, IntervalSet};
+use crate::{Error, Result};
+
+/// A mapping that shifts every disjoint, inclusive source range onto a
+/// destination range of the same length, and leaves values outside all
+/// source ranges unchanged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    /// `((source start, source end), destination start)`, sorted by source
+    entries: Vec<((T, T), T)>,
+}
+
+impl<T: Integer> RangeMap<T> {
+    /// Create the identity mapping
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Number of mapped source ranges
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no values are moved, i.e. this is the identity mapping
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over `(source range, destination start)` in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = ((T, T), T)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    /// Map the inclusive `source` range onto the range starting at
+    /// `destination`. Fails if the source range is empty or overlaps a range
+    /// that is already mapped.
+    pub fn insert(&mut self, source: (T, T), destination: T) -> Result<()> {
+        let (start, end) = source;
+        if start > end {
+            return Err(Error::InvalidRange("cannot map an empty range"));
+        }
+        let idx = self.entries.partition_point(|&((_, e), _)| e < start);
+        if self.entries.get(idx).is_some_and(|&((s, _), _)| s <= end) {
+            return Err(Error::InvalidRange("source overlaps an existing one"));
+        }
+        self.entries.insert(idx, (source, destination));
+        Ok(())
+    }
+
+    /// Where `value` is mapped to
+    #[must_use]
+    pub fn map_point(&self, value: T) -> T {
+        let idx = self.entries.partition_point(|&((_, e), _)| e < value);
+        match self.entries.get(idx) {
+            Some(&((start, _), destination)) if start <= value => destination + (value - start),
+            _ => value,
+        }
+    }
+
+    /// Split the inclusive `range` into consecutive pieces that are each moved
+    /// as a whole, as `(piece, destination start)`
+    fn pieces(&self, range: (T, T)) -> Vec<((T, T), T)> {
+        let (mut start, end) = range;
+        let mut pieces = Vec::new();
+        if start > end {
+            return pieces;
+        }
+        let idx = self.entries.partition_point(|&((_, e), _)| e < start);
+        for &((s, e), destination) in &self.entries[idx..] {
+            if s > end {
+                break;
+            }
+            // Unmapped gap before this source range
+            if start < s {
+                pieces.push(((start, s - T::ONE), start));
+                start = s;
+            }
+            let piece_end = e.min(end);
+            pieces.push(((start, piece_end), destination + (start - s)));
+            if piece_end == end {
+                return pieces;
+            }
+            start = piece_end + T::ONE;
+        }
+        pieces.push(((start, end), start));
+        pieces
+    }
+
+    /// Where the inclusive `range` is mapped to, split into one destination
+    /// range per source range it touches (in source order, so possibly
+    /// overlapping or out of order)
+    #[must_use]
+    pub fn map_range(&self, range: (T, T)) -> Vec<(T, T)> {
+        self.pieces(range)
+            .into_iter()
+            .map(|((start, end), destination)| (destination, destination + (end - start)))
+            .collect()
+    }
+
+    /// Where all values of `set` are mapped to
+    #[must_use]
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.iter().flat_map(|range| self.map_range(range)).collect()
+    }
+
+    /// The mapping that applies `self` and then `then`
+    #[must_use]
+    pub fn compose(&self, then: &Self) -> Self {
+        let mut entries = Vec::new();
+        // Values moved by `self`, then split by `then` at their destination
+        for &((s, e), d) in &self.entries {
+            for ((ps, pe), pd) in then.pieces((d, d + (e - s))) {
+                entries.push(((s + (ps - d), s + (pe - d)), pd));
+            }
+        }
+        // Values left in place by `self` are only moved by `then`
+        let moved = self
+            .iter()
+            .map(|(source, _)| source)
+            .collect::<IntervalSet<_>>();
+        for &((s, e), d) in &then.entries {
+            let untouched = std::iter::once((s, e))
+                .collect::<IntervalSet<_>>()
+                .difference(&moved);
+            for (ps, pe) in &untouched {
+                entries.push(((ps, pe), d + (ps - s)));
+            }
+        }
+        entries.retain(|&((s, _), d)| s != d);
+        entries.sort_unstable();
+        Self { entries }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The seed-to-soil and soil-to-fertilizer maps of 2023 day 5
+    fn maps() -> Result<(RangeMap<u64>, RangeMap<u64>)> {
+        let mut soil = RangeMap::new();
+        soil.insert((98, 99), 50)?;
+        soil.insert((50, 97), 52)?;
+        let mut fertilizer = RangeMap::new();
+        fertilizer.insert((15, 51), 0)?;
+        fertilizer.insert((52, 53), 37)?;
+        fertilizer.insert((0, 14), 39)?;
+        Ok((soil, fertilizer))
+    }
+
+    #[test]
+    fn test_map_point() -> Result<()> {
+        let (mut soil, _) = maps()?;
+        assert_eq!(soil.len(), 2);
+        assert_eq!(soil.map_point(79), 81);
+        assert_eq!(soil.map_point(14), 14);
+        assert_eq!(soil.map_point(55), 57);
+        assert_eq!(soil.map_point(99), 51);
+        assert!(soil.insert((90, 100), 0).is_err());
+        assert!(soil.insert((5, 4), 0).is_err());
+        assert_eq!(soil.len(), 2);
+        assert!(RangeMap::<u8>::new().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_range() -> Result<()> {
+        let (soil, _) = maps()?;
+        assert_eq!(soil.map_range((45, 99)), vec![(45, 49), (52, 99), (50, 51)]);
+        assert_eq!(soil.map_range((10, 20)), vec![(10, 20)]);
+        let seeds = [(79, 92), (55, 67)].into_iter().collect();
+        assert_eq!(soil.map_set(&seeds).as_slice(), &[(57, 69), (81, 94)]);
+        // Ranges reaching the end of the domain do not overflow
+        let mut top = RangeMap::new();
+        top.insert((250u8, 255), 0)?;
+        assert_eq!(top.map_range((200, 255)), vec![(200, 249), (0, 5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose() -> Result<()> {
+        let (soil, fertilizer) = maps()?;
+        let both = soil.compose(&fertilizer);
+        for seed in 0..120 {
+            assert_eq!(
+                both.map_point(seed),
+                fertilizer.map_point(soil.map_point(seed)),
+                "seed {seed}"
+            );
+        }
+        // Round trips cancel out, leaving only the values that `back` moves
+        // without `there` having moved them first
+        let mut there = RangeMap::new();
+        there.insert((0, 9), 100)?;
+        let mut back = RangeMap::new();
+        back.insert((100, 109), 0)?;
+        let round_trip = there.compose(&back);
+        assert_eq!(round_trip.iter().collect::<Vec<_>>(), vec![((100, 109), 0)]);
+        assert_eq!(RangeMap::new().compose(&soil), soil);
+        Ok(())
+    }
+}
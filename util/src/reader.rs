@@ -1,11 +1,26 @@
 //! Common reading and parsing utilities
 
-use std::{fs::File, io::Read};
+mod char_map;
+mod sniff;
+
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufReader, Read},
+    sync::RwLock,
+};
 
-use anyhow::Result;
 use ndarray::Array2;
 
+pub use self::{
+    char_map::{CharMap, ascii},
+    sniff::{InputKind, InputShape, Separator, sniff},
+};
 use super::get_workspace_root;
+use crate::{
+    Result, diagnostics,
+    error::{BoxError, Error},
+};
 
 /// Convert a nested Vec (`Vec<Vec<T>>`) into a 2D ndarray `Array2<T>`
 ///
@@ -15,35 +30,118 @@ use super::get_workspace_root;
 fn nested_vec_to_array2<T>(grid: Vec<Vec<T>>) -> Result<Array2<T>> {
     let row_count = grid.len();
     let col_count = grid.first().map_or(0, Vec::len);
+    if let Some((row, found)) = grid
+        .iter()
+        .map(Vec::len)
+        .enumerate()
+        .find(|&(_, len)| len != col_count)
+    {
+        return Err(Error::ShapeMismatch {
+            line: row + 1,
+            expected: col_count,
+            found,
+        });
+    }
     let flat_data = grid.into_iter().flatten().collect::<Vec<T>>();
-    Ok(Array2::from_shape_vec((row_count, col_count), flat_data)?)
+    Array2::from_shape_vec((row_count, col_count), flat_data).map_err(|e| Error::Other(e.into()))
+}
+
+/// The error of the parser function on a line, counted from 0
+fn parse_error(row: usize, error: impl Into<BoxError>) -> Error {
+    Error::ParseLine {
+        line: row + 1,
+        error: error.into(),
+    }
 }
 
-/// Read the input file for a given day and example flag
+/// Where the inputs of the days come from when not from the `inputs`
+/// directory, such as a text box of a web page where there is no filesystem
+pub trait InputProvider: Send + Sync {
+    /// The input for a given day and example flag
+    ///
+    /// # Errors
+    /// This function will return an error if there is no such input.
+    fn read(&self, day: u8, example: bool) -> Result<String>;
+}
+
+impl<F> InputProvider for F
+where
+    F: Fn(u8, bool) -> Result<String> + Send + Sync,
+{
+    fn read(&self, day: u8, example: bool) -> Result<String> {
+        self(day, example)
+    }
+}
+
+/// The provider [`read_file`] asks before the filesystem, if any
+static PROVIDER: RwLock<Option<Box<dyn InputProvider>>> = RwLock::new(None);
+
+/// Make [`read_file`] take every input from `provider`, or from the `inputs`
+/// directory again with `None`
+pub fn set_input_provider(provider: Option<Box<dyn InputProvider>>) {
+    *PROVIDER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = provider;
+}
+
+/// Open the input file for a given day and example flag, for reading it
+/// piece by piece rather than all at once
 ///
 /// # Errors
 /// This function will return an error if:
 /// - the day is not between 1 and 25, or
 /// - the workspace root cannot be determined, or
-/// - the file cannot be read.
-pub fn read_file(day: u8, example: bool) -> Result<String> {
+/// - the file does not exist, as [`Error::MissingInput`], or
+/// - the file cannot be opened.
+pub fn open_file(day: u8, example: bool) -> Result<BufReader<File>> {
     if day == 0 || day > 25 {
-        anyhow::bail!("Day must be between 1 and 25");
+        return Err(Error::InvalidDay(day));
     }
     let file_path = get_workspace_root()?.join(format!(
         "inputs/day{:02}{}.txt",
         day,
         if example { "-example" } else { "" }
     ));
-    let mut file = File::open(&file_path).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to open file '{}': {}",
-            file_path.to_string_lossy(),
-            e
+    match File::open(&file_path) {
+        Ok(file) => Ok(BufReader::new(file)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(Error::MissingInput { path: file_path })
+        }
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("Failed to open file '{}': {e}", file_path.display()),
         )
-    })?;
+        .into()),
+    }
+}
+
+/// Read the input file for a given day and example flag, or ask the
+/// [`InputProvider`] for it if one is set
+///
+/// # Errors
+/// This function will return an error if:
+/// - the day is not between 1 and 25, or
+/// - the workspace root cannot be determined, or
+/// - the file does not exist, as [`Error::MissingInput`], or
+/// - the file cannot be read, or
+/// - the provider has no such input.
+pub fn read_file(day: u8, example: bool) -> Result<String> {
+    if day == 0 || day > 25 {
+        return Err(Error::InvalidDay(day));
+    }
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("read", day, example).entered();
+    if let Some(provider) = PROVIDER
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .as_ref()
+    {
+        return provider.read(day, example);
+    }
     let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
+    open_file(day, example)?.read_to_string(&mut contents)?;
+    #[cfg(feature = "tracing")]
+    tracing::debug!(bytes = contents.len(), "input read");
     Ok(contents)
 }
 
@@ -87,84 +185,211 @@ pub fn parse_whitespace_separated<T, E>(
 
 /// Parse a grid of characters using a provided parser function
 ///
+/// If the lines are not all as long, trailing whitespace is ignored with a
+/// [`diagnostics::warn`]ing, as editors add and strip it.
+///
 /// # Errors
 /// This function will return an error if:
-/// - any line has a different number of columns, or
-/// - the parser function returns an error.
+/// - any line has a different number of columns, as [`Error::ShapeMismatch`],
+///   or
+/// - the parser function returns an error, as [`Error::ParseLine`], or
+/// - trailing whitespace is ignored while warnings are errors.
 pub fn parse_char_grid<T, E>(
     input: impl AsRef<str>,
     parser: fn(char) -> Result<T, E>,
 ) -> Result<Array2<T>>
 where
-    E: Into<anyhow::Error>,
+    E: Into<BoxError>,
 {
     let content = input.as_ref();
-    let grid = content
-        .lines()
-        .map(|line| line.chars().map(parser).collect())
-        .collect::<Result<Vec<Vec<T>>, E>>()
-        .map_err(Into::into)?;
+    let mut lines = content.lines().collect::<Vec<_>>();
+    let ragged = lines
+        .windows(2)
+        .any(|pair| pair[0].chars().count() != pair[1].chars().count());
+    if ragged {
+        for (row, line) in lines.iter_mut().enumerate() {
+            let trimmed = line.trim_end();
+            if trimmed.len() < line.len() {
+                diagnostics::warn(Some(row + 1), "Ignored trailing whitespace")?;
+                *line = trimmed;
+            }
+        }
+    }
+    let grid = lines
+        .into_iter()
+        .enumerate()
+        .map(|(row, line)| {
+            line.chars()
+                .map(parser)
+                .collect::<Result<_, E>>()
+                .map_err(|e| parse_error(row, e))
+        })
+        .collect::<Result<Vec<Vec<T>>>>()?;
     nested_vec_to_array2(grid)
 }
 
+/// Parse a grid of one byte per cell, looking every byte up in `map`
+///
+/// Does what [`parse_char_grid`] does with a closure matching on each
+/// character, ragged lines included, but faster for grids of ASCII
+/// characters.
+///
+/// # Errors
+/// This function will return an error if:
+/// - any line has a different number of bytes, as [`Error::ShapeMismatch`], or
+/// - a byte is not in `map`, as [`Error::ParseLine`], or
+/// - trailing whitespace is ignored while warnings are errors.
+pub fn parse_byte_grid<T: Copy>(input: impl AsRef<str>, map: &CharMap<T>) -> Result<Array2<T>> {
+    let content = input.as_ref();
+    let mut lines = content.lines().map(str::as_bytes).collect::<Vec<_>>();
+    if lines.windows(2).any(|pair| pair[0].len() != pair[1].len()) {
+        for (row, line) in lines.iter_mut().enumerate() {
+            let trimmed = line.trim_ascii_end();
+            if trimmed.len() < line.len() {
+                diagnostics::warn(Some(row + 1), "Ignored trailing whitespace")?;
+                *line = trimmed;
+            }
+        }
+    }
+    let cols = lines.first().map_or(0, |line| line.len());
+    let mut cells = Vec::with_capacity(lines.len() * cols);
+    for (row, line) in lines.iter().enumerate() {
+        if line.len() != cols {
+            return Err(Error::ShapeMismatch {
+                line: row + 1,
+                expected: cols,
+                found: line.len(),
+            });
+        }
+        for &byte in *line {
+            let Some(cell) = map.get(byte) else {
+                let shown = if byte.is_ascii() {
+                    format!("'{}'", char::from(byte).escape_default())
+                } else {
+                    format!("{byte:#04x}")
+                };
+                return Err(parse_error(
+                    row,
+                    format!("Invalid character in grid: {shown}"),
+                ));
+            };
+            cells.push(cell);
+        }
+    }
+    Array2::from_shape_vec((lines.len(), cols), cells).map_err(|e| Error::Other(e.into()))
+}
+
 /// Parse a grid of whitespace-separated values using a provided parser function
 ///
 /// # Errors
 /// This function will return an error if:
-/// - any line has a different number of columns, or
-/// - the parser function returns an error.
+/// - any line has a different number of columns, as [`Error::ShapeMismatch`],
+///   or
+/// - the parser function returns an error, as [`Error::ParseLine`].
 pub fn parse_grid<T, E>(
     input: impl AsRef<str>,
     parser: fn(&str) -> Result<T, E>,
 ) -> Result<Array2<T>>
 where
-    E: Into<anyhow::Error>,
+    E: Into<BoxError>,
 {
     let content = input.as_ref();
     let grid = content
         .lines()
-        .map(|line| parse_whitespace_separated(line, parser))
-        .collect::<Result<Vec<Vec<T>>, E>>()
-        .map_err(Into::into)?;
+        .enumerate()
+        .map(|(row, line)| {
+            parse_whitespace_separated(line, parser).map_err(|e| parse_error(row, e))
+        })
+        .collect::<Result<Vec<Vec<T>>>>()?;
     nested_vec_to_array2(grid)
 }
 
 /// Parse a fixed-width grid using a provided parser function.
 ///
-/// The widths of each column must be specified.
+/// The widths of each column must be specified, in bytes.
 ///
 /// # Errors
 /// This function will return an error if:
 /// - the specified column widths do not match the input data, or
-/// - the parser function returns an error, or
-/// - the resulting nested Vec cannot be converted into an Array2.
+/// - a column boundary falls inside a multi-byte character, or
+/// - the parser function returns an error, as [`Error::ParseLine`], or
+/// - the lines end up with different numbers of columns, as
+///   [`Error::ShapeMismatch`].
 pub fn parse_fixed_width_grid<T, E>(
     input: impl AsRef<str>,
     column_widths: impl AsRef<[usize]>,
     parser: fn(&str) -> Result<T, E>,
 ) -> Result<Array2<T>>
 where
-    E: Into<anyhow::Error>,
+    E: Into<BoxError>,
 {
-    let content = input.as_ref();
-    let column_widths = column_widths.as_ref();
+    fixed_width_grid(input.as_ref(), column_widths.as_ref(), parser, false)
+}
+
+/// Parse a fixed-width grid like [`parse_fixed_width_grid`], but pad lines
+/// shorter than all the columns with spaces, with a [`diagnostics::warn`]ing,
+/// as editors strip trailing whitespace.
+///
+/// # Errors
+/// This function will return an error if:
+/// - a column boundary falls inside a multi-byte character, or
+/// - the parser function returns an error, as [`Error::ParseLine`], or
+/// - the lines end up with different numbers of columns, as
+///   [`Error::ShapeMismatch`], or
+/// - a line is padded while warnings are errors.
+pub fn parse_padded_fixed_width_grid<T, E>(
+    input: impl AsRef<str>,
+    column_widths: impl AsRef<[usize]>,
+    parser: fn(&str) -> Result<T, E>,
+) -> Result<Array2<T>>
+where
+    E: Into<BoxError>,
+{
+    fixed_width_grid(input.as_ref(), column_widths.as_ref(), parser, true)
+}
+
+/// The fixed-width grid of `content`, with short lines padded if `pad`
+fn fixed_width_grid<T, E>(
+    content: &str,
+    column_widths: &[usize],
+    parser: fn(&str) -> Result<T, E>,
+    pad: bool,
+) -> Result<Array2<T>>
+where
+    E: Into<BoxError>,
+{
+    let total_width = column_widths.iter().sum::<usize>();
     let grid = content
         .lines()
-        .map(|line| {
+        .enumerate()
+        .map(|(row, line)| {
+            let line = if pad && line.len() < total_width {
+                let padding = total_width - line.len();
+                diagnostics::warn(
+                    Some(row + 1),
+                    format!("Padded with {padding} spaces to the column widths"),
+                )?;
+                Cow::Owned(format!("{line}{}", " ".repeat(padding)))
+            } else {
+                Cow::Borrowed(line)
+            };
             let mut cols = Vec::with_capacity(column_widths.len());
-            let mut start = 0;
+            let mut start = 0usize;
             for &width in column_widths {
-                if start >= line.len() {
-                    anyhow::bail!("Line is shorter than expected based on column widths");
-                }
-                let end = start + width;
-                let slice = &line[start..end];
-                cols.push(parser(slice).map_err(Into::into)?);
+                let end = start.saturating_add(width);
+                let Some(slice) = line.get(start..end) else {
+                    return Err(parse_error(
+                        row,
+                        "Line is shorter than expected based on column widths, or a column \
+                         splits a character",
+                    ));
+                };
+                cols.push(parser(slice).map_err(|e| parse_error(row, e))?);
                 start = end;
             }
             // Handle any remaining characters in the line as the last column
             if start < line.len() {
-                cols.push(parser(&line[start..]).map_err(Into::into)?);
+                cols.push(parser(&line[start..]).map_err(|e| parse_error(row, e))?);
             }
             Ok(cols)
         })
@@ -178,7 +403,7 @@ mod tests {
 
     use super::*;
 
-    fn int_parser(s: &str) -> Result<i32> {
+    fn int_parser(s: &str) -> anyhow::Result<i32> {
         s.parse().map_err(Into::into)
     }
 
@@ -187,9 +412,20 @@ mod tests {
         // We don't test successful file reading here since it depends on external
         // files. Instead, we test error handling for invalid days
         let result = read_file(0, false);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(Error::InvalidDay(0))));
         let result = read_file(26, false);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(Error::InvalidDay(26))));
+    }
+
+    #[test]
+    fn test_input_provider() -> Result<()> {
+        set_input_provider(Some(Box::new(|day, example: bool| {
+            Ok(format!("day {day} example {example}"))
+        })));
+        let provided = read_file(3, true);
+        set_input_provider(None);
+        assert_eq!(provided?, "day 3 example true");
+        Ok(())
     }
 
     #[test]
@@ -202,7 +438,14 @@ mod tests {
 
         let vec_inconsistent = vec![vec![1, 2], vec![3, 4, 5]];
         let result = nested_vec_to_array2(vec_inconsistent);
-        assert!(result.is_err());
+        assert!(matches!(
+            result,
+            Err(Error::ShapeMismatch {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        ));
     }
 
     #[test]
@@ -251,7 +494,7 @@ mod tests {
 
         let input_invalid = "1 2 3\n4 five 6\n7 8 9";
         let result = parse_grid(input_invalid, int_parser);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(Error::ParseLine { line: 2, .. })));
     }
 
     #[test]
@@ -268,6 +511,14 @@ mod tests {
         let input_invalid = "abc\ndef\ngh";
         let result = parse_char_grid(input_invalid, anyhow::Ok);
         assert!(result.is_err());
+
+        // Trailing whitespace of ragged lines is ignored, with a warning
+        let array = parse_char_grid("abc \ndef\nghi", anyhow::Ok)
+            .unwrap_or_else(|e| panic!("Failed to parse char grid: {e}"));
+        assert_eq!(array.shape(), &[3, 3]);
+        let warnings = diagnostics::take();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, Some(1));
     }
 
     #[test]
@@ -287,9 +538,26 @@ mod tests {
         assert_eq!(array.shape(), &[2, 4]);
         assert_eq!(array, array![[12, 345, 6789, 9], [1, 234, 5678, 8]]);
 
+        // Lines shorter than the columns are errors, unless padded on request
+        let short = "12 345 67\n01 234 5678";
+        assert!(parse_fixed_width_grid(short, [3, 4, 4], |s| int_parser(s.trim())).is_err());
+        let array = parse_padded_fixed_width_grid(short, [3, 4, 4], |s| int_parser(s.trim()))
+            .unwrap_or_else(|e| panic!("Failed to parse padded fixed-width grid: {e}"));
+        assert_eq!(array, array![[12, 345, 67], [1, 234, 5678]]);
+        let warnings = diagnostics::take();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, Some(1));
+
         let input_invalid = "12 345 6789\n01 234 5678 8";
         let result =
             parse_fixed_width_grid(input_invalid, &column_widths, |s| int_parser(s.trim()));
         assert!(result.is_err());
+
+        // Padded columns must still parse, and none may split a character
+        let result =
+            parse_padded_fixed_width_grid("12 345 67", &column_widths, |s| int_parser(s.trim()));
+        assert!(result.is_err());
+        let result = parse_fixed_width_grid("é1", [1, 2], |s| int_parser(s.trim()));
+        assert!(result.is_err());
     }
 }
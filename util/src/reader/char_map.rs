@@ -0,0 +1,89 @@
+//! Lookup tables from the bytes of a grid to the values of its cells, for
+//! [`parse_byte_grid`](super::parse_byte_grid).
+//!
+//! A table is built once, usually as a `static` with [`char_map!`], and then
+//! every cell is an index into it rather than a call to a closure matching on
+//! a `char`, which is what makes the difference on large grids.
+
+/// The value of the cell of each byte, or `None` for a byte not expected in
+/// the grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CharMap<T> {
+    table: [Option<T>; 256],
+}
+
+impl<T: Copy> CharMap<T> {
+    /// A table mapping each byte of `spec` to its value, the last one winning
+    /// if a byte is given twice
+    #[must_use]
+    pub const fn new(spec: &[(u8, T)]) -> Self {
+        let mut table = [None; 256];
+        let mut i = 0;
+        while i < spec.len() {
+            let (byte, value) = spec[i];
+            table[byte as usize] = Some(value);
+            i += 1;
+        }
+        Self { table }
+    }
+
+    /// The value of the cell of `byte`, if it is expected in the grid
+    #[must_use]
+    #[inline]
+    pub const fn get(&self, byte: u8) -> Option<T> {
+        self.table[byte as usize]
+    }
+}
+
+/// The byte of an ASCII character, failing to compile in a `static` for any
+/// other character
+#[doc(hidden)]
+#[must_use]
+pub const fn ascii(c: char) -> u8 {
+    assert!(c.is_ascii(), "Only ASCII characters fit in a byte");
+    c as u8
+}
+
+/// A [`CharMap`] from ASCII characters to values, as
+/// `char_map!('.' => Cell::Empty, '#' => Cell::Wall)`, usable in a `static`
+#[macro_export]
+macro_rules! char_map {
+    ($($c:literal => $value:expr),* $(,)?) => {
+        $crate::reader::CharMap::new(&[$(($crate::reader::ascii($c), $value)),*])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+    use crate::{
+        reader::{parse_byte_grid, parse_char_grid},
+        rng::Rng,
+    };
+
+    static CELLS: CharMap<u8> = char_map!('.' => 0, '#' => 1, '@' => 2);
+
+    #[test]
+    fn test_char_map() -> crate::Result<()> {
+        assert_eq!(CELLS.get(b'.'), Some(0));
+        assert_eq!(CELLS.get(b'@'), Some(2));
+        assert_eq!(CELLS.get(b'x'), None);
+        // Against the closure of `parse_char_grid` on a large grid
+        let mut rng = Rng::new(25);
+        let mut input = String::new();
+        for _ in 0..1000 {
+            let line = (0..1000).map(|_| ['.', '#', '@'][rng.below(3)]);
+            let _ = writeln!(input, "{}", line.collect::<String>());
+        }
+        let chars = parse_char_grid(&input, |c| match c {
+            '.' => Ok(0),
+            '#' => Ok(1),
+            '@' => Ok(2),
+            _ => Err("Invalid character"),
+        })?;
+        assert_eq!(parse_byte_grid(&input, &CELLS)?, chars);
+        Ok(())
+    }
+}
@@ -0,0 +1,396 @@
+//! A guess at the format of an input, to pick the reader function that
+//! parses it before writing any code, along with what may trip that reader
+//! up, like trailing whitespace or Windows line endings.
+//!
+//! Blocks of lines between blank lines are guessed one by one, and a block
+//! is the first of these that fits all of its lines:
+//! - lines of digits only, all as long, are a grid of digits rather than a
+//!   number each;
+//! - lines of integers, separated by whitespace, commas or dashes;
+//! - several lines of as many characters without spaces, a grid;
+//! - lines of a name, a separator like `: ` or `->` and more names, edges;
+//! - lines with columns of spaces at the same places, fixed-width columns.
+
+use std::{collections::BTreeSet, fmt};
+
+use crate::diagnostics::Warning;
+
+/// Separators between the names of an edge list, tried in order
+const EDGE_SEPARATORS: [&str; 4] = ["->", ": ", ")", "-"];
+
+/// How the integers of a line are separated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Separator {
+    Whitespace,
+    Comma,
+    Dash,
+}
+
+impl Separator {
+    const ALL: [Self; 3] = [Self::Whitespace, Self::Comma, Self::Dash];
+
+    /// The pieces of `line` between separators
+    fn split(self, line: &str) -> Vec<&str> {
+        match self {
+            Self::Whitespace => line.split_whitespace().collect(),
+            Self::Comma => line.split(',').map(str::trim).collect(),
+            Self::Dash => line.split('-').collect(),
+        }
+    }
+}
+
+/// What a block of lines looks like
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputKind {
+    /// Nothing but whitespace
+    Empty,
+    /// Lines of as many characters each, like a map, with the characters used
+    CharGrid {
+        rows: usize,
+        cols: usize,
+        chars: BTreeSet<char>,
+    },
+    /// Lines of integers, from `min_cols` to `max_cols` of them
+    IntegerRows {
+        rows: usize,
+        min_cols: usize,
+        max_cols: usize,
+        separator: Separator,
+    },
+    /// Lines of a name, the separator and one or more names, like a graph
+    EdgeList {
+        rows: usize,
+        separator: &'static str,
+    },
+    /// Columns aligned at the same places on every line, `widths` being
+    /// those of every column but the last, in bytes, spaces after it included
+    FixedWidth { rows: usize, widths: Vec<usize> },
+    /// Blocks of lines between blank lines, each guessed on its own
+    Sections(Vec<Self>),
+    /// Lines of nothing above
+    Lines { rows: usize },
+}
+
+/// `count` and `noun`, in the plural unless there is one
+fn count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun}"),
+        count => format!("{count} {noun}s"),
+    }
+}
+
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty"),
+            Self::CharGrid { rows, cols, chars } => {
+                let chars = chars.iter().map(|c| format!("{c:?}")).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "a grid of {} and {} of {}",
+                    count(*rows, "row"),
+                    count(*cols, "column"),
+                    chars.join(", ")
+                )
+            }
+            Self::IntegerRows {
+                rows,
+                min_cols,
+                max_cols,
+                separator,
+            } => {
+                let cols = if min_cols == max_cols {
+                    count(*min_cols, "integer")
+                } else {
+                    format!("{min_cols} to {max_cols} integers")
+                };
+                let separator = match separator {
+                    Separator::Whitespace => "whitespace",
+                    Separator::Comma => "commas",
+                    Separator::Dash => "dashes",
+                };
+                write!(
+                    f,
+                    "{} of {cols} separated by {separator}",
+                    count(*rows, "row")
+                )
+            }
+            Self::EdgeList { rows, separator } => {
+                write!(
+                    f,
+                    "{} of names separated by {separator:?}",
+                    count(*rows, "edge")
+                )
+            }
+            Self::FixedWidth { rows, widths } => {
+                let rows = count(*rows, "row");
+                write!(f, "{rows} of fixed-width columns {widths:?}")
+            }
+            Self::Sections(sections) => {
+                let sections = sections.iter().map(ToString::to_string);
+                write!(f, "sections of {}", sections.collect::<Vec<_>>().join("; "))
+            }
+            Self::Lines { rows } => f.write_str(&count(*rows, "line")),
+        }
+    }
+}
+
+/// What an input looks like, as told by [`sniff`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputShape {
+    pub kind: InputKind,
+    /// What may trip a reader up, on the first line it is on if on any
+    pub anomalies: Vec<Warning>,
+}
+
+/// Whether the lines are all digits and as long, as one number per line
+/// would rarely be as long as the others
+fn digit_grid(lines: &[&str]) -> bool {
+    let width = lines[0].len();
+    lines.len() > 1
+        && width > 1
+        && (lines.iter())
+            .all(|line| line.len() == width && line.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// The lines as rows of integers, with the first separator that fits them
+fn integer_rows(lines: &[&str]) -> Option<InputKind> {
+    Separator::ALL.into_iter().find_map(|separator| {
+        let counts = lines
+            .iter()
+            .map(|line| {
+                let values = separator.split(line);
+                let integers = values.iter().all(|value| value.parse::<i64>().is_ok());
+                (integers && !values.is_empty()).then_some(values.len())
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(InputKind::IntegerRows {
+            rows: lines.len(),
+            min_cols: counts.iter().copied().min()?,
+            max_cols: counts.iter().copied().max()?,
+            separator,
+        })
+    })
+}
+
+/// Whether `line` is a name, `separator` and more names
+fn is_edge(line: &str, separator: &str) -> bool {
+    line.split_once(separator).is_some_and(|(from, to)| {
+        let from = from.trim();
+        !from.is_empty()
+            && !from.contains(char::is_whitespace)
+            && !to.trim().is_empty()
+            && !to.contains(separator)
+    })
+}
+
+/// The widths of the columns but the last, if the lines have columns of
+/// spaces at the same places and something in every column, short lines
+/// counting as padded with spaces
+fn fixed_widths(lines: &[&str]) -> Option<Vec<usize>> {
+    if lines.len() < 2 || !lines.iter().all(|line| line.is_ascii()) {
+        return None;
+    }
+    let width = lines.iter().map(|line| line.len()).max()?;
+    let blank = (0..width)
+        .map(|col| (lines.iter()).all(|line| line.as_bytes().get(col).is_none_or(|&b| b == b' ')))
+        .collect::<Vec<_>>();
+    // A column starts where a run of blank columns ends
+    let starts = (1..width)
+        .filter(|&col| blank[col - 1] && !blank[col])
+        .collect::<Vec<_>>();
+    let bounds = (std::iter::once(0).chain(starts.iter().copied()))
+        .zip(starts.iter().copied().chain([width]))
+        .collect::<Vec<_>>();
+    let filled = lines.iter().all(|line| {
+        (bounds.iter()).all(|&(start, end)| {
+            let cells = line.as_bytes().get(start..end.min(line.len()));
+            cells.is_some_and(|cells| cells.iter().any(|&b| b != b' '))
+        })
+    });
+    let widths = bounds[..bounds.len() - 1]
+        .iter()
+        .map(|(start, end)| end - start);
+    (filled && bounds.len() > 1).then(|| widths.collect())
+}
+
+/// What a block of non-blank lines looks like
+fn classify(lines: &[&str]) -> InputKind {
+    let rows = lines.len();
+    let cols = lines[0].chars().count();
+    let grid = InputKind::CharGrid {
+        rows,
+        cols,
+        chars: lines.iter().flat_map(|line| line.chars()).collect(),
+    };
+    if digit_grid(lines) {
+        return grid;
+    }
+    if let Some(kind) = integer_rows(lines) {
+        return kind;
+    }
+    let even = (lines.iter()).all(|line| line.chars().count() == cols && !line.contains(' '));
+    if rows > 1 && even {
+        return grid;
+    }
+    if let Some(separator) = (EDGE_SEPARATORS.into_iter())
+        .find(|separator| lines.iter().all(|line| is_edge(line, separator)))
+    {
+        return InputKind::EdgeList { rows, separator };
+    }
+    fixed_widths(lines).map_or(InputKind::Lines { rows }, |widths| InputKind::FixedWidth {
+        rows,
+        widths,
+    })
+}
+
+/// What may trip a reader up in `input`
+fn anomalies(input: &str) -> Vec<Warning> {
+    let mut anomalies = Vec::new();
+    let lines = input.split('\n').collect::<Vec<_>>();
+    let mut note = |found: Option<usize>, count: usize, message: &str| {
+        if let Some(row) = found {
+            let message = match count {
+                1 => message.to_string(),
+                count => format!("{message}, on {count} lines"),
+            };
+            anomalies.push(Warning {
+                line: Some(row + 1),
+                message,
+            });
+        }
+    };
+    let rows_where = |test: &dyn Fn(&str) -> bool| {
+        let rows = (0..lines.len()).filter(|&row| test(lines[row]));
+        rows.fold((None, 0), |(first, count), row| {
+            (first.or(Some(row)), count + 1)
+        })
+    };
+    let (first, count) = rows_where(&|line| line.ends_with('\r'));
+    note(first, count, "Windows line ending");
+    let (first, count) = rows_where(&|line| {
+        let line = line.trim_end_matches('\r');
+        line.trim_end().len() < line.len()
+    });
+    note(first, count, "Trailing whitespace");
+    let (first, count) = rows_where(&|line| line.contains('\t'));
+    note(first, count, "Tab character");
+    let (first, count) = rows_where(&|line| !line.is_ascii());
+    note(first, count, "Non-ASCII character");
+    let blank = |row: usize| lines[row].trim().is_empty();
+    let last = lines.len() - usize::from(input.ends_with('\n'));
+    if let Some(row) = (1..last).find(|&row| blank(row) && blank(row - 1)) {
+        note(Some(row), 1, "Several blank lines in a row");
+    }
+    if !input.trim().is_empty() {
+        if blank(0) {
+            note(Some(0), 1, "Blank line at the start");
+        }
+        if !input.ends_with('\n') {
+            anomalies.push(Warning {
+                line: None,
+                message: "No newline at the end".to_string(),
+            });
+        }
+    }
+    anomalies
+}
+
+/// Guess the format of `input` and find what may trip a reader up
+#[must_use]
+pub fn sniff(input: &str) -> InputShape {
+    let lines = input.lines().map(str::trim_end).collect::<Vec<_>>();
+    let mut blocks = lines
+        .split(|line| line.is_empty())
+        .filter(|block| !block.is_empty())
+        .map(classify)
+        .collect::<Vec<_>>();
+    let kind = match blocks.len() {
+        0 => InputKind::Empty,
+        1 => blocks.remove(0),
+        _ => InputKind::Sections(blocks),
+    };
+    InputShape {
+        kind,
+        anomalies: anomalies(input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff() {
+        let kind = |input: &str| sniff(input).kind;
+        assert_eq!(kind(" \n"), InputKind::Empty);
+        assert_eq!(
+            kind("..@\n@.@\n"),
+            InputKind::CharGrid {
+                rows: 2,
+                cols: 3,
+                chars: BTreeSet::from(['.', '@'])
+            }
+        );
+        assert!(matches!(
+            kind("987654321\n811111111\n"),
+            InputKind::CharGrid {
+                rows: 2,
+                cols: 9,
+                ..
+            }
+        ));
+        assert!(matches!(
+            kind("1 -2 3\n4 5\n"),
+            InputKind::IntegerRows {
+                min_cols: 2,
+                max_cols: 3,
+                separator: Separator::Whitespace,
+                ..
+            }
+        ));
+        let ranges_then_ids = kind("3-5\n10-14\n\n1\n17\n");
+        let InputKind::Sections(sections) = ranges_then_ids else {
+            panic!("Expected sections, got {ranges_then_ids:?}");
+        };
+        assert!(matches!(
+            sections[..],
+            [
+                InputKind::IntegerRows {
+                    separator: Separator::Dash,
+                    ..
+                },
+                InputKind::IntegerRows { max_cols: 1, .. }
+            ]
+        ));
+        assert_eq!(
+            kind("aaa: you hhh\nyou: bbb\n"),
+            InputKind::EdgeList {
+                rows: 2,
+                separator: ": "
+            }
+        );
+        assert_eq!(
+            kind("123 328  51\n 45 64  387\n*   +   *  \n"),
+            InputKind::FixedWidth {
+                rows: 3,
+                widths: vec![4, 4]
+            }
+        );
+        assert_eq!(kind("R68\nL30 now\n"), InputKind::Lines { rows: 2 });
+        let shape = sniff("..#  \r\n.#.\r\n\n\n#..");
+        assert_eq!(
+            shape
+                .anomalies
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "Line 1: Windows line ending, on 2 lines",
+                "Line 1: Trailing whitespace",
+                "Line 4: Several blank lines in a row",
+                "No newline at the end"
+            ]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! A small, seedable pseudo-random number generator for randomized search
+//! and input generation.
+
+/// The `SplitMix64` generator: fast, statistically decent, and good enough for
+/// randomized search. Not suitable for anything security related.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// A generator producing the same sequence for the same `seed`
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Next uniformly distributed 64-bit value
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..n`, with negligible bias for small `n`
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Cannot sample from an empty range");
+        // Multiply-shift maps the full 64-bit range onto `0..n`
+        self.below_u64(n as u64) as usize
+    }
+
+    /// Uniform value in the inclusive range `lo..=hi`
+    ///
+    /// # Panics
+    /// Panics if `lo > hi`.
+    pub fn between(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo <= hi, "Cannot sample from an empty range");
+        let span = hi.abs_diff(lo);
+        // The full 64-bit span is the only one that overflows `span + 1`
+        let offset = match span.checked_add(1) {
+            Some(n) => self.below_u64(n),
+            None => self.next_u64(),
+        };
+        lo.wrapping_add_unsigned(offset)
+    }
+
+    /// Uniform value in `0..n` for a 64-bit bound
+    #[allow(clippy::cast_possible_truncation)]
+    const fn below_u64(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// Uniform value in `[0, 1)`
+    pub fn unit(&mut self) -> f64 {
+        // Fill the 52 mantissa bits of a float in `[1, 2)`
+        f64::from_bits(0x3ff0_0000_0000_0000 | (self.next_u64() >> 12)) - 1.0
+    }
+
+    /// `true` with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.unit() < p
+    }
+
+    /// Shuffle `items` uniformly in place (Fisher-Yates)
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// An independent generator, e.g. for a parallel task
+    #[must_use]
+    pub const fn fork(&mut self) -> Self {
+        Self::new(self.next_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling() {
+        let mut rng = Rng::new(42);
+        assert_eq!(Rng::new(42).next_u64(), rng.clone().next_u64());
+        let mut counts = [0u32; 6];
+        for _ in 0..6000 {
+            counts[rng.below(6)] += 1;
+        }
+        assert!(
+            counts.iter().all(|&c| (900..1100).contains(&c)),
+            "{counts:?}"
+        );
+        for _ in 0..1000 {
+            let x = rng.unit();
+            assert!((0.0..1.0).contains(&x));
+        }
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.between(-3, 3)));
+        }
+        assert_eq!(rng.between(7, 7), 7);
+        rng.between(i64::MIN, i64::MAX);
+        let mut items = (0..10).collect::<Vec<_>>();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..10).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,642 @@
+//! Boolean satisfiability, for puzzles that are easiest to state as CNF.
+//!
+//! The solver is a small conflict-driven clause learning (CDCL) engine with
+//! two watched literals, first-UIP learning, activity-based branching, phase
+//! saving and Luby restarts. It does not delete learnt clauses, which is fine
+//! for puzzle-sized instances. It is only available with the `sat` feature.
+
+use std::ops::Not;
+
+/// A boolean variable of a [`Solver`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Var(usize);
+
+/// A variable or its negation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit(usize);
+
+impl Var {
+    /// Index of the variable, in creation order
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0
+    }
+
+    /// The literal that is true when the variable is true
+    #[must_use]
+    pub const fn positive(self) -> Lit {
+        Lit(self.0 << 1)
+    }
+
+    /// The literal that is true when the variable is false
+    #[must_use]
+    pub const fn negative(self) -> Lit {
+        Lit(self.0 << 1 | 1)
+    }
+}
+
+impl Lit {
+    /// The underlying variable
+    #[must_use]
+    pub const fn var(self) -> Var {
+        Var(self.0 >> 1)
+    }
+
+    /// Whether this is the negation of its variable
+    #[must_use]
+    pub const fn is_negated(self) -> bool {
+        self.0 & 1 == 1
+    }
+}
+
+impl Not for Lit {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(self.0 ^ 1)
+    }
+}
+
+impl From<Var> for Lit {
+    fn from(var: Var) -> Self {
+        var.positive()
+    }
+}
+
+/// A satisfying assignment found by [`Solver::solve`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Model {
+    values: Vec<bool>,
+}
+
+impl Model {
+    /// Value of `var` in the assignment
+    #[must_use]
+    pub fn value(&self, var: Var) -> bool {
+        self.values[var.0]
+    }
+
+    /// Whether `lit` is satisfied by the assignment
+    #[must_use]
+    pub fn lit(&self, lit: Lit) -> bool {
+        self.values[lit.var().0] != lit.is_negated()
+    }
+
+    /// The literals satisfied by the assignment, one per variable
+    pub fn literals(&self) -> impl Iterator<Item = Lit> + '_ {
+        self.values.iter().enumerate().map(|(v, &value)| {
+            if value {
+                Var(v).positive()
+            } else {
+                Var(v).negative()
+            }
+        })
+    }
+}
+
+/// A CNF formula together with the state of its solver.
+///
+/// Clauses can be added between calls to [`Self::solve`], e.g. to block a
+/// model and enumerate the next one.
+#[derive(Clone, Debug)]
+pub struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    /// Clauses watching each literal, triggered when it becomes false
+    watches: Vec<Vec<usize>>,
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    /// Start of each decision level in the trail
+    trail_lim: Vec<usize>,
+    /// Next trail position to propagate
+    queue_head: usize,
+    activity: Vec<f64>,
+    activity_inc: f64,
+    /// Last assigned value of each variable, reused when branching
+    phase: Vec<bool>,
+    seen: Vec<bool>,
+    /// Set once the empty clause has been derived
+    unsatisfiable: bool,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `i`-th element (1-based) of the Luby sequence 1, 1, 2, 1, 1, 2, 4, ...
+const fn luby(mut i: u64) -> u64 {
+    loop {
+        // Find the smallest complete subsequence of length 2^k - 1 covering i
+        let mut k = 1;
+        while (1 << k) - 1 < i {
+            k += 1;
+        }
+        if i == (1 << k) - 1 {
+            return 1 << (k - 1);
+        }
+        i -= (1 << (k - 1)) - 1;
+    }
+}
+
+impl Solver {
+    /// Create a solver for the empty formula
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            clauses: Vec::new(),
+            watches: Vec::new(),
+            assigns: Vec::new(),
+            level: Vec::new(),
+            reason: Vec::new(),
+            trail: Vec::new(),
+            trail_lim: Vec::new(),
+            queue_head: 0,
+            activity: Vec::new(),
+            activity_inc: 1.0,
+            phase: Vec::new(),
+            seen: Vec::new(),
+            unsatisfiable: false,
+        }
+    }
+
+    /// Number of variables
+    #[must_use]
+    pub const fn num_vars(&self) -> usize {
+        self.assigns.len()
+    }
+
+    /// Create a fresh variable
+    pub fn new_var(&mut self) -> Var {
+        let var = Var(self.assigns.len());
+        self.assigns.push(None);
+        self.level.push(0);
+        self.reason.push(None);
+        self.activity.push(0.0);
+        self.phase.push(false);
+        self.seen.push(false);
+        self.watches.extend([Vec::new(), Vec::new()]);
+        var
+    }
+
+    /// Create `n` fresh variables
+    pub fn new_vars(&mut self, n: usize) -> Vec<Var> {
+        (0..n).map(|_| self.new_var()).collect()
+    }
+
+    /// Require at least one of `lits` to be true
+    pub fn add_clause(&mut self, lits: &[Lit]) {
+        if self.unsatisfiable {
+            return;
+        }
+        let mut clause = lits.to_vec();
+        clause.sort_unstable();
+        clause.dedup();
+        // Tautologies contain both polarities of a variable, which are adjacent
+        if clause.windows(2).any(|w| w[0] == !w[1]) {
+            return;
+        }
+        // Clauses are only added at the root level, where assignments are final
+        if clause.iter().any(|&lit| self.value(lit) == Some(true)) {
+            return;
+        }
+        clause.retain(|&lit| self.value(lit).is_none());
+        match clause.len() {
+            0 => self.unsatisfiable = true,
+            1 => self.enqueue(clause[0], None),
+            _ => {
+                self.attach(clause);
+            }
+        }
+    }
+
+    /// Require at most one of `lits` to be true
+    pub fn at_most_one(&mut self, lits: &[Lit]) {
+        if lits.len() <= 6 {
+            // The pairwise encoding is smaller for few literals
+            for (i, &a) in lits.iter().enumerate() {
+                for &b in &lits[i + 1..] {
+                    self.add_clause(&[!a, !b]);
+                }
+            }
+        } else {
+            self.at_most_k(lits, 1);
+        }
+    }
+
+    /// Require exactly one of `lits` to be true
+    pub fn exactly_one(&mut self, lits: &[Lit]) {
+        self.add_clause(lits);
+        self.at_most_one(lits);
+    }
+
+    /// Require at most `k` of `lits` to be true, using the sequential counter
+    /// encoding with `O(n * k)` auxiliary variables and clauses.
+    pub fn at_most_k(&mut self, lits: &[Lit], k: usize) {
+        let n = lits.len();
+        if k >= n {
+            return;
+        }
+        if k == 0 {
+            for &lit in lits {
+                self.add_clause(&[!lit]);
+            }
+            return;
+        }
+        // counter[i][j] means at least j + 1 of the first i + 1 literals are true
+        let counter = (0..n - 1).map(|_| self.new_vars(k)).collect::<Vec<_>>();
+        self.add_clause(&[!lits[0], counter[0][0].positive()]);
+        for var in &counter[0][1..] {
+            self.add_clause(&[var.negative()]);
+        }
+        for i in 1..n - 1 {
+            let (prev, curr) = (&counter[i - 1], &counter[i]);
+            self.add_clause(&[!lits[i], curr[0].positive()]);
+            for j in 0..k {
+                self.add_clause(&[prev[j].negative(), curr[j].positive()]);
+                if j > 0 {
+                    self.add_clause(&[!lits[i], prev[j - 1].negative(), curr[j].positive()]);
+                }
+            }
+            self.add_clause(&[!lits[i], prev[k - 1].negative()]);
+        }
+        self.add_clause(&[!lits[n - 1], counter[n - 2][k - 1].negative()]);
+    }
+
+    /// Require at least `k` of `lits` to be true
+    pub fn at_least_k(&mut self, lits: &[Lit], k: usize) {
+        match lits.len().checked_sub(k) {
+            Some(max_false) => {
+                let negated = lits.iter().map(|&lit| !lit).collect::<Vec<_>>();
+                self.at_most_k(&negated, max_false);
+            }
+            None => self.add_clause(&[]),
+        }
+    }
+
+    /// Require exactly `k` of `lits` to be true
+    pub fn exactly_k(&mut self, lits: &[Lit], k: usize) {
+        self.at_most_k(lits, k);
+        self.at_least_k(lits, k);
+    }
+
+    /// Search for an assignment satisfying all clauses added so far
+    pub fn solve(&mut self) -> Option<Model> {
+        if self.unsatisfiable {
+            return None;
+        }
+        let mut restarts = 1;
+        let mut conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.trail_lim.is_empty() {
+                    self.unsatisfiable = true;
+                    return None;
+                }
+                let (learnt, level) = self.analyze(conflict);
+                self.backtrack(level);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let id = self.attach(learnt);
+                    self.enqueue(asserting, Some(id));
+                }
+                self.activity_inc /= 0.95;
+                conflicts += 1;
+            } else if conflicts >= 100 * luby(restarts) {
+                self.backtrack(0);
+                restarts += 1;
+                conflicts = 0;
+            } else if let Some(var) = self.pick_branch() {
+                self.trail_lim.push(self.trail.len());
+                let lit = if self.phase[var.0] {
+                    var.positive()
+                } else {
+                    var.negative()
+                };
+                self.enqueue(lit, None);
+            } else {
+                let values = self.assigns.iter().map(|v| *v == Some(true)).collect();
+                self.backtrack(0);
+                return Some(Model { values });
+            }
+        }
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[lit.var().0].map(|v| v != lit.is_negated())
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var().0;
+        self.assigns[var] = Some(!lit.is_negated());
+        self.level[var] = self.trail_lim.len();
+        self.reason[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Store a clause of at least 2 literals, watching the first two
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let id = self.clauses.len();
+        self.watches[clause[0].0].push(id);
+        self.watches[clause[1].0].push(id);
+        self.clauses.push(clause);
+        id
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        let Some(&start) = self.trail_lim.get(level) else {
+            return;
+        };
+        for lit in self.trail.drain(start..) {
+            let var = lit.var().0;
+            self.phase[var] = self.assigns[var] == Some(true);
+            self.assigns[var] = None;
+            self.reason[var] = None;
+        }
+        self.trail_lim.truncate(level);
+        self.queue_head = self.queue_head.min(start);
+    }
+
+    /// Unit propagation over the watched literals, returning a conflicting
+    /// clause if any
+    fn propagate(&mut self) -> Option<usize> {
+        while let Some(&lit) = self.trail.get(self.queue_head) {
+            self.queue_head += 1;
+            let false_lit = !lit;
+            let mut watchers = std::mem::take(&mut self.watches[false_lit.0]);
+            let mut i = 0;
+            while i < watchers.len() {
+                let id = watchers[i];
+                let clause = &mut self.clauses[id];
+                // Keep the false literal in the second slot
+                if clause[0] == false_lit {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                let value = |lit: Lit| self.assigns[lit.var().0].map(|v| v != lit.is_negated());
+                if value(first) == Some(true) {
+                    i += 1;
+                    continue;
+                }
+                // Look for another literal to watch instead
+                if let Some(k) = (2..clause.len()).find(|&k| value(clause[k]) != Some(false)) {
+                    clause.swap(1, k);
+                    self.watches[clause[1].0].push(id);
+                    watchers.swap_remove(i);
+                    continue;
+                }
+                i += 1;
+                if value(first) == Some(false) {
+                    self.watches[false_lit.0] = watchers;
+                    return Some(id);
+                }
+                self.enqueue(first, Some(id));
+            }
+            self.watches[false_lit.0] = watchers;
+        }
+        None
+    }
+
+    /// Derive the first-UIP clause from a conflict, returning it with the
+    /// asserting literal first, along with the level to backtrack to.
+    fn analyze(&mut self, mut conflict: usize) -> (Vec<Lit>, usize) {
+        let current_level = self.trail_lim.len();
+        let mut learnt = vec![Lit(0)];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        // The literal implied by the clause being resolved, absent at first
+        let mut implied = None;
+        let uip = loop {
+            for k in 0..self.clauses[conflict].len() {
+                let lit = self.clauses[conflict][k];
+                let var = lit.var().0;
+                if Some(lit) == implied || self.seen[var] || self.level[var] == 0 {
+                    continue;
+                }
+                self.seen[var] = true;
+                self.bump(var);
+                if self.level[var] == current_level {
+                    pending += 1;
+                } else {
+                    learnt.push(lit);
+                }
+            }
+            // Walk back the trail to the next literal involved in the conflict
+            let lit = loop {
+                index -= 1;
+                if self.seen[self.trail[index].var().0] {
+                    break self.trail[index];
+                }
+            };
+            self.seen[lit.var().0] = false;
+            pending -= 1;
+            if pending == 0 {
+                break lit;
+            }
+            implied = Some(lit);
+            conflict = self.reason[lit.var().0]
+                .unwrap_or_else(|| unreachable!("Implied literal without a reason"));
+        };
+        learnt[0] = !uip;
+        for lit in &learnt[1..] {
+            self.seen[lit.var().0] = false;
+        }
+        // Backtrack to the second highest level, keeping its literal watched
+        let level = match (1..learnt.len()).max_by_key(|&k| self.level[learnt[k].var().0]) {
+            Some(k) => {
+                learnt.swap(1, k);
+                self.level[learnt[1].var().0]
+            }
+            None => 0,
+        };
+        (learnt, level)
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.activity[var] += self.activity_inc;
+        if self.activity[var] > 1e100 {
+            self.activity.iter_mut().for_each(|a| *a *= 1e-100);
+            self.activity_inc *= 1e-100;
+        }
+    }
+
+    /// The unassigned variable with the highest activity
+    fn pick_branch(&self) -> Option<Var> {
+        (0..self.assigns.len())
+            .filter(|&v| self.assigns[v].is_none())
+            .max_by(|&a, &b| self.activity[a].total_cmp(&self.activity[b]))
+            .map(Var)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Count all models over `vars` by repeatedly blocking the last one
+    fn count_models(solver: &mut Solver, vars: &[Var]) -> usize {
+        let mut count = 0;
+        while let Some(model) = solver.solve() {
+            count += 1;
+            let blocking = vars
+                .iter()
+                .map(|&v| {
+                    if model.value(v) {
+                        v.negative()
+                    } else {
+                        v.positive()
+                    }
+                })
+                .collect::<Vec<_>>();
+            solver.add_clause(&blocking);
+        }
+        count
+    }
+
+    #[test]
+    fn test_simple() {
+        let mut solver = Solver::new();
+        let [a, b, c] = [solver.new_var(), solver.new_var(), solver.new_var()];
+        solver.add_clause(&[a.positive(), b.positive()]);
+        solver.add_clause(&[!a.positive(), c.positive()]);
+        solver.add_clause(&[b.negative()]);
+        let model = solver
+            .solve()
+            .unwrap_or_else(|| panic!("Formula should be satisfiable"));
+        assert!(model.value(a) && !model.value(b) && model.value(c));
+        assert!(model.lit(c.positive()) && model.lit(b.negative()));
+        solver.add_clause(&[c.negative()]);
+        assert_eq!(solver.solve(), None);
+        assert_eq!(solver.solve(), None);
+    }
+
+    #[test]
+    fn test_pigeonhole() {
+        // n + 1 pigeons never fit into n holes
+        for n in 2..6 {
+            let mut solver = Solver::new();
+            let place = (0..=n).map(|_| solver.new_vars(n)).collect::<Vec<_>>();
+            for pigeon in &place {
+                solver.add_clause(&pigeon.iter().map(|v| v.positive()).collect::<Vec<_>>());
+            }
+            for hole in 0..n {
+                let lits = place.iter().map(|p| p[hole].positive()).collect::<Vec<_>>();
+                solver.at_most_one(&lits);
+            }
+            assert_eq!(solver.solve(), None, "{n} holes");
+        }
+    }
+
+    #[test]
+    fn test_queens() {
+        let n = 10;
+        let mut solver = Solver::new();
+        let board = (0..n).map(|_| solver.new_vars(n)).collect::<Vec<_>>();
+        let lits = |cells: Vec<(usize, usize)>| {
+            cells
+                .into_iter()
+                .map(|(r, c)| board[r][c].positive())
+                .collect::<Vec<_>>()
+        };
+        for i in 0..n {
+            solver.exactly_one(&lits((0..n).map(|c| (i, c)).collect()));
+            solver.at_most_one(&lits((0..n).map(|r| (r, i)).collect()));
+        }
+        for d in 0..2 * n - 1 {
+            let diag = (0..n).filter_map(|r| Some((r, (d + r).checked_sub(n - 1)?)));
+            solver.at_most_one(&lits(diag.filter(|&(_, c)| c < n).collect()));
+            let anti = (0..n).filter_map(|r| Some((r, d.checked_sub(r)?)));
+            solver.at_most_one(&lits(anti.filter(|&(_, c)| c < n).collect()));
+        }
+        let model = solver
+            .solve()
+            .unwrap_or_else(|| panic!("10 queens should be solvable"));
+        let queens = (0..n)
+            .map(|r| (0..n).position(|c| model.value(board[r][c])))
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_else(|| panic!("Every row should have a queen"));
+        for i in 0..n {
+            for j in i + 1..n {
+                assert_ne!(queens[i], queens[j]);
+                assert_ne!(queens[i].abs_diff(queens[j]), j - i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cardinality() {
+        for k in 0..=6 {
+            let mut solver = Solver::new();
+            let vars = solver.new_vars(5);
+            let lits = vars.iter().map(|v| v.positive()).collect::<Vec<_>>();
+            solver.exactly_k(&lits, k);
+            let binomial = [1, 5, 10, 10, 5, 1, 0];
+            assert_eq!(count_models(&mut solver, &vars), binomial[k], "k = {k}");
+
+            let mut solver = Solver::new();
+            let vars = solver.new_vars(5);
+            let lits = vars.iter().map(|v| v.positive()).collect::<Vec<_>>();
+            solver.at_most_k(&lits, k);
+            let expected = binomial[..=k.min(5)].iter().sum::<usize>();
+            assert_eq!(count_models(&mut solver, &vars), expected, "k = {k}");
+        }
+    }
+
+    #[test]
+    fn test_random_3sat() {
+        // Cross-check against brute force near the satisfiability threshold
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            usize::try_from(state % bound as u64).unwrap_or_default()
+        };
+        for _ in 0..200 {
+            let n = 10;
+            let clauses = (0..43)
+                .map(|_| {
+                    (0..3)
+                        .map(|_| {
+                            let var = Var(next(n));
+                            if next(2) == 0 {
+                                var.positive()
+                            } else {
+                                var.negative()
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let mut solver = Solver::new();
+            solver.new_vars(n);
+            for clause in &clauses {
+                solver.add_clause(clause);
+            }
+            let satisfied = |model: &Model| {
+                clauses
+                    .iter()
+                    .all(|clause| clause.iter().any(|&lit| model.lit(lit)))
+            };
+            let brute = (0..1 << n).any(|mask: usize| {
+                satisfied(&Model {
+                    values: (0..n).map(|v| mask >> v & 1 == 1).collect(),
+                })
+            });
+            match solver.solve() {
+                Some(model) => assert!(satisfied(&model)),
+                None => assert!(!brute),
+            }
+        }
+    }
+
+    #[test]
+    fn test_luby() {
+        let sequence = (1..=15).map(luby).collect::<Vec<_>>();
+        assert_eq!(sequence, [1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+}
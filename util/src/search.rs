@@ -0,0 +1,9 @@
+//! Generic search routines over implicit state spaces
+
+mod bisect;
+mod shortest_path;
+mod traversal;
+
+pub use bisect::{bisect, bisect_float, bisect_last};
+pub use shortest_path::{SearchResult, astar, bfs_01, dijkstra};
+pub use traversal::{BfsReach, bfs_reach, dfs, label_components};
@@ -0,0 +1,129 @@
+//! Binary search over monotone predicates, a.k.a. "binary search the answer".
+
+use crate::ranges::Integer;
+
+/// The smallest value in `lo..=hi` satisfying `predicate`, which must be
+/// monotone: once it holds for some value, it holds for every larger one.
+///
+/// Returns `None` when the predicate holds nowhere in the range, including
+/// when the range is empty.
+pub fn bisect<T: Integer>(mut lo: T, mut hi: T, mut predicate: impl FnMut(T) -> bool) -> Option<T> {
+    if lo > hi {
+        return None;
+    }
+    // Invariant: the answer, if any, lies in lo..=hi
+    while lo < hi {
+        let mid = lo.floor_midpoint(hi);
+        if predicate(mid) {
+            hi = mid;
+        } else {
+            lo = mid + T::ONE;
+        }
+    }
+    predicate(lo).then_some(lo)
+}
+
+/// The largest value in `lo..=hi` satisfying `predicate`, which must hold for
+/// a prefix of the range, e.g. the most of something that still fits a budget.
+///
+/// Returns `None` when the predicate holds nowhere in the range, including
+/// when the range is empty.
+pub fn bisect_last<T: Integer>(
+    mut lo: T,
+    mut hi: T,
+    mut predicate: impl FnMut(T) -> bool,
+) -> Option<T> {
+    if lo > hi {
+        return None;
+    }
+    // Invariant: the answer, if any, lies in lo..=hi
+    while lo < hi {
+        // Round up so that `lo = mid` always makes progress
+        let mid = lo.floor_midpoint(hi) + T::ONE;
+        if predicate(mid) {
+            lo = mid;
+        } else {
+            hi = mid - T::ONE;
+        }
+    }
+    predicate(lo).then_some(lo)
+}
+
+/// Locate where a monotone `predicate` over `lo..=hi` switches from false to
+/// true, to within `tolerance`.
+///
+/// Returns a value at most `tolerance` above the switching point, which is
+/// `hi` when the predicate never holds and `lo` when it always does.
+#[must_use]
+pub fn bisect_float(
+    mut lo: f64,
+    mut hi: f64,
+    tolerance: f64,
+    mut predicate: impl FnMut(f64) -> bool,
+) -> f64 {
+    if predicate(lo) {
+        return lo;
+    }
+    // Invariant: the predicate fails at lo, and holds at hi unless hi is the
+    // original upper bound
+    loop {
+        let mid = lo.midpoint(hi);
+        // Also stop when floating point precision runs out
+        if hi - lo <= tolerance || mid <= lo || mid >= hi {
+            break;
+        }
+        if predicate(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bisect() {
+        for threshold in -5..=15 {
+            let first = bisect(-3i64, 10, |x| x >= threshold);
+            let expected = (-3..=10).find(|&x| x >= threshold);
+            assert_eq!(first, expected);
+            let last = bisect_last(-3i64, 10, |x| x < threshold);
+            let expected = (-3..=10).rev().find(|&x| x < threshold);
+            assert_eq!(last, expected);
+        }
+        assert_eq!(bisect(5u8, 4, |_| true), None);
+        assert_eq!(bisect_last(5u8, 4, |_| true), None);
+        // Extreme bounds do not overflow
+        assert_eq!(
+            bisect(0, u64::MAX, |x| x >= u64::MAX - 1),
+            Some(u64::MAX - 1)
+        );
+        assert_eq!(bisect(i64::MIN, i64::MAX, |x| x >= -1), Some(-1));
+        assert_eq!(bisect_last(0u64, u64::MAX, |_| true), Some(u64::MAX));
+        assert_eq!(
+            bisect_last(i8::MIN, i8::MAX, |x| x == i8::MIN),
+            Some(i8::MIN)
+        );
+        // The largest integer square root
+        assert_eq!(
+            bisect_last(0u64, 1 << 31, |x| x * x <= 1_000_000_007),
+            Some(31_622)
+        );
+    }
+
+    #[test]
+    fn test_bisect_float() {
+        let sqrt2 = bisect_float(0.0, 2.0, 1e-12, |x| x * x >= 2.0);
+        assert!((sqrt2 - std::f64::consts::SQRT_2).abs() <= 1e-12);
+        assert!(sqrt2 >= std::f64::consts::SQRT_2);
+        assert!((bisect_float(1.0, 5.0, 1e-9, |_| true) - 1.0).abs() < f64::EPSILON);
+        assert!((bisect_float(1.0, 5.0, 1e-9, |_| false) - 5.0).abs() < f64::EPSILON);
+        // A zero tolerance stops at adjacent floats
+        let exact = bisect_float(0.0, 1.0, 0.0, |x| x >= 0.3);
+        assert!(exact >= 0.3 && exact - 0.3 <= f64::EPSILON);
+    }
+}
@@ -0,0 +1,288 @@
+//! Single-source shortest paths over implicit graphs.
+//!
+//! Graphs are never materialized: callers provide a closure producing the
+//! successors of a state along with the cost of each move, so the same
+//! routines work for grid positions, bitmask states, or anything hashable.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Outcome of a successful shortest-path search.
+///
+/// The path itself is not materialized during the search, only predecessor
+/// links are kept, so it can be reconstructed on demand with [`Self::path`].
+#[derive(Clone, Debug)]
+pub struct SearchResult<N, C> {
+    /// Total cost from the start to the goal
+    pub cost: C,
+    /// All states discovered during the search, indexed by their id
+    nodes: Vec<N>,
+    /// Predecessor of each discovered state (by id) on its best known path
+    parents: Vec<Option<usize>>,
+    /// Id of the goal state that terminated the search
+    goal: usize,
+}
+
+impl<N: Clone, C> SearchResult<N, C> {
+    /// The goal state that terminated the search
+    pub fn goal(&self) -> &N {
+        &self.nodes[self.goal]
+    }
+
+    /// Reconstruct the path from the start to the goal, both inclusive
+    #[must_use]
+    pub fn path(&self) -> Vec<N> {
+        let mut path = vec![self.nodes[self.goal].clone()];
+        let mut curr = self.goal;
+        while let Some(parent) = self.parents[curr] {
+            path.push(self.nodes[parent].clone());
+            curr = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Bookkeeping shared by all searches: interning of states into dense ids,
+/// best known costs, and predecessor links.
+struct Arena<N, C> {
+    ids: HashMap<N, usize>,
+    nodes: Vec<N>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy + Ord> Arena<N, C> {
+    fn new(start: N, zero: C) -> Self {
+        Self {
+            ids: HashMap::from([(start.clone(), 0)]),
+            nodes: vec![start],
+            costs: vec![zero],
+            parents: vec![None],
+        }
+    }
+
+    /// Record that `node` is reachable from `parent` with `cost`, returning its
+    /// id if this improves the best known cost.
+    fn relax(&mut self, node: N, parent: usize, cost: C) -> Option<usize> {
+        if let Some(&id) = self.ids.get(&node) {
+            if cost >= self.costs[id] {
+                return None;
+            }
+            self.costs[id] = cost;
+            self.parents[id] = Some(parent);
+            return Some(id);
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.costs.push(cost);
+        self.parents.push(Some(parent));
+        Some(id)
+    }
+
+    fn finish(self, goal: usize) -> SearchResult<N, C> {
+        SearchResult {
+            cost: self.costs[goal],
+            nodes: self.nodes,
+            parents: self.parents,
+            goal,
+        }
+    }
+}
+
+/// Find the cheapest path from `start` to any state satisfying `is_goal`.
+///
+/// `neighbors` yields the successors of a state together with the
+/// (non-negative) cost of moving there.
+///
+/// Returns `None` if no goal state is reachable.
+pub fn dijkstra<N, C, FN, I, FG>(start: N, neighbors: FN, is_goal: FG) -> Option<SearchResult<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    astar(start, neighbors, is_goal, |_| C::default())
+}
+
+/// Find the cheapest path from `start` to any state satisfying `is_goal`,
+/// guided by `heuristic`.
+///
+/// The heuristic must never overestimate the remaining cost to a goal
+/// (admissible), otherwise the result may not be optimal. States are allowed
+/// to be re-expanded, so the heuristic does not need to be consistent.
+///
+/// Returns `None` if no goal state is reachable.
+pub fn astar<N, C, FN, I, FG, FH>(
+    start: N,
+    mut neighbors: FN,
+    mut is_goal: FG,
+    mut heuristic: FH,
+) -> Option<SearchResult<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+    FH: FnMut(&N) -> C,
+{
+    let zero = C::default();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), zero, 0))]);
+    let mut arena = Arena::new(start, zero);
+    while let Some(Reverse((_, cost, id))) = heap.pop() {
+        // Skip stale entries that have been improved since being pushed
+        if cost > arena.costs[id] {
+            continue;
+        }
+        if is_goal(&arena.nodes[id]) {
+            return Some(arena.finish(id));
+        }
+        for (next, step) in neighbors(&arena.nodes[id]) {
+            let next_cost = cost + step;
+            let estimate = next_cost + heuristic(&next);
+            if let Some(next_id) = arena.relax(next, id, next_cost) {
+                heap.push(Reverse((estimate, next_cost, next_id)));
+            }
+        }
+    }
+    None
+}
+
+/// Find the cheapest path from `start` to any state satisfying `is_goal`,
+/// where every move costs either 0 or 1.
+///
+/// This replaces the binary heap of [`dijkstra`] with a deque, pushing free
+/// moves to the front and unit moves to the back.
+///
+/// Returns `None` if no goal state is reachable.
+pub fn bfs_01<N, FN, I, FG>(
+    start: N,
+    mut neighbors: FN,
+    mut is_goal: FG,
+) -> Option<SearchResult<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, bool)>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut deque = VecDeque::from([(0, 0)]);
+    let mut arena = Arena::new(start, 0);
+    while let Some((cost, id)) = deque.pop_front() {
+        if cost > arena.costs[id] {
+            continue;
+        }
+        if is_goal(&arena.nodes[id]) {
+            return Some(arena.finish(id));
+        }
+        for (next, weighted) in neighbors(&arena.nodes[id]) {
+            let next_cost = cost + usize::from(weighted);
+            if let Some(next_id) = arena.relax(next, id, next_cost) {
+                if weighted {
+                    deque.push_back((next_cost, next_id));
+                } else {
+                    deque.push_front((next_cost, next_id));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Pos = (i64, i64);
+
+    /// A 5x5 open grid with a wall at column 2, except for the bottom row
+    fn grid_neighbors(&(r, c): &Pos) -> Vec<(Pos, u64)> {
+        [(0, 1), (1, 0), (0, -1), (-1, 0)]
+            .into_iter()
+            .map(|(dr, dc)| (r + dr, c + dc))
+            .filter(|&(r, c)| (0..5).contains(&r) && (0..5).contains(&c))
+            .filter(|&(r, c)| c != 2 || r == 4)
+            .map(|p| (p, 1))
+            .collect()
+    }
+
+    fn assert_valid_path(path: &[Pos], start: Pos, goal: Pos) {
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for pair in path.windows(2) {
+            assert!(grid_neighbors(&pair[0]).iter().any(|&(p, _)| p == pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let result = dijkstra((0, 0), grid_neighbors, |&p| p == (0, 4))
+            .unwrap_or_else(|| panic!("Goal should be reachable"));
+        assert_eq!(result.cost, 12);
+        assert_eq!(result.goal(), &(0, 4));
+        let path = result.path();
+        assert_eq!(path.len(), 13);
+        assert_valid_path(&path, (0, 0), (0, 4));
+
+        // Start is already a goal
+        let result = dijkstra((1, 1), grid_neighbors, |_| true)
+            .unwrap_or_else(|| panic!("Start should be a goal"));
+        assert_eq!(result.cost, 0);
+        assert_eq!(result.path(), vec![(1, 1)]);
+
+        // Unreachable goal
+        assert!(dijkstra((0, 0), grid_neighbors, |&p| p == (9, 9)).is_none());
+    }
+
+    #[test]
+    fn test_dijkstra_weighted() {
+        // 0 -> 1 (1), 1 -> 3 (10), 0 -> 2 (4), 2 -> 3 (2): best is 0-2-3
+        let edges = [vec![(1, 1), (2, 4)], vec![(3, 10)], vec![(3, 2)], vec![]];
+        let result = dijkstra(0usize, |&n| edges[n].clone(), |&n| n == 3)
+            .unwrap_or_else(|| panic!("Goal should be reachable"));
+        assert_eq!(result.cost, 6);
+        assert_eq!(result.path(), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_astar() {
+        let goal = (0, 4);
+        let manhattan = |&(r, c): &Pos| r.abs_diff(goal.0) + c.abs_diff(goal.1);
+        let result = astar((0, 0), grid_neighbors, |&p| p == goal, manhattan)
+            .unwrap_or_else(|| panic!("Goal should be reachable"));
+        assert_eq!(result.cost, 12);
+        assert_valid_path(&result.path(), (0, 0), goal);
+    }
+
+    #[test]
+    fn test_bfs_01() {
+        // Moving right is free, any other move costs 1
+        let neighbors = |&(r, c): &Pos| {
+            grid_neighbors(&(r, c))
+                .into_iter()
+                .map(move |(p, _)| (p, p != (r, c + 1)))
+                .collect::<Vec<_>>()
+        };
+        let result = bfs_01((0, 0), neighbors, |&p| p == (0, 4))
+            .unwrap_or_else(|| panic!("Goal should be reachable"));
+        // 4 down, 2 right for free, 4 up, 2 right for free
+        assert_eq!(result.cost, 8);
+        let path = result.path();
+        assert_valid_path(&path, (0, 0), (0, 4));
+        let dijkstra_cost = dijkstra(
+            (0, 0),
+            |p| neighbors(p).into_iter().map(|(p, w)| (p, usize::from(w))),
+            |&p| p == (0, 4),
+        )
+        .map(|r| r.cost);
+        assert_eq!(dijkstra_cost, Some(8));
+    }
+}
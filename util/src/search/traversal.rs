@@ -0,0 +1,209 @@
+//! Unweighted traversal over implicit graphs.
+//!
+//! Like the shortest-path routines, states are discovered through a closure
+//! producing their successors, so both abstract states and `(row, col)` grid
+//! positions can be traversed without building a graph first.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// Breadth-first iterator over all states reachable from a start state.
+///
+/// Created by [`bfs_reach`].
+pub struct BfsReach<N, FN> {
+    queue: VecDeque<(N, usize)>,
+    seen: HashSet<N>,
+    neighbors: FN,
+    depth: usize,
+}
+
+impl<N, FN> BfsReach<N, FN> {
+    /// Number of steps from the start to the most recently yielded state
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<N, FN, I> Iterator for BfsReach<N, FN>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, depth) = self.queue.pop_front()?;
+        for next in (self.neighbors)(&node) {
+            if self.seen.insert(next.clone()) {
+                self.queue.push_back((next, depth + 1));
+            }
+        }
+        self.depth = depth;
+        Some(node)
+    }
+}
+
+/// Lazily visit every state reachable from `start` in breadth-first order,
+/// starting with `start` itself. Each state is yielded exactly once.
+pub fn bfs_reach<N, FN, I>(start: N, neighbors: FN) -> BfsReach<N, FN>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    BfsReach {
+        seen: HashSet::from([start.clone()]),
+        queue: VecDeque::from([(start, 0)]),
+        neighbors,
+        depth: 0,
+    }
+}
+
+/// Depth-first traversal from `start` with visitor callbacks.
+///
+/// `pre_order` is called when a state is first entered, and `post_order` once
+/// all states reachable through it have been finished. The traversal uses an
+/// explicit stack, so deep graphs cannot overflow the call stack.
+///
+/// Returns the set of visited states.
+pub fn dfs<N, FN, I, FPre, FPost>(
+    start: N,
+    mut neighbors: FN,
+    mut pre_order: FPre,
+    mut post_order: FPost,
+) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    FPre: FnMut(&N),
+    FPost: FnMut(&N),
+{
+    let mut visited = HashSet::from([start.clone()]);
+    pre_order(&start);
+    let mut stack = vec![(neighbors(&start).into_iter(), start)];
+    while let Some((children, node)) = stack.last_mut() {
+        if let Some(child) = children.find(|child| !visited.contains(child)) {
+            visited.insert(child.clone());
+            pre_order(&child);
+            stack.push((neighbors(&child).into_iter(), child));
+        } else {
+            post_order(node);
+            stack.pop();
+        }
+    }
+    visited
+}
+
+/// Label the connected components among `nodes`.
+///
+/// `neighbors` is expected to be symmetric, i.e. describe an undirected graph.
+/// Labels are dense and assigned in the order components are first discovered
+/// while iterating `nodes`, so the number of components is one more than the
+/// largest label.
+pub fn label_components<N, FN, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbors: FN,
+) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut labels = HashMap::new();
+    let mut count = 0;
+    for node in nodes {
+        if labels.contains_key(&node) {
+            continue;
+        }
+        for member in bfs_reach(node, &mut neighbors) {
+            labels.insert(member, count);
+        }
+        count += 1;
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Neighbors of open cells in a small maze, as `(row, col)` positions
+    fn maze_neighbors(maze: &[&str]) -> impl FnMut(&(usize, usize)) -> Vec<(usize, usize)> {
+        let cells = maze
+            .iter()
+            .map(|row| row.chars().map(|c| c == '.').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        move |&(r, c)| {
+            [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                .into_iter()
+                .filter_map(|(dr, dc)| Some((r.checked_add_signed(dr)?, c.checked_add_signed(dc)?)))
+                .filter(|&(r, c)| cells.get(r).and_then(|row| row.get(c)) == Some(&true))
+                .collect()
+        }
+    }
+
+    const MAZE: [&str; 4] = ["..#.", ".##.", "...#", "#.#."];
+
+    #[test]
+    fn test_bfs_reach() {
+        let mut reach = bfs_reach((0, 0), maze_neighbors(&MAZE));
+        let mut visited = Vec::new();
+        let mut depths = Vec::new();
+        while let Some(pos) = reach.next() {
+            visited.push(pos);
+            depths.push(reach.depth());
+        }
+        assert_eq!(visited.len(), 7);
+        assert_eq!(visited[0], (0, 0));
+        // Depths never decrease in BFS order, and the farthest cell is (3, 1)
+        assert!(depths.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(visited.last(), Some(&(3, 1)));
+        assert_eq!(depths.last(), Some(&4));
+
+        // Abstract states: numbers reachable by doubling modulo 10
+        let reached = bfs_reach(1u32, |&n| [n * 2 % 10]).collect::<Vec<_>>();
+        assert_eq!(reached, vec![1, 2, 4, 8, 6]);
+    }
+
+    #[test]
+    fn test_dfs() {
+        // 0 -> 1 -> 3, 0 -> 2 -> 3
+        let edges = [vec![1, 2], vec![3], vec![3], vec![]];
+        let mut pre = Vec::new();
+        let mut post = Vec::new();
+        let visited = dfs(
+            0,
+            |&n| edges[n].clone(),
+            |&n| pre.push(n),
+            |&n| post.push(n),
+        );
+        assert_eq!(visited.len(), 4);
+        assert_eq!(pre, vec![0, 1, 3, 2]);
+        assert_eq!(post, vec![3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_label_components() {
+        let cells = MAZE
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter_map(move |(c, ch)| (ch == '.').then_some((r, c)))
+            })
+            .collect::<Vec<_>>();
+        let labels = label_components(cells, maze_neighbors(&MAZE));
+        assert_eq!(labels.len(), 10);
+        assert_eq!(labels.values().max(), Some(&2));
+        assert_eq!(labels[&(0, 0)], 0);
+        assert_eq!(labels[&(0, 3)], 1);
+        assert_eq!(labels[&(1, 3)], 1);
+        assert_eq!(labels[&(3, 3)], 2);
+        assert_eq!(labels.values().filter(|&&l| l == 0).count(), 7);
+    }
+}
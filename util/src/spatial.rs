@@ -0,0 +1,5 @@
+//! Spatial indexes for proximity queries over point sets
+
+mod kd_tree;
+
+pub use kd_tree::KdTree;
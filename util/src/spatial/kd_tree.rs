@@ -0,0 +1,278 @@
+//! A static k-d tree over integer points, using squared Euclidean distances.
+
+use std::collections::BinaryHeap;
+
+/// A balanced k-d tree over a fixed set of `K`-dimensional integer points.
+///
+/// The tree is stored implicitly: the points of every subtree occupy a
+/// contiguous range of `order`, with the splitting point at its middle.
+/// Queries report points by their index in the original slice, along with
+/// their squared Euclidean distance to the query.
+#[derive(Clone, Debug)]
+pub struct KdTree<const K: usize = 3> {
+    points: Vec<[i64; K]>,
+    order: Vec<usize>,
+}
+
+/// Squared Euclidean distance between two points
+fn dist2<const K: usize>(a: &[i64; K], b: &[i64; K]) -> i64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Accumulates candidates during a tree traversal
+trait Collector {
+    /// Largest squared distance still worth exploring
+    fn bound(&self) -> i64;
+    /// Consider the point `idx` at squared distance `dist`
+    fn offer(&mut self, dist: i64, idx: usize);
+}
+
+struct Nearest<F> {
+    best: Option<(i64, usize)>,
+    predicate: F,
+}
+
+impl<F: FnMut(usize) -> bool> Collector for Nearest<F> {
+    fn bound(&self) -> i64 {
+        self.best.map_or(i64::MAX, |(dist, _)| dist)
+    }
+
+    fn offer(&mut self, dist: i64, idx: usize) {
+        if self.best.is_none_or(|best| (dist, idx) < best) && (self.predicate)(idx) {
+            self.best = Some((dist, idx));
+        }
+    }
+}
+
+struct KNearest {
+    k: usize,
+    heap: BinaryHeap<(i64, usize)>,
+}
+
+impl Collector for KNearest {
+    fn bound(&self) -> i64 {
+        if self.heap.len() < self.k {
+            i64::MAX
+        } else {
+            self.heap.peek().map_or(i64::MAX, |&(dist, _)| dist)
+        }
+    }
+
+    fn offer(&mut self, dist: i64, idx: usize) {
+        self.heap.push((dist, idx));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+}
+
+struct Radius {
+    radius2: i64,
+    found: Vec<(i64, usize)>,
+}
+
+impl Collector for Radius {
+    fn bound(&self) -> i64 {
+        self.radius2
+    }
+
+    fn offer(&mut self, dist: i64, idx: usize) {
+        if dist <= self.radius2 {
+            self.found.push((dist, idx));
+        }
+    }
+}
+
+impl<const K: usize> KdTree<K> {
+    /// Build the tree over `points` in `O(n log n)`
+    #[must_use]
+    pub fn new(points: &[[i64; K]]) -> Self {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        Self::build(points, &mut order, 0);
+        Self {
+            points: points.to_vec(),
+            order,
+        }
+    }
+
+    fn build(points: &[[i64; K]], order: &mut [usize], depth: usize) {
+        if order.len() <= 1 {
+            return;
+        }
+        let mid = order.len() / 2;
+        let axis = depth % K;
+        order.select_nth_unstable_by_key(mid, |&i| points[i][axis]);
+        let (left, right) = order.split_at_mut(mid);
+        Self::build(points, left, depth + 1);
+        Self::build(points, &mut right[1..], depth + 1);
+    }
+
+    /// Number of points in the tree
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the tree has no points
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The point with index `idx` in the original slice
+    #[must_use]
+    pub fn point(&self, idx: usize) -> &[i64; K] {
+        &self.points[idx]
+    }
+
+    fn visit(
+        &self,
+        range: (usize, usize),
+        depth: usize,
+        query: &[i64; K],
+        collector: &mut impl Collector,
+    ) {
+        let (lo, hi) = range;
+        if lo >= hi {
+            return;
+        }
+        let mid = lo.midpoint(hi);
+        let idx = self.order[mid];
+        let point = &self.points[idx];
+        collector.offer(dist2(point, query), idx);
+        let axis = depth % K;
+        let diff = query[axis] - point[axis];
+        let (near, far) = if diff < 0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.visit(near, depth + 1, query, collector);
+        // The far side is at least `diff` away along the splitting axis
+        if diff * diff <= collector.bound() {
+            self.visit(far, depth + 1, query, collector);
+        }
+    }
+
+    /// The closest point to `query` as `(squared distance, index)`, with ties
+    /// broken by the smaller index
+    #[must_use]
+    pub fn nearest(&self, query: &[i64; K]) -> Option<(i64, usize)> {
+        self.nearest_where(query, |_| true)
+    }
+
+    /// The closest point to `query` among those whose index satisfies
+    /// `predicate`, e.g. to exclude the query point itself.
+    ///
+    /// Rejected points do not help pruning, so this degrades towards a linear
+    /// scan when most of the nearby points are rejected.
+    pub fn nearest_where(
+        &self,
+        query: &[i64; K],
+        predicate: impl FnMut(usize) -> bool,
+    ) -> Option<(i64, usize)> {
+        let mut collector = Nearest {
+            best: None,
+            predicate,
+        };
+        self.visit((0, self.order.len()), 0, query, &mut collector);
+        collector.best
+    }
+
+    /// The `k` closest points to `query` as `(squared distance, index)`, in
+    /// ascending order.
+    ///
+    /// Ties are broken by the smaller index, so the `k` closest points are
+    /// always the first `k` of any query for more of them.
+    #[must_use]
+    pub fn k_nearest(&self, query: &[i64; K], k: usize) -> Vec<(i64, usize)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut collector = KNearest {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        };
+        self.visit((0, self.order.len()), 0, query, &mut collector);
+        collector.heap.into_sorted_vec()
+    }
+
+    /// All points within squared distance `radius2` of `query` (inclusive) as
+    /// `(squared distance, index)`, in ascending order
+    #[must_use]
+    pub fn within_radius(&self, query: &[i64; K], radius2: i64) -> Vec<(i64, usize)> {
+        let mut collector = Radius {
+            radius2,
+            found: Vec::new(),
+        };
+        self.visit((0, self.order.len()), 0, query, &mut collector);
+        collector.found.sort_unstable();
+        collector.found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random points with plenty of duplicate coordinates
+    fn points(n: usize) -> Vec<[i64; 3]> {
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            i64::try_from(state % 50).unwrap_or_default()
+        };
+        (0..n).map(|_| [next(), next(), next()]).collect()
+    }
+
+    fn brute_force(points: &[[i64; 3]], query: &[i64; 3]) -> Vec<(i64, usize)> {
+        let mut all = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (dist2(p, query), i))
+            .collect::<Vec<_>>();
+        all.sort_unstable();
+        all
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let points = points(300);
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.len(), 300);
+        for query in [[0, 0, 0], [25, 25, 25], [49, 3, 17], [-10, 60, 20]]
+            .into_iter()
+            .chain(points.iter().copied().take(20))
+        {
+            let expected = brute_force(&points, &query);
+            assert_eq!(tree.nearest(&query), expected.first().copied());
+            assert_eq!(tree.k_nearest(&query, 7), expected[..7]);
+            assert_eq!(tree.k_nearest(&query, 7), tree.k_nearest(&query, 8)[..7]);
+            assert_eq!(tree.k_nearest(&query, 1000), expected);
+            let within = expected
+                .iter()
+                .copied()
+                .take_while(|&(d, _)| d <= 100)
+                .collect::<Vec<_>>();
+            assert_eq!(tree.within_radius(&query, 100), within);
+            let odd = expected.iter().copied().find(|&(_, i)| i % 2 == 1);
+            assert_eq!(tree.nearest_where(&query, |i| i % 2 == 1), odd);
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let tree = KdTree::<2>::new(&[]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.nearest(&[0, 0]), None);
+        assert!(tree.k_nearest(&[0, 0], 3).is_empty());
+
+        let tree = KdTree::new(&[[1, 2], [1, 2], [5, 5]]);
+        assert_eq!(tree.nearest(&[1, 2]), Some((0, 0)));
+        assert_eq!(tree.nearest_where(&[1, 2], |i| i != 0), Some((0, 1)));
+        assert_eq!(tree.k_nearest(&[0, 0], 0), vec![]);
+        assert_eq!(tree.point(2), &[5, 5]);
+    }
+}
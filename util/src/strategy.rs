@@ -0,0 +1,130 @@
+//! Proptest strategies for puzzle-shaped inputs, with the `proptest` feature.
+//!
+//! Where [`crate::generate`] draws inputs from a seeded [`crate::rng::Rng`]
+//! for [`crate::generate::stress_test`], these are [`Strategy`]s, so they
+//! compose with the rest of proptest and shrink on their own. The days build
+//! on them in their tests to render whole inputs in their own format, to
+//! check that parsing never panics and that solvers agree.
+
+use std::ops::RangeInclusive;
+
+use proptest::{collection::vec, prelude::*, sample::Index};
+
+/// Text grids of `rows` by `cols` cells drawn from `alphabet`, one line per
+/// row, each ending with a newline
+pub fn grid(
+    rows: RangeInclusive<usize>,
+    cols: RangeInclusive<usize>,
+    alphabet: &'static [char],
+) -> impl Strategy<Value = String> {
+    (rows, cols).prop_flat_map(move |(rows, cols)| {
+        vec(vec(proptest::sample::select(alphabet), cols), rows).prop_map(|lines| {
+            lines
+                .into_iter()
+                .map(|line| line.into_iter().chain(['\n']).collect::<String>())
+                .collect()
+        })
+    })
+}
+
+/// Lists of `len` integers drawn from `values`
+pub fn int_list(
+    len: RangeInclusive<usize>,
+    values: RangeInclusive<i64>,
+) -> impl Strategy<Value = Vec<i64>> {
+    vec(values, len)
+}
+
+/// DAGs with `nodes` nodes, as the children of each node, where every edge
+/// points from a smaller to a larger node and is present with probability
+/// `density`. Shrinking drops edges.
+pub fn dag(nodes: RangeInclusive<usize>, density: f64) -> impl Strategy<Value = Vec<Vec<usize>>> {
+    nodes.prop_flat_map(move |n| {
+        let pairs = n * n.saturating_sub(1) / 2;
+        vec(proptest::bool::weighted(density), pairs).prop_map(move |edges| {
+            let mut children = vec![Vec::new(); n];
+            let mut edges = edges.into_iter();
+            for (from, children) in children.iter_mut().enumerate() {
+                for to in from + 1..n {
+                    if edges.next() == Some(true) {
+                        children.push(to);
+                    }
+                }
+            }
+            children
+        })
+    })
+}
+
+/// Lines from `line`, `len` of them, each ending with a newline
+pub fn lines(
+    line: impl Strategy<Value = String>,
+    len: RangeInclusive<usize>,
+) -> impl Strategy<Value = String> {
+    vec(line, len).prop_map(|lines| lines.into_iter().map(|line| line + "\n").collect())
+}
+
+/// Inputs from `input` with up to `edits` characters replaced by, or
+/// inserted from, `alphabet`
+///
+/// Being mostly well-formed, they reach deeper into a parser than arbitrary
+/// strings, for checking that it errors instead of panicking.
+pub fn corrupted(
+    input: impl Strategy<Value = String>,
+    edits: usize,
+    alphabet: &'static [char],
+) -> impl Strategy<Value = String> {
+    let edit = (
+        any::<Index>(),
+        proptest::sample::select(alphabet),
+        any::<bool>(),
+    );
+    (input, vec(edit, 0..=edits)).prop_map(|(input, edits)| {
+        let mut chars = input.chars().collect::<Vec<_>>();
+        for (at, c, insert) in edits {
+            if insert || chars.is_empty() {
+                chars.insert(at.index(chars.len() + 1), c);
+            } else {
+                let at = at.index(chars.len());
+                chars[at] = c;
+            }
+        }
+        chars.into_iter().collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::test_runner::TestRunner;
+
+    use super::*;
+
+    #[test]
+    fn test_strategies() {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&grid(1..=5, 2..=2, &['.', '#']), |grid| {
+            prop_assert!(grid.lines().all(|line| line.len() == 2));
+            prop_assert!((1..=5).contains(&grid.lines().count()));
+            prop_assert!(grid.chars().all(|c| ".#\n".contains(c)));
+            Ok(())
+        });
+        assert_eq!(result, Ok(()));
+        let result = runner.run(&dag(0..=6, 0.5), |children| {
+            prop_assert!(children.len() <= 6);
+            for (from, children) in children.iter().enumerate() {
+                prop_assert!(children.is_sorted());
+                prop_assert!(children.iter().all(|&to| from < to && to < 6));
+            }
+            Ok(())
+        });
+        assert_eq!(result, Ok(()));
+        let numbers = lines(int_list(1..=1, 0..=9).prop_map(|x| x[0].to_string()), 3..=3);
+        let result = runner.run(&corrupted(numbers, 2, &['x']), |input| {
+            let xs = input.matches('x').count();
+            prop_assert!(xs <= 2);
+            prop_assert!(input.len() - xs <= 6);
+            Ok(())
+        });
+        assert_eq!(result, Ok(()));
+    }
+}
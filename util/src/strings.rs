@@ -0,0 +1,245 @@
+//! String algorithms for pattern matching and repetition.
+//!
+//! Everything works on slices of any comparable symbol, so `str::as_bytes`,
+//! `Vec<char>` and digit vectors can be used alike.
+
+/// Length of the longest common prefix of two sequences
+#[must_use]
+pub fn longest_common_prefix<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// The Z-function of `s`: `z[i]` is the length of the longest common prefix of
+/// `s` and `s[i..]`, with `z[0] = s.len()` by convention.
+#[must_use]
+pub fn z_function<T: PartialEq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut z = vec![0; n];
+    if n == 0 {
+        return z;
+    }
+    z[0] = n;
+    // [left, right) is the rightmost window known to match a prefix of s
+    let (mut left, mut right) = (0, 0);
+    for i in 1..n {
+        let mut len = if i < right {
+            z[i - left].min(right - i)
+        } else {
+            0
+        };
+        len += longest_common_prefix(&s[len..], &s[i + len..]);
+        z[i] = len;
+        if i + len > right {
+            (left, right) = (i, i + len);
+        }
+    }
+    z
+}
+
+/// The prefix function of `s` (the KMP failure function): `pi[i]` is the length
+/// of the longest proper prefix of `s[..=i]` that is also its suffix.
+#[must_use]
+pub fn prefix_function<T: PartialEq>(s: &[T]) -> Vec<usize> {
+    let mut pi = vec![0; s.len()];
+    for i in 1..s.len() {
+        let mut len = pi[i - 1];
+        while len > 0 && s[i] != s[len] {
+            len = pi[len - 1];
+        }
+        if s[i] == s[len] {
+            len += 1;
+        }
+        pi[i] = len;
+    }
+    pi
+}
+
+/// Starting positions of all (possibly overlapping) occurrences of `needle` in
+/// `haystack`, found with the Knuth-Morris-Pratt algorithm in linear time
+#[must_use]
+pub fn find_all<T: PartialEq>(haystack: &[T], needle: &[T]) -> Vec<usize> {
+    if needle.is_empty() {
+        return (0..=haystack.len()).collect();
+    }
+    let pi = prefix_function(needle);
+    let mut found = Vec::new();
+    let mut len = 0;
+    for (i, symbol) in haystack.iter().enumerate() {
+        while len > 0 && *symbol != needle[len] {
+            len = pi[len - 1];
+        }
+        if *symbol == needle[len] {
+            len += 1;
+        }
+        if len == needle.len() {
+            found.push(i + 1 - len);
+            len = pi[len - 1];
+        }
+    }
+    found
+}
+
+/// Length of the shortest block that `s` is a whole number of repetitions of,
+/// e.g. 2 for `"abababab"` and 5 for `"abcab"`
+#[must_use]
+pub fn smallest_period<T: PartialEq>(s: &[T]) -> usize {
+    let n = s.len();
+    let period = n - prefix_function(s).last().copied().unwrap_or_default();
+    if period > 0 && n.is_multiple_of(period) {
+        period
+    } else {
+        n
+    }
+}
+
+/// Collapse runs of equal symbols into `(symbol, run length)` pairs
+#[must_use]
+pub fn run_length_encode<T: Clone + PartialEq>(s: &[T]) -> Vec<(T, usize)> {
+    s.chunk_by(PartialEq::eq)
+        .map(|run| (run[0].clone(), run.len()))
+        .collect()
+}
+
+/// Expand `(symbol, run length)` pairs back into a sequence
+#[must_use]
+pub fn run_length_decode<T: Clone>(runs: &[(T, usize)]) -> Vec<T> {
+    runs.iter()
+        .flat_map(|(symbol, len)| std::iter::repeat_n(symbol.clone(), *len))
+        .collect()
+}
+
+/// Polynomial hashes of every prefix of a sequence, giving the hash of any
+/// substring in constant time.
+///
+/// Hashes are taken modulo the Mersenne prime `2^61 - 1`, which makes
+/// accidental collisions unlikely enough to compare substrings by hash alone.
+#[derive(Clone, Debug)]
+pub struct RollingHash {
+    prefix: Vec<u64>,
+    powers: Vec<u64>,
+}
+
+impl RollingHash {
+    const MOD: u64 = (1 << 61) - 1;
+    const BASE: u64 = 0x5851_f42d_4c95_7f2d % Self::MOD;
+
+    /// Hash every prefix of `s`
+    #[must_use]
+    pub fn new<T: Copy + Into<u64>>(s: &[T]) -> Self {
+        let mut prefix = Vec::with_capacity(s.len() + 1);
+        let mut powers = Vec::with_capacity(s.len() + 1);
+        prefix.push(0);
+        powers.push(1);
+        for (i, &symbol) in s.iter().enumerate() {
+            // Offset symbols by one so that leading zeros still change the hash
+            let value = (symbol.into() % Self::MOD + 1) % Self::MOD;
+            prefix.push(Self::add(Self::mul(prefix[i], Self::BASE), value));
+            powers.push(Self::mul(powers[i], Self::BASE));
+        }
+        Self { prefix, powers }
+    }
+
+    /// Length of the hashed sequence
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.powers.len() - 1
+    }
+
+    /// Whether the hashed sequence is empty
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hash of the substring `s[start..end]`
+    #[must_use]
+    pub fn hash(&self, start: usize, end: usize) -> u64 {
+        let shifted = Self::mul(self.prefix[start], self.powers[end - start]);
+        Self::add(self.prefix[end], Self::MOD - shifted)
+    }
+
+    /// Whether `s[a..a + len]` and `s[b..b + len]` are (almost surely) equal
+    #[must_use]
+    pub fn equal(&self, a: usize, b: usize, len: usize) -> bool {
+        self.hash(a, a + len) == self.hash(b, b + len)
+    }
+
+    const fn add(a: u64, b: u64) -> u64 {
+        let sum = a + b;
+        if sum >= Self::MOD {
+            sum - Self::MOD
+        } else {
+            sum
+        }
+    }
+
+    const fn mul(a: u64, b: u64) -> u64 {
+        let product = a as u128 * b as u128;
+        // Fold the high bits back in, since 2^61 = 1 (mod 2^61 - 1)
+        let folded = (product >> 61) + (product & Self::MOD as u128);
+        #[allow(clippy::cast_possible_truncation)]
+        Self::add((folded >> 61) as u64, folded as u64 & Self::MOD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_and_z_functions() {
+        let s = b"aabxaab";
+        assert_eq!(z_function(s), vec![7, 1, 0, 0, 3, 1, 0]);
+        assert_eq!(prefix_function(s), vec![0, 1, 0, 0, 1, 2, 3]);
+        assert!(z_function::<u8>(&[]).is_empty());
+        assert_eq!(longest_common_prefix(b"12345", b"123"), 3);
+    }
+
+    #[test]
+    fn test_find_all() {
+        assert_eq!(find_all(b"abababa", b"aba"), vec![0, 2, 4]);
+        assert_eq!(find_all(b"abc", b"d"), Vec::<usize>::new());
+        assert_eq!(find_all(b"ab", b""), vec![0, 1, 2]);
+        assert_eq!(find_all(&[1, 2, 1, 2, 1], &[1, 2, 1]), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_periods_and_runs() {
+        assert_eq!(smallest_period(b"abababab"), 2);
+        assert_eq!(smallest_period(b"abcab"), 5);
+        assert_eq!(smallest_period(b"824824824"), 3);
+        assert_eq!(smallest_period(b"1111"), 1);
+        let runs = run_length_encode(b"aaabccdddd");
+        assert_eq!(runs, vec![(b'a', 3), (b'b', 1), (b'c', 2), (b'd', 4)]);
+        assert_eq!(run_length_decode(&runs), b"aaabccdddd");
+    }
+
+    #[test]
+    fn test_rolling_hash() {
+        let s = "abcabcxabc".chars().collect::<Vec<_>>();
+        let hash = RollingHash::new(&s);
+        assert_eq!(hash.len(), 10);
+        assert!(hash.equal(0, 3, 3));
+        assert!(hash.equal(0, 7, 3));
+        assert!(!hash.equal(0, 1, 3));
+        // Hashes only depend on the content, not the position
+        assert_eq!(
+            RollingHash::new(b"xabc").hash(1, 4),
+            RollingHash::new(b"abc").hash(0, 3)
+        );
+        assert_ne!(
+            RollingHash::new(&[0u8, 1]).hash(0, 2),
+            RollingHash::new(&[1u8]).hash(0, 1)
+        );
+        // Compare every pair of substrings against direct comparison
+        let s = b"abaabaabbab";
+        let hash = RollingHash::new(s);
+        for len in 1..s.len() {
+            for a in 0..=s.len() - len {
+                for b in 0..=s.len() - len {
+                    assert_eq!(hash.equal(a, b, len), s[a..a + len] == s[b..b + len]);
+                }
+            }
+        }
+    }
+}
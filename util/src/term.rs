@@ -0,0 +1,130 @@
+//! ANSI terminal output, for coloring text and replaying simple animations.
+//!
+//! Animations draw their frames into a [`FrameSink`]: the terminal through
+//! [`Animation`], or with the `gif` feature an animated GIF through [`Gif`].
+
+#[cfg(feature = "gif")]
+mod gif;
+
+use std::{
+    fmt::Display,
+    io::{self, Stdout, Write},
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "gif")]
+pub use self::gif::Gif;
+use crate::Result;
+
+/// Clear the whole screen
+const CLEAR: &str = "\x1b[2J";
+/// Move the cursor to the top left corner
+const HOME: &str = "\x1b[H";
+/// Reset all colors and styles
+const RESET: &str = "\x1b[0m";
+
+/// Foreground colors of the standard 16-color palette
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+impl Color {
+    /// SGR parameter selecting this foreground color
+    const fn code(self) -> u8 {
+        match self {
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::Gray => 90,
+        }
+    }
+}
+
+/// `text` in the given color, resetting the style afterwards
+pub fn paint(text: impl Display, color: Color) -> String {
+    format!("\x1b[{}m{text}{RESET}", color.code())
+}
+
+/// Where the frames of an animation go
+pub trait FrameSink {
+    /// Take the next frame, lines of text colored with [`paint`]
+    fn frame(&mut self, frame: &str) -> Result<()>;
+}
+
+/// Frames drawn over each other at a fixed pace.
+///
+/// The screen is cleared once before the first frame, and every frame after
+/// that is drawn from the top left corner, so frames of the same shape
+/// replace each other in place without flickering.
+pub struct Animation<W: Write> {
+    out: W,
+    delay: Duration,
+    started: bool,
+}
+
+impl Animation<Stdout> {
+    /// Animate on the standard output, waiting `delay` after every frame
+    #[must_use]
+    pub fn stdout(delay: Duration) -> Self {
+        Self::new(io::stdout(), delay)
+    }
+}
+
+impl<W: Write> Animation<W> {
+    /// Animate on `out`, waiting `delay` after every frame
+    pub const fn new(out: W, delay: Duration) -> Self {
+        Self {
+            out,
+            delay,
+            started: false,
+        }
+    }
+
+    /// Stop animating, giving back the output
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> FrameSink for Animation<W> {
+    /// Draw `frame`, then wait for the frame delay
+    fn frame(&mut self, frame: &str) -> Result<()> {
+        if !self.started {
+            self.started = true;
+            write!(self.out, "{CLEAR}")?;
+        }
+        write!(self.out, "{HOME}{frame}")?;
+        self.out.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_animation() -> Result<()> {
+        assert_eq!(paint('^', Color::Yellow), "\x1b[33m^\x1b[0m");
+        let mut animation = Animation::new(Vec::new(), Duration::ZERO);
+        animation.frame("a\n")?;
+        animation.frame("b\n")?;
+        let out = String::from_utf8(animation.into_inner())?;
+        assert_eq!(out, "\x1b[2J\x1b[Ha\n\x1b[Hb\n");
+        Ok(())
+    }
+}
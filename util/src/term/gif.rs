@@ -0,0 +1,193 @@
+//! Animated GIFs of terminal animations, to share a simulation rather than
+//! only watch it in the terminal.
+//!
+//! Every character becomes a square of its color, with spaces and dots left
+//! as background, so a grid reads as an image and a line of text as a bar.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use gif::{Encoder, Frame, Repeat};
+
+use super::{Color, FrameSink};
+use crate::{Error, Result, writer::output_path};
+
+/// Every color a frame can have, the background first and the color of text
+/// that is not painted last
+const PALETTE: [Option<Color>; 9] = [
+    None,
+    Some(Color::Red),
+    Some(Color::Green),
+    Some(Color::Yellow),
+    Some(Color::Blue),
+    Some(Color::Magenta),
+    Some(Color::Cyan),
+    Some(Color::Gray),
+    None,
+];
+/// Index of the background in [`PALETTE`]
+const BACKGROUND: u8 = 0;
+/// Index of unpainted text in [`PALETTE`]
+const DEFAULT: u8 = 8;
+
+/// Red, green and blue of a palette entry
+const fn rgb(index: usize) -> [u8; 3] {
+    match PALETTE[index] {
+        Some(Color::Red) => [205, 49, 49],
+        Some(Color::Green) => [13, 188, 121],
+        Some(Color::Yellow) => [229, 229, 16],
+        Some(Color::Blue) => [36, 114, 200],
+        Some(Color::Magenta) => [188, 63, 188],
+        Some(Color::Cyan) => [17, 168, 205],
+        Some(Color::Gray) => [102, 102, 102],
+        None if index == BACKGROUND as usize => [16, 16, 16],
+        None => [229, 229, 229],
+    }
+}
+
+/// Palette indices of every character of every line of `frame`, following
+/// the colors set by ANSI escape codes
+fn cells(frame: &str) -> Vec<Vec<u8>> {
+    let mut color = DEFAULT;
+    let mut lines = Vec::new();
+    for line in frame.lines() {
+        let mut cells = Vec::new();
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' => {
+                    let code = chars
+                        .by_ref()
+                        .take_while(|&c| c != 'm')
+                        .filter(char::is_ascii_digit)
+                        .collect::<String>();
+                    color = code
+                        .parse()
+                        .ok()
+                        .and_then(|code: u8| {
+                            let index = PALETTE.iter().position(|color| {
+                                color.is_some_and(|color| color.code() == code)
+                            })?;
+                            u8::try_from(index).ok()
+                        })
+                        .unwrap_or(DEFAULT);
+                }
+                ' ' | '.' => cells.push(BACKGROUND),
+                _ => cells.push(color),
+            }
+        }
+        lines.push(cells);
+    }
+    lines
+}
+
+/// Frames collected to be written as one animated GIF, looping forever
+pub struct Gif {
+    /// Hundredths of a second each frame is shown for
+    delay: u16,
+    /// Pixels on each side of the square drawn for a character
+    scale: u16,
+    frames: Vec<Vec<Vec<u8>>>,
+}
+
+impl Gif {
+    /// An empty animation showing each frame for `delay_ms` and drawing each
+    /// character as a square of `scale` pixels
+    #[must_use]
+    pub const fn new(delay_ms: u16, scale: u16) -> Self {
+        Self {
+            delay: delay_ms / 10,
+            scale,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Encode the frames so far into `out`, every frame on a canvas as large
+    /// as the largest one
+    pub fn write<W: Write>(&self, out: W) -> Result<W> {
+        if self.frames.is_empty() {
+            return Err(Error::NoFrames);
+        }
+        let columns = self.frames.iter().flatten().map(Vec::len).max();
+        let rows = self.frames.iter().map(Vec::len).max();
+        let size = |cells: Option<usize>| -> Result<u16> {
+            let pixels = cells.unwrap_or_default().max(1) * usize::from(self.scale);
+            u16::try_from(pixels).map_err(|_| Error::ImageTooLarge { pixels })
+        };
+        let (width, height) = (size(columns)?, size(rows)?);
+        let palette = (0..PALETTE.len()).flat_map(rgb).collect::<Vec<_>>();
+        let mut encoder = Encoder::new(out, width, height, &palette)?;
+        encoder.set_repeat(Repeat::Infinite)?;
+        let scale = usize::from(self.scale);
+        for lines in &self.frames {
+            let mut buffer = vec![BACKGROUND; usize::from(width) * usize::from(height)];
+            for (row, cells) in lines.iter().enumerate() {
+                for (column, &cell) in cells.iter().enumerate() {
+                    for y in row * scale..(row + 1) * scale {
+                        let start = y * usize::from(width) + column * scale;
+                        buffer[start..start + scale].fill(cell);
+                    }
+                }
+            }
+            encoder.write_frame(&Frame {
+                width,
+                height,
+                delay: self.delay,
+                buffer: buffer.into(),
+                ..Frame::default()
+            })?;
+        }
+        Ok(encoder.into_inner()?)
+    }
+
+    /// Write the animation of a day to `outputs/animation-dayXX.gif`,
+    /// returning the path
+    pub fn save(&self, day: u8) -> Result<PathBuf> {
+        let path = output_path(day, "animation", "gif")?;
+        self.write(BufWriter::new(File::create(&path)?))?.flush()?;
+        Ok(path)
+    }
+}
+
+impl FrameSink for Gif {
+    fn frame(&mut self, frame: &str) -> Result<()> {
+        self.frames.push(cells(frame));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::term::paint;
+
+    #[test]
+    fn test_gif() -> Result<()> {
+        let first = format!("{}.#\nab\n", paint("^", Color::Yellow));
+        assert_eq!(cells(&first), [vec![3, 0, 8], vec![8, 8]]);
+        let mut gif = Gif::new(100, 2);
+        assert!(gif.write(Vec::new()).is_err());
+        gif.frame(&first)?;
+        gif.frame("#\n")?;
+        let bytes = gif.write(Vec::new())?;
+        assert!(bytes.starts_with(b"GIF89a"));
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(bytes.as_slice())?;
+        assert_eq!((decoder.width(), decoder.height()), (6, 4));
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame()? {
+            assert_eq!(frame.delay, 10);
+            // The top left square is painted in both frames
+            assert_ne!(frame.buffer[0], BACKGROUND);
+            frames += 1;
+        }
+        assert_eq!(frames, 2);
+        Ok(())
+    }
+}
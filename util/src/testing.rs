@@ -0,0 +1,116 @@
+//! Assertions of the answers of a day against `answers.json` at the root of
+//! the workspace, so that an expected answer lives in one file rather than in
+//! the tests of its day.
+//!
+//! `answers.json` maps each input, `example` or `real`, to the answers of
+//! every day, in the same shape as `inputs/expected.json`, which fills in
+//! the answers it lacks, like the real ones that are not committed:
+//!
+//! ```json
+//! { "example": { "1": ["3", "6"] } }
+//! ```
+
+use std::{collections::BTreeMap, fs, io};
+
+use crate::{Error, Result, get_workspace_root};
+
+/// Name of the file of the answers, at the root of the workspace
+pub const ANSWERS_FILE: &str = "answers.json";
+
+/// Answers of both parts, if known, of every day, for every input
+pub type Answers = BTreeMap<String, BTreeMap<u8, [Option<String>; 2]>>;
+
+/// Answers of a file, or none if there is no such file
+fn read_answers(path: &std::path::Path) -> Result<Answers> {
+    match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| {
+            Error::Other(anyhow::anyhow!(
+                "Invalid answers in '{}': {e}",
+                path.display()
+            ))
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The expected answers from `answers.json`, with the gaps filled from
+/// `inputs/expected.json`
+///
+/// # Errors
+/// This function will return an error if either file cannot be read or is
+/// not valid.
+pub fn answers() -> Result<Answers> {
+    let root = get_workspace_root()?;
+    let mut answers = read_answers(&root.join(ANSWERS_FILE))?;
+    for (input, days) in read_answers(&root.join("inputs/expected.json"))? {
+        for (day, parts) in days {
+            let known = answers
+                .entry(input.clone())
+                .or_default()
+                .entry(day)
+                .or_default();
+            for (known, part) in known.iter_mut().zip(parts) {
+                if known.is_none() {
+                    *known = part;
+                }
+            }
+        }
+    }
+    Ok(answers)
+}
+
+/// The expected answer of a part of a day on an input, `example` or `real`
+///
+/// # Panics
+/// This function panics if the answers cannot be read or there is no answer
+/// for this part, which fails the test asking for it.
+#[must_use]
+pub fn expected(day: u8, input: &str, part: u8) -> String {
+    let answers = answers().unwrap_or_else(|e| panic!("Failed to read the answers: {e}"));
+    answers
+        .get(input)
+        .and_then(|days| days.get(&day))
+        .and_then(|parts| parts.get(usize::from(part).wrapping_sub(1))?.clone())
+        .unwrap_or_else(|| {
+            panic!("No {input} answer of day {day} part {part}, add it to {ANSWERS_FILE}")
+        })
+}
+
+/// Assert that a part of a puzzle gives the answer of `answers.json` on an
+/// input, `example` or `real`: `assert_answer!(Puzzle, part1, example)`
+#[macro_export]
+macro_rules! assert_answer {
+    (@check $puzzle:ty, $method:ident, $part:literal, $input:ident) => {{
+        let input = stringify!($input);
+        let day = <$puzzle as $crate::Solution>::DAY;
+        let puzzle = <$puzzle as $crate::Solution>::parse(input == "example");
+        assert_eq!(
+            $crate::Solution::$method(&puzzle),
+            $crate::testing::expected(day, input, $part),
+            "Day {day} part {} on the {input} input",
+            $part
+        );
+    }};
+    ($puzzle:ty, part1, $input:ident) => {
+        $crate::assert_answer!(@check $puzzle, part1, 1, $input)
+    };
+    ($puzzle:ty, part2, $input:ident) => {
+        $crate::assert_answer!(@check $puzzle, part2, 2, $input)
+    };
+}
+
+pub use crate::assert_answer;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected() {
+        assert_eq!(expected(1, "example", 1), "3");
+        assert_eq!(expected(12, "example", 2), "Final star on top of the tree");
+        let missing = std::panic::catch_unwind(|| expected(1, "example", 3));
+        assert!(missing.is_err());
+    }
+}
@@ -1,13 +1,20 @@
 //! Custom benchmarking for general functions.
 pub use std::hint::black_box;
+// The clock of std panics in the browser, where this one asks the page
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
 use std::{
     collections::BTreeMap,
     fmt::Display,
     ops::{Add, Div, Mul},
-    time::{Duration, Instant},
+    time::Duration,
 };
 
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
 use super::writer::CsvEntry;
+use crate::{Error, Result};
 
 const NANOSECOND_IN_NANOS: u128 = 1;
 const MICROSECOND_IN_NANOS: u128 = 1_000 * NANOSECOND_IN_NANOS;
@@ -15,6 +22,10 @@ const MILLISECOND_IN_NANOS: u128 = 1_000 * MICROSECOND_IN_NANOS;
 const SECOND_IN_NANOS: u128 = 1_000 * MILLISECOND_IN_NANOS;
 const MINUTE_IN_NANOS: u128 = 60 * SECOND_IN_NANOS;
 
+/// Environment variable that makes benchmarks keep every measurement in
+/// [`BenchmarkResult::samples`], as they are written out as `.npy` arrays then
+pub const SAMPLES_ENV: &str = "AOC_BENCH_SAMPLES";
+
 #[derive(Clone, Debug)]
 pub struct BenchmarkResult {
     pub name: String,
@@ -26,6 +37,9 @@ pub struct BenchmarkResult {
     pub std_dev: Duration,
     pub median: Duration,
     pub mad: Duration,
+    /// Every measurement in the order taken, kept only with [`SAMPLES_ENV`]
+    /// set, as there can be a million of them
+    pub samples: Vec<Duration>,
 }
 
 impl BenchmarkResult {
@@ -121,6 +135,100 @@ impl CsvEntry for BenchmarkResult {
     }
 }
 
+impl BenchmarkResult {
+    /// Results read back from a CSV written by
+    /// [`Serializable::to_csv`](crate::Serializable::to_csv)
+    ///
+    /// # Errors
+    /// This function will return an error if the header is not the one
+    /// written, or if any line does not have a value for every column.
+    pub fn from_csv(content: &str) -> Result<Vec<Self>> {
+        let mut lines = content.lines();
+        let header = lines.next().unwrap_or_default();
+        if header != Self::columns().join(",") {
+            return Err(results_error(header, "unexpected header".to_string()));
+        }
+        lines
+            .map(|line| {
+                // Names are written as they are, commas included, so they get
+                // whatever is left of the other columns
+                let mut values = line.rsplitn(Self::columns().len(), ',').collect::<Vec<_>>();
+                values.reverse();
+                let [
+                    name,
+                    iterations,
+                    time_limit,
+                    fastest,
+                    slowest,
+                    mean,
+                    std_dev,
+                    median,
+                    mad,
+                ] = values[..]
+                else {
+                    let reason = format!("expected {} values", Self::columns().len());
+                    return Err(results_error(line, reason));
+                };
+                Ok(Self {
+                    name: name.to_owned(),
+                    time_limit: parse_duration(time_limit)?,
+                    iterations: iterations
+                        .parse::<u128>()
+                        .map_err(|e| results_error(line, e.to_string()))?,
+                    fastest: parse_duration(fastest)?,
+                    slowest: parse_duration(slowest)?,
+                    mean: parse_duration(mean)?,
+                    std_dev: parse_duration(std_dev)?,
+                    median: parse_duration(median)?,
+                    mad: parse_duration(mad)?,
+                    samples: Vec::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A duration as benchmark results are written, such as `7.788µs` or `1s`
+///
+/// # Errors
+/// This function will return an error if the number or the unit is invalid.
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let units = [
+        ("ns", 1e-9),
+        ("µs", 1e-6),
+        ("us", 1e-6),
+        ("ms", 1e-3),
+        ("s", 1.0),
+        ("m", 60.0),
+    ];
+    let (value, seconds) = units
+        .iter()
+        .find_map(|&(unit, seconds)| Some((text.strip_suffix(unit)?, seconds)))
+        .ok_or_else(|| duration_error(text, "missing unit".to_string()))?;
+    let value = value
+        .parse::<f64>()
+        .map_err(|e| duration_error(text, e.to_string()))?;
+    Duration::try_from_secs_f64(value * seconds).map_err(|e| duration_error(text, e.to_string()))
+}
+
+/// A line of benchmark results that cannot be read back
+fn results_error(text: &str, reason: String) -> Error {
+    Error::Parse {
+        what: "benchmark results",
+        text: text.to_string(),
+        reason,
+    }
+}
+
+/// A duration that cannot be read back
+fn duration_error(text: &str, reason: String) -> Error {
+    Error::Parse {
+        what: "duration",
+        text: text.to_string(),
+        reason,
+    }
+}
+
 /// A simple square root function using Newton's method.
 fn sqrt<T>(x: T) -> T
 where
@@ -149,6 +257,73 @@ where
     }
 }
 
+/// The result of `f` along with how long it took
+pub fn timed<F, T>(f: F) -> (T, Duration)
+where
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Measures from its creation until it is dropped, then reports how long
+/// that took.
+///
+/// The report goes to stderr, or with the `tracing` feature to an event in a
+/// `timer` span, to see where the time goes inside a solution without setting
+/// up a benchmark. See also [`time!`](crate::time!).
+pub struct ScopedTimer {
+    label: String,
+    start: Instant,
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+impl ScopedTimer {
+    #[must_use]
+    pub fn new(label: impl Into<String>) -> Self {
+        let label = label.into();
+        Self {
+            #[cfg(feature = "tracing")]
+            _span: tracing::info_span!("timer", label = label.as_str()).entered(),
+            label,
+            start: Instant::now(),
+        }
+    }
+
+    /// Time since the timer was created
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for ScopedTimer {
+    fn drop(&mut self) {
+        let elapsed = self.elapsed();
+        #[cfg(feature = "tracing")]
+        tracing::info!(label = self.label.as_str(), ?elapsed, "timed");
+        #[cfg(not(feature = "tracing"))]
+        eprintln!("{}: {elapsed:?}", self.label);
+    }
+}
+
+/// Evaluate the expression with a [`ScopedTimer`](crate::timer::ScopedTimer)
+/// around it, labelled with the expression itself or with a label given
+/// first, e.g. `time!("sort", edges.sort_unstable())`
+#[macro_export]
+macro_rules! time {
+    ($label:literal, $($body:tt)+) => {{
+        let _timer = $crate::timer::ScopedTimer::new($label);
+        $($body)+
+    }};
+    ($($body:tt)+) => {{
+        let _timer = $crate::timer::ScopedTimer::new(stringify!($($body)+));
+        $($body)+
+    }};
+}
+
 pub fn measure_once<F, T>(f: F) -> Duration
 where
     F: FnOnce() -> T,
@@ -164,6 +339,8 @@ where
     F: FnMut() -> T,
     S: AsRef<str>,
 {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("bench", name = name.as_ref()).entered();
     // Cold run to get a sense of how long a single run takes, which will be used to
     // determine how many iterations we can run in the given time limit.
     let single_run = measure_once(&mut f);
@@ -176,11 +353,14 @@ where
     // Update the estimation of iterations to account for burn-in.
     let iterations = time_limit.as_nanos() / cold_run_time.as_nanos();
     let iterations = match iterations {
-        ..10 => iterations.min(3),
+        // Even a run slower than the time limit is measured three times
+        ..10 => 3,
         10..100 => iterations / 10 * 10,
         100..1000 => iterations / 100 * 100,
         _ => (iterations / 1000 * 1000).min(1_000_000),
     };
+    #[cfg(feature = "tracing")]
+    tracing::debug!(burn_in, iterations, ?cold_run_time, "measuring");
     let measurements = (0..iterations)
         .map(|_| black_box(measure_once(&mut f)).as_nanos())
         .collect::<Vec<_>>();
@@ -206,6 +386,14 @@ where
         .iter()
         .map(|&x| x.abs_diff(median))
         .collect::<Vec<_>>());
+    let samples = if std::env::var_os(SAMPLES_ENV).is_some() {
+        measurements
+            .iter()
+            .map(|&nanos| Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX)))
+            .collect()
+    } else {
+        Vec::new()
+    };
     // We allow the cast here, because even u64 is large enough to hold values that
     // are over 500 years in nanoseconds. No test results will ever be that large.
     #[allow(clippy::cast_possible_truncation)]
@@ -219,6 +407,7 @@ where
         std_dev: Duration::from_nanos(std_dev as u64),
         median: Duration::from_nanos(median as u64),
         mad: Duration::from_nanos(mad as u64),
+        samples,
     }
 }
 
@@ -226,6 +415,16 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_measure_many() {
+        // Slower than the time limit, which would otherwise be no iteration
+        let slow = measure_many("slow", Duration::from_millis(1), || {
+            std::thread::sleep(Duration::from_millis(2));
+        });
+        assert_eq!(slow.iterations, 3);
+        assert!(slow.fastest >= Duration::from_millis(2));
+    }
+
     #[test]
     fn test_sqrt() {
         for i in 0..=100 {
@@ -234,4 +433,42 @@ mod tests {
             assert!((sqrt_i + 1) * (sqrt_i + 1) > i);
         }
     }
+
+    #[test]
+    fn test_scoped_timer() {
+        let timer = ScopedTimer::new("sleep");
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(timer.elapsed() >= Duration::from_millis(2));
+        assert_eq!(timer.label, "sleep");
+        drop(timer);
+        // The macro gives the value of the expression, with or without a label
+        assert_eq!(crate::time!((1..=10).sum::<u32>()), 55);
+        assert_eq!(crate::time!("product", (1..=5).product::<u32>()), 120);
+    }
+
+    #[test]
+    fn test_from_csv() -> Result<()> {
+        let csv = "\
+name,iterations,time_limit,fastest,slowest,mean,std_dev,median,mad
+Parse,69000,1s,7.788µs,4069.745µs,12.902µs,23.354µs,12.444µs,0.996µs
+Part 2 (M=10, N=100),934000,500ms,881.000ns,4023237.000ns,1104.000ns,7903.000ns,996.000ns,55.000ns
+";
+        let results = BenchmarkResult::from_csv(csv)?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "Parse");
+        assert_eq!(results[0].iterations, 69000);
+        assert_eq!(results[0].median, Duration::from_nanos(12_444));
+        assert_eq!(results[1].name, "Part 2 (M=10, N=100)");
+        assert_eq!(results[1].time_limit, Duration::from_millis(500));
+        assert_eq!(results[1].slowest, Duration::from_nanos(4_023_237));
+        // What is written reads back the same, to the printed precision
+        let line = results[1].values().join(",");
+        assert!(csv.contains(&line));
+        assert_eq!(parse_duration("1.5m")?, Duration::from_secs(90));
+        assert!(parse_duration("12").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(BenchmarkResult::from_csv("name\n").is_err());
+        assert!(BenchmarkResult::from_csv(&csv.replace(",55.000ns", "")).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,53 @@
+//! Optional tracing of runs, compiled in with the `tracing` feature.
+//!
+//! Reading an input, parsing it and solving each part are spans, and caches
+//! report their statistics as debug events. What gets printed is filtered by
+//! `RUST_LOG` once [`init`] is called, e.g.
+//! `RUST_LOG=debug cargo run -p day10 --features util/tracing`. Without the
+//! feature, everything here does nothing.
+#![cfg_attr(
+    not(feature = "tracing"),
+    allow(unused_variables, clippy::missing_const_for_fn)
+)]
+
+/// Install a subscriber printing to stderr the events allowed by `RUST_LOG`,
+/// or only warnings without it, along with closed spans and their timings.
+/// Does nothing if a subscriber is already installed.
+pub fn init() {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan};
+
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .try_init();
+    }
+}
+
+/// Run `f`, parsing the input of a day, in a `parse` span
+pub fn parse<T>(day: u8, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("parse", day).entered();
+    f()
+}
+
+/// Run `f`, solving a part of a day, in a `part1` or `part2` span
+pub fn part<T>(day: u8, part: u8, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "tracing")]
+    let _span = if part == 1 {
+        tracing::info_span!("part1", day)
+    } else {
+        tracing::info_span!("part2", day)
+    }
+    .entered();
+    f()
+}
+
+/// Report how well a cache did, as a debug event
+pub fn cache_stats(cache: &str, hits: u64, misses: u64, entries: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(cache, hits, misses, entries, "cache statistics");
+}
@@ -0,0 +1,315 @@
+//! A small virtual machine scaffold for "interpret this assembly" puzzles.
+//!
+//! Puzzles define their own instruction set by implementing [`Instruction`]
+//! on an enum, and get the fetch-execute loop, breakpoints and tracing for
+//! free.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::Result;
+
+/// What the machine should do after executing an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Control {
+    /// Continue with the next instruction
+    Next,
+    /// Jump relative to the current instruction
+    Jump(isize),
+    /// Jump to an absolute instruction index
+    Goto(usize),
+    /// Pause without advancing, e.g. to wait for more input
+    Wait,
+    /// Stop the machine
+    Halt,
+}
+
+/// Why [`Vm::run`] returned
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stop {
+    /// An instruction halted, or the program counter left the program
+    Halted,
+    /// The program counter reached a breakpoint, before executing it
+    Breakpoint(usize),
+    /// An instruction asked to wait, and will be retried on the next run
+    Waiting,
+    /// The step limit was reached
+    StepLimit,
+}
+
+/// The mutable state instructions operate on
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cpu<V> {
+    /// General purpose registers, indexed however the instruction set likes
+    pub registers: Vec<V>,
+    /// Index of the instruction being executed
+    pub pc: usize,
+    /// Values waiting to be read by input instructions
+    pub input: VecDeque<V>,
+    /// Values written by output instructions
+    pub output: Vec<V>,
+}
+
+/// A single instruction of a puzzle-specific instruction set
+pub trait Instruction {
+    /// Type of the register values
+    type Value: Clone + Default;
+
+    /// Apply the instruction to `cpu`. Instructions never touch `cpu.pc`
+    /// directly, but report how execution continues instead, and fail with
+    /// [`Error::Execution`](crate::Error::Execution).
+    fn execute(&self, cpu: &mut Cpu<Self::Value>) -> Result<Control>;
+}
+
+/// One executed instruction, as recorded when tracing is enabled
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEntry<V> {
+    /// Index of the executed instruction
+    pub pc: usize,
+    /// Register values right before executing it
+    pub registers: Vec<V>,
+}
+
+/// A machine running a fixed program of instructions
+#[derive(Clone, Debug)]
+pub struct Vm<I: Instruction> {
+    program: Vec<I>,
+    cpu: Cpu<I::Value>,
+    steps: usize,
+    halted: bool,
+    breakpoints: BTreeSet<usize>,
+    trace: Option<Vec<TraceEntry<I::Value>>>,
+}
+
+impl<I: Instruction> Vm<I> {
+    /// Load `program` with the given number of zeroed registers
+    #[must_use]
+    pub fn new(program: Vec<I>, registers: usize) -> Self {
+        Self {
+            program,
+            cpu: Cpu {
+                registers: vec![I::Value::default(); registers],
+                pc: 0,
+                input: VecDeque::new(),
+                output: Vec::new(),
+            },
+            steps: 0,
+            halted: false,
+            breakpoints: BTreeSet::new(),
+            trace: None,
+        }
+    }
+
+    /// The loaded program
+    #[must_use]
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    /// The machine state
+    #[must_use]
+    pub const fn cpu(&self) -> &Cpu<I::Value> {
+        &self.cpu
+    }
+
+    /// The machine state, e.g. to set registers or feed input
+    pub const fn cpu_mut(&mut self) -> &mut Cpu<I::Value> {
+        &mut self.cpu
+    }
+
+    /// Number of instructions executed so far
+    #[must_use]
+    pub const fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Whether the machine has halted, after which it executes nothing more
+    #[must_use]
+    pub const fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Stop before executing the instruction at `pc`
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    /// Remove a breakpoint, returning whether it was set
+    pub fn remove_breakpoint(&mut self, pc: usize) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+
+    /// Start recording every executed instruction
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    /// Instructions executed since tracing was enabled
+    #[must_use]
+    pub fn trace(&self) -> &[TraceEntry<I::Value>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Execute a single instruction, returning why the machine stopped if it
+    /// did
+    pub fn step(&mut self) -> Result<Option<Stop>> {
+        let pc = self.cpu.pc;
+        let Some(instruction) = self.program.get(pc).filter(|_| !self.halted) else {
+            self.halted = true;
+            return Ok(Some(Stop::Halted));
+        };
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                pc,
+                registers: self.cpu.registers.clone(),
+            });
+        }
+        let control = instruction.execute(&mut self.cpu)?;
+        self.steps += 1;
+        let next = match control {
+            Control::Next => pc.checked_add(1),
+            Control::Jump(offset) => pc.checked_add_signed(offset),
+            Control::Goto(target) => Some(target),
+            Control::Wait => return Ok(Some(Stop::Waiting)),
+            Control::Halt => None,
+        };
+        match next {
+            Some(next) if next < self.program.len() => {
+                self.cpu.pc = next;
+                Ok(None)
+            }
+            // Jumping out of the program halts, leaving pc at the last
+            // executed instruction
+            _ => {
+                self.halted = true;
+                Ok(Some(Stop::Halted))
+            }
+        }
+    }
+
+    /// Run until the machine halts, waits, or reaches a breakpoint. A
+    /// breakpoint at the current instruction is skipped, so that calling
+    /// this again resumes execution.
+    pub fn run(&mut self) -> Result<Stop> {
+        self.run_for(usize::MAX)
+    }
+
+    /// Like [`Self::run`], but stop after at most `max_steps` instructions
+    pub fn run_for(&mut self, max_steps: usize) -> Result<Stop> {
+        for i in 0..max_steps {
+            if i > 0 && self.breakpoints.contains(&self.cpu.pc) {
+                return Ok(Stop::Breakpoint(self.cpu.pc));
+            }
+            if let Some(stop) = self.step()? {
+                return Ok(stop);
+            }
+        }
+        Ok(Stop::StepLimit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    /// A tiny instruction set in the spirit of 2016's assembunny
+    enum Op {
+        Cpy(i64, usize),
+        Inc(usize),
+        Dec(usize),
+        Jnz(usize, isize),
+        In(usize),
+        Out(usize),
+        Div(usize, usize),
+    }
+
+    impl Instruction for Op {
+        type Value = i64;
+
+        fn execute(&self, cpu: &mut Cpu<i64>) -> Result<Control> {
+            let r = &mut cpu.registers;
+            match *self {
+                Self::Cpy(v, x) => r[x] = v,
+                Self::Inc(x) => r[x] += 1,
+                Self::Dec(x) => r[x] -= 1,
+                Self::Jnz(x, offset) if r[x] != 0 => return Ok(Control::Jump(offset)),
+                Self::Jnz(..) => {}
+                Self::In(x) => match cpu.input.pop_front() {
+                    Some(v) => r[x] = v,
+                    None => return Ok(Control::Wait),
+                },
+                Self::Out(x) => cpu.output.push(r[x]),
+                Self::Div(x, y) => {
+                    if r[y] == 0 {
+                        return Err(Error::Execution {
+                            pc: cpu.pc,
+                            message: "Division by zero".to_string(),
+                        });
+                    }
+                    r[x] /= r[y];
+                }
+            }
+            Ok(Control::Next)
+        }
+    }
+
+    /// Add register 1 into register 0, one at a time
+    fn adder() -> Vec<Op> {
+        vec![Op::Inc(0), Op::Dec(1), Op::Jnz(1, -2), Op::Out(0)]
+    }
+
+    #[test]
+    fn test_run() -> Result<()> {
+        let mut vm = Vm::new(adder(), 2);
+        vm.cpu_mut().registers = vec![3, 4];
+        assert_eq!(vm.run()?, Stop::Halted);
+        assert_eq!(vm.cpu().registers, vec![7, 0]);
+        assert_eq!(vm.cpu().output, vec![7]);
+        assert_eq!(vm.steps(), 13);
+        // Running a halted machine does nothing
+        assert!(vm.is_halted());
+        assert_eq!(vm.run()?, Stop::Halted);
+        assert_eq!(vm.steps(), 13);
+        Ok(())
+    }
+
+    #[test]
+    fn test_breakpoints_and_limits() -> Result<()> {
+        let mut vm = Vm::new(adder(), 2);
+        vm.cpu_mut().registers = vec![0, 3];
+        vm.add_breakpoint(1);
+        assert_eq!(vm.run()?, Stop::Breakpoint(1));
+        assert_eq!(vm.cpu().registers, vec![1, 3]);
+        // Resuming skips the breakpoint we are sitting on
+        assert_eq!(vm.run()?, Stop::Breakpoint(1));
+        assert_eq!(vm.cpu().registers, vec![2, 2]);
+        assert!(vm.remove_breakpoint(1));
+        assert_eq!(vm.run_for(2)?, Stop::StepLimit);
+        assert_eq!(vm.cpu().pc, 0);
+        assert_eq!(vm.run()?, Stop::Halted);
+        assert_eq!(vm.cpu().output, vec![3]);
+        assert_eq!(vm.cpu().pc, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace_and_input() -> Result<()> {
+        let program = vec![Op::In(0), Op::In(1), Op::Div(0, 1), Op::Out(0)];
+        let mut vm = Vm::new(program, 2);
+        vm.enable_trace();
+        vm.cpu_mut().input.push_back(12);
+        assert_eq!(vm.run()?, Stop::Waiting);
+        assert_eq!(vm.cpu().pc, 1);
+        vm.cpu_mut().input.push_back(4);
+        assert_eq!(vm.run()?, Stop::Halted);
+        assert_eq!(vm.cpu().output, vec![3]);
+        let pcs = vm.trace().iter().map(|entry| entry.pc).collect::<Vec<_>>();
+        assert_eq!(pcs, vec![0, 1, 1, 2, 3]);
+        assert_eq!(vm.trace()[3].registers, vec![12, 4]);
+        // Errors from instructions are propagated
+        let mut vm = Vm::new(vec![Op::Cpy(1, 0), Op::Div(0, 1)], 2);
+        assert!(matches!(vm.run(), Err(Error::Execution { pc: 1, .. })));
+        assert_eq!(Vm::new(Vec::<Op>::new(), 0).run()?, Stop::Halted);
+        Ok(())
+    }
+}
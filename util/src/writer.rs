@@ -1,15 +1,61 @@
 //! Writer for writing data to a file in a specific format.
 
+mod dot;
+mod mermaid;
+mod npy;
+mod table;
+
 use std::{
     fs::{self, File},
     io::Write,
     marker::PhantomData,
+    path::PathBuf,
 };
 
-use anyhow::Result;
-
+pub use self::{
+    dot::Dot,
+    mermaid::Mermaid,
+    npy::npy_u64,
+    table::{Align, Table},
+};
 use super::get_workspace_root;
-use crate::timer::BenchmarkResult;
+use crate::{Result, timer::BenchmarkResult};
+
+/// Graph formats that a graph can be written to without knowing which one
+/// it is, so the same code dumps a graph as DOT or as Mermaid
+pub trait GraphWriter {
+    /// Add a node shown as `label`, filled with a CSS color if given
+    fn add_node(&mut self, id: &str, label: &str, fill: Option<&str>);
+
+    /// Add a directed edge between two nodes
+    fn add_edge(&mut self, from: &str, to: &str);
+}
+
+/// Path of the `outputs/{name}` file, creating the `outputs` directory if
+/// needed
+pub fn output_file(name: &str) -> Result<PathBuf> {
+    let path = get_workspace_root()?.join("outputs").join(name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(path)
+}
+
+/// Path of the `outputs/{name}-dayXX.{extension}` file for a day, creating
+/// the `outputs` directory if needed
+pub fn output_path(day: u8, name: &str, extension: &str) -> Result<PathBuf> {
+    output_file(&format!(
+        "{name}-day{day:02}.{}",
+        extension.trim_matches('.')
+    ))
+}
+
+/// Write `contents` to the [`output_path`] of a day, returning the path
+pub fn write_output(day: u8, name: &str, extension: &str, contents: &str) -> Result<PathBuf> {
+    let path = output_path(day, name, extension)?;
+    fs::write(&path, contents)?;
+    Ok(path)
+}
 
 pub struct FileWriter {
     file: File,
@@ -17,14 +63,7 @@ pub struct FileWriter {
 
 impl FileWriter {
     fn new(day: u8, extension: impl AsRef<str>) -> Result<Self> {
-        let path = get_workspace_root()?.join(format!(
-            "outputs/benchmark-day{day:02}.{}",
-            extension.as_ref().trim_matches('.')
-        ));
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        let file = File::create(path)?;
+        let file = File::create(output_path(day, "benchmark", extension.as_ref())?)?;
         Ok(Self { file })
     }
 
@@ -73,11 +112,38 @@ pub trait Serializable {
 }
 
 impl<T: AsRef<[BenchmarkResult]>> Serializable for T {
+    /// Write the results to `outputs/benchmark-dayXX.csv`, along with the
+    /// samples of the results that kept them, see [`write_samples`]
     fn to_csv(&self, day: u8) -> Result<()> {
         let mut writer = CsvWriter::new(day)?;
         for result in self.as_ref() {
             writer.write_entry(result)?;
+            if !result.samples.is_empty() {
+                write_samples(day, result)?;
+            }
         }
         Ok(())
     }
 }
+
+/// A name as a file name, in lowercase with anything but letters and digits
+/// replaced by single dashes, e.g. `part-1` for `Part 1`
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Write the samples of a benchmark in nanoseconds to
+/// `outputs/samples-{phase}-dayXX.npy`, as one array per phase to load with
+/// `numpy.load`, returning the path
+pub fn write_samples(day: u8, result: &BenchmarkResult) -> Result<PathBuf> {
+    let nanos = (result.samples.iter())
+        .map(|sample| u64::try_from(sample.as_nanos()).unwrap_or(u64::MAX))
+        .collect::<Vec<_>>();
+    let path = output_path(day, &format!("samples-{}", slug(&result.name)), "npy")?;
+    fs::write(&path, npy_u64(&nanos))?;
+    Ok(path)
+}
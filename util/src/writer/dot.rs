@@ -0,0 +1,138 @@
+//! Graphviz DOT output, for inspecting graphs with external tools.
+
+use std::fmt::{self, Display};
+
+use super::GraphWriter;
+
+/// A DOT string literal, quoted and escaped
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `[key="value", ...]`, or nothing without attributes
+fn attribute_list(attributes: &[(&str, &str)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let list = attributes
+        .iter()
+        .map(|&(key, value)| format!("{key}={}", quote(value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(" [{list}]")
+}
+
+/// A graph in the DOT language, built up statement by statement and written
+/// out through [`Display`].
+///
+/// Node ids are quoted, so any text works as an id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dot {
+    directed: bool,
+    statements: Vec<String>,
+}
+
+impl Dot {
+    /// An empty undirected graph
+    #[must_use]
+    pub const fn graph() -> Self {
+        Self {
+            directed: false,
+            statements: Vec::new(),
+        }
+    }
+
+    /// An empty directed graph
+    #[must_use]
+    pub const fn digraph() -> Self {
+        Self {
+            directed: true,
+            statements: Vec::new(),
+        }
+    }
+
+    /// Set an attribute of the graph itself, or the defaults of all nodes or
+    /// edges when `target` is `node` or `edge` rather than `graph`
+    pub fn defaults(&mut self, target: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        self.statements
+            .push(format!("{target}{}", attribute_list(attributes)));
+        self
+    }
+
+    /// Add a node, or set more attributes on an existing one
+    pub fn node(&mut self, id: impl Display, attributes: &[(&str, &str)]) -> &mut Self {
+        self.statements.push(format!(
+            "{}{}",
+            quote(&id.to_string()),
+            attribute_list(attributes)
+        ));
+        self
+    }
+
+    /// Add an edge, which also adds its ends as nodes if needed
+    pub fn edge(
+        &mut self,
+        from: impl Display,
+        to: impl Display,
+        attributes: &[(&str, &str)],
+    ) -> &mut Self {
+        let arrow = if self.directed { "->" } else { "--" };
+        self.statements.push(format!(
+            "{} {arrow} {}{}",
+            quote(&from.to_string()),
+            quote(&to.to_string()),
+            attribute_list(attributes)
+        ));
+        self
+    }
+}
+
+impl GraphWriter for Dot {
+    fn add_node(&mut self, id: &str, label: &str, fill: Option<&str>) {
+        match fill {
+            Some(fill) => self.node(
+                id,
+                &[("label", label), ("style", "filled"), ("fillcolor", fill)],
+            ),
+            None => self.node(id, &[("label", label)]),
+        };
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str) {
+        self.edge(from, to, &[]);
+    }
+}
+
+impl Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {{", if self.directed { "digraph" } else { "graph" })?;
+        for statement in &self.statements {
+            writeln!(f, "  {statement};")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot() {
+        let mut graph = Dot::graph();
+        graph
+            .defaults("node", &[("shape", "point")])
+            .node(0, &[("color", "red"), ("tooltip", "say \"hi\"")])
+            .edge(0, 1, &[]);
+        assert_eq!(
+            graph.to_string(),
+            "graph {\n  node [shape=\"point\"];\n  \"0\" [color=\"red\", tooltip=\"say \\\"hi\\\"\"];\n  \"0\" -- \"1\";\n}\n"
+        );
+        let mut graph = Dot::digraph();
+        graph.edge("a", "b", &[("style", "bold")]);
+        assert_eq!(
+            graph.to_string(),
+            "digraph {\n  \"a\" -> \"b\" [style=\"bold\"];\n}\n"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Mermaid flowchart output, for graphs that render directly in Markdown.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use super::GraphWriter;
+
+/// A Mermaid label, quoted with the quotes inside escaped as entities
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;"))
+}
+
+/// A top-down Mermaid flowchart, built up node by node and edge by edge and
+/// written out through [`Display`].
+///
+/// Mermaid ids are restricted and some words such as `end` are reserved, so
+/// every id is replaced by a generated `n0`, `n1`, ... and shown as a label.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mermaid {
+    ids: HashMap<String, usize>,
+    statements: Vec<String>,
+}
+
+impl Mermaid {
+    /// An empty flowchart
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generated id of `id`, declaring it labelled `label` the first time
+    fn id(&mut self, id: &str, label: &str) -> String {
+        if let Some(&k) = self.ids.get(id) {
+            return format!("n{k}");
+        }
+        let k = self.ids.len();
+        self.ids.insert(id.to_string(), k);
+        self.statements.push(format!("n{k}[{}]", quote(label)));
+        format!("n{k}")
+    }
+}
+
+impl GraphWriter for Mermaid {
+    fn add_node(&mut self, id: &str, label: &str, fill: Option<&str>) {
+        let node = self.id(id, label);
+        if let Some(fill) = fill {
+            self.statements.push(format!("style {node} fill:{fill}"));
+        }
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str) {
+        let (from, to) = (self.id(from, from), self.id(to, to));
+        self.statements.push(format!("{from} --> {to}"));
+    }
+}
+
+impl Display for Mermaid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "flowchart TD")?;
+        for statement in &self.statements {
+            writeln!(f, "  {statement}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mermaid() {
+        let mut chart = Mermaid::new();
+        chart.add_node("end", "the \"end\"", Some("#ffd700"));
+        chart.add_edge("start", "end");
+        assert_eq!(
+            chart.to_string(),
+            "flowchart TD\n  n0[\"the #quot;end#quot;\"]\n  style n0 fill:#ffd700\n  \
+             n1[\"start\"]\n  n1 --> n0\n"
+        );
+    }
+}
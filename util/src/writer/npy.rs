@@ -0,0 +1,49 @@
+//! `NumPy` `.npy` arrays, which `numpy.load` reads as they are, to plot the
+//! raw samples of a benchmark without parsing anything.
+
+/// `values` as a one-dimensional `.npy` array of little-endian `u64`, in
+/// version 1.0 of the format
+#[must_use]
+pub fn npy_u64(values: &[u64]) -> Vec<u8> {
+    const MAGIC: &[u8] = b"\x93NUMPY\x01\x00";
+    let mut header = format!(
+        "{{'descr': '<u8', 'fortran_order': False, 'shape': ({},), }}",
+        values.len()
+    );
+    // The magic, the length of the header and the header itself, ending with
+    // a newline, take a multiple of 64 bytes so that the data is aligned
+    let unpadded = MAGIC.len() + 2 + header.len() + 1;
+    header.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+    header.push('\n');
+    let length = u16::try_from(header.len())
+        .unwrap_or_else(|_| unreachable!("A header of one dimension is short"));
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 2 + header.len() + 8 * values.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&length.to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for value in values {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npy_u64() {
+        let bytes = npy_u64(&[1, 258]);
+        assert!(bytes.starts_with(b"\x93NUMPY\x01\x00"));
+        let length = usize::from(u16::from_le_bytes([bytes[8], bytes[9]]));
+        let header = std::str::from_utf8(&bytes[10..10 + length]).unwrap_or_default();
+        assert!(header.starts_with("{'descr': '<u8', 'fortran_order': False, 'shape': (2,), }"));
+        assert!(header.ends_with('\n'));
+        assert_eq!((10 + length) % 64, 0);
+        assert_eq!(bytes.len(), 10 + length + 16);
+        assert_eq!(
+            &bytes[10 + length..],
+            [1, 0, 0, 0, 0, 0, 0, 0, 2, 1, 0, 0, 0, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Tables of text, written as aligned plain text, as Markdown or as HTML.
+
+use std::fmt::Display;
+
+/// How the cells of a column line up
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Width of `text` as shown in a terminal, skipping ANSI color codes
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+/// `text` padded with spaces to `width`
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let fill = width.saturating_sub(visible_width(text));
+    let (left, right) = match align {
+        Align::Left => (0, fill),
+        Align::Center => (fill / 2, fill - fill / 2),
+        Align::Right => (fill, 0),
+    };
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+}
+
+/// Rows of cells under a header, with one alignment per column
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    headers: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// An empty table with these column headers, all left aligned
+    pub fn new<S: Into<String>>(headers: impl IntoIterator<Item = S>) -> Self {
+        let headers = headers.into_iter().map(Into::into).collect::<Vec<_>>();
+        Self {
+            aligns: vec![Align::Left; headers.len()],
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Align the cells of a column
+    #[must_use]
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        if let Some(slot) = self.aligns.get_mut(column) {
+            *slot = align;
+        }
+        self
+    }
+
+    /// Add a row, leaving missing cells empty and dropping extra ones
+    pub fn row(&mut self, cells: impl IntoIterator<Item = impl Display>) -> &mut Self {
+        let mut row = cells
+            .into_iter()
+            .map(|cell| cell.to_string())
+            .collect::<Vec<_>>();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+        self
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Width of each column, from its widest cell
+    fn widths(&self, minimum: usize) -> Vec<usize> {
+        (0..self.headers.len())
+            .map(|c| {
+                self.rows
+                    .iter()
+                    .map(|row| visible_width(&row[c]))
+                    .chain([visible_width(&self.headers[c]), minimum])
+                    .max()
+                    .unwrap_or(minimum)
+            })
+            .collect()
+    }
+
+    /// The table as aligned plain text, with a rule under the headers
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let widths = self.widths(0);
+        let line = |cells: &[String]| {
+            let padded = cells
+                .iter()
+                .zip(&widths)
+                .zip(&self.aligns)
+                .map(|((cell, &width), &align)| pad(cell, width, align))
+                .collect::<Vec<_>>();
+            padded.join("  ").trim_end().to_string() + "\n"
+        };
+        let rule = widths.iter().map(|&w| "-".repeat(w)).collect::<Vec<_>>();
+        let mut text = line(&self.headers) + &line(&rule);
+        for row in &self.rows {
+            text += &line(row);
+        }
+        text
+    }
+
+    /// The table as a Markdown pipe table, padded to line up in the source
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        // Alignment markers need at least three characters
+        let widths = self.widths(3);
+        let line = |cells: &[String]| {
+            let padded = cells
+                .iter()
+                .zip(&widths)
+                .zip(&self.aligns)
+                .map(|((cell, &width), &align)| pad(&cell.replace('|', "\\|"), width, align))
+                .collect::<Vec<_>>();
+            format!("| {} |\n", padded.join(" | "))
+        };
+        let markers = widths
+            .iter()
+            .zip(&self.aligns)
+            .map(|(&width, align)| match align {
+                Align::Left => "-".repeat(width),
+                Align::Center => format!(":{}:", "-".repeat(width - 2)),
+                Align::Right => format!("{}:", "-".repeat(width - 1)),
+            })
+            .collect::<Vec<_>>();
+        let mut markdown = line(&self.headers) + &format!("| {} |\n", markers.join(" | "));
+        for row in &self.rows {
+            markdown += &line(row);
+        }
+        markdown
+    }
+
+    /// The table as an HTML `<table>`, with the cells escaped
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let escape = |text: &str| {
+            text.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+        let line = |tag: &str, cells: &[String]| {
+            let cells = cells
+                .iter()
+                .zip(&self.aligns)
+                .map(|(cell, align)| {
+                    let align = match align {
+                        Align::Left => "left",
+                        Align::Center => "center",
+                        Align::Right => "right",
+                    };
+                    format!(
+                        "<{tag} style=\"text-align: {align}\">{}</{tag}>",
+                        escape(cell)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("<tr>{}</tr>\n", cells.concat())
+        };
+        let body = self
+            .rows
+            .iter()
+            .map(|row| line("td", row))
+            .collect::<Vec<_>>();
+        format!(
+            "<table>\n<thead>\n{}</thead>\n<tbody>\n{}</tbody>\n</table>\n",
+            line("th", &self.headers),
+            body.concat()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::term::{Color, paint};
+
+    #[test]
+    fn test_table() {
+        let mut table = Table::new(["Day", "Name", "Stars"])
+            .align(0, Align::Right)
+            .align(2, Align::Center);
+        table.row([1.to_string(), "a|b".into(), paint("**", Color::Yellow)]);
+        table.row(["12", "longer name"]);
+        assert_eq!(table.len(), 2);
+        assert_eq!(
+            table.to_text(),
+            format!(
+                "Day  Name         Stars\n---  -----------  -----\n  1  a|b           {}\n 12  longer name\n",
+                paint("**", Color::Yellow)
+            )
+        );
+        let mut table = Table::new(["Day", "Note"]).align(0, Align::Right);
+        table.row(["1", "a|b"]);
+        assert_eq!(
+            table.to_markdown(),
+            "| Day | Note |\n| --: | ---- |\n|   1 | a\\|b |\n"
+        );
+        assert_eq!(
+            table.to_html(),
+            "<table>\n<thead>\n\
+             <tr><th style=\"text-align: right\">Day</th><th style=\"text-align: left\">Note</th></tr>\n\
+             </thead>\n<tbody>\n\
+             <tr><td style=\"text-align: right\">1</td><td style=\"text-align: left\">a|b</td></tr>\n\
+             </tbody>\n</table>\n"
+        );
+    }
+}
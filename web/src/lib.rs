@@ -0,0 +1,58 @@
+//! The days compiled for the browser, where `index.html` runs them on an
+//! input pasted into the page.
+//!
+//! ```text
+//! wasm-pack build web --target web
+//! python3 -m http.server -d web
+//! ```
+
+use solutions::{all, with_input};
+use wasm_bindgen::prelude::*;
+
+/// Answers of a day with their timings, as handed to the page
+#[wasm_bindgen]
+pub struct Report {
+    answers: [String; 2],
+    /// Milliseconds spent parsing, on part 1 and on part 2
+    timings: [f64; 3],
+}
+
+#[wasm_bindgen]
+impl Report {
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn part1(&self) -> String {
+        self.answers[0].clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn part2(&self) -> String {
+        self.answers[1].clone()
+    }
+
+    /// Milliseconds spent parsing, on part 1 and on part 2
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn timings(&self) -> Vec<f64> {
+        self.timings.to_vec()
+    }
+}
+
+/// The days that can be run
+#[wasm_bindgen]
+#[must_use]
+pub fn days() -> Vec<u8> {
+    all().iter().map(|solution| solution.day()).collect()
+}
+
+/// Solve both parts of a day for `input`
+#[wasm_bindgen]
+pub fn run(day: u8, input: &str) -> Result<Report, JsError> {
+    let solution = solutions::find(day).map_err(|e| JsError::new(&e.to_string()))?;
+    let run = with_input(input, || solution.run(false));
+    Ok(Report {
+        answers: run.answers,
+        timings: run.timings.map(|timing| timing.as_secs_f64() * 1e3),
+    })
+}
@@ -0,0 +1,225 @@
+//! One report of every day, whether it builds, whether its example tests
+//! pass and whether its answers on the real input are the accepted ones, in
+//! place of scanning the output of `cargo test --workspace`.
+//!
+//! Days are the `dayNN` crates of the workspace, built and tested one by one
+//! so that a day that does not build does not hide the others. The report is
+//! printed and written to `outputs/check-all.md`.
+
+use std::{
+    env, fs,
+    process::{Command, Output},
+};
+
+use anyhow::{Result, bail};
+use runner::{Expected, Flags, Format, REAL, Verdict, answers};
+use util::{
+    get_workspace_root,
+    writer::{Align, Table, output_file},
+};
+
+/// What became of a day
+struct DayCheck {
+    day: u8,
+    /// Whether the day builds in release mode
+    builds: bool,
+    /// Passed and failed example tests, unless the tests did not build
+    tests: Option<(usize, usize)>,
+    /// Answers on the real input, if there is one
+    answers: Option<Result<[String; 2]>>,
+}
+
+/// Run cargo with `args`, quietly
+fn cargo(args: &[&str]) -> Result<Output> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    Ok(Command::new(cargo).arg("--quiet").args(args).output()?)
+}
+
+/// Passed and failed tests summed over every `test result` line of the output
+/// of `cargo test`, or `None` without any
+fn count_tests(stdout: &str) -> Option<(usize, usize)> {
+    let count = |summary: &str, outcome: &str| {
+        summary
+            .split(';')
+            .find_map(|part| {
+                let count = part
+                    .trim()
+                    .strip_suffix(outcome)?
+                    .split_whitespace()
+                    .last()?;
+                count.parse().ok()
+            })
+            .unwrap_or(0)
+    };
+    stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("test result: "))
+        .map(|summary| (count(summary, "passed"), count(summary, "failed")))
+        .reduce(|(p1, f1), (p2, f2)| (p1 + p2, f1 + f2))
+}
+
+/// Build, test and run a day
+fn check(day: u8, has_input: bool) -> Result<DayCheck> {
+    let package = format!("day{day:02}");
+    let build = cargo(&["build", "--release", "-p", &package])?;
+    let builds = build.status.success();
+    if !builds {
+        eprint!("{}", String::from_utf8_lossy(&build.stderr));
+        return Ok(DayCheck {
+            day,
+            builds,
+            tests: None,
+            answers: None,
+        });
+    }
+    let test = cargo(&["test", "-p", &package, "test_"])?;
+    let tests = count_tests(&String::from_utf8_lossy(&test.stdout));
+    if !test.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&test.stdout));
+    }
+    Ok(DayCheck {
+        day,
+        builds,
+        tests,
+        answers: has_input.then(|| answers(day)),
+    })
+}
+
+/// A row for every day with how it fared, and how many things failed
+fn report(checks: &[DayCheck], expected: &Expected, format: Format) -> (Table, usize) {
+    let headers = ["Day", "Build", "Examples", "Part 1", "Part 2"];
+    let mut table = Table::new(headers).align(0, Align::Right);
+    let mut failed = 0;
+    let mut mark = |text: &str, verdict: Verdict| {
+        if verdict == Verdict::Wrong {
+            failed += 1;
+        }
+        format.verdict(text, verdict)
+    };
+    for check in checks {
+        let build = if check.builds {
+            mark("ok", Verdict::Right)
+        } else {
+            mark("failed", Verdict::Wrong)
+        };
+        let examples = match check.tests {
+            _ if !check.builds => String::new(),
+            None => mark("not built", Verdict::Wrong),
+            Some((passed, 0)) => mark(&format!("{passed} passed"), Verdict::Right),
+            Some((passed, broken)) => mark(
+                &format!("{broken} of {} failed", passed + broken),
+                Verdict::Wrong,
+            ),
+        };
+        let parts: [String; 2] = match &check.answers {
+            None => [String::new(), String::new()],
+            Some(Err(_)) => [mark("error", Verdict::Wrong), String::new()],
+            Some(Ok(answers)) => {
+                let expected = (expected.get(REAL)).and_then(|days| days.get(&check.day));
+                [0, 1].map(|part| {
+                    let expected = expected.and_then(|parts| parts[part].as_ref());
+                    mark(&answers[part], Verdict::of(&answers[part], expected))
+                })
+            }
+        };
+        let row = [format!("{:02}", check.day), build, examples]
+            .into_iter()
+            .chain(parts)
+            .collect::<Vec<_>>();
+        table.row(row);
+    }
+    (table, failed)
+}
+
+/// `check-all [--format text|markdown]`: build every day, run its example
+/// tests and its real input, and report all of it in one table, failing if
+/// anything did
+pub fn run(args: &[String]) -> Result<()> {
+    let flags = Flags::parse(args, &["format"])?;
+    let format = flags.format()?;
+    let root = get_workspace_root()?;
+    let mut days = fs::read_dir(&root)?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name();
+            let day = name.to_str()?.strip_prefix("day")?;
+            (day.len() == 2).then(|| day.parse::<u8>().ok()).flatten()
+        })
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    let mut checks = Vec::new();
+    for day in days {
+        eprintln!("Checking day {day:02}");
+        let input = root.join(format!("inputs/day{day:02}.txt"));
+        let has_input = fs::metadata(input).is_ok_and(|metadata| metadata.len() > 0);
+        checks.push(check(day, has_input)?);
+    }
+    let expected = runner::expected(&root.join("inputs"))?;
+    let (table, failed) = report(&checks, &expected, format);
+    print!("{}", format.render(&table));
+    let path = output_file("check-all.md")?;
+    let (markdown, _) = report(&checks, &expected, Format::Markdown);
+    fs::write(&path, markdown.to_markdown())?;
+    println!("Report written to {}", path.display());
+    if failed > 0 {
+        bail!("{failed} checks failed over {} days", checks.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let stdout = "\
+running 2 tests
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 3 filtered out
+
+running 1 test
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+";
+        assert_eq!(count_tests(stdout), Some((2, 1)));
+        assert_eq!(count_tests("error: could not compile"), None);
+        let checks = [
+            DayCheck {
+                day: 1,
+                builds: true,
+                tests: Some((4, 0)),
+                answers: Some(Ok(["3".to_string(), "7".to_string()])),
+            },
+            DayCheck {
+                day: 2,
+                builds: true,
+                tests: Some((2, 1)),
+                answers: Some(Err(anyhow!("panicked"))),
+            },
+            DayCheck {
+                day: 3,
+                builds: false,
+                tests: None,
+                answers: None,
+            },
+        ];
+        let expected = Expected::from([(
+            REAL.to_string(),
+            BTreeMap::from([(1, [Some("3".to_string()), Some("6".to_string())])]),
+        )]);
+        let (table, failed) = report(&checks, &expected, Format::Markdown);
+        assert_eq!(failed, 4);
+        assert_eq!(
+            table.to_markdown(),
+            "\
+| Day | Build        | Examples            | Part 1      | Part 2  |
+| --: | ------------ | ------------------- | ----------- | ------- |
+|  01 | ok ✓         | 4 passed ✓          | 3 ✓         | **7 ✗** |
+|  02 | ok ✓         | **1 of 3 failed ✗** | **error ✗** |         |
+|  03 | **failed ✗** |                     |             |         |
+"
+        );
+    }
+}
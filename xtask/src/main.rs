@@ -0,0 +1,23 @@
+//! Tasks over the whole workspace, run as `cargo xtask TASK` through the
+//! alias in `.cargo/config.toml`.
+//!
+//! ```text
+//! cargo xtask check-all [--format text|markdown]
+//! ```
+
+mod check;
+
+use std::env;
+
+use anyhow::{Result, bail};
+
+fn main() -> Result<()> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let Some((task, rest)) = args.split_first() else {
+        bail!("Usage: cargo xtask <check-all> [...]");
+    };
+    match task.as_str() {
+        "check-all" => check::run(rest),
+        other => bail!("Unknown task {other}, expected check-all"),
+    }
+}